@@ -6,6 +6,7 @@
 use crate::placement::{self, CoreId};
 use crate::run_queue::{Stealer, Worker};
 use crate::worker;
+use fxhash::FxHashMap;
 use lightproc::prelude::*;
 use std::thread;
 
@@ -20,12 +21,12 @@ impl Distributor {
         }
     }
 
-    pub(crate) fn assign(self) -> Vec<Stealer<LightProc>> {
-        let mut stealers = Vec::<Stealer<LightProc>>::new();
+    pub(crate) fn assign(self) -> FxHashMap<usize, Stealer<LightProc>> {
+        let mut stealers = FxHashMap::default();
 
         for core in self.cores {
             let wrk = Worker::new_fifo();
-            stealers.push(wrk.stealer());
+            stealers.insert(core.id, wrk.stealer());
 
             thread::Builder::new()
                 .name("bastion-async-thread".to_string())
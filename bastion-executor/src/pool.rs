@@ -8,6 +8,7 @@ use crate::distributor::Distributor;
 use crate::run_queue::{Injector, Stealer};
 use crate::sleepers::Sleepers;
 use crate::worker;
+use fxhash::FxHashMap;
 use lazy_static::lazy_static;
 use lightproc::prelude::*;
 use std::future::Future;
@@ -53,8 +54,11 @@ pub struct Pool {
     /// Global run queue implementation
     pub(crate) injector: Injector<LightProc>,
     ///
-    /// Stealers of the workers
-    pub(crate) stealers: Vec<Stealer<LightProc>>,
+    /// Stealers of the workers' run queues, by core id
+    pub(crate) stealers: FxHashMap<usize, Stealer<LightProc>>,
+    ///
+    /// Run queues of the processes pinned to a core, by core id
+    pub(crate) pinned: FxHashMap<usize, Injector<LightProc>>,
     ///
     /// Container of parked threads
     pub(crate) sleepers: Sleepers,
@@ -85,11 +89,19 @@ pub fn get() -> &'static Pool {
     lazy_static! {
         static ref POOL: Pool = {
             let distributor = Distributor::new();
+            // Core ids aren't necessarily contiguous (eg. when the
+            // process is restricted to some cores).
+            let pinned = distributor
+                .cores
+                .iter()
+                .map(|core| (core.id, Injector::new()))
+                .collect();
             let stealers = distributor.assign();
 
             Pool {
                 injector: Injector::new(),
                 stealers,
+                pinned,
                 sleepers: Sleepers::new(),
             }
         };
@@ -2,8 +2,10 @@
 //! Where workers went to parking while no workload is in their worker queue.
 //!
 //! If a workload received pool will wake them up.
+use fxhash::FxHashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Condvar, Mutex};
+use std::sync::Mutex;
+use std::thread::{self, Thread};
 
 /// The place where worker threads go to sleep.
 ///
@@ -11,34 +13,47 @@ use std::sync::{Condvar, Mutex};
 /// the next thread that attempts to go to sleep will pick up the notification immediately.
 #[derive(Debug)]
 pub struct Sleepers {
-    /// How many threads are currently a sleep.
-    sleep: Mutex<usize>,
-
-    /// A condvar for notifying sleeping threads.
-    wake: Condvar,
+    /// The threads that are currently asleep and the pending notifications of each core.
+    sleep: Mutex<Sleeping>,
 
     /// Set to `true` if a notification came up while nobody was sleeping.
     notified: AtomicBool,
 }
 
+#[derive(Debug, Default)]
+struct Sleeping {
+    /// The threads that are currently asleep, with the core they are running on.
+    threads: Vec<(usize, Thread)>,
+
+    /// The cores whose thread was notified on its own while it wasn't sleeping.
+    pending: FxHashSet<usize>,
+}
+
 impl Sleepers {
     /// Creates a new `Sleepers`.
     pub fn new() -> Sleepers {
         Sleepers {
-            sleep: Mutex::new(0),
-            wake: Condvar::new(),
+            sleep: Mutex::new(Sleeping::default()),
             notified: AtomicBool::new(false),
         }
     }
 
-    /// Puts the current thread to sleep.
-    pub fn wait(&self) {
+    /// Puts the current thread, which is running on the given core, to sleep.
+    pub fn wait(&self, core: usize) {
         let mut sleep = self.sleep.lock().unwrap();
 
-        if !self.notified.swap(false, Ordering::SeqCst) {
-            *sleep += 1;
-            let _ = self.wake.wait(sleep).unwrap();
+        if self.notified.swap(false, Ordering::SeqCst) || sleep.pending.remove(&core) {
+            return;
         }
+
+        sleep.threads.push((core, thread::current()));
+        drop(sleep);
+        // A thread notified before parking doesn't park.
+        thread::park();
+
+        // The thread might have been unparked spuriously.
+        let mut sleep = self.sleep.lock().unwrap();
+        sleep.threads.retain(|(sleeping, _)| *sleeping != core);
     }
 
     /// Notifies one thread.
@@ -46,11 +61,22 @@ impl Sleepers {
         if !self.notified.load(Ordering::SeqCst) {
             let mut sleep = self.sleep.lock().unwrap();
 
-            if *sleep > 0 {
-                *sleep -= 1;
-                self.wake.notify_one();
-            } else {
-                self.notified.store(true, Ordering::SeqCst);
+            match sleep.threads.pop() {
+                Some((_, thread)) => thread.unpark(),
+                None => self.notified.store(true, Ordering::SeqCst),
+            }
+        }
+    }
+
+    /// Notifies the thread running on the given core only.
+    pub fn notify(&self, core: usize) {
+        let mut sleep = self.sleep.lock().unwrap();
+
+        let sleeping = sleep.threads.iter().position(|(other, _)| *other == core);
+        match sleeping {
+            Some(index) => sleep.threads.swap_remove(index).1.unpark(),
+            None => {
+                sleep.pending.insert(core);
             }
         }
     }
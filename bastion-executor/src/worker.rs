@@ -3,6 +3,9 @@
 //!
 //! This worker implementation relies on worker run queue statistics which are hold in the pinned global memory
 //! where workload distribution calculated and amended to their own local queues.
+//!
+//! Every worker runs the processes of its own run queue, and steals from the global run queue and
+//! then from the other workers' run queues (the most loaded ones first) once it is empty.
 use crate::load_balancer;
 use crate::pool::{self, Pool};
 use crate::run_queue::{Steal, Worker};
@@ -55,100 +58,155 @@ where
     }
 }
 
+///
+/// How many processes a worker fetches before checking the global run queue first.
+const GLOBAL_QUEUE_INTERVAL: u32 = 61;
+
 thread_local! {
     static QUEUE: UnsafeCell<Option<Worker<LightProc>>> = UnsafeCell::new(None);
+    static AFFINITY: Cell<Option<usize>> = Cell::new(None);
 }
 
 pub(crate) fn schedule(proc: LightProc) {
+    let pool = pool::get();
+
+    // Pinned processes never enter the local run queues, which
+    // other workers are stealing from.
+    if let Some(core) = pinned_core(pool, proc.stack()) {
+        pool.pinned[&core].push(proc);
+        // Only the worker of this core can run the process.
+        pool.sleepers.notify(core);
+        return;
+    }
+
     QUEUE.with(|queue| {
         let local = unsafe { (*queue.get()).as_ref() };
 
         match local {
-            None => pool::get().injector.push(proc),
+            None => pool.injector.push(proc),
             Some(q) => q.push(proc),
         }
     });
 
-    pool::get().sleepers.notify_one();
+    pool.sleepers.notify_one();
+}
+
+///
+/// Get the core that a process pinned with [ProcStack::with_affinity] should run on,
+/// preferring the current core and otherwise the least loaded one of its core set.
+fn pinned_core(pool: &Pool, stack: &ProcStack) -> Option<usize> {
+    let cores = stack.get_affinity()?;
+
+    if let Some(current) = AFFINITY.with(|affinity| affinity.get()) {
+        if cores.contains(&current) {
+            return Some(current);
+        }
+    }
+
+    // Cores that don't exist are ignored.
+    let mut available = cores
+        .iter()
+        .copied()
+        .filter(|core| pool.pinned.contains_key(core));
+    match load_balancer::stats().try_read() {
+        Ok(stats) => {
+            available.min_by_key(|core| stats.smp_queues.get(core).copied().unwrap_or_default())
+        }
+        Err(_) => available.next(),
+    }
 }
 
 ///
 /// Fetch the process from the run queue.
 /// Does the work of work-stealing if process doesn't exist in the local run queue.
 pub fn fetch_proc(affinity: usize) -> Option<LightProc> {
+    fetch(affinity, false)
+}
+
+fn fetch(affinity: usize, global_first: bool) -> Option<LightProc> {
     let pool = pool::get();
 
     QUEUE.with(|queue| {
         let local = unsafe { (*queue.get()).as_ref().unwrap() };
-        local.pop().or_else(|| affine_steal(pool, local, affinity))
+
+        let global = if global_first {
+            steal_until_done(|| pool.injector.steal_batch_and_pop(local))
+        } else {
+            None
+        };
+
+        global
+            .or_else(|| fetch_pinned(pool, affinity))
+            .or_else(|| local.pop())
+            .or_else(|| affine_steal(pool, local, affinity))
     })
 }
 
-fn affine_steal(pool: &Pool, local: &Worker<LightProc>, affinity: usize) -> Option<LightProc> {
-    // Pop a task from the local queue, if not empty.
-    local.pop().or_else(|| {
-        // Otherwise, we need to look for a task elsewhere.
-        iter::repeat_with(|| match load_balancer::stats().try_read() {
-            Ok(stats) => {
-                // Collect run queue statistics
-                let mut core_vec: Vec<_> = stats
-                    .smp_queues
-                    .iter()
-                    .map(|(&x, &y)| (x, y))
-                    .collect::<Vec<(usize, usize)>>();
-
-                // Sort cores and their run queue sizes.
-                //
-                // This sort will be ordered by descending order
-                // so we can pick up from the most overloaded queue.
-                core_vec.sort_by(|x, y| y.1.cmp(&x.1));
-
-                // First try to get procs from global queue
-                pool.injector.steal_batch_and_pop(&local).or_else(|| {
-                    match core_vec.get(0) {
-                        Some((core, _)) => {
-                            // If affinity is the one with the highest let other's do the stealing
-                            if *core == affinity {
-                                Steal::Retry
-                            } else {
-                                // Try iterating through biggest to smallest
-                                core_vec
-                                    .iter()
-                                    .map(|s| {
-                                        // Steal the mean amount to balance all queues considering incoming workloads
-                                        // Otherwise do an ignorant steal (which is going to be useless)
-                                        if stats.mean_level > 0 {
-                                            pool.stealers
-                                                .get(s.0)
-                                                .unwrap()
-                                                .steal_batch_and_pop_with_amount(
-                                                    &local,
-                                                    stats.mean_level,
-                                                )
-                                        } else {
-                                            pool.stealers
-                                                .get(s.0)
-                                                .unwrap()
-                                                .steal_batch_and_pop(&local)
-                                            // TODO: Set evacuation flag in thread_local
-                                        }
-                                    })
-                                    .collect()
-                            }
-                        }
-                        _ => Steal::Retry,
-                    }
-                })
-            }
-            Err(_) => Steal::Empty, // Behave like there is no job under the contention.
-        })
-        // Loop while no task was stolen and any steal operation needs to be retried.
+fn fetch_pinned(pool: &Pool, affinity: usize) -> Option<LightProc> {
+    let pinned = pool.pinned.get(&affinity)?;
+
+    steal_until_done(|| pinned.steal())
+}
+
+///
+/// Keep stealing while the steal operation needs to be retried, and extract the stolen process,
+/// if there is one.
+fn steal_until_done<F>(steal: F) -> Option<LightProc>
+where
+    F: FnMut() -> Steal<LightProc>,
+{
+    iter::repeat_with(steal)
         .find(|s| !s.is_retry())
-        // Extract the stolen task, if there is one.
         .and_then(|s| s.success())
+}
+
+fn affine_steal(pool: &Pool, local: &Worker<LightProc>, affinity: usize) -> Option<LightProc> {
+    steal_until_done(|| {
+        // First try to get procs from global queue, and otherwise from the other workers.
+        pool.injector
+            .steal_batch_and_pop(local)
+            .or_else(|| steal_from_workers(pool, local, affinity))
     })
 }
 
+fn steal_from_workers(pool: &Pool, local: &Worker<LightProc>, affinity: usize) -> Steal<LightProc> {
+    // Our own run queue is empty, even if its (stale) statistics say otherwise.
+    let mut victims = pool
+        .stealers
+        .keys()
+        .filter(|&&core| core != affinity)
+        .map(|&core| (core, 0))
+        .collect::<Vec<(usize, usize)>>();
+
+    // Under contention, the run queues are stolen from in no particular order.
+    let mut mean_level = 0;
+    if let Ok(stats) = load_balancer::stats().try_read() {
+        for (core, size) in victims.iter_mut() {
+            *size = stats.smp_queues.get(core).copied().unwrap_or_default();
+        }
+        mean_level = stats.mean_level;
+    }
+
+    // Sort cores by their run queue sizes, in descending order,
+    // so we can pick up from the most overloaded queue.
+    victims.sort_by(|x, y| y.1.cmp(&x.1));
+
+    victims
+        .iter()
+        .map(|(core, _)| {
+            let stealer = &pool.stealers[core];
+            // Steal the mean amount to balance all queues considering incoming workloads
+            // Otherwise do an ignorant steal (which is going to be useless)
+            if mean_level > 0 {
+                stealer.steal_batch_and_pop_with_amount(local, mean_level)
+            } else {
+                stealer.steal_batch_and_pop(local)
+                // TODO: Set evacuation flag in thread_local
+            }
+        })
+        .collect()
+}
+
 pub(crate) fn stats_generator(affinity: usize, local: &Worker<LightProc>) {
     loop {
         if let Ok(mut stats) = load_balancer::stats().try_write() {
@@ -162,16 +220,27 @@ pub(crate) fn stats_generator(affinity: usize, local: &Worker<LightProc>) {
 
 pub(crate) fn main_loop(affinity: usize, local: Worker<LightProc>) {
     QUEUE.with(|queue| unsafe { *queue.get() = Some(local) });
+    AFFINITY.with(|current| current.set(Some(affinity)));
 
+    let mut tick: u32 = 0;
     loop {
         QUEUE.with(|queue| {
             let local = unsafe { (*queue.get()).as_ref().unwrap() };
             stats_generator(affinity, local);
         });
 
-        match fetch_proc(affinity) {
+        // The global run queue is checked first from time to time, so that the processes
+        // scheduled from outside of the workers aren't starved by the ones that keep
+        // rescheduling themselves onto the local run queue.
+        tick += 1;
+        let global_first = tick == GLOBAL_QUEUE_INTERVAL;
+        if global_first {
+            tick = 0;
+        }
+
+        match fetch(affinity, global_first) {
             Some(proc) => set_stack(proc.stack(), || proc.run()),
-            None => pool::get().sleepers.wait(),
+            None => pool::get().sleepers.wait(affinity),
         }
     }
 }
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use bastion_executor::{placement, pool};
+    use bastion_executor::{placement, pool, run::run};
+    use futures::future::join_all;
+    use lightproc::proc_stack::ProcStack;
 
     #[test]
     fn affinity_replacement() {
@@ -12,4 +14,37 @@ mod tests {
     fn pool_check() {
         pool::get();
     }
+
+    #[test]
+    fn pinned_procs() {
+        // Core ids aren't necessarily contiguous, so the last one
+        // might not be the number of cores minus one.
+        let core = placement::get_core_ids().unwrap().last().unwrap().id;
+        let stack = ProcStack::default().with_affinity(vec![core]);
+        let handle = pool::spawn(async { 42 }, stack);
+        assert_eq!(run(handle, ProcStack::default()), Some(42));
+
+        // Processes pinned to cores that don't exist run anywhere.
+        let stack = ProcStack::default().with_affinity(vec![usize::MAX]);
+        let handle = pool::spawn(async { 42 }, stack);
+        assert_eq!(run(handle, ProcStack::default()), Some(42));
+    }
+
+    #[test]
+    fn spawns_from_procs() {
+        // The processes spawned by other ones are pushed onto their worker's own run queue,
+        // from which the other workers steal.
+        let handle = pool::spawn(
+            async {
+                let handles = (0..1000)
+                    .map(|i| pool::spawn(async move { i }, ProcStack::default()))
+                    .collect::<Vec<_>>();
+
+                join_all(handles).await.into_iter().flatten().sum::<usize>()
+            },
+            ProcStack::default(),
+        );
+
+        assert_eq!(run(handle, ProcStack::default()), Some(499_500));
+    }
 }
@@ -0,0 +1,123 @@
+//!
+//! Human-readable addresses of the supervisors, children groups
+//! and elements of the system, derived from the supervision tree
+//! (eg. `bastion://sys/user/payment/workers/3`).
+//!
+//! Every supervisor and children group is referenced using its
+//! name if it has one, or its identifier otherwise, while the
+//! elements of a children group are referenced using their index
+//! in it (or their identifier).
+use crate::child_ref::ChildRef;
+use crate::children_ref::ChildrenRef;
+use crate::context::BastionId;
+use crate::supervisor::SupervisorRef;
+
+// The address of the system, which every other address starts
+// with.
+const ROOT: &str = "bastion://sys";
+
+// Returns the element of the children group at the given
+// address (if any).
+pub(crate) fn resolve(supervisors: Vec<SupervisorRef>, address: &str) -> Option<ChildRef> {
+    if !address.starts_with(ROOT) || !address[ROOT.len()..].starts_with('/') {
+        return None;
+    }
+
+    let segments = address[ROOT.len()..]
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let (elem, path) = segments.split_last()?;
+    let (group, path) = path.split_last()?;
+
+    let mut supervisors = supervisors;
+    let mut parent = None;
+    for segment in path {
+        let supervisor = supervisors
+            .into_iter()
+            .find(|supervisor| matches(segment, supervisor.name(), supervisor.id()))?;
+        supervisors = supervisor.supervisors();
+        parent = Some(supervisor);
+    }
+
+    let children = parent?
+        .children_groups()
+        .into_iter()
+        .find(|children| matches(group, children.name(), children.id()))?;
+
+    let elems = children.elems();
+    match elem.parse::<usize>() {
+        Ok(index) => elems.get(index).cloned(),
+        Err(_) => elems
+            .iter()
+            .find(|child_ref| child_ref.id().to_string() == *elem)
+            .cloned(),
+    }
+}
+
+// Returns the address of the supervisor, children group or
+// element with the given identifier (if any).
+pub(crate) fn address_of(supervisors: &[SupervisorRef], id: &BastionId) -> Option<String> {
+    find(supervisors, id, ROOT)
+}
+
+// Returns the first children group found with the given name
+// (if any).
+pub(crate) fn children_named(supervisors: &[SupervisorRef], name: &str) -> Option<ChildrenRef> {
+    for supervisor in supervisors {
+        let children = supervisor
+            .children_groups()
+            .into_iter()
+            .find(|children| children.name() == Some(name));
+        if children.is_some() {
+            return children;
+        }
+
+        if let Some(children) = children_named(&supervisor.supervisors(), name) {
+            return Some(children);
+        }
+    }
+
+    None
+}
+
+fn find(supervisors: &[SupervisorRef], id: &BastionId, parent: &str) -> Option<String> {
+    for supervisor in supervisors {
+        let address = format!("{}/{}", parent, segment(supervisor.name(), supervisor.id()));
+        if supervisor.id() == id {
+            return Some(address);
+        }
+
+        for children in supervisor.children_groups() {
+            let group = format!("{}/{}", address, segment(children.name(), children.id()));
+            if children.id() == id {
+                return Some(group);
+            }
+
+            let index = children
+                .elems()
+                .iter()
+                .position(|child_ref| child_ref.id() == id);
+            if let Some(index) = index {
+                return Some(format!("{}/{}", group, index));
+            }
+        }
+
+        if let Some(address) = find(&supervisor.supervisors(), id, &address) {
+            return Some(address);
+        }
+    }
+
+    None
+}
+
+fn segment(name: Option<&str>, id: &BastionId) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None => id.to_string(),
+    }
+}
+
+fn matches(segment: &str, name: Option<&str>, id: &BastionId) -> bool {
+    name == Some(segment) || id.to_string() == segment
+}
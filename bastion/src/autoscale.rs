@@ -0,0 +1,146 @@
+//!
+//! Autoscaling of children groups, growing or shrinking the
+//! number of elements of a group depending on how many messages
+//! are waiting in their mailboxes.
+//!
+//! A children group can be autoscaled using
+//! [`Children::with_autoscaler`].
+//!
+//! [`Children::with_autoscaler`]: ../children/struct.Children.html#method.with_autoscaler
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The configuration of the autoscaler of a children group,
+/// which regularly checks the mean number of messages waiting
+/// in the mailboxes of the group's elements and starts or
+/// retires elements (see [`ChildrenRef::scale_to`]) to keep it
+/// between two thresholds, within the given bounds.
+///
+/// When the mean depth is above the high threshold, the group
+/// grows to have enough elements for it to fall back to the
+/// threshold. When it is below the low threshold, the group
+/// retires one element.
+///
+/// The default thresholds are `1` and `16` messages per element,
+/// checked every second.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     // Between 2 and 16 elements, with at most 100 messages
+///     // waiting per element...
+///     children.with_autoscaler(
+///         Autoscaler::new(2, 16)
+///             .with_thresholds(10, 100)
+///             .with_interval(Duration::from_millis(500))
+///     )
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildrenRef::scale_to`]: ../children_ref/struct.ChildrenRef.html#method.scale_to
+pub struct Autoscaler {
+    min: usize,
+    max: usize,
+    low: usize,
+    high: usize,
+    interval: Duration,
+}
+
+impl Autoscaler {
+    /// Creates a new autoscaler keeping the number of elements of
+    /// a children group between the given bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The minimum number of elements (at least `1`).
+    /// * `max` - The maximum number of elements (at least `min`).
+    pub fn new(min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        let max = max.max(min);
+
+        Autoscaler {
+            min,
+            max,
+            low: 1,
+            high: 16,
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the mean number of messages waiting per element below
+    /// which the group shrinks and above which it grows.
+    ///
+    /// # Arguments
+    ///
+    /// * `low` - The mean depth below which an element is retired.
+    /// * `high` - The mean depth above which elements are started
+    ///     (at least `low` and `1`).
+    pub fn with_thresholds(mut self, low: usize, high: usize) -> Self {
+        self.low = low;
+        self.high = high.max(low).max(1);
+        self
+    }
+
+    /// Sets how often the mailboxes of the group's elements are
+    /// checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The duration between two checks.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the minimum number of elements.
+    pub fn min(&self) -> usize {
+        self.min
+    }
+
+    /// Returns the maximum number of elements.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Returns the mean depths below which the group shrinks and
+    /// above which it grows.
+    pub fn thresholds(&self) -> (usize, usize) {
+        (self.low, self.high)
+    }
+
+    /// Returns how often the mailboxes of the group's elements are
+    /// checked.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    // Returns the number of elements a group with `elems`
+    // elements and `depth` messages waiting in their mailboxes
+    // should have.
+    pub(crate) fn target(&self, elems: usize, depth: usize) -> usize {
+        let elems = elems.max(1);
+        let target = if depth > self.high * elems {
+            // Rounded up so that the mean depth falls to the
+            // threshold.
+            (depth + self.high - 1) / self.high
+        } else if depth < self.low * elems {
+            elems - 1
+        } else {
+            elems
+        };
+
+        target.max(self.min).min(self.max)
+    }
+}
@@ -1,15 +1,20 @@
+use crate::address;
 use crate::broadcast::{Broadcast, Parent};
+use crate::child_ref::ChildRef;
 use crate::children::Children;
 use crate::children_ref::ChildrenRef;
 use crate::config::Config;
 use crate::context::{BastionContext, BastionId};
 use crate::envelope::Envelope;
-use crate::message::{BastionMessage, Message};
+use crate::message::{BastionMessage, Message, Ready};
 use crate::path::BastionPathElement;
-use crate::supervisor::{Supervisor, SupervisorRef};
+use crate::supervisor::{Supervisor, SupervisorRef, TerminationReason};
 use crate::system::SYSTEM;
+use crate::tree::SupervisionTree;
 
+use bastion_executor::blocking;
 use core::future::Future;
+use lightproc::prelude::*;
 
 use std::fmt::{self, Debug, Formatter};
 
@@ -274,7 +279,7 @@ impl Bastion {
         let msg = BastionMessage::deploy_supervisor(supervisor);
         let envelope = Envelope::new(msg, SYSTEM.path().clone(), SYSTEM.sender().clone());
         trace!("Bastion: Sending envelope: {:?}", envelope);
-        SYSTEM.sender().unbounded_send(envelope).map_err(|_| ())?;
+        SYSTEM.sender().try_send(envelope).map_err(|_| ())?;
 
         Ok(supervisor_ref)
     }
@@ -290,6 +295,14 @@ impl Bastion {
     /// Note that the "system supervisor" is a supervisor created
     /// by the system at startup.
     ///
+    /// [`Children`] is a builder: every option of the group (its
+    /// future, redundancy, name, mailbox, rate limit, etc.) is
+    /// set by chaining its `with_*` methods in `init`, so that
+    /// options can be added without changing this method's
+    /// signature. A group can also be configured outside of a
+    /// closure using [`Children::builder`] and then deployed with
+    /// [`Bastion::children_from`].
+    ///
     /// # Arguments
     ///
     /// * `init` - The closure taking the new [`Children`] as an
@@ -327,6 +340,8 @@ impl Bastion {
     ///
     /// [`Children`]: children/struct.Children.html
     /// [`ChildrenRef`]: children/struct.ChildrenRef.html
+    /// [`Children::builder`]: children/struct.Children.html#method.builder
+    /// [`Bastion::children_from`]: #method.children_from
     pub fn children<C>(init: C) -> Result<ChildrenRef, ()>
     where
         C: FnOnce(Children) -> Children,
@@ -335,6 +350,151 @@ impl Bastion {
         SYSTEM.supervisor().children(init)
     }
 
+    /// Sends a children group created using [`Children::builder`]
+    /// and configured by chaining its `with_*` methods to the
+    /// system supervisor for it to start supervising it.
+    ///
+    /// This method returns a [`ChildrenRef`] referencing the
+    /// children group if it succeeded, or `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `children` - The configured children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children = Children::builder()
+    ///     .with_redundancy(8)
+    ///     .with_name("workers")
+    ///     .with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Send and receive messages...
+    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
+    ///             // ...and return `Ok(())` or `Err(())` when you are done...
+    ///             Ok(())
+    ///         }
+    ///     });
+    ///
+    /// let children_ref: ChildrenRef = Bastion::children_from(children)
+    ///     .expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::builder`]: children/struct.Children.html#method.builder
+    /// [`ChildrenRef`]: children/struct.ChildrenRef.html
+    pub fn children_from(children: Children) -> Result<ChildrenRef, ()> {
+        debug!("Bastion: Deploying children group.");
+        SYSTEM.supervisor().children_from(children)
+    }
+
+    /// Creates a new named [`Children`], passes it through the
+    /// specified `init` closure and then sends it to the system
+    /// supervisor for it to start supervising it.
+    ///
+    /// The name shows up in the group's `Debug` output and in
+    /// the [`tree`], can be used in its address (see
+    /// [`actor_at`]) and to retrieve the group using
+    /// [`children_by_name`].
+    /// This is the same as calling [`Children::with_name`] in
+    /// the closure passed to [`Bastion::children`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the children group.
+    /// * `init` - The closure taking the new [`Children`] as an
+    ///     argument and returning it once configured.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref: ChildrenRef = Bastion::children_named("resizer", |children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // ...
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// assert_eq!(children_ref.name(), Some("resizer"));
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children`]: children/struct.Children.html
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    /// [`Bastion::children`]: #method.children
+    /// [`tree`]: #method.tree
+    /// [`actor_at`]: #method.actor_at
+    /// [`children_by_name`]: #method.children_by_name
+    pub fn children_named<N, C>(name: N, init: C) -> Result<ChildrenRef, ()>
+    where
+        N: Into<String>,
+        C: FnOnce(Children) -> Children,
+    {
+        let name = name.into();
+        debug!("Bastion: Creating children group: {}", name);
+        SYSTEM
+            .supervisor()
+            .children(move |children| init(children.with_name(name)))
+    }
+
+    /// Returns a [`ChildrenRef`] referencing the first children
+    /// group found with the given name (see
+    /// [`Children::with_name`]), looking through the whole
+    /// supervision tree, or `None` if there isn't any.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children_named("resizer", |children| children)
+    ///     .expect("Couldn't create the children group.");
+    ///
+    /// Bastion::start();
+    ///
+    /// if let Some(children_ref) = Bastion::children_by_name("resizer") {
+    ///     children_ref.broadcast("Hello!").ok();
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: children_ref/struct.ChildrenRef.html
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    pub fn children_by_name(name: &str) -> Option<ChildrenRef> {
+        debug!("Bastion: Looking up children group: {}", name);
+        address::children_named(&SYSTEM.supervisors(), name)
+    }
+
     /// Creates a new [`Children`] which will have the given closure
     /// as action and then sends it to the system's default supervisor.
     ///
@@ -380,6 +540,51 @@ impl Bastion {
         Bastion::children(|ch| ch.with_redundancy(1).with_exec(action))
     }
 
+    /// Runs the given closure on the blocking thread pool,
+    /// returning a handle that can be awaited to get its
+    /// result (or `None` if it panicked).
+    ///
+    /// This should be used by children that need to do blocking IO
+    /// or heavy computations, which would otherwise stall the
+    /// executor's threads and thus other children.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure to run on the blocking thread pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::spawn(|ctx: BastionContext| {
+    ///     async move {
+    ///         let sum = Bastion::spawn_blocking(|| (0..1_000u64).sum::<u64>())
+    ///             .await
+    ///             .ok_or(())?;
+    ///         assert_eq!(sum, 499_500);
+    ///
+    ///         Ok(())
+    ///     }
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn spawn_blocking<F, R>(f: F) -> RecoverableHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        debug!("Bastion: Spawning blocking task.");
+        blocking::spawn_blocking(async move { f() }, ProcStack::default())
+    }
+
     /// Sends a message to the system which will then send it to all
     /// the root-level supervisors and their supervised children and
     /// supervisors, etc.
@@ -433,8 +638,8 @@ impl Bastion {
         // FIXME: panics?
         SYSTEM
             .sender()
-            .unbounded_send(envelope)
-            .map_err(|err| err.into_inner().into_msg().unwrap())
+            .try_send(envelope)
+            .map_err(|env| env.into_msg().unwrap())
     }
 
     /// Sends a message to the system to tell it to start
@@ -465,7 +670,7 @@ impl Bastion {
         let envelope = Envelope::from_dead_letters(msg);
         trace!("Bastion: Sending envelope: {:?}", envelope);
         // FIXME: Err(Error)
-        SYSTEM.sender().unbounded_send(envelope).ok();
+        SYSTEM.sender().try_send(envelope).ok();
     }
 
     /// Sends a message to the system to tell it to stop
@@ -496,7 +701,7 @@ impl Bastion {
         let envelope = Envelope::from_dead_letters(msg);
         trace!("Bastion: Sending envelope: {:?}", envelope);
         // FIXME: Err(Error)
-        SYSTEM.sender().unbounded_send(envelope).ok();
+        SYSTEM.sender().try_send(envelope).ok();
     }
 
     /// Sends a message to the system to tell it to kill every
@@ -526,7 +731,7 @@ impl Bastion {
         let envelope = Envelope::from_dead_letters(msg);
         trace!("Bastion: Sending envelope: {:?}", envelope);
         // FIXME: Err(Error)
-        SYSTEM.sender().unbounded_send(envelope).ok();
+        SYSTEM.sender().try_send(envelope).ok();
 
         // FIXME: panics
         let mut system = SYSTEM.handle().lock().wait().unwrap();
@@ -535,7 +740,7 @@ impl Bastion {
             system.cancel();
         }
 
-        SYSTEM.notify_stopped();
+        SYSTEM.notify_stopped(TerminationReason::Killed);
     }
 
     /// Blocks the current thread until the system is stopped
@@ -566,8 +771,229 @@ impl Bastion {
     /// [`Bastion::stop()`]: #method.stop
     /// [`Bastion::kill()`]: #method.kill
     pub fn block_until_stopped() {
+        Bastion::block_until_stopped_with_reason();
+    }
+
+    /// Blocks the current thread until the system is stopped
+    /// like [`Bastion::block_until_stopped`], returning why it
+    /// was.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bastion::prelude::*;
+    ///
+    /// fn main() {
+    ///     Bastion::init();
+    ///
+    ///     // Use bastion, spawn children and supervisors...
+    ///
+    ///     Bastion::start();
+    ///     // Send messages to children and/or do some
+    ///     // work...
+    ///
+    ///     # Bastion::stop();
+    ///     let reason: TerminationReason = Bastion::block_until_stopped_with_reason();
+    ///     // The system is now stopped, either normally or
+    ///     // because it was killed...
+    /// }
+    /// ```
+    ///
+    /// [`Bastion::block_until_stopped`]: #method.block_until_stopped
+    pub fn block_until_stopped_with_reason() -> TerminationReason {
         debug!("Bastion: Blocking until system is stopped.");
-        SYSTEM.wait_until_stopped();
+        SYSTEM.wait_until_stopped()
+    }
+
+    /// Returns a [`Ready`] resolving once all the elements of all
+    /// the children groups of the system told that they finished
+    /// initializing (using [`BastionContext::notify_started`]),
+    /// allowing to wait for them after calling [`Bastion::start()`]
+    /// instead of racing against the ones still initializing.
+    ///
+    /// Note that, like [`Bastion::tree`], this relies on what the
+    /// supervisors shared when they last handled a message, so it
+    /// might not wait for the children groups that were just
+    /// created (see [`ChildrenRef::ready`] to wait for them).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Initializing...
+    ///             ctx.notify_started().expect("Couldn't send the message.");
+    ///
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::start();
+    ///
+    /// // Waiting for every element to be initialized...
+    /// run!(Bastion::ready()).expect("A children group was dropped.");
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Ready`]: message/struct.Ready.html
+    /// [`BastionContext::notify_started`]: context/struct.BastionContext.html#method.notify_started
+    /// [`Bastion::start()`]: #method.start
+    /// [`Bastion::tree`]: #method.tree
+    /// [`ChildrenRef::ready`]: children_ref/struct.ChildrenRef.html#method.ready
+    pub fn ready() -> Ready {
+        debug!("Bastion: Waiting for the children groups to be ready.");
+        let mut readies = Vec::new();
+        let mut supervisors = SYSTEM.supervisors();
+        while let Some(supervisor) = supervisors.pop() {
+            // The groups that already terminated aren't waited for.
+            readies.extend(
+                supervisor
+                    .children_groups()
+                    .iter()
+                    .filter_map(|children_ref| children_ref.ready().ok()),
+            );
+            supervisors.extend(supervisor.supervisors());
+        }
+
+        Ready::all(readies)
+    }
+
+    /// Returns a snapshot of the whole supervision tree, with the
+    /// identifiers, states and restart counts of its supervisors
+    /// and children groups, and the identifiers of the elements of
+    /// the children groups.
+    ///
+    /// Note that the supervisors only update what they share with
+    /// the snapshots when they handle a message, so the snapshot
+    /// might not contain the supervisors and children groups that
+    /// were just created.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::start();
+    ///
+    /// let tree: SupervisionTree = Bastion::tree();
+    /// println!("{}", tree);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn tree() -> SupervisionTree {
+        debug!("Bastion: Taking a snapshot of the supervision tree.");
+        SupervisionTree::new(SYSTEM.supervisors())
+    }
+
+    /// Returns a [`ChildRef`] referencing the element at the given
+    /// address, if it exists.
+    ///
+    /// Addresses are derived from the supervision tree: every
+    /// supervisor and children group is referenced using its name
+    /// (see [`Supervisor::with_name`] and [`Children::with_name`])
+    /// or its identifier if it doesn't have one, and the elements
+    /// of a children group are referenced using their index in
+    /// [`ChildrenRef::elems`] (or their identifier). The children
+    /// groups created using [`Bastion::children`] are supervised
+    /// by the system supervisor, named `user`.
+    ///
+    /// Like [`tree`], this uses what the supervisors share with
+    /// the snapshots, so the supervisors and children groups that
+    /// were just created might not be found yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the element, like
+    ///     `bastion://sys/payment/workers/3` or
+    ///     `bastion://sys/user/workers/0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_name("payment").children(|children| {
+    ///         children.with_name("workers").with_redundancy(4)
+    ///     })
+    /// }).expect("Couldn't create the supervisor.");
+    ///
+    /// Bastion::start();
+    ///
+    /// if let Some(child_ref) = Bastion::actor_at("bastion://sys/payment/workers/3") {
+    ///     child_ref.tell_anonymously("Hello!").ok();
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: child_ref/struct.ChildRef.html
+    /// [`Supervisor::with_name`]: supervisor/struct.Supervisor.html#method.with_name
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    /// [`ChildrenRef::elems`]: children_ref/struct.ChildrenRef.html#method.elems
+    /// [`Bastion::children`]: #method.children
+    /// [`tree`]: #method.tree
+    pub fn actor_at(address: &str) -> Option<ChildRef> {
+        debug!("Bastion: Resolving address: {}", address);
+        address::resolve(SYSTEM.supervisors(), address)
+    }
+
+    /// Returns the address of the supervisor, children group or
+    /// element with the given identifier, if it exists (see
+    /// [`actor_at`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the supervisor, children group
+    ///     or element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_name("workers")
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::start();
+    ///
+    /// // Eg. "bastion://sys/user/workers"...
+    /// let address: Option<String> = Bastion::address_of(children_ref.id());
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`actor_at`]: #method.actor_at
+    pub fn address_of(id: &BastionId) -> Option<String> {
+        address::address_of(&SYSTEM.supervisors(), id)
     }
 }
 
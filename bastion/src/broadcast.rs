@@ -1,19 +1,74 @@
+use crate::channel::{ChannelFactory, ChannelReceiver, ChannelSender};
 use crate::children_ref::ChildrenRef;
 use crate::context::BastionId;
 use crate::envelope::Envelope;
+use crate::mailbox::MailboxStatus;
 use crate::message::BastionMessage;
 use crate::path::{BastionPath, BastionPathElement};
-use crate::supervisor::SupervisorRef;
+use crate::supervisor::{SupervisorRef, TerminationReason};
 use crate::system::SYSTEM;
 use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use futures::prelude::*;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-pub(crate) type Sender = UnboundedSender<Envelope>;
-pub(crate) type Receiver = UnboundedReceiver<Envelope>;
+#[derive(Debug, Clone)]
+// The sending half of the two "lanes" every element receives its
+// messages through: system messages (`Start`, `Stop`, `Kill`,
+// `SuperviseWith`, etc.) are sent through the system lane while
+// user messages are sent through the user lane.
+//
+// The system lane is always unbounded while the user lane uses
+// the channel set for the element (see `Children::with_channel`),
+// and is bounded by the element's mailbox when its overflow
+// policy requires it (see `Broadcast::bind_mailbox`).
+pub(crate) struct Sender {
+    system: UnboundedSender<Envelope>,
+    user: Arc<dyn ChannelSender<Envelope>>,
+    mailbox: Option<Arc<MailboxStatus>>,
+}
+
+#[derive(Debug)]
+// The receiving half of the two lanes, which always yields the
+// messages of the system lane first so that a deep user lane
+// can't delay a `Stop` or `Kill`.
+pub(crate) struct Receiver {
+    system: UnboundedReceiver<Envelope>,
+    user: Box<dyn ChannelReceiver<Envelope>>,
+    // Whether the user lane is paused, in which case only the
+    // messages of the system lane are yielded.
+    user_paused: bool,
+    mailbox: Option<Arc<MailboxStatus>>,
+    // Whether the last message that was yielded still has room
+    // reserved for it in the element's mailbox.
+    reserved: bool,
+}
+
+pub(crate) fn channel() -> (Sender, Receiver) {
+    channel_with(&ChannelFactory::default())
+}
+
+pub(crate) fn channel_with(channel: &ChannelFactory) -> (Sender, Receiver) {
+    let (system_sender, system_recver) = mpsc::unbounded();
+    let (user_sender, user_recver) = channel.create();
+
+    let sender = Sender {
+        system: system_sender,
+        user: Arc::from(user_sender),
+        mailbox: None,
+    };
+    let recver = Receiver {
+        system: system_recver,
+        user: user_recver,
+        user_paused: false,
+        mailbox: None,
+        reserved: false,
+    };
+
+    (sender, recver)
+}
 
 #[derive(Debug)]
 pub(crate) struct Broadcast {
@@ -50,11 +105,31 @@ impl Parent {
 
 impl Broadcast {
     pub(crate) fn new(parent: Parent, element: BastionPathElement) -> Self {
-        let (sender, recver) = mpsc::unbounded();
+        Broadcast::with_channel(parent, element, &ChannelFactory::default())
+    }
+
+    pub(crate) fn with_channel(
+        parent: Parent,
+        element: BastionPathElement,
+        channel: &ChannelFactory,
+    ) -> Self {
+        let (sender, recver) = channel_with(channel);
         let children = FxHashMap::default();
+        let path = Self::child_path(&parent, element);
+
+        Broadcast {
+            parent,
+            sender,
+            recver,
+            path,
+            children,
+        }
+    }
 
-        let parent_path: BastionPath = match &parent {
-            Parent::None | Parent::System => BastionPath::root(),
+    fn child_path(parent: &Parent, element: BastionPathElement) -> Arc<BastionPath> {
+        let parent_path: BastionPath = match parent {
+            Parent::None => return Arc::new(BastionPath::detached(element)),
+            Parent::System => BastionPath::root(),
             Parent::Supervisor(sv_ref) => BastionPath::clone(sv_ref.path()),
             Parent::Children(ch_ref) => BastionPath::clone(ch_ref.path()),
         };
@@ -63,22 +138,40 @@ impl Broadcast {
         let path = parent_path
             .append(element)
             .expect("Can't append path in Broadcast::new");
-        let path = Arc::new(path);
 
-        Broadcast {
-            parent,
-            sender,
-            recver,
-            path,
-            children,
-        }
+        Arc::new(path)
+    }
+
+    // Moves this broadcast under another parent (eg. when the
+    // children group of an element is restarted), keeping its
+    // identifier and channel.
+    pub(crate) fn reparent(&mut self, parent: Parent) {
+        // FIXME: panics?
+        let element = self.path.elem().clone().unwrap();
+        self.path = Self::child_path(&parent, element);
+        self.parent = parent;
+    }
+
+    // Makes the user messages sent to the element reserve room
+    // in its mailbox (see `MailboxStatus::try_reserve`), which
+    // needs to be done before its sender gets cloned.
+    pub(crate) fn bind_mailbox(&mut self, status: Arc<MailboxStatus>) {
+        self.sender.mailbox = Some(status.clone());
+        self.recver.mailbox = Some(status);
+    }
+
+    // Drops the system messages that were sent to the element
+    // after it stopped (like the `Kill` sent when its group was
+    // restarted) so that it doesn't handle them once relaunched.
+    pub(crate) fn drop_system_msgs(&mut self) {
+        while let Some(Some(_)) = self.recver.system.next().now_or_never() {}
     }
 
     pub(crate) fn new_root(parent: Parent) -> Self {
         // FIXME
         assert!(parent.is_none() || parent.is_system());
 
-        let (sender, recver) = mpsc::unbounded();
+        let (sender, recver) = channel();
         let children = FxHashMap::default();
         let path = BastionPath::root();
         let path = Arc::new(path);
@@ -108,6 +201,16 @@ impl Broadcast {
         &self.parent
     }
 
+    // Stops yielding user messages (while still yielding system
+    // messages) until `resume_user_lane` gets called.
+    pub(crate) fn pause_user_lane(&mut self) {
+        self.recver.user_paused = true;
+    }
+
+    pub(crate) fn resume_user_lane(&mut self) {
+        self.recver.user_paused = false;
+    }
+
     pub(crate) fn register(&mut self, child: &Self) {
         self.children
             .insert(child.id().clone(), child.sender.clone());
@@ -153,19 +256,13 @@ impl Broadcast {
         self.clear_children();
     }
 
-    pub(crate) fn stopped(&mut self) {
-        self.stop_children();
-
-        let msg = BastionMessage::stopped(self.id().clone());
-        let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
-        // FIXME: Err(msg)
-        self.send_parent(env).ok();
-    }
-
-    pub(crate) fn faulted(&mut self) {
-        self.kill_children();
+    pub(crate) fn terminated(&mut self, reason: TerminationReason) {
+        match reason {
+            TerminationReason::Normal | TerminationReason::Custom(_) => self.stop_children(),
+            TerminationReason::Killed | TerminationReason::Fault(_) => self.kill_children(),
+        }
 
-        let msg = BastionMessage::faulted(self.id().clone());
+        let msg = BastionMessage::termination(self.id().clone(), reason);
         let env = Envelope::new(msg, self.path.clone(), self.sender.clone());
         // FIXME: Err(msg)
         self.send_parent(env).ok();
@@ -179,23 +276,45 @@ impl Broadcast {
         // FIXME: Err if None?
         if let Some(child) = self.children.get(id) {
             // FIXME: handle errors
-            child.unbounded_send(envelope).ok();
+            child.try_send(envelope).ok();
         }
     }
 
     pub(crate) fn send_children(&self, env: Envelope) {
-        for child in self.children.values() {
+        self.send_children_except(env, &FxHashSet::default());
+    }
+
+    // Sends the message to the children that aren't excluded
+    // (eg. because they are standbys).
+    pub(crate) fn send_children_except(&self, env: Envelope, excluded: &FxHashSet<BastionId>) {
+        let mut children = self
+            .children
+            .iter()
+            .filter(|(id, _)| !excluded.contains(id))
+            .map(|(_, child)| child)
+            .peekable();
+        while let Some(child) = children.next() {
+            if children.peek().is_none() {
+                // The last child gets the original envelope.
+                // FIXME: handle errors
+                child.try_send(env).ok();
+                return;
+            }
+
+            // Cloning an envelope only clones the pointers to its
+            // (shared) message and signature, the only allocations
+            // being the ones of the children's channels.
             // FIXME: Err(Error) if None
             if let Some(env) = env.try_clone() {
                 // FIXME: handle errors
-                child.unbounded_send(env).ok();
+                child.try_send(env).ok();
             }
         }
     }
 
     pub(crate) fn send_self(&self, env: Envelope) {
         // FIXME: handle errors
-        self.sender.unbounded_send(env).ok();
+        self.sender.try_send(env).ok();
     }
 }
 
@@ -234,18 +353,87 @@ impl Parent {
 
     fn send(&self, env: Envelope) -> Result<(), Envelope> {
         match self {
-            // FIXME
-            Parent::None => unimplemented!(),
-            Parent::System => SYSTEM
-                .sender()
-                .unbounded_send(env)
-                .map_err(|err| err.into_inner()),
+            // Groups built using `Children::builder` have no
+            // parent until they are deployed.
+            Parent::None => Err(env),
+            Parent::System => SYSTEM.sender().try_send(env),
             Parent::Supervisor(supervisor) => supervisor.send(env),
             Parent::Children(children) => children.send(env),
         }
     }
 }
 
+impl Sender {
+    pub(crate) fn try_send(&self, env: Envelope) -> Result<(), Envelope> {
+        if env.msg.is_system() {
+            return self
+                .system
+                .unbounded_send(env)
+                .map_err(|err| err.into_inner());
+        }
+
+        if let Some(mailbox) = &self.mailbox {
+            if !mailbox.try_reserve() {
+                return Err(env);
+            }
+        }
+
+        self.send_reserved(env)
+    }
+
+    // Sends a user message for which room was already reserved
+    // in the element's mailbox.
+    pub(crate) fn send_reserved(&self, env: Envelope) -> Result<(), Envelope> {
+        self.user.try_send(env).map_err(|env| {
+            if let Some(mailbox) = &self.mailbox {
+                mailbox.release();
+            }
+
+            env
+        })
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.system.is_closed() || self.user.is_closed()
+    }
+}
+
+impl Stream for Receiver {
+    type Item = Envelope;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<Self::Item>> {
+        let recver = self.get_mut();
+
+        // The room reserved for the last user message is only
+        // given back once the element handled it (this being
+        // called again), at which point it is counted as part of
+        // the element's mailbox.
+        if recver.reserved {
+            recver.reserved = false;
+            if let Some(mailbox) = &recver.mailbox {
+                mailbox.release();
+            }
+        }
+
+        // The system lane is always polled first, the user lane
+        // only being polled when it has nothing to yield.
+        if let Poll::Ready(Some(env)) = Pin::new(&mut recver.system).poll_next(ctx) {
+            return Poll::Ready(Some(env));
+        }
+
+        if recver.user_paused {
+            return Poll::Pending;
+        }
+
+        let poll = recver.user.poll_recv(ctx);
+        if let Poll::Ready(Some(_)) = poll {
+            recver.reserved = true;
+        }
+
+        poll
+    }
+}
+
 impl Stream for Broadcast {
     type Item = Envelope;
 
@@ -256,11 +444,10 @@ impl Stream for Broadcast {
 
 #[cfg(test)]
 mod tests {
-    use super::{BastionMessage, Broadcast, Parent};
+    use super::{channel, BastionMessage, Broadcast, Parent};
     use crate::context::{BastionId, NIL_ID};
     use crate::envelope::Envelope;
     use crate::path::{BastionPath, BastionPathElement};
-    use futures::channel::mpsc;
     use futures::executor;
     use futures::poll;
     use futures::prelude::*;
@@ -284,7 +471,7 @@ mod tests {
         let msg = BastionMessage::start();
 
         // need manual construction because SYSTEM is not running in this test
-        let (sender, _) = mpsc::unbounded();
+        let (sender, _) = channel();
         let env = Envelope::new(
             msg,
             Arc::new(
@@ -334,4 +521,55 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn send_detached_parent() {
+        let bcast = Broadcast::new(
+            Parent::none(),
+            BastionPathElement::Children(BastionId::new()),
+        );
+
+        let msg = BastionMessage::start();
+        let env = Envelope::new(msg, bcast.path().clone(), bcast.sender().clone());
+        assert!(bcast.send_parent(env).is_err());
+    }
+
+    #[test]
+    fn system_lane_first() {
+        let mut bcast = Broadcast::new_root(Parent::System);
+        let path = bcast.path().clone();
+        let sender = bcast.sender().clone();
+
+        for i in 0..4 {
+            let msg = BastionMessage::tell(i);
+            let env = Envelope::new(msg, path.clone(), sender.clone());
+            bcast.send_self(env);
+        }
+
+        let msg = BastionMessage::stop();
+        let env = Envelope::new(msg, path.clone(), sender.clone());
+        bcast.send_self(env);
+
+        executor::block_on(async {
+            match poll!(bcast.next()) {
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::Stop,
+                    ..
+                })) => (),
+                _ => panic!(),
+            }
+
+            for i in 0..4 {
+                match poll!(bcast.next()) {
+                    Poll::Ready(Some(Envelope {
+                        msg: BastionMessage::Message(msg),
+                        ..
+                    })) => assert_eq!(msg.downcast::<i32>().ok(), Some(i)),
+                    _ => panic!(),
+                }
+            }
+
+            assert!(poll!(bcast.next()).is_pending());
+        });
+    }
 }
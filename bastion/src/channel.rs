@@ -0,0 +1,387 @@
+//!
+//! Channels are carrying the messages sent to the elements of
+//! children groups until they receive them.
+//!
+//! The channel used by the elements of a children group can be
+//! changed using [`Children::with_channel`], allowing to trade
+//! latency, throughput and memory usage (eg. by bounding the
+//! number of messages waiting to be received).
+//!
+//! Note that the system messages (eg. to stop or kill the
+//! elements) are always sent through an unbounded channel, and
+//! received before the messages sent through the elements'
+//! channel (see [`ShutdownMode`]).
+//!
+//! [`Children::with_channel`]: ../children/struct.Children.html#method.with_channel
+//! [`ShutdownMode`]: ../children/enum.ShutdownMode.html
+use crate::envelope::Envelope;
+use crossbeam_queue::{ArrayQueue, PushError};
+use futures::channel::mpsc;
+use futures::prelude::*;
+use futures::task::AtomicWaker;
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// A trait that every channel needs to implement, creating the
+/// sending and receiving halves of new channels.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use bastion::channel::{ChannelReceiver, ChannelSender};
+/// #
+/// // A channel which uses `BoundedChannel` with a capacity
+/// // depending on an environment variable...
+/// #[derive(Debug)]
+/// struct ConfigurableChannel;
+///
+/// impl Channel for ConfigurableChannel {
+///     fn create<T: Send + 'static>(
+///         &self,
+///     ) -> (Box<dyn ChannelSender<T>>, Box<dyn ChannelReceiver<T>>) {
+///         let capacity = std::env::var("CHANNEL_CAPACITY")
+///             .ok()
+///             .and_then(|capacity| capacity.parse().ok())
+///             .unwrap_or(1024);
+///
+///         BoundedChannel::with_capacity(capacity).create()
+///     }
+/// }
+///
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// // ...which will be used by every element of a children group.
+/// Bastion::children(|children| {
+///     children.with_channel(ConfigurableChannel)
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub trait Channel: Send + Sync + Debug + 'static {
+    /// Creates a new channel, returning its sending and
+    /// receiving halves.
+    fn create<T: Send + 'static>(&self)
+        -> (Box<dyn ChannelSender<T>>, Box<dyn ChannelReceiver<T>>);
+}
+
+/// The sending half of a channel created by a [`Channel`], which
+/// is shared by all the senders of the channel.
+///
+/// [`Channel`]: trait.Channel.html
+pub trait ChannelSender<T>: Send + Sync + Debug {
+    /// Sends an item through the channel without waiting.
+    ///
+    /// This method returns `()` if the item was sent, or
+    /// `Err(item)` if it couldn't be (eg. because the channel is
+    /// full or because its receiving half was dropped).
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item to send.
+    fn try_send(&self, item: T) -> Result<(), T>;
+
+    /// Returns whether the receiving half of the channel was
+    /// dropped, in which case no item can be sent anymore.
+    fn is_closed(&self) -> bool;
+}
+
+/// The receiving half of a channel created by a [`Channel`].
+///
+/// [`Channel`]: trait.Channel.html
+pub trait ChannelReceiver<T>: Send + Debug {
+    /// Attempts to receive the next item sent through the
+    /// channel, registering the current task to be woken up
+    /// when one is sent if there is none yet.
+    ///
+    /// This method returns `Poll::Ready(None)` once the sending
+    /// half of the channel was dropped and all its items were
+    /// received.
+    fn poll_recv(&mut self, ctx: &mut Context) -> Poll<Option<T>>;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// The channel used by default by the elements of children
+/// groups, which can carry an unlimited amount of messages.
+pub struct UnboundedChannel;
+
+#[derive(Debug, Clone, Copy)]
+/// A bounded channel backed by a lock-free ring buffer, which
+/// refuses the messages sent to it while it is full (eg.
+/// [`ChildRef::tell_anonymously`] returns `Err(msg)`).
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     // Up to 1024 messages will be waiting for every element to
+///     // receive them...
+///     children.with_channel(BoundedChannel::with_capacity(1024))
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildRef::tell_anonymously`]: ../children/struct.ChildRef.html#method.tell_anonymously
+pub struct BoundedChannel {
+    capacity: usize,
+}
+
+struct UnboundedSender<T>(mpsc::UnboundedSender<T>);
+
+struct UnboundedReceiver<T>(mpsc::UnboundedReceiver<T>);
+
+// The state of a bounded channel, shared between its halves.
+struct Bounded<T> {
+    items: ArrayQueue<T>,
+    // The receiving half's task, woken up when an item is sent
+    // or when the sending half is dropped.
+    waker: AtomicWaker,
+    // Whether the sending half was dropped.
+    disconnected: AtomicBool,
+    // Whether the receiving half was dropped.
+    closed: AtomicBool,
+}
+
+struct BoundedSender<T>(Arc<Bounded<T>>);
+
+struct BoundedReceiver<T>(Arc<Bounded<T>>);
+
+type Halves<T> = (Box<dyn ChannelSender<T>>, Box<dyn ChannelReceiver<T>>);
+
+#[derive(Clone)]
+pub(crate) struct ChannelFactory(Arc<dyn Fn() -> Halves<Envelope> + Send + Sync>);
+
+impl Channel for UnboundedChannel {
+    fn create<T: Send + 'static>(
+        &self,
+    ) -> (Box<dyn ChannelSender<T>>, Box<dyn ChannelReceiver<T>>) {
+        let (sender, recver) = mpsc::unbounded();
+
+        (
+            Box::new(UnboundedSender(sender)),
+            Box::new(UnboundedReceiver(recver)),
+        )
+    }
+}
+
+impl BoundedChannel {
+    /// Creates a new `BoundedChannel` whose channels can carry
+    /// up to `capacity` messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of messages that the
+    ///     channels will be able to carry (it needs to be greater
+    ///     than `0`).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is `0`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+
+        BoundedChannel { capacity }
+    }
+
+    /// Returns the maximum number of messages that the channels
+    /// can carry.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl Channel for BoundedChannel {
+    fn create<T: Send + 'static>(
+        &self,
+    ) -> (Box<dyn ChannelSender<T>>, Box<dyn ChannelReceiver<T>>) {
+        let bounded = Arc::new(Bounded {
+            items: ArrayQueue::new(self.capacity),
+            waker: AtomicWaker::new(),
+            disconnected: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+        });
+
+        (
+            Box::new(BoundedSender(bounded.clone())),
+            Box::new(BoundedReceiver(bounded)),
+        )
+    }
+}
+
+impl<T: Send + 'static> ChannelSender<T> for UnboundedSender<T> {
+    fn try_send(&self, item: T) -> Result<(), T> {
+        self.0.unbounded_send(item).map_err(|err| err.into_inner())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+}
+
+impl<T: Send + 'static> ChannelReceiver<T> for UnboundedReceiver<T> {
+    fn poll_recv(&mut self, ctx: &mut Context) -> Poll<Option<T>> {
+        Pin::new(&mut self.0).poll_next(ctx)
+    }
+}
+
+impl<T: Send + 'static> ChannelSender<T> for BoundedSender<T> {
+    fn try_send(&self, item: T) -> Result<(), T> {
+        if self.0.closed.load(Ordering::Acquire) {
+            return Err(item);
+        }
+
+        self.0.items.push(item).map_err(|PushError(item)| item)?;
+        self.0.waker.wake();
+
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.closed.load(Ordering::Acquire)
+    }
+}
+
+impl<T: Send + 'static> ChannelReceiver<T> for BoundedReceiver<T> {
+    fn poll_recv(&mut self, ctx: &mut Context) -> Poll<Option<T>> {
+        if let Ok(item) = self.0.items.pop() {
+            return Poll::Ready(Some(item));
+        }
+
+        self.0.waker.register(ctx.waker());
+
+        // An item might have been sent (or the sending half
+        // dropped) before the waker was registered.
+        match self.0.items.pop() {
+            Ok(item) => Poll::Ready(Some(item)),
+            Err(_) if self.0.disconnected.load(Ordering::Acquire) => Poll::Ready(None),
+            Err(_) => Poll::Pending,
+        }
+    }
+}
+
+impl ChannelFactory {
+    pub(crate) fn new<C: Channel>(channel: C) -> Self {
+        let factory = Arc::new(move || channel.create::<Envelope>());
+
+        ChannelFactory(factory)
+    }
+
+    pub(crate) fn create(&self) -> Halves<Envelope> {
+        (self.0)()
+    }
+}
+
+impl<T> Drop for BoundedSender<T> {
+    fn drop(&mut self) {
+        self.0.disconnected.store(true, Ordering::Release);
+        self.0.waker.wake();
+    }
+}
+
+impl<T> Drop for BoundedReceiver<T> {
+    fn drop(&mut self) {
+        self.0.closed.store(true, Ordering::Release);
+    }
+}
+
+impl Default for ChannelFactory {
+    fn default() -> Self {
+        ChannelFactory::new(UnboundedChannel)
+    }
+}
+
+impl Debug for ChannelFactory {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("ChannelFactory").finish()
+    }
+}
+
+impl<T> Debug for UnboundedSender<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("UnboundedSender").finish()
+    }
+}
+
+impl<T> Debug for UnboundedReceiver<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("UnboundedReceiver").finish()
+    }
+}
+
+impl<T> Debug for BoundedSender<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("BoundedSender")
+            .field("len", &self.0.items.len())
+            .field("capacity", &self.0.items.capacity())
+            .finish()
+    }
+}
+
+impl<T> Debug for BoundedReceiver<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("BoundedReceiver")
+            .field("len", &self.0.items.len())
+            .field("capacity", &self.0.items.capacity())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedChannel, Channel, ChannelReceiver};
+    use futures::executor;
+    use futures::future;
+    use futures::poll;
+    use futures::prelude::*;
+    use std::task::Poll;
+
+    fn recv(
+        recver: &mut Box<dyn ChannelReceiver<usize>>,
+    ) -> impl Future<Output = Option<usize>> + Unpin + '_ {
+        future::poll_fn(move |ctx| recver.poll_recv(ctx))
+    }
+
+    #[test]
+    fn bounded_channel() {
+        let (sender, mut recver) = BoundedChannel::with_capacity(2).create::<usize>();
+
+        assert!(sender.try_send(0).is_ok());
+        assert!(sender.try_send(1).is_ok());
+        assert_eq!(sender.try_send(2), Err(2));
+
+        executor::block_on(async {
+            assert_eq!(recv(&mut recver).await, Some(0));
+            assert_eq!(recv(&mut recver).await, Some(1));
+            assert_eq!(poll!(recv(&mut recver)), Poll::Pending);
+
+            drop(sender);
+            assert_eq!(recv(&mut recver).await, None);
+        });
+    }
+
+    #[test]
+    fn closed_channel() {
+        let (sender, recver) = BoundedChannel::with_capacity(1).create::<usize>();
+        assert!(!sender.is_closed());
+
+        drop(recver);
+        assert!(sender.is_closed());
+        assert_eq!(sender.try_send(0), Err(0));
+    }
+}
@@ -1,49 +1,214 @@
 //!
 //! Child is a element of Children group executing user-defined computation
 use crate::broadcast::Broadcast;
-use crate::context::{BastionContext, BastionId, ContextState};
-use crate::envelope::Envelope;
-use crate::message::BastionMessage;
+use crate::child_ref::ChildRef;
+use crate::children::{PreStartMsgs, PreStartOverflow, ShutdownMode, SlowPollAction};
+use crate::context::{BastionContext, BastionId, ContextState, LastWill, Tasks, Undelivered};
+use crate::envelope::{Envelope, RefAddr};
+use crate::message::{BastionMessage, Exit, Msg, OneshotSender, Terminated};
+use crate::rate_limit::{RateLimitPolicy, RateLimiter};
+use crate::supervisor::{ChildError, FaultReason, TerminationReason};
+use crate::usage::UsageStatus;
+use crate::validation::Validation;
 use bastion_executor::pool;
+use futures::future;
 use futures::pending;
 use futures::poll;
 use futures::prelude::*;
+use futures_timer::Delay;
 use lightproc::prelude::*;
 use lightproc::proc_state::EmptyProcState;
-use qutex::Qutex;
+use std::collections::VecDeque;
+use std::error::Error;
 use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
-pub(crate) struct Init(pub(crate) Box<dyn Fn(BastionContext) -> Exec + Send + Sync>);
-pub(crate) struct Exec(Pin<Box<dyn Future<Output = Result<(), ()>> + Send>>);
+// The closure returning the future of an element given its
+// index in its group and its context.
+pub(crate) struct Init(pub(crate) Box<dyn Fn(usize, BastionContext) -> Exec + Send + Sync>);
+// The future of an element, whose error is `None` if it
+// returned `Err(())`.
+pub(crate) struct Exec(Pin<Box<dyn Future<Output = Result<(), Option<ChildError>>> + Send>>);
+
+#[derive(Debug)]
+// The maximum duration of a poll of a child's future, and the
+// number of consecutive polls that exceeded it.
+struct PollDeadline {
+    deadline: Duration,
+    max_slow_polls: usize,
+    action: SlowPollAction,
+    slow_polls: usize,
+}
+
+#[derive(Debug, Clone)]
+// The settings of a children group that its elements use (see
+// the group's `with_*` methods).
+pub(crate) struct ChildConfig {
+    // The validation that messages need to pass to be
+    // pushed to the child's context state.
+    pub(crate) validation: Validation,
+    // The maximum number of messages that are handled before
+    // the child's future gets polled and the child yields.
+    pub(crate) batch_size: usize,
+    // The cores that the child's process is pinned to (or none
+    // if it can run on any core).
+    pub(crate) affinity: Vec<usize>,
+    // The maximum number of messages that the child can receive
+    // per second, and what it does with the messages exceeding
+    // it.
+    pub(crate) rate_limit: Option<(u32, RateLimitPolicy)>,
+    // How the child stops when it is told to.
+    pub(crate) shutdown_mode: ShutdownMode,
+    // Whether the child's future receives an `Exit` message
+    // when it is told to stop, instead of being stopped.
+    pub(crate) trap_exits: bool,
+    // The maximum duration of a poll of the child's future, how
+    // many consecutive polls can exceed it before acting and
+    // what to do then.
+    pub(crate) poll_deadline: Option<(Duration, usize, SlowPollAction)>,
+    // How long the child's future can run once it is started
+    // (if it is limited).
+    pub(crate) max_runtime: Option<Duration>,
+    // The maximum number of messages sent by users that the
+    // child buffers before being started, and what happens to
+    // the ones exceeding it (if it is limited).
+    pub(crate) pre_start_limit: Option<(usize, PreStartOverflow)>,
+}
 
 #[derive(Debug)]
 pub(crate) struct Child {
     bcast: Broadcast,
     // The future that this child is executing.
     exec: Exec,
-    // A lock behind which is the child's context state.
+    // The settings of the child's group that it uses.
+    config: ChildConfig,
+    // The child's context state, shared with its context.
     // This is used to store the messages that were received
     // for the child's associated future to be able to
     // retrieve them.
-    state: Qutex<ContextState>,
+    state: Arc<ContextState>,
+    // The resources used by the child, shared with the
+    // `ChildRef`s referencing it.
+    usage: Arc<UsageStatus>,
+    // Whether the child stopped receiving messages because its
+    // mailbox was full (when using `OverflowPolicy::Block` or
+    // `Fail`).
+    blocked: bool,
+    // The rate limit that the messages need to fit in to be
+    // pushed to the child's context state.
+    rate_limiter: Option<RateLimiter>,
+    // Messages that were held back because they exceeded the
+    // rate limit (when using `RateLimitPolicy::Delay`).
+    delayed: VecDeque<(Msg, RefAddr)>,
+    // The duration that the polls of the child's future
+    // shouldn't exceed (if any).
+    poll_deadline: Option<PollDeadline>,
+    // Where the messages that the child didn't handle are kept
+    // for the element replacing it (if they are).
+    leftovers: Option<Leftovers>,
+    // Whether the child was told to stop and is waiting for
+    // its mailbox to be empty to do so (when not using
+    // `ShutdownMode::Immediate`).
+    draining: bool,
+    // When the child gets killed if it didn't stop yet, and the
+    // sender told whether it stopped gracefully (when told to
+    // stop using `ChildRef::stop_with_timeout`).
+    stop_deadline: Option<(Delay, OneshotSender<bool>)>,
+    // When the child's future faults if it is still running
+    // (when its runtime is limited).
+    runtime_deadline: Option<Delay>,
     // Messages that were received before the child was
     // started. Those will be "replayed" once a start message
     // is received.
-    pre_start_msgs: Vec<Envelope>,
+    pre_start_msgs: PreStartMsgs,
     started: bool,
+    // Whether the child's future stopped being polled because
+    // its group was paused (see `ChildrenRef::pause`), in which
+    // case the messages it receives queue up in its mailbox.
+    paused: bool,
+    // The elements that are told when the child stops or
+    // faults.
+    watchers: Watchers,
+    // The message sent if the child faults or is killed.
+    will: Will,
+    // The tasks spawned by the child's future, which are
+    // cancelled once the child is dropped.
+    tasks: Subtasks,
+}
+
+#[derive(Debug)]
+// The elements watching a child (using `BastionContext::watch`)
+// and the futures waiting for it to exit (using `ChildRef::exited`),
+// which are told when it stops or faults, or when it is dropped
+// without doing so (eg. because it was killed).
+struct Watchers {
+    id: BastionId,
+    watchers: Vec<ChildRef>,
+    exited: Vec<OneshotSender<TerminationReason>>,
+}
+
+#[derive(Debug)]
+// The messages that a child didn't handle, which are moved out
+// of its context state once it is dropped (even if it was
+// killed) for its group to deliver them to the element replacing
+// it (when using `Children::with_preserved_mailboxes`).
+struct Leftovers {
+    state: Arc<ContextState>,
+    undelivered: Undelivered,
+}
+
+#[derive(Debug)]
+// The last will of a child (set using `BastionContext::set_last_will`),
+// which is sent when it faults or when it is dropped without
+// stopping (eg. because it was killed or panicked).
+struct Will {
+    id: BastionId,
+    will: LastWill,
 }
 
+#[derive(Debug)]
+// The tasks spawned by a child (using `BastionContext::spawn`),
+// which are cancelled once it is dropped (whether it stopped,
+// faulted or was killed).
+struct Subtasks(Tasks);
+
 impl Init {
     pub(crate) fn new<C, F>(init: C) -> Self
     where
         C: Fn(BastionContext) -> F + Send + Sync + 'static,
         F: Future<Output = Result<(), ()>> + Send + 'static,
     {
-        let init = Box::new(move |ctx: BastionContext| {
-            let fut = init(ctx);
+        Init::indexed(move |_, ctx| init(ctx))
+    }
+
+    pub(crate) fn fallible<C, F, E>(init: C) -> Self
+    where
+        C: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let init = Box::new(move |_, ctx: BastionContext| {
+            let fut = init(ctx).map_err(|err| Some(ChildError::new(err)));
+            let exec = Box::pin(fut);
+
+            Exec(exec)
+        });
+
+        Init(init)
+    }
+
+    pub(crate) fn indexed<C, F>(init: C) -> Self
+    where
+        C: Fn(usize, BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        let init = Box::new(move |index: usize, ctx: BastionContext| {
+            let fut = init(index, ctx).map_err(|()| None);
             let exec = Box::pin(fut);
 
             Exec(exec)
@@ -53,18 +218,82 @@ impl Init {
     }
 }
 
+impl PollDeadline {
+    fn new(deadline: Duration, max_slow_polls: usize, action: SlowPollAction) -> Self {
+        PollDeadline {
+            deadline,
+            max_slow_polls: max_slow_polls.max(1),
+            action,
+            slow_polls: 0,
+        }
+    }
+}
+
+impl Default for ChildConfig {
+    fn default() -> Self {
+        ChildConfig {
+            validation: Validation::new(),
+            batch_size: 64,
+            affinity: Vec::new(),
+            rate_limit: None,
+            shutdown_mode: ShutdownMode::default(),
+            trap_exits: false,
+            poll_deadline: None,
+            max_runtime: None,
+            pre_start_limit: None,
+        }
+    }
+}
+
 impl Child {
-    pub(crate) fn new(exec: Exec, bcast: Broadcast, state: Qutex<ContextState>) -> Self {
+    pub(crate) fn new(
+        exec: Exec,
+        bcast: Broadcast,
+        state: Arc<ContextState>,
+        usage: Arc<UsageStatus>,
+        config: ChildConfig,
+        undelivered: Option<Undelivered>,
+    ) -> Self {
         debug!("Child({}): Initializing.", bcast.id());
-        let pre_start_msgs = Vec::new();
+        let blocked = false;
+        let rate_limiter = config
+            .rate_limit
+            .map(|(max, policy)| RateLimiter::new(max, policy));
+        let delayed = VecDeque::new();
+        let poll_deadline = config
+            .poll_deadline
+            .map(|(deadline, max, action)| PollDeadline::new(deadline, max, action));
+        let draining = false;
+        let stop_deadline = None;
+        let runtime_deadline = None;
+        let pre_start_msgs = PreStartMsgs::new(config.pre_start_limit);
         let started = false;
+        let paused = false;
+        let watchers = Watchers::new(bcast.id().clone());
+        let will = Will::new(bcast.id().clone(), state.last_will().clone());
+        let tasks = Subtasks(state.tasks().clone());
+        let leftovers = undelivered.map(|undelivered| Leftovers::new(state.clone(), undelivered));
 
         Child {
             bcast,
             exec,
+            config,
             state,
+            usage,
+            blocked,
+            rate_limiter,
+            delayed,
+            poll_deadline,
+            leftovers,
+            draining,
+            stop_deadline,
+            runtime_deadline,
             pre_start_msgs,
             started,
+            paused,
+            watchers,
+            will,
+            tasks,
         }
     }
 
@@ -77,30 +306,115 @@ impl Child {
         let sender = self.bcast.sender().clone();
 
         // FIXME: with_pid
-        ProcStack::default().with_after_panic(move |_state: &mut EmptyProcState| {
-            // FIXME: clones
-            let id = id.clone();
-            warn!("Child({}): Panicked.", id);
+        ProcStack::default()
+            .with_affinity(self.config.affinity.iter().copied())
+            .with_after_panic(move |_state: &mut EmptyProcState| {
+                // FIXME: clones
+                let id = id.clone();
+                warn!("Child({}): Panicked.", id);
 
-            let msg = BastionMessage::faulted(id);
-            let env = Envelope::new(msg, path.clone(), sender.clone());
-            // TODO: handle errors
-            parent.send(env).ok();
-        })
+                let reason = TerminationReason::Fault(FaultReason::Panic(None));
+                let msg = BastionMessage::termination(id, reason);
+                let env = Envelope::new(msg, path.clone(), sender.clone());
+                // TODO: handle errors
+                parent.send(env).ok();
+            })
     }
 
     pub(crate) fn id(&self) -> &BastionId {
         self.bcast.id()
     }
 
+    // Makes the child wait until it is resumed before its
+    // future gets polled (eg. because it replaces an element of
+    // a paused group).
+    pub(crate) fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    // Makes the child stop once its future retrieved all the
+    // messages it received. A paused child is resumed, as it
+    // would otherwise never get to retrieve them.
+    fn drain(&mut self) {
+        self.paused = false;
+        self.draining = true;
+    }
+
+    // Makes the child handle the given message before the
+    // others it received, once it is started.
+    pub(crate) fn push_pre_start_msg(&mut self, env: Envelope) {
+        if let Err((env, policy)) = self.pre_start_msgs.push(env) {
+            warn!(
+                "Child({}): Too many messages received before starting ({:?}): {:?}",
+                self.id(),
+                policy,
+                env
+            );
+            policy.apply(env);
+        }
+
+        // The next messages are refused until the child is
+        // started.
+        if self.pre_start_msgs.is_full() && self.pre_start_limit() == Some(PreStartOverflow::Fail) {
+            self.state.refuse_msgs(true);
+        }
+    }
+
+    fn pre_start_limit(&self) -> Option<PreStartOverflow> {
+        self.pre_start_msgs.limit().map(|(_, policy)| policy)
+    }
+
     fn stopped(&mut self) {
-        debug!("Child({}): Stopped.", self.id());
-        self.bcast.stopped();
+        // The future might have set a custom reason before
+        // returning.
+        let reason = match self.state.take_exit_reason() {
+            Some(msg) => TerminationReason::Custom(msg),
+            None => TerminationReason::Normal,
+        };
+
+        self.terminated(reason);
+    }
+
+    fn faulted(&mut self, reason: FaultReason) {
+        self.terminated(TerminationReason::Fault(reason));
+    }
+
+    fn terminated(&mut self, reason: TerminationReason) {
+        debug!("Child({}): Terminated: {:?}", self.id(), reason);
+        if self.leftovers.is_some() {
+            self.keep_undelivered();
+        }
+
+        if let Some((_, sender)) = self.stop_deadline.take() {
+            match reason {
+                TerminationReason::Normal | TerminationReason::Custom(_) => sender.send(true),
+                TerminationReason::Killed | TerminationReason::Fault(_) => sender.send(false),
+            }
+        }
+
+        match reason {
+            TerminationReason::Normal | TerminationReason::Custom(_) => self.will.revoke(),
+            TerminationReason::Killed | TerminationReason::Fault(_) => self.will.send(),
+        }
+        self.tasks.cancel();
+        self.watchers.notify(&reason);
+        self.bcast.terminated(reason);
     }
 
-    fn faulted(&mut self) {
-        debug!("Child({}): Faulted.", self.id());
-        self.bcast.faulted();
+    // Moves the messages that were received before the child was
+    // started or that were held back because of the rate limit
+    // to its context state, for its group to deliver them to the
+    // element replacing it.
+    fn keep_undelivered(&mut self) {
+        for env in self.pre_start_msgs.take() {
+            if let BastionMessage::Message(msg) = env.msg {
+                self.state.push_msg(msg, env.sign);
+            }
+        }
+
+        while let Some((msg, sign)) = self.delayed.pop_front() {
+            self.state.push_msg(msg, sign);
+        }
     }
 
     async fn handle(&mut self, env: Envelope) -> Result<(), ()> {
@@ -111,8 +425,27 @@ impl Child {
             } => unreachable!(),
             Envelope {
                 msg: BastionMessage::Stop,
-                ..
+                sign,
             } => {
+                self.state.request_shutdown();
+
+                if self.config.trap_exits {
+                    debug!("Child({}): Trapped exit.", self.id());
+                    self.deliver(Msg::tell(Exit), sign);
+                    return Ok(());
+                }
+
+                // The future gets to finish its work if it waits
+                // for the shutdown signal.
+                if self.started
+                    && (self.config.shutdown_mode != ShutdownMode::Immediate
+                        || self.state.handles_shutdown())
+                {
+                    debug!("Child({}): Draining mailbox before stopping.", self.id());
+                    self.drain();
+                    return Ok(());
+                }
+
                 self.stopped();
 
                 return Err(());
@@ -121,143 +454,695 @@ impl Child {
                 msg: BastionMessage::Kill,
                 ..
             } => {
-                self.stopped();
+                self.terminated(TerminationReason::Killed);
 
                 return Err(());
             }
-            // FIXME
+            // Elements can't supervise what is deployed to
+            // them, so it is dropped.
+            Envelope {
+                msg: BastionMessage::Deploy(deployment),
+                ..
+            } => {
+                warn!(
+                    "Child({}): Dropping deployment: {:?}",
+                    self.id(),
+                    deployment
+                );
+            }
+            // Elements don't supervise anything, so there is
+            // nothing to prune.
+            Envelope {
+                msg: BastionMessage::Prune { id },
+                ..
+            } => {
+                debug!("Child({}): Ignoring pruning of {}.", self.id(), id);
+            }
+            // Elements don't have elements, so there is nothing
+            // to scale.
             Envelope {
-                msg: BastionMessage::Deploy(_),
+                msg: BastionMessage::Scale(scaling),
                 ..
-            } => unimplemented!(),
-            // FIXME
+            } => {
+                debug!("Child({}): Ignoring scaling: {:?}", self.id(), scaling);
+            }
+            // Only children groups notify when they stopped.
             Envelope {
-                msg: BastionMessage::Prune { .. },
+                msg: BastionMessage::NotifyStopped { .. },
                 ..
-            } => unimplemented!(),
-            // FIXME
+            } => {
+                debug!("Child({}): Ignoring stop notification.", self.id());
+            }
+            // Only children groups track whether their elements
+            // are ready.
             Envelope {
-                msg: BastionMessage::SuperviseWith(_),
+                msg: BastionMessage::ElemReady { id },
                 ..
-            } => unimplemented!(),
+            } => {
+                debug!("Child({}): Ignoring readiness of Child({}).", self.id(), id);
+            }
             Envelope {
-                msg: BastionMessage::Message(msg),
+                msg: BastionMessage::NotifyReady { .. },
+                ..
+            } => {
+                debug!("Child({}): Ignoring ready notification.", self.id());
+            }
+            Envelope {
+                msg: BastionMessage::Pause,
+                ..
+            } => {
+                debug!("Child({}): Pausing.", self.id());
+                self.paused = true;
+            }
+            Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => {
+                debug!("Child({}): Resuming.", self.id());
+                self.paused = false;
+            }
+            Envelope {
+                msg: BastionMessage::NotifyExited { sender },
+                ..
+            } => {
+                debug!("Child({}): Will notify when it exits.", self.id());
+                self.watchers.push_exited(sender);
+            }
+            Envelope {
+                msg: BastionMessage::PrepareStop { msg },
                 sign,
             } => {
-                debug!("Child({}): Received a message: {:?}", self.id(), msg);
-                let mut state = self.state.clone().lock_async().await.map_err(|_| ())?;
-                state.push_msg(msg, sign);
+                debug!("Child({}): Preparing to stop.", self.id());
+                self.deliver(msg, sign);
+            }
+            // Children groups pick the element that receives the
+            // message on their own.
+            Envelope {
+                msg: BastionMessage::SendOne(msg),
+                ..
+            } => {
+                debug!("Child({}): Ignoring anycast message: {:?}", self.id(), msg);
+            }
+            Envelope {
+                msg: BastionMessage::SendSiblings { msg, .. },
+                ..
+            } => {
+                debug!("Child({}): Ignoring siblings message: {:?}", self.id(), msg);
+            }
+            Envelope {
+                msg: BastionMessage::Ping { sender },
+                ..
+            } => {
+                trace!("Child({}): Answering ping.", self.id());
+                sender.send(());
+            }
+            // Elements are restarted by their children group.
+            Envelope {
+                msg: BastionMessage::RestartElem { id },
+                ..
+            } => {
+                debug!("Child({}): Asking to be restarted.", self.id());
+                let msg = BastionMessage::restart_elem(id);
+                let env =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                // TODO: handle errors
+                self.bcast.send_parent(env).ok();
+            }
+            // The element gets to finish its work (ie. to retrieve
+            // the messages it received or to handle its `Exit`
+            // message) before being killed once the deadline passed.
+            Envelope {
+                msg: BastionMessage::StopWithin { deadline, sender },
+                sign,
+            } => {
+                self.state.request_shutdown();
+
+                if !self.started {
+                    self.stopped();
+                    sender.send(true);
+
+                    return Err(());
+                }
+
+                if self.config.trap_exits {
+                    debug!("Child({}): Trapped exit.", self.id());
+                    self.deliver(Msg::tell(Exit), sign);
+                } else {
+                    debug!(
+                        "Child({}): Draining mailbox before stopping within {:?}.",
+                        self.id(),
+                        deadline
+                    );
+                    self.drain();
+                }
+
+                self.stop_deadline = Some((Delay::new(deadline), sender));
             }
-            // FIXME
             Envelope {
-                msg: BastionMessage::Stopped { .. },
+                msg: BastionMessage::Watch { watcher },
                 ..
-            } => unimplemented!(),
-            // FIXME
+            } => {
+                debug!("Child({}): Watched by Child({}).", self.id(), watcher.id());
+                self.watchers.push(watcher);
+            }
             Envelope {
-                msg: BastionMessage::Faulted { .. },
+                msg: BastionMessage::Unwatch { watcher },
                 ..
-            } => unimplemented!(),
+            } => {
+                debug!("Child({}): Unwatched by Child({}).", self.id(), watcher);
+                self.watchers.remove(&watcher);
+            }
+            // Elements don't supervise anything, so the strategy
+            // is the one of their group.
+            Envelope {
+                msg: BastionMessage::SuperviseWith(strategy),
+                ..
+            } => {
+                debug!(
+                    "Child({}): Forwarding strategy to its group: {:?}",
+                    self.id(),
+                    strategy
+                );
+                let msg = BastionMessage::supervise_with(strategy);
+                let env =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                // TODO: handle errors
+                self.bcast.send_parent(env).ok();
+            }
+            Envelope {
+                msg: BastionMessage::Message(msg),
+                sign,
+            } => {
+                debug!("Child({}): Received a message: {:?}", self.id(), msg);
+                if !self.config.validation.is_valid(&msg) {
+                    warn!("Child({}): Rejected a message: {:?}", self.id(), msg);
+                    return Ok(());
+                }
+
+                self.usage.received(msg.size());
+
+                if !self.delayed.is_empty() {
+                    self.delayed.push_back((msg, sign));
+                    return Ok(());
+                }
+
+                let acquired = match &mut self.rate_limiter {
+                    Some(limiter) => limiter.try_acquire(),
+                    None => true,
+                };
+
+                if !acquired {
+                    match self.rate_limiter.as_ref().map(RateLimiter::policy) {
+                        Some(RateLimitPolicy::Delay) => {
+                            debug!(
+                                "Child({}): Rate limit exceeded, delaying messages.",
+                                self.id()
+                            );
+                            self.delayed.push_back((msg, sign));
+                            self.update_user_lane();
+                        }
+                        _ => {
+                            warn!(
+                                "Child({}): Rate limit exceeded, rejected a message: {:?}",
+                                self.id(),
+                                msg
+                            );
+                        }
+                    }
+
+                    return Ok(());
+                }
+
+                self.deliver(msg, sign);
+            }
+            // Elements don't supervise anything, so the termination
+            // of another element is delivered like when watching it.
+            Envelope {
+                msg: BastionMessage::Termination { id, reason },
+                sign,
+            } => {
+                if &id == self.id() {
+                    warn!(
+                        "Child({}): Ignoring its own termination: {:?}",
+                        self.id(),
+                        reason
+                    );
+                    return Ok(());
+                }
+
+                debug!(
+                    "Child({}): Received termination of Child({}): {:?}",
+                    self.id(),
+                    id,
+                    reason
+                );
+                self.deliver(Msg::tell(Terminated::new(id, reason)), sign);
+            }
         }
 
         Ok(())
     }
 
-    async fn run(mut self) {
+    fn deliver(&mut self, msg: Msg, sign: RefAddr) {
+        self.state.push_msg(msg, sign);
+        if !self.blocked && self.state.is_blocked() {
+            debug!("Child({}): Mailbox full, holding messages back.", self.id());
+            self.blocked = true;
+            self.update_user_lane();
+        }
+    }
+
+    // Stops the reception of messages while some are held back
+    // (because the mailbox is full or because of the rate limit)
+    // and resumes it otherwise.
+    fn update_user_lane(&mut self) {
+        if self.blocked || !self.delayed.is_empty() || self.waits_for_start() {
+            self.bcast.pause_user_lane();
+        } else {
+            self.bcast.resume_user_lane();
+        }
+    }
+
+    // Whether the user messages are left in the user lane until
+    // the child is started, since the room reserved for them in
+    // its mailbox would be given back once they are received,
+    // while the messages received before starting aren't
+    // counted as part of the mailbox.
+    fn waits_for_start(&self) -> bool {
+        !self.started && self.state.reserves_room()
+    }
+
+    // Moves the messages that were held back to the child's
+    // mailbox, until it is full again. Returns whether the
+    // child starts receiving messages again.
+    fn flush_blocked(&mut self) -> bool {
+        if !self.blocked || !self.state.flush() {
+            return false;
+        }
+
+        debug!("Child({}): Mailbox has room, resuming.", self.id());
+        self.blocked = false;
+        self.update_user_lane();
+        self.delayed.is_empty()
+    }
+
+    // Delivers the messages that were delayed because of the
+    // rate limit, as long as it allows them to be. Returns
+    // whether the child starts receiving messages again.
+    async fn release_delayed(&mut self) -> bool {
+        if self.delayed.is_empty() {
+            return false;
+        }
+
+        while !self.blocked && !self.delayed.is_empty() {
+            let limiter = match &mut self.rate_limiter {
+                Some(limiter) => limiter,
+                None => break,
+            };
+
+            // This registers a timer waking the child up once
+            // the next message can be delivered.
+            if poll!(future::poll_fn(|ctx| limiter.poll_acquire(ctx))).is_pending() {
+                return false;
+            }
+
+            let (msg, sign) = self.delayed.pop_front().unwrap();
+            self.deliver(msg, sign);
+        }
+
+        self.update_user_lane();
+        !self.blocked && self.delayed.is_empty()
+    }
+
+    // Checks the duration of the last poll of the child's future
+    // against its deadline, returning whether the child should
+    // fault because too many consecutive polls exceeded it.
+    fn is_too_slow(&mut self, elapsed: Duration) -> bool {
+        let id = self.id().clone();
+        let poll_deadline = match &mut self.poll_deadline {
+            Some(poll_deadline) => poll_deadline,
+            None => return false,
+        };
+
+        if elapsed <= poll_deadline.deadline {
+            poll_deadline.slow_polls = 0;
+            return false;
+        }
+
+        trace!(
+            "Child({}): Polling the future took {:?} (deadline: {:?}).",
+            id,
+            elapsed,
+            poll_deadline.deadline
+        );
+        poll_deadline.slow_polls += 1;
+        if poll_deadline.slow_polls < poll_deadline.max_slow_polls {
+            return false;
+        }
+
+        warn!(
+            "Child({}): Polling the future exceeded {:?} {} times in a row (last: {:?}).",
+            id, poll_deadline.deadline, poll_deadline.slow_polls, elapsed
+        );
+        poll_deadline.slow_polls = 0;
+        poll_deadline.action == SlowPollAction::Escalate
+    }
+
+    // Whether all the messages that the child received have
+    // been retrieved by its future.
+    fn is_drained(&self) -> bool {
+        !self.blocked && self.delayed.is_empty() && self.state.is_empty()
+    }
+
+    // Delivers the messages of the ticks that fired (registered
+    // using `BastionContext::tick_every`) to the child itself,
+    // returning whether there were any.
+    async fn deliver_ticks(&mut self) -> bool {
+        let ticks = self.state.ticks().clone();
+        let msgs = future::poll_fn(|ctx| Poll::Ready(ticks.poll_fired(ctx))).await;
+        let ticked = !msgs.is_empty();
+        for msg in msgs {
+            trace!("Child({}): Ticking: {:?}", self.id(), msg);
+            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+            self.bcast.send_self(env);
+        }
+
+        ticked
+    }
+
+    // Runs the child until it stops, returning its broadcast so
+    // that its group can relaunch it with the same identity.
+    async fn run(mut self) -> Broadcast {
+        self.execute().await;
+        self.bcast
+    }
+
+    async fn execute(&mut self) {
         debug!("Child({}): Launched.", self.id());
+        self.update_user_lane();
         loop {
-            match poll!(&mut self.bcast.next()) {
-                // TODO: Err if started == true?
-                Poll::Ready(Some(Envelope {
-                    msg: BastionMessage::Start,
-                    ..
-                })) => {
-                    trace!(
-                        "Child({}): Received a new message (started=false): {:?}",
-                        self.id(),
-                        BastionMessage::Start
-                    );
-                    debug!("Child({}): Starting.", self.id());
-                    self.started = true;
+            self.flush_blocked();
+            self.release_delayed().await;
 
-                    let msgs = self.pre_start_msgs.drain(..).collect::<Vec<_>>();
-                    self.pre_start_msgs.shrink_to_fit();
+            // Whether all the messages that were ready have been
+            // handled (otherwise, the batch size was reached).
+            let mut drained = false;
+            for _ in 0..self.config.batch_size {
+                match poll!(&mut self.bcast.next()) {
+                    // TODO: Err if started == true?
+                    Poll::Ready(Some(Envelope {
+                        msg: BastionMessage::Start,
+                        ..
+                    })) => {
+                        trace!(
+                            "Child({}): Received a new message (started=false): {:?}",
+                            self.id(),
+                            BastionMessage::Start
+                        );
+                        debug!("Child({}): Starting.", self.id());
+                        self.started = true;
+                        self.runtime_deadline = self.config.max_runtime.map(Delay::new);
+                        self.update_user_lane();
 
-                    debug!(
-                        "Child({}): Replaying messages received before starting.",
-                        self.id()
-                    );
-                    for msg in msgs {
-                        trace!("Child({}): Replaying message: {:?}", self.id(), msg);
+                        let msgs = self.pre_start_msgs.take();
+                        self.state.refuse_msgs(false);
+
+                        debug!(
+                            "Child({}): Replaying messages received before starting.",
+                            self.id()
+                        );
+                        for msg in msgs {
+                            trace!("Child({}): Replaying message: {:?}", self.id(), msg);
+                            if self.handle(msg).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Poll::Ready(Some(msg)) if !self.started => {
+                        trace!(
+                            "Child({}): Received a new message (started=false): {:?}",
+                            self.id(),
+                            msg
+                        );
+                        self.push_pre_start_msg(msg);
+                    }
+                    Poll::Ready(Some(msg)) => {
+                        trace!(
+                            "Child({}): Received a new message (started=true): {:?}",
+                            self.id(),
+                            msg
+                        );
                         if self.handle(msg).await.is_err() {
                             return;
                         }
                     }
-
-                    continue;
+                    // NOTE: because `Broadcast` always holds both a `Sender` and
+                    //      `Receiver` of the same channel, this would only be
+                    //      possible if the channel was closed, which never happens.
+                    Poll::Ready(None) => unreachable!(),
+                    Poll::Pending => {
+                        drained = true;
+                        break;
+                    }
                 }
-                Poll::Ready(Some(msg)) if !self.started => {
-                    trace!(
-                        "Child({}): Received a new message (started=false): {:?}",
-                        self.id(),
-                        msg
-                    );
-                    self.pre_start_msgs.push(msg);
+            }
 
-                    continue;
+            if self.started && !self.paused {
+                // The future's budget of messages it can retrieve
+                // before being forced to yield is reset every time
+                // it gets polled.
+                self.state.reset_budget();
+                let polled_at = Instant::now();
+                // The panics of the future are caught here (instead
+                // of by the proc) to retrieve their payload.
+                let poll = poll!(AssertUnwindSafe(&mut self.exec).catch_unwind());
+                let elapsed = polled_at.elapsed();
+                self.usage.polled(elapsed);
+                if self.is_too_slow(elapsed) {
+                    return self.faulted(FaultReason::TooSlow);
                 }
-                Poll::Ready(Some(msg)) => {
-                    trace!(
-                        "Child({}): Received a new message (started=true): {:?}",
-                        self.id(),
-                        msg
-                    );
-                    if self.handle(msg).await.is_err() {
-                        return;
+
+                match poll {
+                    Poll::Ready(Err(payload)) => {
+                        let msg = payload
+                            .downcast_ref::<&str>()
+                            .map(|msg| msg.to_string())
+                            .or_else(|| payload.downcast_ref::<String>().cloned());
+
+                        warn!("Child({}): The future panicked: {:?}", self.id(), msg);
+                        return self.faulted(FaultReason::Panic(msg));
+                    }
+                    Poll::Ready(Ok(Ok(()))) => {
+                        debug!(
+                            "Child({}): The future finished executing successfully.",
+                            self.id()
+                        );
+                        return self.stopped();
                     }
+                    Poll::Ready(Ok(Err(None))) => {
+                        warn!("Child({}): The future returned an error.", self.id());
+                        return self.faulted(FaultReason::Error);
+                    }
+                    Poll::Ready(Ok(Err(Some(err)))) => {
+                        warn!(
+                            "Child({}): The future returned an error: {}",
+                            self.id(),
+                            err
+                        );
+                        return self.faulted(FaultReason::Failure(err));
+                    }
+                    Poll::Pending => (),
+                }
 
-                    continue;
+                // The future might have made room in the mailbox,
+                // in which case the messages that were held back
+                // need to be received.
+                if self.flush_blocked() || self.release_delayed().await {
+                    drained = false;
                 }
-                // NOTE: because `Broadcast` always holds both a `Sender` and
-                //      `Receiver` of the same channel, this would only be
-                //      possible if the channel was closed, which never happens.
-                Poll::Ready(None) => unreachable!(),
-                Poll::Pending => (),
-            }
 
-            if !self.started {
-                pending!();
+                // The future stops on its own once it handled the
+                // shutdown signal.
+                if self.draining && drained && self.is_drained() && !self.state.handles_shutdown() {
+                    debug!("Child({}): Mailbox drained.", self.id());
+                    return self.stopped();
+                }
+            }
 
-                continue;
+            // The ticks that fired are received the next time
+            // the child loops.
+            if self.deliver_ticks().await {
+                drained = false;
             }
 
-            match poll!(&mut self.exec) {
-                Poll::Ready(Ok(())) => {
-                    debug!(
-                        "Child({}): The future finished executing successfully.",
+            // The deadlines are checked last so that those that
+            // were just set get polled (and thus wake the child
+            // up) before it waits.
+            if let Some((deadline, _)) = &mut self.stop_deadline {
+                if poll!(deadline).is_ready() {
+                    warn!(
+                        "Child({}): Still running after deadline, killing it.",
                         self.id()
                     );
-                    return self.stopped();
+                    return self.terminated(TerminationReason::Killed);
                 }
-                Poll::Ready(Err(())) => {
-                    warn!("Child({}): The future returned an error.", self.id());
-                    return self.faulted();
+            }
+
+            if let Some(deadline) = &mut self.runtime_deadline {
+                if poll!(deadline).is_ready() {
+                    warn!(
+                        "Child({}): Timed out after running for {:?}, faulting.",
+                        self.id(),
+                        self.config.max_runtime
+                    );
+                    return self.faulted(FaultReason::Timeout);
                 }
-                Poll::Pending => (),
             }
 
-            pending!();
+            if drained {
+                pending!();
+            } else {
+                trace!(
+                    "Child({}): Yielding after handling {} messages.",
+                    self.id(),
+                    self.config.batch_size
+                );
+                yield_now().await;
+            }
         }
     }
 
-    pub(crate) fn launch(self) -> RecoverableHandle<()> {
+    pub(crate) fn launch(self) -> RecoverableHandle<Broadcast> {
         let stack = self.stack();
         pool::spawn(self.run(), stack)
     }
 }
 
+impl Watchers {
+    fn new(id: BastionId) -> Self {
+        let watchers = Vec::new();
+        let exited = Vec::new();
+
+        Watchers {
+            id,
+            watchers,
+            exited,
+        }
+    }
+
+    fn push(&mut self, watcher: ChildRef) {
+        self.watchers.push(watcher);
+    }
+
+    fn remove(&mut self, watcher: &BastionId) {
+        self.watchers.retain(|child_ref| child_ref.id() != watcher);
+    }
+
+    fn push_exited(&mut self, sender: OneshotSender<TerminationReason>) {
+        self.exited.push(sender);
+    }
+
+    // Tells the watchers that the child terminated, after which
+    // they stop watching it.
+    fn notify(&mut self, reason: &TerminationReason) {
+        for watcher in self.watchers.drain(..) {
+            trace!(
+                "Child({}): Telling Child({}) that it terminated.",
+                self.id,
+                watcher.id()
+            );
+            let msg = Terminated::new(self.id.clone(), reason.clone());
+            // The watcher might have stopped meanwhile.
+            watcher.tell_anonymously(msg).ok();
+        }
+
+        for sender in self.exited.drain(..) {
+            sender.send(reason.clone());
+        }
+    }
+}
+
+impl Drop for Watchers {
+    fn drop(&mut self) {
+        self.notify(&TerminationReason::Killed);
+    }
+}
+
+impl Will {
+    fn new(id: BastionId, will: LastWill) -> Self {
+        Will { id, will }
+    }
+
+    fn revoke(&self) {
+        if self.will.take().is_some() {
+            trace!("Child({}): Revoked its last will.", self.id);
+        }
+    }
+
+    fn send(&self) {
+        if let Some((recipient, msg)) = self.will.take() {
+            debug!(
+                "Child({}): Sending its last will to Child({}).",
+                self.id,
+                recipient.id()
+            );
+            let msg = BastionMessage::Message(msg);
+            let env = Envelope::from_dead_letters(msg);
+            // The recipient might have stopped meanwhile.
+            recipient.send(env).ok();
+        }
+    }
+}
+
+impl Leftovers {
+    fn new(state: Arc<ContextState>, undelivered: Undelivered) -> Self {
+        Leftovers { state, undelivered }
+    }
+}
+
+impl Drop for Leftovers {
+    fn drop(&mut self) {
+        self.undelivered.extend(self.state.take_msgs());
+    }
+}
+
+impl Drop for Will {
+    fn drop(&mut self) {
+        self.send();
+    }
+}
+
+impl Subtasks {
+    fn cancel(&self) {
+        self.0.cancel();
+    }
+}
+
+impl Drop for Subtasks {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+// Yields to the executor while making sure that the current
+// task gets polled again (unlike `pending!`, which relies on
+// the wakers that were previously registered).
+pub(crate) async fn yield_now() {
+    let mut yielded = false;
+    future::poll_fn(|ctx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            ctx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
 impl Future for Exec {
-    type Output = Result<(), ()>;
+    type Output = Result<(), Option<ChildError>>;
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
         Pin::new(&mut self.get_mut().0).poll(ctx)
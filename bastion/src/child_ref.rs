@@ -2,32 +2,66 @@
 //! Allows users to communicate with Child through the mailboxes.
 use crate::broadcast::Sender;
 use crate::context::BastionId;
-use crate::envelope::{Envelope, RefAddr};
-use crate::message::{Answer, BastionMessage, Message};
+use crate::envelope::{Envelope, RefAddr, SignedMessage};
+use crate::mailbox::{MailboxStats, MailboxStatus, OverflowPolicy};
+use crate::message::{Answer, AskError, BastionMessage, Exited, Message, OneshotSender, Shutdown};
 use crate::path::BastionPath;
+use crate::usage::{Usage, UsageStatus};
+use crate::validation::Validation;
+use futures::future;
 use std::cmp::{Eq, PartialEq};
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 /// A "reference" to an element of a children group, allowing to
 /// communicate with it.
 pub struct ChildRef {
     id: BastionId,
+    // The ordinal of the child within its group (see
+    // `Children::with_indexed_exec`).
+    index: usize,
     sender: Sender,
     path: Arc<BastionPath>,
+    // The validation that messages sent to the child need
+    // to pass to be accepted.
+    validation: Validation,
+    // The status of the child's mailbox.
+    mailbox: Arc<MailboxStatus>,
+    // The resources used by the child.
+    usage: Arc<UsageStatus>,
 }
 
 impl ChildRef {
-    pub(crate) fn new(id: BastionId, sender: Sender, path: Arc<BastionPath>) -> ChildRef {
-        ChildRef { id, sender, path }
+    pub(crate) fn new(
+        id: BastionId,
+        index: usize,
+        sender: Sender,
+        path: Arc<BastionPath>,
+        validation: Validation,
+        mailbox: Arc<MailboxStatus>,
+        usage: Arc<UsageStatus>,
+    ) -> ChildRef {
+        ChildRef {
+            id,
+            index,
+            sender,
+            path,
+            validation,
+            mailbox,
+            usage,
+        }
     }
 
     /// Returns the identifier of the children group element this
     /// `ChildRef` is referencing.
     ///
     /// Note that the children group element's identifier is reset
-    /// when it is restarted.
+    /// when it is restarted, unless its children group was created
+    /// using [`Children::with_stable_ids`].
+    ///
+    /// [`Children::with_stable_ids`]: children/struct.Children.html#method.with_stable_ids
     ///
     /// # Example
     ///
@@ -56,12 +90,57 @@ impl ChildRef {
         &self.id
     }
 
+    /// Returns the ordinal of the children group element this
+    /// `ChildRef` is referencing within its group, which it keeps
+    /// when it is restarted (unlike its identifier), allowing to
+    /// deterministically map shards of a workload to elements.
+    ///
+    /// The elements of a group have the indices from `0` to the
+    /// number of elements minus one. The same index is given to
+    /// the closure passed to [`Children::with_indexed_exec`], and
+    /// is used instead of the identifier in the addresses of the
+    /// elements of named groups (see [`Children::with_name`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| children.with_redundancy(4))
+    ///     .expect("Couldn't create the children group.");
+    ///
+    /// let mut indices = children_ref
+    ///     .elems()
+    ///     .iter()
+    ///     .map(|child_ref| child_ref.index())
+    ///     .collect::<Vec<_>>();
+    /// indices.sort();
+    /// assert_eq!(indices, vec![0, 1, 2, 3]);
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_indexed_exec`]: children/struct.Children.html#method.with_indexed_exec
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing.
     /// This message is intended to be used outside of Bastion context when
     /// there is no way for receiver to identify message sender
     ///
     /// This method returns `()` if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// otherwise (including when the message was rejected by the
+    /// children group's validation, see [`Children::with_validator`],
+    /// or when the child has no room left and the group's overflow
+    /// policy is [`OverflowPolicy::Fail`] or [`OverflowPolicy::Block`]).
     ///
     /// # Argument
     ///
@@ -107,10 +186,97 @@ impl ChildRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
+    ///
+    /// [`Children::with_validator`]: children/struct.Children.html#method.with_validator
+    /// [`OverflowPolicy::Fail`]: mailbox/enum.OverflowPolicy.html#variant.Fail
+    /// [`OverflowPolicy::Block`]: mailbox/enum.OverflowPolicy.html#variant.Block
     pub fn tell_anonymously<M: Message>(&self, msg: M) -> Result<(), M> {
+        self.tell_signed(msg, RefAddr::dead_letters())
+    }
+
+    // Sends a message to the child like `tell_anonymously` does,
+    // signed with the given signature.
+    pub(crate) fn tell_signed<M: Message>(&self, msg: M, sign: RefAddr) -> Result<(), M> {
         debug!("ChildRef({}): Telling message: {:?}", self.id(), msg);
+        if self.mailbox.rejects() {
+            debug!("ChildRef({}): Refusing messages, failing to send.", self.id());
+            return Err(msg);
+        }
+
+        let msg = self.validation.check(msg)?;
+        let msg = BastionMessage::tell(msg);
+        let env = Envelope::new_with_sign(msg, sign);
+        self.send(env).map_err(Envelope::take_back)
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing
+    /// like [`tell_anonymously`] does, except that when the group's
+    /// overflow policy is [`OverflowPolicy::Block`], this method
+    /// waits (asynchronously) for the child to have room for the
+    /// message instead of failing.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise (see [`tell_anonymously`]).
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox_limit(16, OverflowPolicy::Block)
+    ///         # .with_exec(|ctx: BastionContext| {
+    ///             # async move {
+    ///                 # loop {
+    ///                     # ctx.recv().await?;
+    ///                 # }
+    ///             # }
+    ///         # })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    ///     # Bastion::start();
+    ///     #
+    /// let child_ref = &children_ref.elems()[0];
+    /// run!(async {
+    ///     for i in 0..64 {
+    ///         // This will wait while the child has 16 messages waiting...
+    ///         child_ref.tell_async(i).await.expect("Couldn't send the message.");
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`tell_anonymously`]: #method.tell_anonymously
+    /// [`OverflowPolicy::Block`]: mailbox/enum.OverflowPolicy.html#variant.Block
+    pub async fn tell_async<M: Message>(&self, msg: M) -> Result<(), M> {
+        debug!("ChildRef({}): Telling message: {:?}", self.id(), msg);
+        if self.mailbox.rejects() {
+            debug!("ChildRef({}): Refusing messages, failing to send.", self.id());
+            return Err(msg);
+        }
+
+        let msg = self.validation.check(msg)?;
         let msg = BastionMessage::tell(msg);
         let env = Envelope::from_dead_letters(msg);
+        if self.mailbox.policy() == OverflowPolicy::Block && self.reserve().await {
+            // FIXME: panics?
+            return self
+                .sender
+                .send_reserved(env)
+                .map_err(|env| env.into_msg().unwrap());
+        }
+
         // FIXME: panics?
         self.send(env).map_err(|env| env.into_msg().unwrap())
     }
@@ -121,7 +287,10 @@ impl ChildRef {
     /// there is no way for receiver to identify message sender
     ///
     /// This method returns [`Answer`](../message/struct.Answer.html) if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// otherwise (including when the message was rejected by the
+    /// children group's validation, see [`Children::with_validator`],
+    /// or when the child has no room left and the group's overflow
+    /// policy is [`OverflowPolicy::Fail`] or [`OverflowPolicy::Block`]).
     ///
     /// # Argument
     ///
@@ -193,19 +362,110 @@ impl ChildRef {
     /// ```
     ///
     /// [`Answer`]: message/struct.Answer.html
+    /// [`Children::with_validator`]: children/struct.Children.html#method.with_validator
+    /// [`OverflowPolicy::Fail`]: mailbox/enum.OverflowPolicy.html#variant.Fail
+    /// [`OverflowPolicy::Block`]: mailbox/enum.OverflowPolicy.html#variant.Block
     pub fn ask_anonymously<M: Message>(&self, msg: M) -> Result<Answer, M> {
+        self.ask_signed(msg, None, RefAddr::dead_letters())
+    }
+
+    // Sends a message to the child like `ask_anonymously` does,
+    // signed with the given signature and with the deadline after
+    // which its answer isn't waited for anymore (if any).
+    pub(crate) fn ask_signed<M: Message>(
+        &self,
+        msg: M,
+        deadline: Option<Instant>,
+        sign: RefAddr,
+    ) -> Result<Answer, M> {
         debug!("ChildRef({}): Asking message: {:?}", self.id(), msg);
-        let (msg, answer) = BastionMessage::ask(msg);
-        let env = Envelope::from_dead_letters(msg);
-        // FIXME: panics?
-        self.send(env).map_err(|env| env.into_msg().unwrap())?;
+        if self.mailbox.rejects() {
+            debug!("ChildRef({}): Refusing messages, failing to send.", self.id());
+            return Err(msg);
+        }
+
+        let msg = self.validation.check(msg)?;
+        let (msg, answer) = BastionMessage::ask_within(msg, deadline);
+        let env = Envelope::new_with_sign(msg, sign);
+        self.send(env).map_err(Envelope::take_back)?;
 
         Ok(answer)
     }
 
+    /// Sends a message to the child this `ChildRef` is referencing
+    /// like [`ask_anonymously`] does, and waits (asynchronously)
+    /// for its answer, which is expected to be of type `Resp`.
+    ///
+    /// This method returns the answer if the child answered with
+    /// a message of type `Resp`, or an [`AskError`] otherwise
+    /// (giving the message back if it couldn't be sent, or the
+    /// answer if it was of another type).
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref =
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             msg! { ctx.recv().await?,
+    ///                 msg: u64 =!> {
+    ///                     answer!(ctx, msg * 2).ok();
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    ///     # let child_ref = children_ref.elems()[0].clone();
+    ///     # Bastion::start();
+    /// // The type of the answer is given at the call site...
+    /// let doubled = run!(child_ref.ask::<_, u64>(21u64)).expect("Couldn't get the answer.");
+    /// assert_eq!(doubled, 42);
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ask_anonymously`]: #method.ask_anonymously
+    /// [`AskError`]: ../message/enum.AskError.html
+    pub async fn ask<Req: Message, Resp: Message>(&self, req: Req) -> Result<Resp, AskError<Req>> {
+        let answer = self.ask_anonymously(req).map_err(AskError::Rejected)?;
+        let answer = answer.await.map_err(|()| AskError::NoAnswer)?;
+
+        let (msg, sign) = answer.extract();
+        msg.downcast().map_err(|msg| {
+            debug!(
+                "ChildRef({}): Received unexpected answer: {:?}",
+                self.id(),
+                msg
+            );
+            AskError::UnexpectedAnswer(SignedMessage::new(msg, sign))
+        })
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing
     /// to tell it to stop its execution.
     ///
+    /// The child receives this message before the messages that
+    /// were sent to it but that it didn't receive yet (eg. because
+    /// its mailbox is full), which are only handled if its group
+    /// drains the elements' mailboxes when stopping them (see
+    /// [`ShutdownMode`]).
+    ///
     /// This method returns `()` if it succeeded, or `Err(())`
     /// otherwise.
     ///
@@ -226,6 +486,8 @@ impl ChildRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
+    ///
+    /// [`ShutdownMode`]: children/enum.ShutdownMode.html
     pub fn stop(&self) -> Result<(), ()> {
         debug!("ChildRef({}): Stopping.", self.id);
         let msg = BastionMessage::stop();
@@ -233,9 +495,175 @@ impl ChildRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Sends a message to the child this `ChildRef` is referencing
+    /// to tell it to stop once it finished its current work (ie.
+    /// once its future retrieved all the messages it received, or
+    /// returned after receiving an [`Exit`] message if it traps
+    /// exits), killing it if it didn't after the given timeout.
+    ///
+    /// This method returns a [`Shutdown`] resolving to `Ok(true)`
+    /// once the child stopped gracefully or to `Ok(false)` if it
+    /// had to be killed (or faulted meanwhile), if it succeeded,
+    /// or `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the child to stop before
+    ///     killing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| {
+    ///         # children.with_exec(|ctx: BastionContext| async move {
+    ///             # loop {
+    ///                 # ctx.recv().await?;
+    ///             # }
+    ///         # })
+    ///     # }).unwrap();
+    ///     # let child_ref = &children_ref.elems()[0];
+    ///     # Bastion::start();
+    /// let shutdown: Shutdown = child_ref
+    ///     .stop_with_timeout(Duration::from_secs(5))
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// // Waiting for it to stop...
+    /// if let Ok(false) = run!(shutdown) {
+    ///     // It had to be killed...
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Exit`]: ../message/struct.Exit.html
+    /// [`Shutdown`]: ../message/struct.Shutdown.html
+    pub fn stop_with_timeout(&self, timeout: Duration) -> Result<Shutdown, ()> {
+        debug!("ChildRef({}): Stopping within {:?}.", self.id(), timeout);
+        let (sender, shutdown) = Shutdown::new();
+        let msg = BastionMessage::stop_within(timeout, sender);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())?;
+
+        Ok(shutdown)
+    }
+
+    /// Returns an [`Exited`] resolving once the child this
+    /// `ChildRef` is referencing terminated (ie. once it stopped,
+    /// faulted or was killed), to the [`TerminationReason`]
+    /// describing why.
+    ///
+    /// This allows reacting to a specific element's completion
+    /// without watching it from another element (see
+    /// [`BastionContext::watch`]) or waiting for its whole
+    /// children group to stop.
+    ///
+    /// This method returns `Err(())` if the message couldn't be
+    /// sent to the child (eg. because it already terminated).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| {
+    ///         # children.with_exec(|ctx: BastionContext| async move {
+    ///             # loop {
+    ///                 # ctx.recv().await?;
+    ///             # }
+    ///         # })
+    ///     # }).unwrap();
+    ///     # let child_ref = &children_ref.elems()[0];
+    ///     # Bastion::start();
+    /// let exited: Exited = child_ref.exited().expect("Couldn't send the message.");
+    /// child_ref.stop().expect("Couldn't send the message.");
+    ///
+    /// // Waiting for it to stop...
+    /// let reason = run!(exited).expect("The child was dropped.");
+    /// assert!(!reason.is_fault());
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Exited`]: ../message/struct.Exited.html
+    /// [`TerminationReason`]: ../supervisor/enum.TerminationReason.html
+    /// [`BastionContext::watch`]: ../context/struct.BastionContext.html#method.watch
+    pub fn exited(&self) -> Result<Exited, ()> {
+        debug!("ChildRef({}): Waiting for it to exit.", self.id());
+        if self.mailbox.is_closed() {
+            return Err(());
+        }
+
+        let (sender, exited) = Exited::new();
+        let msg = BastionMessage::notify_exited(sender);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())?;
+
+        Ok(exited)
+    }
+
+    /// Sends a message to the child this `ChildRef` is referencing
+    /// to ask its children group to stop it and restart it on its
+    /// own, with a new future returned by the group's closure
+    /// (see [`Children::with_exec`]), without restarting its
+    /// other elements or involving its supervisor.
+    ///
+    /// The child is stopped like when its group is (see
+    /// [`Children::with_shutdown_mode`]) and, unless its group
+    /// uses [`Children::with_stable_ids`], is replaced by an
+    /// element with a new identifier (which this `ChildRef`
+    /// won't reference).
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # let child_ref = &children_ref.elems()[0];
+    /// child_ref.restart().expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_exec`]: ../children/struct.Children.html#method.with_exec
+    /// [`Children::with_shutdown_mode`]: ../children/struct.Children.html#method.with_shutdown_mode
+    /// [`Children::with_stable_ids`]: ../children/struct.Children.html#method.with_stable_ids
+    pub fn restart(&self) -> Result<(), ()> {
+        debug!("ChildRef({}): Restarting.", self.id());
+        let msg = BastionMessage::restart_elem(self.id().clone());
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
     /// Sends a message to the child this `ChildRef` is referencing
     /// to tell it to suicide.
     ///
+    /// The child receives this message before the messages that
+    /// were sent to it but that it didn't receive yet, which are
+    /// dropped.
+    ///
     /// This method returns `()` if it succeeded, or `Err(())`
     /// otherwise.
     ///
@@ -263,6 +691,144 @@ impl ChildRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Returns whether the child this `ChildRef` is referencing
+    /// is still alive, ie. whether it didn't stop, fault or get
+    /// killed (in which case the messages sent to it are lost).
+    ///
+    /// Note that an element that was restarted on its own or
+    /// with its group gets a new identifier (unless its group
+    /// uses [`Children::with_stable_ids`]), so a `ChildRef`
+    /// referencing it isn't alive anymore.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # let child_ref = &children_ref.elems()[0];
+    /// if !child_ref.is_alive() {
+    ///     // Look up the element again...
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_stable_ids`]: ../children/struct.Children.html#method.with_stable_ids
+    pub fn is_alive(&self) -> bool {
+        !self.mailbox.is_closed() && !self.sender.is_closed()
+    }
+
+    /// Sends a system message to the child this `ChildRef` is
+    /// referencing and waits for it to answer, returning whether
+    /// it did (ie. whether it is alive and handling its messages).
+    ///
+    /// Note that a child only answers once it started.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # let child_ref = children_ref.elems()[0].clone();
+    ///     # Bastion::start();
+    /// let alive: bool = run!(child_ref.ping());
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub async fn ping(&self) -> bool {
+        trace!("ChildRef({}): Pinging.", self.id());
+        let (sender, pong) = OneshotSender::new();
+        let msg = BastionMessage::ping(sender);
+        let env = Envelope::from_dead_letters(msg);
+        if self.send(env).is_err() {
+            return false;
+        }
+
+        pong.await.is_ok()
+    }
+
+    /// Returns statistics about the mailbox of the child this
+    /// `ChildRef` is referencing (the number of messages it
+    /// currently stores, the time they stayed in it, etc.).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # let child_ref = &children_ref.elems()[0];
+    /// let stats: MailboxStats = child_ref.mailbox_stats();
+    /// println!("{} messages are waiting to be handled.", stats.depth());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn mailbox_stats(&self) -> MailboxStats {
+        self.mailbox.stats()
+    }
+
+    pub(crate) fn mailbox_status(&self) -> &Arc<MailboxStatus> {
+        &self.mailbox
+    }
+
+    /// Returns statistics about the resources used by the children
+    /// group's element this `ChildRef` is referencing since it was
+    /// launched (see [`Usage`]), allowing to find the elements
+    /// using more than their share in large groups.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # let child_ref = &children_ref.elems()[0];
+    /// let usage: Usage = child_ref.usage();
+    /// println!("Polled for {:?}.", usage.poll_time());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Usage`]: ../usage/struct.Usage.html
+    pub fn usage(&self) -> Usage {
+        self.usage.usage()
+    }
+
+    pub(crate) fn usage_status(&self) -> &Arc<UsageStatus> {
+        &self.usage
+    }
+
+    // Waits until room was reserved for a message in the child's
+    // mailbox, returning `false` if the child stopped instead.
+    pub(crate) async fn reserve(&self) -> bool {
+        future::poll_fn(|ctx| self.mailbox.poll_reserve(ctx)).await
+    }
+
     /// Returns [`RefAddr`] for the child
     pub fn addr(&self) -> RefAddr {
         RefAddr::new(self.path.clone(), self.sender.clone())
@@ -270,9 +836,7 @@ impl ChildRef {
 
     pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
         trace!("ChildRef({}): Sending message: {:?}", self.id(), env);
-        self.sender
-            .unbounded_send(env)
-            .map_err(|err| err.into_inner())
+        self.sender.try_send(env)
     }
 
     pub(crate) fn sender(&self) -> &Sender {
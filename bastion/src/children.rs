@@ -1,26 +1,41 @@
 //!
 //! Children are a group of child supervised under a supervisor
-use crate::broadcast::{Broadcast, Parent, Sender};
+use crate::autoscale::Autoscaler;
+use crate::broadcast::{Broadcast, Parent};
 use crate::callbacks::Callbacks;
-use crate::child::{Child, Init};
+use crate::channel::{Channel, ChannelFactory};
+use crate::child::{Child, ChildConfig, Init};
 use crate::child_ref::ChildRef;
 use crate::children_ref::ChildrenRef;
-use crate::context::{BastionContext, BastionId, ContextState};
-use crate::envelope::Envelope;
-use crate::message::BastionMessage;
+use crate::context::{
+    BastionContext, BastionId, ContextState, Heartbeat, SavedState, Snapshot, Undelivered,
+};
+use crate::envelope::{Envelope, RefAddr, SignedMessage};
+use crate::mailbox::{FifoMailbox, Mailbox, MailboxFactory, MailboxStatus, OverflowPolicy};
+use crate::message::{
+    BastionMessage, Message, MessageSize, Msg, OneshotSender, Scaling, Terminated,
+};
 use crate::path::BastionPathElement;
+use crate::rate_limit::RateLimitPolicy;
+use crate::supervisor::{FaultReason, SupervisionStrategy, TerminationReason};
+use crate::system::SYSTEM;
+use crate::usage::UsageStatus;
 use bastion_executor::pool;
+use futures::future::{self, Either};
 use futures::pending;
 use futures::poll;
 use futures::prelude::*;
-use futures::stream::{FuturesOrdered, FuturesUnordered};
-use fxhash::FxHashMap;
+use futures_timer::Delay;
+use fxhash::{FxHashMap, FxHashSet};
 use lightproc::prelude::*;
-use qutex::Qutex;
-use std::fmt::Debug;
+use std::any::type_name;
+use std::error::Error;
+use std::fmt::{self, Debug, Formatter};
 use std::future::Future;
-use std::iter::FromIterator;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
 use std::task::Poll;
+use std::time::Duration;
 
 #[derive(Debug)]
 /// A children group that will contain a defined number of
@@ -70,43 +85,640 @@ use std::task::Poll;
 /// [`SupervisionStrategy`]: supervisor/enum.SupervisionStrategy.html
 pub struct Children {
     bcast: Broadcast,
+    // The name used instead of the group's identifier in the
+    // addresses of its elements (if any).
+    name: Option<String>,
     // The currently launched elements of the group.
-    launched: FxHashMap<BastionId, (Sender, RecoverableHandle<()>)>,
+    launched: FxHashMap<BastionId, (ChildRef, RecoverableHandle<Broadcast>)>,
+    // The elements of the group that will be launched once they
+    // receive their first message (when using `with_lazy_elems`).
+    idle: FxHashMap<BastionId, (ChildRef, Broadcast, Arc<ContextState>)>,
+    // The launched elements that were told to stop because the
+    // group was scaled down, and which will be removed from it
+    // once they did.
+    retiring: FxHashSet<BastionId>,
+    // The launched elements that were told to stop because they
+    // were asked to be restarted (see `ChildRef::restart`), and
+    // which will be restarted once they did.
+    restarting: FxHashSet<BastionId>,
+    // Whether the elements of the group are only launched once
+    // they receive their first message.
+    lazy: bool,
+    // Whether the elements of the group keep their identity
+    // when the group is restarted.
+    stable_ids: bool,
+    // The elements that stopped and will be relaunched with the
+    // same identity when the group is restarted (when using
+    // `with_stable_ids`).
+    stopped_elems: Vec<(ChildRef, Broadcast)>,
+    // Whether the elements of the group are restarted on their
+    // own instead of the whole group when they fault.
+    isolated: bool,
+    // The snapshots saved by the launched elements of the group,
+    // shared with them.
+    saved: FxHashMap<BastionId, SavedState>,
+    // The snapshots saved by the elements that were stopped to
+    // restart the group, by index, which will be restored by the
+    // elements replacing them.
+    snapshots: FxHashMap<usize, Snapshot>,
+    // The state cloned into the context of every element when it
+    // is launched (when using `with_seed_state`).
+    seed: Option<Seed>,
+    // Whether the messages that an element didn't handle are
+    // delivered to the element replacing it.
+    preserve_mailboxes: bool,
+    // The messages that the launched elements of the group
+    // didn't handle, shared with them (when using
+    // `with_preserved_mailboxes`).
+    undelivered: FxHashMap<BastionId, Undelivered>,
+    // The messages that the elements that were stopped to restart
+    // them (or the group) didn't handle, by index, which will be
+    // delivered to the elements replacing them.
+    leftover_msgs: FxHashMap<usize, Vec<SignedMessage>>,
+    // The hook called with every saved snapshot before it is
+    // restored (when using `with_before_restart_hook`).
+    restart_hook: Option<RestartHook>,
+    // The hooks called on the group's task before its elements
+    // are started for the first time, before they are started
+    // again once the group was restarted, and once they stopped.
+    before_start_hook: Option<LifecycleHook>,
+    after_restart_hook: Option<LifecycleHook>,
+    after_stop_hook: Option<LifecycleHook>,
+    // Whether the group was restarted since it was last started.
+    restarted: bool,
+    // The senders notified once the group terminated (see
+    // `ChildrenRef::stopped`).
+    stopped_senders: Vec<OneshotSender<Terminated>>,
+    // The launched elements that told they finished initializing
+    // (see `BastionContext::notify_started`), and the senders
+    // notified once all of them did (see `ChildrenRef::ready`).
+    ready: FxHashSet<BastionId>,
+    ready_senders: Vec<OneshotSender<()>>,
     // The closure returning the future that will be used by
     // every element of the group.
     init: Init,
+    // The index of every element of the group, passed to `init`
+    // and kept when the element is restarted.
+    indices: FxHashMap<BastionId, usize>,
     redundancy: usize,
+    // Whether the group can be scaled down to no elements (when
+    // it was created without any).
+    elastic: bool,
+    // The number of elements that are launched in addition to
+    // the group's redundancy to replace the ones that fault (when
+    // using `with_standbys`).
+    standby_count: usize,
+    // The elements that are launched but don't receive the
+    // messages sent to the group until they replace an element
+    // that faulted.
+    standbys: FxHashSet<BastionId>,
     // The callbacks called at the group's different lifecycle
     // events.
     callbacks: Callbacks,
+    // The settings used by every element of the group (and some
+    // by the group itself, like the validation that messages
+    // sent to it need to pass).
+    config: ChildConfig,
+    // The factory creating the mailbox of every element of
+    // the group.
+    mailbox: MailboxFactory,
+    // The factory creating the channel through which every
+    // element of the group receives its messages.
+    channel: ChannelFactory,
+    // The policy applied by every element of the group when
+    // its mailbox is full.
+    overflow_policy: OverflowPolicy,
+    // The maximum number of messages every element's future can
+    // retrieve before being forced to yield.
+    poll_budget: usize,
+    // The autoscaler resizing the group depending on its
+    // elements' mailboxes, and when it next checks them.
+    autoscaler: Option<(Autoscaler, Delay)>,
+    // How long the group waits for its elements to acknowledge
+    // a `PrepareStop` message before stopping them (if it asks
+    // them to).
+    prepare_stop: Option<Duration>,
+    // Whether the group is restarted by its supervisor when one
+    // of its elements stops or faults.
+    restart_policy: GroupRestartPolicy,
+    // The strategy the group's supervisor uses when it faults,
+    // instead of its own (if any). It is shared with the group's
+    // `ChildrenRef`s so that the supervisor uses the latest one
+    // when it changes at runtime.
+    strategy: Arc<RwLock<Option<SupervisionStrategy>>>,
+    // How often every element needs to send a heartbeat, how
+    // many of them it can miss before being considered as hung,
+    // what to do then and when the group next checks them.
+    heartbeat: Option<(Duration, usize, HangAction, Delay)>,
+    // The heartbeats sent by the launched elements of the group,
+    // shared with them.
+    heartbeats: FxHashMap<BastionId, Heartbeat>,
+    // The launched elements that are considered as hung, until
+    // they send a heartbeat again.
+    hung: FxHashSet<BastionId>,
+    // The hook called with every element considered as hung
+    // (when using `with_hang_hook`).
+    hang_hook: Option<HangHook>,
     // Messages that were received before the group was
     // started. Those will be "replayed" once a start message
     // is received.
-    pre_start_msgs: Vec<Envelope>,
+    pre_start_msgs: PreStartMsgs,
+    // How the element receiving a message sent using
+    // `ChildrenRef::send_one` is picked.
+    dispatch_mode: DispatchMode,
+    // The position (among the group's elements) of the element
+    // that will receive the next message sent using
+    // `ChildrenRef::send_one`.
+    next_elem: usize,
     started: bool,
+    // Whether the group's elements stopped being polled until
+    // the group is resumed (see `ChildrenRef::pause`).
+    paused: bool,
+    // A snapshot of the group's state which is shared by the
+    // `ChildrenRef`s returned by `as_ref` until it changes (eg.
+    // when elements are launched or stopped).
+    children_ref: ChildrenRef,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How the elements of a children group are stopped (eg. when
+/// the group or its supervisor is stopped).
+///
+/// This doesn't apply when the group is killed, faults or is
+/// restarted, in which case its elements are killed right away.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     // Every element will be given up to five seconds to handle
+///     // the messages in its mailbox before being killed...
+///     children.with_shutdown_mode(ShutdownMode::Timeout(Duration::from_secs(5)))
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub enum ShutdownMode {
+    /// The elements stop as soon as possible, dropping the
+    /// messages left in their mailbox. This is the default
+    /// mode.
+    ///
+    /// Because the messages telling the elements to stop are
+    /// received before the messages sent by users, the messages
+    /// that were sent before an element was told to stop but
+    /// that it didn't receive yet (eg. because its mailbox was
+    /// full) are dropped too.
+    Immediate,
+    /// The elements keep running until their future retrieved
+    /// all the messages of their mailbox (including the ones
+    /// that were sent before they were told to stop but that
+    /// they didn't receive yet, and the ones received in the
+    /// meantime), and then stop.
+    ///
+    /// Note that an element whose future never retrieves its
+    /// messages never stops (see [`ShutdownMode::Timeout`]).
+    ///
+    /// [`ShutdownMode::Timeout`]: #variant.Timeout
+    Drain,
+    /// Like [`ShutdownMode::Drain`], except that the elements
+    /// that didn't stop after the given duration are killed.
+    ///
+    /// [`ShutdownMode::Drain`]: #variant.Drain
+    Timeout(Duration),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Which element of a children group receives a message sent
+/// using [`ChildrenRef::send_one`].
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     // Every message will be sent to the element with the
+///     // fewest messages waiting in its mailbox...
+///     children.with_dispatch_mode(DispatchMode::LeastLoaded)
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildrenRef::send_one`]: ../children_ref/struct.ChildrenRef.html#method.send_one
+pub enum DispatchMode {
+    /// The elements receive the messages in turn. This is the
+    /// default mode.
+    RoundRobin,
+    /// The element with the fewest messages waiting in its
+    /// mailbox (see [`MailboxStats::depth`]) receives the
+    /// message, the elements receiving them in turn when
+    /// several of them are equally loaded.
+    ///
+    /// [`MailboxStats::depth`]: ../mailbox/struct.MailboxStats.html#method.depth
+    LeastLoaded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Whether a children group is restarted by its supervisor
+/// when one of its elements stops (because its future returned
+/// `Ok(())`) or faults (because its future returned `Err(())`
+/// or panicked).
+///
+/// In all cases, the whole group is stopped or killed when one
+/// of its elements stops or faults.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     // The group will never be restarted...
+///     children.with_restart_policy(GroupRestartPolicy::Temporary)
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub enum GroupRestartPolicy {
+    /// The group is always restarted, even when one of its
+    /// elements stopped normally.
+    Permanent,
+    /// The group is only restarted when one of its elements
+    /// faulted. This is the default policy.
+    Transient,
+    /// The group is never restarted, even when one of its
+    /// elements faulted.
+    Temporary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What an element of a children group does when the polls of
+/// its future keep exceeding the deadline set with
+/// [`Children::with_poll_deadline`].
+///
+/// A warning is always logged.
+///
+/// [`Children::with_poll_deadline`]: struct.Children.html#method.with_poll_deadline
+pub enum SlowPollAction {
+    /// The element only logs a warning and keeps running.
+    Warn,
+    /// The element faults, letting its supervisor handle it
+    /// (eg. by restarting it) depending on its strategy.
+    Escalate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What a children group does with its elements that stopped
+/// sending heartbeats, when using [`Children::with_heartbeat`].
+///
+/// A warning is always logged and the hook set with
+/// [`Children::with_hang_hook`] (if any) is called.
+///
+/// [`Children::with_heartbeat`]: struct.Children.html#method.with_heartbeat
+/// [`Children::with_hang_hook`]: struct.Children.html#method.with_hang_hook
+pub enum HangAction {
+    /// The element keeps running.
+    Warn,
+    /// The element is killed, letting the group handle it
+    /// depending on its restart policy.
+    Kill,
+    /// The element is stopped and restarted on its own (see
+    /// [`ChildRef::restart`]).
+    ///
+    /// [`ChildRef::restart`]: ../child_ref/struct.ChildRef.html#method.restart
+    Restart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What happens to the messages that a children group or one of
+/// its elements receives before being started once it buffered
+/// as many of them as allowed by [`Children::with_pre_start_limit`].
+///
+/// A warning is always logged.
+///
+/// [`Children::with_pre_start_limit`]: struct.Children.html#method.with_pre_start_limit
+pub enum PreStartOverflow {
+    /// The messages are dropped.
+    Drop,
+    /// The messages are dropped and the elements refuse the next
+    /// ones (the methods of [`ChildRef`] sending messages failing)
+    /// until they are started. Note that the messages sent to the
+    /// whole group can't be refused and are only dropped.
+    ///
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    Fail,
+    /// The messages are sent to the dead letters.
+    DeadLetters,
+}
+
+#[derive(Debug)]
+// The messages received by a children group or one of its
+// elements before it was started, which are replayed once it
+// is.
+pub(crate) struct PreStartMsgs {
+    msgs: Vec<Envelope>,
+    // How many of the messages were sent by users (the system
+    // messages are never limited).
+    user_msgs: usize,
+    limit: Option<(usize, PreStartOverflow)>,
+}
+
+// The hook called with the `ChildRef` of every element that
+// is considered as hung.
+struct HangHook(Box<dyn Fn(ChildRef) + Send + Sync>);
+
+// Creates a clone of the state given to `with_seed_state` for
+// every element that is launched.
+struct Seed(Box<dyn Fn() -> Snapshot + Send + Sync>);
+
+// The hook called with the snapshot saved by an element before
+// it is restored by the element replacing it.
+struct RestartHook(
+    Box<dyn Fn(Snapshot) -> Pin<Box<dyn Future<Output = Option<Snapshot>> + Send>> + Send + Sync>,
+);
+
+// A hook called with the group's `ChildrenRef` at one of the
+// group's lifecycle events.
+struct LifecycleHook(
+    Box<dyn Fn(ChildrenRef) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>,
+);
+
+impl LifecycleHook {
+    fn new<H, F>(hook: H) -> Self
+    where
+        H: Fn(ChildrenRef) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        LifecycleHook(Box::new(move |children_ref: ChildrenRef| {
+            let fut: Pin<Box<dyn Future<Output = ()> + Send>> = Box::pin(hook(children_ref));
+            fut
+        }))
+    }
+}
+
+impl PreStartOverflow {
+    // Drops the message that exceeded the pre-start limit or
+    // sends it to the dead letters.
+    pub(crate) fn apply(self, env: Envelope) {
+        if self == PreStartOverflow::DeadLetters {
+            // FIXME: Err(env)
+            SYSTEM.dead_letters().send(env).ok();
+        }
+    }
+}
+
+impl PreStartMsgs {
+    pub(crate) fn new(limit: Option<(usize, PreStartOverflow)>) -> Self {
+        PreStartMsgs {
+            msgs: Vec::new(),
+            user_msgs: 0,
+            limit,
+        }
+    }
+
+    // Buffers the message, unless it exceeds the limit in which
+    // case it is returned with what should happen to it.
+    pub(crate) fn push(&mut self, env: Envelope) -> Result<(), (Envelope, PreStartOverflow)> {
+        if let BastionMessage::Message(_)
+        | BastionMessage::SendOne(_)
+        | BastionMessage::SendSiblings { .. } = env.msg
+        {
+            if let Some((max, policy)) = self.limit {
+                if self.user_msgs >= max {
+                    return Err((env, policy));
+                }
+            }
+
+            self.user_msgs += 1;
+        }
+
+        self.msgs.push(env);
+        Ok(())
+    }
+
+    // Whether the next message sent by a user would exceed the
+    // limit.
+    pub(crate) fn is_full(&self) -> bool {
+        match self.limit {
+            Some((max, _)) => self.user_msgs >= max,
+            None => false,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.msgs.len()
+    }
+
+    pub(crate) fn limit(&self) -> Option<(usize, PreStartOverflow)> {
+        self.limit
+    }
+
+    // Removes all the messages, releasing their memory.
+    pub(crate) fn take(&mut self) -> Vec<Envelope> {
+        self.user_msgs = 0;
+        std::mem::replace(&mut self.msgs, Vec::new())
+    }
+
+    // Drops the system messages, keeping the ones sent by users.
+    pub(crate) fn retain_user_msgs(&mut self) {
+        self.msgs.retain(|env| match env.msg {
+            BastionMessage::Message(_)
+            | BastionMessage::SendOne(_)
+            | BastionMessage::SendSiblings { .. } => true,
+            _ => false,
+        });
+    }
 }
 
 impl Children {
     pub(crate) fn new(bcast: Broadcast) -> Self {
         debug!("Children({}): Initializing.", bcast.id());
+        let name = None;
         let launched = FxHashMap::default();
+        let idle = FxHashMap::default();
+        let retiring = FxHashSet::default();
+        let restarting = FxHashSet::default();
+        let lazy = false;
+        let stable_ids = false;
+        let stopped_elems = Vec::new();
+        let isolated = false;
+        let saved = FxHashMap::default();
+        let snapshots = FxHashMap::default();
+        let seed = None;
+        let preserve_mailboxes = false;
+        let undelivered = FxHashMap::default();
+        let leftover_msgs = FxHashMap::default();
+        let restart_hook = None;
+        let before_start_hook = None;
+        let after_restart_hook = None;
+        let after_stop_hook = None;
+        let restarted = false;
+        let stopped_senders = Vec::new();
+        let ready = FxHashSet::default();
+        let ready_senders = Vec::new();
         let init = Init::default();
+        let indices = FxHashMap::default();
         let redundancy = 1;
+        let elastic = false;
+        let standby_count = 0;
+        let standbys = FxHashSet::default();
         let callbacks = Callbacks::new();
-        let pre_start_msgs = Vec::new();
+        let config = ChildConfig::default();
+        let mailbox = MailboxFactory::default();
+        let channel = ChannelFactory::default();
+        let overflow_policy = OverflowPolicy::default();
+        let poll_budget = 128;
+        let autoscaler = None;
+        let prepare_stop = None;
+        let restart_policy = GroupRestartPolicy::default();
+        let strategy: Arc<RwLock<Option<SupervisionStrategy>>> = Arc::default();
+        let heartbeat = None;
+        let heartbeats = FxHashMap::default();
+        let hung = FxHashSet::default();
+        let hang_hook = None;
+        let pre_start_msgs = PreStartMsgs::new(None);
+        let dispatch_mode = DispatchMode::default();
+        let next_elem = 0;
         let started = false;
+        let paused = false;
+        let children_ref = ChildrenRef::new(
+            bcast.id().clone(),
+            name.clone(),
+            bcast.sender().clone(),
+            bcast.path().clone(),
+            Vec::new(),
+            config.validation.clone(),
+            strategy.clone(),
+        );
 
         Children {
             bcast,
+            name,
             launched,
+            idle,
+            retiring,
+            restarting,
+            lazy,
+            stable_ids,
+            stopped_elems,
+            isolated,
+            saved,
+            snapshots,
+            seed,
+            preserve_mailboxes,
+            undelivered,
+            leftover_msgs,
+            restart_hook,
+            before_start_hook,
+            after_restart_hook,
+            after_stop_hook,
+            restarted,
+            stopped_senders,
+            ready,
+            ready_senders,
             init,
+            indices,
             redundancy,
+            elastic,
+            standby_count,
+            standbys,
             callbacks,
+            config,
+            mailbox,
+            channel,
+            overflow_policy,
+            poll_budget,
+            autoscaler,
+            prepare_stop,
+            restart_policy,
+            strategy,
+            heartbeat,
+            heartbeats,
+            hung,
+            hang_hook,
             pre_start_msgs,
+            dispatch_mode,
+            next_elem,
             started,
+            paused,
+            children_ref,
         }
     }
 
+    /// Creates a new children group that isn't supervised yet,
+    /// to be configured by chaining its `with_*` methods and then
+    /// deployed using [`Bastion::children_from`] or
+    /// [`SupervisorRef::children_from`].
+    ///
+    /// This allows building a group's configuration step by step
+    /// (eg. in a function returning it) instead of in the closure
+    /// passed to [`Bastion::children`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children = Children::builder()
+    ///     .with_redundancy(8)
+    ///     .with_name("workers")
+    ///     .with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Send and receive messages...
+    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
+    ///             // ...and return `Ok(())` or `Err(())` when you are done...
+    ///             Ok(())
+    ///         }
+    ///     });
+    ///
+    /// let children_ref: ChildrenRef = Bastion::children_from(children)
+    ///     .expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::children_from`]: ../struct.Bastion.html#method.children_from
+    /// [`SupervisorRef::children_from`]: ../supervisor/struct.SupervisorRef.html#method.children_from
+    /// [`Bastion::children`]: ../struct.Bastion.html#method.children
+    pub fn builder() -> Self {
+        // The group has no parent until it is deployed (and moved
+        // under its supervisor by `SupervisorRef::children_from`).
+        let bcast = Broadcast::new(
+            Parent::none(),
+            BastionPathElement::Children(BastionId::new()),
+        );
+
+        Children::new(bcast)
+    }
+
     fn stack(&self) -> ProcStack {
         trace!("Children({}): Creating ProcStack.", self.id());
         // FIXME: with_pid
@@ -119,21 +731,39 @@ impl Children {
             self.id(),
             bcast.id()
         );
-        // TODO: stop or kill?
         self.kill().await;
+        self.save_snapshots().await;
+        self.save_undelivered_msgs();
+        self.retiring.clear();
+        self.restarting.clear();
+        self.heartbeats.clear();
+        self.hung.clear();
+        self.standbys.clear();
 
         self.bcast = bcast;
         self.started = false;
+        self.restarted = true;
+        self.refresh_ref();
 
-        trace!(
-            "Children({}): Removing {} pre-start messages.",
-            self.id(),
-            self.pre_start_msgs.len()
-        );
-        self.pre_start_msgs.clear();
-        self.pre_start_msgs.shrink_to_fit();
+        if self.preserve_mailboxes {
+            // The messages are kept, to be replayed once the
+            // restarted group is started.
+            self.pre_start_msgs.retain_user_msgs();
+        } else {
+            trace!(
+                "Children({}): Removing {} pre-start messages.",
+                self.id(),
+                self.pre_start_msgs.len()
+            );
+            self.pre_start_msgs.take();
+        }
 
         self.launch_elems();
+
+        // The snapshots and messages of the elements that weren't
+        // replaced (eg. because of lazy elements) are dropped.
+        self.snapshots.clear();
+        self.leftover_msgs.clear();
     }
 
     /// Returns this children group's identifier.
@@ -173,25 +803,58 @@ impl Children {
     }
 
     pub(crate) fn as_ref(&self) -> ChildrenRef {
+        trace!(
+            "Children({}): Cloning ChildrenRef({}).",
+            self.id(),
+            self.id()
+        );
+        self.children_ref.clone()
+    }
+
+    // Moves the group under the supervisor deploying it (when it
+    // was created using `Children::builder`).
+    pub(crate) fn reparent(&mut self, parent: Parent) {
+        debug!("Children({}): Reparenting.", self.id());
+        self.bcast.reparent(parent);
+        self.refresh_ref();
+    }
+
+    // Rebuilds the snapshot returned by `as_ref`, which needs
+    // to be done every time the group's identity, validation or
+    // elements change.
+    fn refresh_ref(&mut self) {
         trace!(
             "Children({}): Creating new ChildrenRef({}).",
             self.id(),
             self.id()
         );
-        // TODO: clone or ref?
         let id = self.bcast.id().clone();
         let sender = self.bcast.sender().clone();
         let path = self.bcast.path().clone();
 
-        let mut children = Vec::with_capacity(self.launched.len());
-        for (id, (sender, _)) in &self.launched {
-            trace!("Children({}): Creating new ChildRef({}).", self.id(), id);
-            // TODO: clone or ref?
-            let child = ChildRef::new(id.clone(), sender.clone(), path.clone());
-            children.push(child);
+        let mut children = Vec::with_capacity(self.launched.len() + self.idle.len());
+        for (id, (child_ref, _)) in self.launched.iter() {
+            if !self.retiring.contains(id) && !self.standbys.contains(id) {
+                children.push(child_ref.clone());
+            }
+        }
+        for (id, (child_ref, _, _)) in self.idle.iter() {
+            if !self.standbys.contains(id) {
+                children.push(child_ref.clone());
+            }
         }
 
-        ChildrenRef::new(id, sender, path, children)
+        self.children_ref = ChildrenRef::new(
+            id,
+            self.name.clone(),
+            sender,
+            path,
+            children,
+            self.config.validation.clone(),
+            self.strategy.clone(),
+        )
+        .with_dispatch_mode(self.dispatch_mode)
+        .with_restart_policy(self.restart_policy);
     }
 
     /// Sets the closure taking a [`BastionContext`] and returning a
@@ -247,28 +910,39 @@ impl Children {
         self
     }
 
-    /// Sets the number of number of elements this children group will
-    /// contain. Each element will call the closure passed in
-    /// [`with_exec`] and run the returned future until it stops,
-    /// panics or another element in the group stops or panics.
+    /// Sets the closure taking a [`BastionContext`] and returning
+    /// a [`Future`] that every element of this children group
+    /// will execute, like [`with_exec`] but allowing the future
+    /// to return any error convertible into a boxed [`Error`]
+    /// instead of `()`.
     ///
-    /// The default number of elements a children group contains is `1`.
+    /// When the future of an element returns an error, the group
+    /// faults with [`FaultReason::Failure`], carrying the error
+    /// for its supervisor (eg. its fault callbacks or strategy)
+    /// and the elements watching it to inspect it.
     ///
     /// # Arguments
     ///
-    /// * `redundancy` - The number of elements this group will contain.
+    /// * `init` - The closure taking a [`BastionContext`] and
+    ///     returning a [`Future`] that will be used by every
+    ///     element of this children group.
     ///
     /// # Example
     ///
     /// ```rust
     /// # use bastion::prelude::*;
+    /// # use std::io;
     /// #
     /// # fn main() {
     ///     # Bastion::init();
     ///     #
     /// Bastion::children(|children| {
-    ///     // Note that "1" is the default number of elements.
-    ///     children.with_redundancy(1)
+    ///     children.with_fallible_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Any error convertible into a `Box<dyn Error>`...
+    ///             Err::<(), _>(io::Error::new(io::ErrorKind::Other, "oops"))
+    ///         }
+    ///     })
     /// }).expect("Couldn't create the children group.");
     ///     #
     ///     # Bastion::start();
@@ -277,32 +951,42 @@ impl Children {
     /// # }
     /// ```
     ///
+    /// [`BastionContext`]: context/struct.BastionContext.html
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`Error`]: https://doc.rust-lang.org/std/error/trait.Error.html
     /// [`with_exec`]: #method.with_exec
-    pub fn with_redundancy(mut self, redundancy: usize) -> Self {
-        trace!(
-            "Children({}): Setting redundancy: {}",
-            self.id(),
-            redundancy
-        );
-        if redundancy == std::usize::MIN {
-            self.redundancy = redundancy.saturating_add(1);
-        } else {
-            self.redundancy = redundancy;
-        }
-
+    /// [`FaultReason::Failure`]: supervisor/enum.FaultReason.html#variant.Failure
+    pub fn with_fallible_exec<I, F, E>(mut self, init: I) -> Self
+    where
+        I: Fn(BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        trace!("Children({}): Setting fallible exec closure.", self.id());
+        self.init = Init::fallible(init);
         self
     }
 
-    /// Sets the callbacks that will get called at this children group's
-    /// different lifecycle events.
+    /// Sets the closure taking a [`BastionContext`] and the index
+    /// of the element in the group and returning a [`Future`]
+    /// that every element of this children group will execute,
+    /// allowing the elements of a group to behave differently
+    /// while being supervised and receiving broadcasts together.
     ///
-    /// See [`Callbacks`]'s documentation for more information about the
-    /// different callbacks available.
+    /// The elements are indexed from `0` to the group's
+    /// redundancy (see [`with_redundancy`]), and an element that
+    /// is restarted keeps its index. Elements added to a running
+    /// group (see [`ChildrenRef::scale_to`]) are given the
+    /// lowest unused indices.
+    ///
+    /// This replaces the closure set with [`with_exec`] (and the
+    /// other way around).
     ///
     /// # Arguments
     ///
-    /// * `callbacks` - The callbacks that will get called for this
-    ///     children group.
+    /// * `init` - The closure taking the index of an element and
+    ///     a [`BastionContext`] and returning a [`Future`] that
+    ///     will be used by the element.
     ///
     /// # Example
     ///
@@ -313,19 +997,18 @@ impl Children {
     ///     # Bastion::init();
     ///     #
     /// Bastion::children(|children| {
-    ///     let callbacks = Callbacks::new()
-    ///         .with_before_start(|| println!("Children group started."))
-    ///         .with_after_stop(|| println!("Children group stopped."));
-    ///
     ///     children
-    ///         .with_callbacks(callbacks)
-    ///         .with_exec(|ctx| {
-    ///             // -- Children group started.
+    ///         .with_redundancy(3)
+    ///         .with_indexed_exec(|index, ctx: BastionContext| {
     ///             async move {
-    ///                 // ...
-    ///                 # Ok(())
+    ///                 if index == 0 {
+    ///                     // The first element coordinates the others...
+    ///                 } else {
+    ///                     // ...which do the actual work.
+    ///                 }
+    ///
+    ///                 Ok(())
     ///             }
-    ///             // -- Children group stopped.
     ///         })
     /// }).expect("Couldn't create the children group.");
     ///     #
@@ -335,55 +1018,2114 @@ impl Children {
     /// # }
     /// ```
     ///
-    /// [`Callbacks`]: struct.Callbacks.html
-    pub fn with_callbacks(mut self, callbacks: Callbacks) -> Self {
-        trace!(
-            "Children({}): Setting callbacks: {:?}",
-            self.id(),
-            callbacks
-        );
-        self.callbacks = callbacks;
+    /// [`BastionContext`]: context/struct.BastionContext.html
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`with_redundancy`]: #method.with_redundancy
+    /// [`with_exec`]: #method.with_exec
+    /// [`ChildrenRef::scale_to`]: children_ref/struct.ChildrenRef.html#method.scale_to
+    pub fn with_indexed_exec<I, F>(mut self, init: I) -> Self
+    where
+        I: Fn(usize, BastionContext) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!("Children({}): Setting indexed exec closure.", self.id());
+        self.init = Init::indexed(init);
         self
     }
 
-    async fn stop(&mut self) {
-        debug!("Children({}): Stopping.", self.id());
-        self.bcast.stop_children();
-
-        let launched = self.launched.drain().map(|(_, (_, launched))| launched);
-        FuturesUnordered::from_iter(launched)
-            .for_each_concurrent(None, |_| async {
-                trace!("Children({}): Unknown child stopped.", self.id());
-            })
-            .await;
+    /// Sets the closure taking a [`BastionContext`] and a clone
+    /// of the given initialization arguments and returning a
+    /// [`Future`] that every element of this children group will
+    /// execute, instead of having the closure capture them (see
+    /// [`with_exec`]).
+    ///
+    /// The arguments are cloned every time an element is
+    /// launched (including when it is restarted).
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments passed to the closure.
+    /// * `init` - The closure taking a [`BastionContext`] and the
+    ///     arguments and returning a [`Future`] that will be used
+    ///     by every element of this children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::sync::Arc;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let config = Arc::new(String::from("postgres://localhost"));
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec_args(config, |ctx: BastionContext, config: Arc<String>| {
+    ///         async move {
+    ///             // Use the configuration...
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext`]: context/struct.BastionContext.html
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`with_exec`]: #method.with_exec
+    pub fn with_exec_args<A, I, F>(mut self, args: A, init: I) -> Self
+    where
+        A: Clone + Send + Sync + 'static,
+        I: Fn(BastionContext, A) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!(
+            "Children({}): Setting exec closure with arguments.",
+            self.id()
+        );
+        self.init = Init::new(move |ctx| init(ctx, args.clone()));
+        self
     }
 
+    /// Sets the closure taking a [`BastionContext`] and the
+    /// initialization arguments of an element and returning a
+    /// [`Future`] that every element of this children group will
+    /// execute, as well as the group's redundancy (see
+    /// [`with_redundancy`]) to the number of arguments given.
+    ///
+    /// Every element is given a clone of the arguments at its
+    /// index in the group (see [`with_indexed_exec`]), which it
+    /// gets again when it is restarted. The elements added to the
+    /// group while it is running (see [`ChildrenRef::scale_to`])
+    /// are given the arguments at their index modulo the number
+    /// of arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments of every element of the group (at
+    ///     least one).
+    /// * `init` - The closure taking a [`BastionContext`] and the
+    ///     arguments of an element and returning a [`Future`]
+    ///     that will be used by the element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Three elements, each listening on its own port...
+    ///     children.with_elems_args(vec![8080, 8081, 8082], |ctx: BastionContext, port: u16| {
+    ///         async move {
+    ///             // ...
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext`]: context/struct.BastionContext.html
+    /// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+    /// [`with_redundancy`]: #method.with_redundancy
+    /// [`with_indexed_exec`]: #method.with_indexed_exec
+    /// [`ChildrenRef::scale_to`]: children_ref/struct.ChildrenRef.html#method.scale_to
+    pub fn with_elems_args<A, I, F>(self, args: Vec<A>, init: I) -> Self
+    where
+        A: Clone + Send + Sync + 'static,
+        I: Fn(BastionContext, A) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<(), ()>> + Send + 'static,
+    {
+        trace!(
+            "Children({}): Setting exec closure with arguments for {} elements.",
+            self.id(),
+            args.len()
+        );
+        if args.is_empty() {
+            warn!(
+                "Children({}): Ignoring exec closure without arguments.",
+                self.id()
+            );
+            return self;
+        }
+
+        let redundancy = args.len();
+        self.with_redundancy(redundancy)
+            .with_indexed_exec(move |index, ctx| init(ctx, args[index % args.len()].clone()))
+    }
+
+    /// Sets the name of this children group, used instead of its
+    /// identifier in its address and in the addresses of its
+    /// elements (see [`Bastion::actor_at`]).
+    ///
+    /// Unlike its identifier, the group's name is kept when it
+    /// is restarted.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the group, which shouldn't contain
+    ///     any `/`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // The address of its first element will be
+    ///     // "bastion://sys/user/workers/0"...
+    ///     children.with_name("workers")
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::actor_at`]: struct.Bastion.html#method.actor_at
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Self {
+        let name = name.into();
+        trace!("Children({}): Setting name: {}", self.id(), name);
+        self.name = Some(name);
+        self.refresh_ref();
+        self
+    }
+
+    /// Sets the number of number of elements this children group will
+    /// contain. Each element will call the closure passed in
+    /// [`with_exec`] and run the returned future until it stops,
+    /// panics or another element in the group stops or panics.
+    ///
+    /// The default number of elements a children group contains is `1`.
+    ///
+    /// A group can also be created without any element, reserving
+    /// its place in the system until elements are added to it (see
+    /// [`ChildrenRef::add_elem`] and [`ChildrenRef::scale_to`]).
+    /// Such a group can also be scaled back down to no elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `redundancy` - The number of elements this group will contain.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Note that "1" is the default number of elements.
+    ///     children.with_redundancy(1)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_exec`]: #method.with_exec
+    /// [`ChildrenRef::add_elem`]: children_ref/struct.ChildrenRef.html#method.add_elem
+    /// [`ChildrenRef::scale_to`]: children_ref/struct.ChildrenRef.html#method.scale_to
+    pub fn with_redundancy(mut self, redundancy: usize) -> Self {
+        trace!(
+            "Children({}): Setting redundancy: {}",
+            self.id(),
+            redundancy
+        );
+        self.redundancy = redundancy;
+        self.elastic = redundancy == 0;
+        self
+    }
+
+    /// Makes this children group launch the given number of
+    /// elements in addition to its redundancy (see
+    /// [`with_redundancy`]) as hot standbys, which run their
+    /// future (eg. to initialize themselves) but don't receive
+    /// the messages sent to the group and aren't part of its
+    /// elements (see [`ChildrenRef::elems`]).
+    ///
+    /// When an element of the group faults, a standby is promoted
+    /// to replace it right away (instead of restarting the whole
+    /// group or the element, see [`with_isolated_elems`]), and the
+    /// element that faulted is restarted as a standby (unless the
+    /// group's restart policy is [`GroupRestartPolicy::Temporary`]).
+    ///
+    /// Note that standbys are launched even if the group's
+    /// elements are lazy (see [`with_lazy_elems`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The number of standbys this group will contain.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Four elements will handle the messages sent to the
+    ///     // group, while two others will be ready to replace them...
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_standbys(2)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_redundancy`]: #method.with_redundancy
+    /// [`with_isolated_elems`]: #method.with_isolated_elems
+    /// [`with_lazy_elems`]: #method.with_lazy_elems
+    /// [`ChildrenRef::elems`]: children_ref/struct.ChildrenRef.html#method.elems
+    /// [`GroupRestartPolicy::Temporary`]: enum.GroupRestartPolicy.html#variant.Temporary
+    pub fn with_standbys(mut self, count: usize) -> Self {
+        trace!("Children({}): Setting standbys: {}", self.id(), count);
+        self.standby_count = count;
+        self
+    }
+
+    /// Sets the callbacks that will get called at this children group's
+    /// different lifecycle events.
+    ///
+    /// See [`Callbacks`]'s documentation for more information about the
+    /// different callbacks available.
+    ///
+    /// # Arguments
+    ///
+    /// * `callbacks` - The callbacks that will get called for this
+    ///     children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     let callbacks = Callbacks::new()
+    ///         .with_before_start(|| println!("Children group started."))
+    ///         .with_after_stop(|| println!("Children group stopped."));
+    ///
+    ///     children
+    ///         .with_callbacks(callbacks)
+    ///         .with_exec(|ctx| {
+    ///             // -- Children group started.
+    ///             async move {
+    ///                 // ...
+    ///                 # Ok(())
+    ///             }
+    ///             // -- Children group stopped.
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Callbacks`]: struct.Callbacks.html
+    pub fn with_callbacks(mut self, callbacks: Callbacks) -> Self {
+        trace!(
+            "Children({}): Setting callbacks: {:?}",
+            self.id(),
+            callbacks
+        );
+        self.callbacks = callbacks;
+        self
+    }
+
+    /// Sets the closure that will decide whether a message sent to
+    /// this children group or one of its elements should be accepted
+    /// or rejected, before it gets enqueued.
+    ///
+    /// When a message is rejected while being sent through a
+    /// [`ChildrenRef`] or [`ChildRef`], it is returned to its sender
+    /// as an error. Messages that are rejected after having been
+    /// routed (eg. broadcasted by a supervisor) are dropped instead,
+    /// which makes the [`Answer`] of an "asked" message resolve to
+    /// `Err(())`.
+    ///
+    /// # Arguments
+    ///
+    /// * `validator` - The closure taking a reference to a [`Msg`] and
+    ///     returning `true` if it should be accepted or `false` if it
+    ///     should be rejected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_validator(|msg: &Msg| {
+    ///         // Only accept `u64`s...
+    ///         msg.peek::<u64>().is_some()
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// // This message will be rejected...
+    /// assert!(children_ref.broadcast("A message containing data.").is_err());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: children_ref/struct.ChildrenRef.html
+    /// [`ChildRef`]: child_ref/struct.ChildRef.html
+    /// [`Answer`]: message/struct.Answer.html
+    /// [`Msg`]: message/struct.Msg.html
+    pub fn with_validator<V>(mut self, validator: V) -> Self
+    where
+        V: Fn(&Msg) -> bool + Send + Sync + 'static,
+    {
+        trace!("Children({}): Setting validator.", self.id());
+        self.config.validation = self.config.validation.with_validator(validator);
+        self.refresh_ref();
+        self
+    }
+
+    /// Sets the maximum size (in bytes) that a message of type `M`
+    /// sent to this children group or one of its elements can have to
+    /// be accepted.
+    ///
+    /// The size of a message is the one returned by its
+    /// [`MessageSize`] implementation, which includes the heap
+    /// allocations it owns (eg. the length of a `String`). Messages
+    /// of other types aren't checked, and this can be called once for
+    /// every type whose size should be bounded.
+    ///
+    /// Messages that are too large are rejected the same way as the
+    /// ones rejected by [`with_validator`].
+    ///
+    /// # Arguments
+    ///
+    /// * `max_size` - The maximum size of a message of type `M`, in
+    ///     bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_max_message_size::<String>(8)
+    ///         .with_max_message_size::<Vec<u8>>(1024)
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// // This message will be accepted...
+    /// assert!(children_ref.broadcast("Small".to_string()).is_ok());
+    /// // ...but not this one.
+    /// assert!(children_ref.broadcast("Much too large".to_string()).is_err());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`MessageSize`]: message/trait.MessageSize.html
+    /// [`with_validator`]: #method.with_validator
+    pub fn with_max_message_size<M>(mut self, max_size: usize) -> Self
+    where
+        M: Message + MessageSize,
+    {
+        trace!(
+            "Children({}): Setting max size of {}: {}",
+            self.id(),
+            type_name::<M>(),
+            max_size
+        );
+        self.config.validation = self.config.validation.with_max_size::<M>(max_size);
+        self.refresh_ref();
+        self
+    }
+
+    /// Sets the closure creating the [`Mailbox`] that every element
+    /// of this children group will use to store the messages it
+    /// receives until its future retrieves them.
+    ///
+    /// The closure is called once for every element that is
+    /// launched (including when the group is restarted).
+    ///
+    /// The default mailbox is a [`FifoMailbox`].
+    ///
+    /// # Arguments
+    ///
+    /// * `mailbox` - The closure returning a new [`Mailbox`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Note that `FifoMailbox` is the default mailbox.
+    ///     children.with_mailbox(FifoMailbox::new)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Mailbox`]: mailbox/trait.Mailbox.html
+    /// [`FifoMailbox`]: mailbox/struct.FifoMailbox.html
+    pub fn with_mailbox<F, M>(mut self, mailbox: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+        M: Mailbox,
+    {
+        trace!("Children({}): Setting mailbox.", self.id());
+        self.mailbox = MailboxFactory::new(mailbox);
+        self
+    }
+
+    /// Sets the [`Channel`] creating the channel through which
+    /// every element of this children group will receive its
+    /// messages (except for system messages, which are always
+    /// sent through an unbounded channel).
+    ///
+    /// The default channel is an [`UnboundedChannel`].
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The [`Channel`] creating a channel for every
+    ///     element that is launched (including when the group is
+    ///     restarted).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Sending a message to an element will fail if 1024 of them
+    ///     // are already waiting for it to receive them...
+    ///     children.with_channel(BoundedChannel::with_capacity(1024))
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Channel`]: channel/trait.Channel.html
+    /// [`UnboundedChannel`]: channel/struct.UnboundedChannel.html
+    pub fn with_channel<C: Channel>(mut self, channel: C) -> Self {
+        trace!("Children({}): Setting channel: {:?}", self.id(), channel);
+        self.channel = ChannelFactory::new(channel);
+        self
+    }
+
+    /// Sets the maximum number of ready messages that every element
+    /// of this children group will handle at once, before polling
+    /// its future and yielding to let other children run.
+    ///
+    /// A higher batch size reduces the number of times an element
+    /// gets rescheduled when it receives a lot of messages, while a
+    /// lower one makes it poll its future more often.
+    ///
+    /// The default batch size is `64` (and it can't be lower than
+    /// `1`).
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_size` - The maximum number of messages handled at once.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_batch_size(256)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        trace!(
+            "Children({}): Setting batch size: {}",
+            self.id(),
+            batch_size
+        );
+        self.config.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets the maximum number of messages that the future of
+    /// every element of this children group can retrieve (using
+    /// [`BastionContext::recv`] or [`BastionContext::try_recv`])
+    /// before being forced to yield, so that an element which
+    /// always has messages available can't starve the other
+    /// children running on the same thread.
+    ///
+    /// The default poll budget is `128` (and it can't be lower
+    /// than `1`).
+    ///
+    /// # Arguments
+    ///
+    /// * `poll_budget` - The maximum number of messages retrieved
+    ///     before yielding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_poll_budget(32)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::recv`]: ../context/struct.BastionContext.html#method.recv
+    /// [`BastionContext::try_recv`]: ../context/struct.BastionContext.html#method.try_recv
+    pub fn with_poll_budget(mut self, poll_budget: usize) -> Self {
+        trace!(
+            "Children({}): Setting poll budget: {}",
+            self.id(),
+            poll_budget
+        );
+        self.poll_budget = poll_budget.max(1);
+        self
+    }
+
+    /// Pins every element of this children group to the given
+    /// cores, so that they only get executed by the executor's
+    /// threads running on those cores instead of migrating
+    /// across all of them.
+    ///
+    /// This is useful for latency-critical elements which would
+    /// benefit from keeping their caches warm. Cores that don't
+    /// exist are ignored and by default, elements can run on any
+    /// core.
+    ///
+    /// # Arguments
+    ///
+    /// * `cores` - The identifiers of the cores the elements are
+    ///     allowed to run on (an element runs on a single one at
+    ///     a time).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Every element will only run on the first two cores...
+    ///     children.with_core_affinity(vec![0, 1])
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_core_affinity<I>(mut self, cores: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.config.affinity = cores.into_iter().collect();
+        trace!(
+            "Children({}): Setting core affinity: {:?}",
+            self.id(),
+            self.config.affinity
+        );
+        self
+    }
+
+    /// Limits the number of messages that every element of this
+    /// children group can receive per second (up to one second
+    /// worth of messages being allowed in bursts), protecting
+    /// slow elements from bursty producers.
+    ///
+    /// The messages exceeding the limit are either delayed or
+    /// dropped, depending on the given [`RateLimitPolicy`]. By
+    /// default, the elements' intake rate isn't limited.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_per_second` - The maximum number of messages that
+    ///     every element can receive per second (it can't be
+    ///     lower than `1`).
+    /// * `policy` - What the elements do with the messages
+    ///     exceeding the limit.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_rate_limit(1000, RateLimitPolicy::Reject)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`RateLimitPolicy`]: rate_limit/enum.RateLimitPolicy.html
+    pub fn with_rate_limit(mut self, max_per_second: u32, policy: RateLimitPolicy) -> Self {
+        trace!(
+            "Children({}): Setting rate limit: {} messages per second ({:?})",
+            self.id(),
+            max_per_second,
+            policy
+        );
+        self.config.rate_limit = Some((max_per_second, policy));
+        self
+    }
+
+    /// Sets the autoscaler of this children group, which
+    /// regularly checks how many messages are waiting in its
+    /// elements' mailboxes and starts or retires elements
+    /// (between the autoscaler's bounds) depending on it.
+    ///
+    /// The elements are started or retired the same way as with
+    /// [`ChildrenRef::scale_to`], and the group keeps its size
+    /// when it is restarted. By default, the group isn't
+    /// autoscaled and keeps the number of elements set with
+    /// [`with_redundancy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `autoscaler` - The autoscaler's configuration.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_autoscaler(Autoscaler::new(1, 8))
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::scale_to`]: children_ref/struct.ChildrenRef.html#method.scale_to
+    /// [`with_redundancy`]: #method.with_redundancy
+    pub fn with_autoscaler(mut self, autoscaler: Autoscaler) -> Self {
+        trace!(
+            "Children({}): Setting autoscaler: {:?}",
+            self.id(),
+            autoscaler
+        );
+        let delay = Delay::new(autoscaler.interval());
+        self.autoscaler = Some((autoscaler, delay));
+        self
+    }
+
+    /// Sets the policy applied by every element of this children
+    /// group when its mailbox refuses a message because it is
+    /// full (see [`Mailbox::is_full`]).
+    ///
+    /// The default policy is [`OverflowPolicy::DropNewest`].
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy applied when a mailbox is full.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox(|| RingMailbox::with_capacity(1024))
+    ///         .with_overflow_policy(OverflowPolicy::Block)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Mailbox::is_full`]: mailbox/trait.Mailbox.html#method.is_full
+    /// [`OverflowPolicy::DropNewest`]: mailbox/enum.OverflowPolicy.html#variant.DropNewest
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        trace!(
+            "Children({}): Setting overflow policy: {:?}",
+            self.id(),
+            policy
+        );
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Limits the number of messages that every element of this
+    /// children group stores until its future retrieves them, and
+    /// sets the policy applied to the messages exceeding it.
+    ///
+    /// This is the same as using a [`FifoMailbox`] created with
+    /// [`FifoMailbox::with_limit`] and [`with_overflow_policy`],
+    /// and prevents an element that doesn't retrieve its messages
+    /// from accumulating them without limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of messages every element
+    ///     stores.
+    /// * `policy` - The policy applied to the messages exceeding
+    ///     it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // The messages received by an element which already
+    ///     // stores 1000 of them will be sent to the dead letters...
+    ///     children.with_mailbox_limit(1000, OverflowPolicy::DeadLetters)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`FifoMailbox`]: mailbox/struct.FifoMailbox.html
+    /// [`FifoMailbox::with_limit`]: mailbox/struct.FifoMailbox.html#method.with_limit
+    /// [`with_overflow_policy`]: #method.with_overflow_policy
+    pub fn with_mailbox_limit(self, limit: usize, policy: OverflowPolicy) -> Self {
+        trace!(
+            "Children({}): Setting mailbox limit: {} ({:?})",
+            self.id(),
+            limit,
+            policy
+        );
+        self.with_mailbox(move || FifoMailbox::with_limit(limit))
+            .with_overflow_policy(policy)
+    }
+
+    /// Sets how the elements of this children group are stopped
+    /// when the group is being stopped. Its elements are always
+    /// killed right away when it is killed, faults or is restarted.
+    ///
+    /// The default mode is [`ShutdownMode::Immediate`].
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - How the elements of the group are stopped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_shutdown_mode(ShutdownMode::Drain)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ShutdownMode::Immediate`]: enum.ShutdownMode.html#variant.Immediate
+    pub fn with_shutdown_mode(mut self, mode: ShutdownMode) -> Self {
+        trace!("Children({}): Setting shutdown mode: {:?}", self.id(), mode);
+        self.config.shutdown_mode = mode;
+        self
+    }
+
+    /// Sets how the element receiving a message sent using
+    /// [`ChildrenRef::send_one`] is picked.
+    ///
+    /// The default mode is [`DispatchMode::RoundRobin`].
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - How the element receiving each message is
+    ///     picked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_dispatch_mode(DispatchMode::LeastLoaded)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::send_one`]: children_ref/struct.ChildrenRef.html#method.send_one
+    /// [`DispatchMode::RoundRobin`]: enum.DispatchMode.html#variant.RoundRobin
+    pub fn with_dispatch_mode(mut self, mode: DispatchMode) -> Self {
+        trace!("Children({}): Setting dispatch mode: {:?}", self.id(), mode);
+        self.dispatch_mode = mode;
+        self
+    }
+
+    /// Makes this children group ask a [`PrepareStop`] message to
+    /// its running elements before stopping them, giving them a
+    /// chance to finish their in-flight work and flush their
+    /// buffers.
+    ///
+    /// The elements are only stopped (see [`with_shutdown_mode`])
+    /// once all of them answered the message (or dropped it), or
+    /// once the given deadline passed. This doesn't apply when
+    /// the group is killed or restarted.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - How long to wait for the elements to answer
+    ///     before stopping them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_prepare_stop(Duration::from_secs(5))
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`PrepareStop`]: message/struct.PrepareStop.html
+    /// [`with_shutdown_mode`]: #method.with_shutdown_mode
+    pub fn with_prepare_stop(mut self, deadline: Duration) -> Self {
+        trace!(
+            "Children({}): Setting stop preparation deadline: {:?}",
+            self.id(),
+            deadline
+        );
+        self.prepare_stop = Some(deadline);
+        self
+    }
+
+    /// Sets whether this children group is restarted by its
+    /// supervisor when one of its elements stops or faults.
+    ///
+    /// The default policy is [`GroupRestartPolicy::Transient`].
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Whether the group is restarted when one of
+    ///     its elements stops or faults.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_restart_policy(GroupRestartPolicy::Permanent)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 // The group will be restarted once this
+    ///                 // element stops...
+    ///                 Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`GroupRestartPolicy::Transient`]: enum.GroupRestartPolicy.html#variant.Transient
+    pub fn with_restart_policy(mut self, policy: GroupRestartPolicy) -> Self {
+        trace!(
+            "Children({}): Setting restart policy: {:?}",
+            self.id(),
+            policy
+        );
+        self.restart_policy = policy;
+        self
+    }
+
+    /// Sets the strategy this children group's supervisor uses
+    /// when the group faults, overriding the one set with
+    /// [`Supervisor::with_strategy`] for this group only.
+    ///
+    /// This allows eg. a group to be restarted on its own under
+    /// a "one-for-all" supervisor. Combined with
+    /// [`GroupRestartPolicy::Temporary`], the group is never
+    /// restarted and its faults don't affect the other children
+    /// groups and supervisors of its supervisor.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy to use when the group faults.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_strategy(SupervisionStrategy::OneForAll)
+    ///         .children(|children| {
+    ///             // Only this group will be restarted when it
+    ///             // faults...
+    ///             children.with_supervision_strategy(SupervisionStrategy::OneForOne)
+    ///         })
+    ///         .children(|children| {
+    ///             // ...while all the groups will be restarted
+    ///             // when this one faults.
+    ///             children
+    ///         })
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Supervisor::with_strategy`]: ../supervisor/struct.Supervisor.html#method.with_strategy
+    /// [`GroupRestartPolicy::Temporary`]: enum.GroupRestartPolicy.html#variant.Temporary
+    pub fn with_supervision_strategy(self, strategy: SupervisionStrategy) -> Self {
+        trace!(
+            "Children({}): Setting supervision strategy: {:?}",
+            self.id(),
+            strategy
+        );
+        // FIXME: panics?
+        *self.strategy.write().unwrap() = Some(strategy);
+        self
+    }
+
+    /// Measures how long every poll of the future of every element
+    /// of this children group takes, acting when `max_slow_polls`
+    /// consecutive polls took longer than `deadline`. This helps
+    /// finding the elements that block the executor's threads
+    /// (eg. because they do blocking IO or heavy computations,
+    /// which should instead use [`BastionContext::blocking`]).
+    ///
+    /// By default, the duration of the polls isn't measured.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - The duration that a poll of an element's
+    ///     future shouldn't exceed.
+    /// * `max_slow_polls` - The number of consecutive polls
+    ///     exceeding the deadline after which the element acts
+    ///     (it can't be lower than `1`).
+    /// * `action` - What the element does then.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Every element will fault if its future blocks for more
+    ///     // than 10ms three times in a row...
+    ///     children.with_poll_deadline(Duration::from_millis(10), 3, SlowPollAction::Escalate)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::blocking`]: ../struct.BastionContext.html#method.blocking
+    pub fn with_poll_deadline(
+        mut self,
+        deadline: Duration,
+        max_slow_polls: usize,
+        action: SlowPollAction,
+    ) -> Self {
+        trace!(
+            "Children({}): Setting poll deadline: {:?} ({} times, {:?})",
+            self.id(),
+            deadline,
+            max_slow_polls,
+            action
+        );
+        self.config.poll_deadline = Some((deadline, max_slow_polls, action));
+        self
+    }
+
+    /// Limits how long the future of every element of this
+    /// children group can run once it is started. The elements
+    /// whose future didn't complete within `max_runtime` are
+    /// killed and fault with [`FaultReason::Timeout`], letting
+    /// the group handle them depending on its restart policy.
+    /// This is useful for batch-style elements that shouldn't run
+    /// forever.
+    ///
+    /// By default, the elements can run for as long as they need.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_runtime` - How long the future of every element can
+    ///     run.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Every element will fault if it is still running after
+    ///     // a minute...
+    ///     children.with_max_runtime(Duration::from_secs(60))
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`FaultReason::Timeout`]: supervisor/enum.FaultReason.html#variant.Timeout
+    pub fn with_max_runtime(mut self, max_runtime: Duration) -> Self {
+        trace!(
+            "Children({}): Setting max runtime: {:?}",
+            self.id(),
+            max_runtime
+        );
+        self.config.max_runtime = Some(max_runtime);
+        self
+    }
+
+    /// Limits the number of messages sent by users that this
+    /// children group and every one of its elements buffer while
+    /// they aren't started yet (eg. because [`Bastion::start`]
+    /// wasn't called yet or because the group is restarting),
+    /// applying `policy` to the ones exceeding it. This prevents
+    /// a late start from making the buffers grow unboundedly.
+    ///
+    /// By default, the number of buffered messages isn't limited.
+    /// Note that the system messages (eg. telling the group or
+    /// its elements to stop) are never limited.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of messages sent by users
+    ///     that the group and every element buffer.
+    /// * `policy` - What happens to the messages exceeding it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // The messages received before starting will be sent
+    ///     // to the dead letters after the 1000th one...
+    ///     children.with_pre_start_limit(1000, PreStartOverflow::DeadLetters)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::start`]: ../struct.Bastion.html#method.start
+    pub fn with_pre_start_limit(mut self, max: usize, policy: PreStartOverflow) -> Self {
+        trace!(
+            "Children({}): Setting pre-start limit: {} ({:?})",
+            self.id(),
+            max,
+            policy
+        );
+        self.config.pre_start_limit = Some((max, policy));
+        self.pre_start_msgs = PreStartMsgs::new(self.config.pre_start_limit);
+        self
+    }
+
+    /// Requires every element of this children group to send
+    /// heartbeats (using [`BastionContext::heartbeat`]) at least
+    /// every `interval`, considering the elements that missed
+    /// `max_missed` of them in a row as hung and acting on them.
+    /// This helps finding the elements that are stuck (eg. waiting
+    /// for something that will never happen) without faulting.
+    ///
+    /// A hung element is only acted on once, until it sends a
+    /// heartbeat again. By default, no heartbeats are required.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The duration between two heartbeats.
+    /// * `max_missed` - The number of consecutive heartbeats an
+    ///     element can miss before being considered as hung (it
+    ///     can't be lower than `1`).
+    /// * `action` - What the group does with hung elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     // Every element will be restarted if it doesn't send a
+    ///     // heartbeat for three seconds...
+    ///     children.with_heartbeat(Duration::from_secs(1), 3, HangAction::Restart)
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::heartbeat`]: ../struct.BastionContext.html#method.heartbeat
+    pub fn with_heartbeat(
+        mut self,
+        interval: Duration,
+        max_missed: usize,
+        action: HangAction,
+    ) -> Self {
+        trace!(
+            "Children({}): Setting heartbeat: {:?} ({} missed, {:?})",
+            self.id(),
+            interval,
+            max_missed,
+            action
+        );
+        let max_missed = max_missed.max(1);
+        self.heartbeat = Some((interval, max_missed, action, Delay::new(interval)));
+        self
+    }
+
+    /// Sets the hook called with the [`ChildRef`] of every element
+    /// of this children group that is considered as hung because
+    /// it stopped sending heartbeats (see [`with_heartbeat`]),
+    /// before the group acts on it.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The closure called with the hung element's
+    ///     [`ChildRef`], on the group's task.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_heartbeat(Duration::from_secs(1), 3, HangAction::Warn)
+    ///         .with_hang_hook(|child_ref: ChildRef| {
+    ///             println!("{} is hung.", child_ref.id());
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: child_ref/struct.ChildRef.html
+    /// [`with_heartbeat`]: #method.with_heartbeat
+    pub fn with_hang_hook<H>(mut self, hook: H) -> Self
+    where
+        H: Fn(ChildRef) + Send + Sync + 'static,
+    {
+        trace!("Children({}): Setting hang hook.", self.id());
+        self.hang_hook = Some(HangHook(Box::new(hook)));
+        self
+    }
+
+    /// Makes the elements of this children group only get
+    /// launched (thus calling the closure set with [`with_exec`])
+    /// once they receive their first message, the messages sent
+    /// to them being queued until then.
+    ///
+    /// This allows to declare large supervision trees without
+    /// paying the cost of launching all of their elements
+    /// upfront. Note that broadcasting a message to the group
+    /// launches all of its elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(10_000)
+    ///         .with_lazy_elems()
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 // Only the elements receiving messages will
+    ///                 // get here...
+    ///                 # Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// // ...like this one.
+    /// let child_ref = &children_ref.elems()[42];
+    /// child_ref.tell_anonymously("Wake up!").expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_exec`]: #method.with_exec
+    pub fn with_lazy_elems(mut self) -> Self {
+        trace!("Children({}): Setting lazy elements.", self.id());
+        self.lazy = true;
+        self
+    }
+
+    /// Makes the elements of this children group keep their
+    /// identifier when the group is restarted, so that the
+    /// [`ChildRef`]s referencing them stay valid (the messages
+    /// sent through them while the group is restarting are
+    /// received by the restarted elements).
+    ///
+    /// Note that the elements need to stop to be relaunched with
+    /// the same identity: an element that panics outside of its
+    /// future, that is killed because it didn't stop in time (see
+    /// [`ShutdownMode::Timeout`]) or that wasn't launched yet (see
+    /// [`with_lazy_elems`]) is replaced by a new one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_stable_ids()
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// // This `ChildRef` will stay valid even if the group is
+    /// // restarted...
+    /// let child_ref = children_ref.elems()[0].clone();
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: struct.ChildRef.html
+    /// [`ShutdownMode::Timeout`]: enum.ShutdownMode.html#variant.Timeout
+    /// [`with_lazy_elems`]: #method.with_lazy_elems
+    pub fn with_stable_ids(mut self) -> Self {
+        trace!("Children({}): Setting stable identifiers.", self.id());
+        self.stable_ids = true;
+        self
+    }
+
+    /// Makes the messages that an element of this children group
+    /// didn't handle before being restarted (because it faulted,
+    /// was asked to or because the whole group was restarted) get
+    /// delivered to the element replacing it, instead of being
+    /// dropped. This includes the messages waiting in its mailbox
+    /// and the ones it received before being started.
+    ///
+    /// Note that the messages of an element that panicked outside
+    /// of its future or that wasn't launched yet (see
+    /// [`with_lazy_elems`]) are still dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_isolated_elems()
+    ///         .with_preserved_mailboxes()
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_lazy_elems`]: #method.with_lazy_elems
+    pub fn with_preserved_mailboxes(mut self) -> Self {
+        trace!("Children({}): Setting preserved mailboxes.", self.id());
+        self.preserve_mailboxes = true;
+        self
+    }
+
+    /// Makes the elements of this children group be restarted on
+    /// their own when they fault, instead of the whole group
+    /// being restarted by its supervisor.
+    ///
+    /// The group respawns a faulted element itself, without
+    /// notifying its supervisor or waiting for it: the group
+    /// keeps its identifier, its other elements keep running
+    /// and the supervisor's statistics don't record a restart.
+    /// This makes recovering stateless workers faster.
+    ///
+    /// The group's [`GroupRestartPolicy`] then applies to each of
+    /// its elements: an element that faulted is restarted unless
+    /// the policy is `Temporary`, and an element that stopped is
+    /// only restarted if the policy is `Permanent`. The elements
+    /// that aren't restarted are removed from the group, which
+    /// stops once all of them were removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(4)
+    ///         .with_isolated_elems()
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 // Only this element will be restarted if
+    ///                 // this returns an error...
+    ///                 ctx.recv().await?;
+    ///                 Err(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`GroupRestartPolicy`]: enum.GroupRestartPolicy.html
+    pub fn with_isolated_elems(mut self) -> Self {
+        trace!("Children({}): Setting isolated elements.", self.id());
+        self.isolated = true;
+        self
+    }
+
+    /// Makes the elements of this children group receive an
+    /// [`Exit`] message when they are told to stop (eg. when the
+    /// group is stopped or restarted), instead of being stopped
+    /// right away. They can then clean up and decide when to stop
+    /// by returning from their future.
+    ///
+    /// Note that the group waits for its elements to stop, unless
+    /// it uses [`ShutdownMode::Timeout`], and that the elements
+    /// are still killed right away when the group is killed.
+    /// The [`Terminated`] messages of the elements they watch
+    /// are received as ordinary messages in any case.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_trapped_exits()
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 loop {
+    ///                     msg! { ctx.recv().await?,
+    ///                         _msg: Exit => {
+    ///                             // Flush what needs to be and stop...
+    ///                             return Ok(());
+    ///                         };
+    ///                         _: _ => ();
+    ///                     }
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Exit`]: ../message/struct.Exit.html
+    /// [`ShutdownMode::Timeout`]: enum.ShutdownMode.html#variant.Timeout
+    /// [`Terminated`]: ../message/struct.Terminated.html
+    pub fn with_trapped_exits(mut self) -> Self {
+        trace!("Children({}): Setting trapped exits.", self.id());
+        self.config.trap_exits = true;
+        self
+    }
+
+    /// Sets the state that every element of this children group
+    /// starts from, which is cloned into the element's context
+    /// every time it is launched (including when it is restarted)
+    /// and can be taken using [`BastionContext::take_seed_state`],
+    /// instead of having the closure passed to [`with_exec`]
+    /// capture and clone it.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state cloned for every element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::collections::HashMap;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let mut config = HashMap::new();
+    /// config.insert("endpoint", "http://localhost:8080");
+    ///
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_seed_state(config)
+    ///         .with_exec(|ctx: BastionContext| async move {
+    ///             let config: HashMap<&str, &str> = ctx
+    ///                 .take_seed_state()
+    ///                 .expect("Couldn't take the seed state.");
+    ///             // Use the configuration...
+    ///
+    ///             Ok(())
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::take_seed_state`]: context/struct.BastionContext.html#method.take_seed_state
+    /// [`with_exec`]: #method.with_exec
+    pub fn with_seed_state<S>(mut self, state: S) -> Self
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        trace!("Children({}): Setting seed state.", self.id());
+        self.seed = Some(Seed(Box::new(move || Snapshot::new(state.clone()))));
+        self
+    }
+
+    /// Sets the hook called with the snapshot saved by an element
+    /// of this children group (using [`BastionContext::save_state`])
+    /// when the group or the element is restarted, before the
+    /// element replacing it can restore it (using
+    /// [`BastionContext::take_restored_state`]).
+    ///
+    /// The hook can inspect or replace the snapshot, or return
+    /// `None` for the new element to start from scratch. Without a
+    /// hook, the snapshot is restored as-is.
+    ///
+    /// Note that the new elements only get the identity of the
+    /// ones they replace when using [`with_stable_ids`], but they
+    /// always get the snapshot saved by the element with the same
+    /// index (see [`ChildRef::index`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The closure returning the future called with
+    ///     every saved snapshot before it is restored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_before_restart_hook(|snapshot: Snapshot| {
+    ///             async move {
+    ///                 // Only resume from the previous count if it
+    ///                 // isn't too high...
+    ///                 match snapshot.downcast_ref::<usize>() {
+    ///                     Some(count) if *count < 100 => Some(snapshot),
+    ///                     _ => None,
+    ///                 }
+    ///             }
+    ///         })
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 let mut count = ctx.take_restored_state::<usize>().unwrap_or(0);
+    ///                 loop {
+    ///                     ctx.recv().await?;
+    ///                     count += 1;
+    ///                     ctx.save_state(count);
+    ///                 }
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionContext::save_state`]: ../context/struct.BastionContext.html#method.save_state
+    /// [`BastionContext::take_restored_state`]: ../context/struct.BastionContext.html#method.take_restored_state
+    /// [`with_stable_ids`]: #method.with_stable_ids
+    /// [`ChildRef::index`]: ../child_ref/struct.ChildRef.html#method.index
+    pub fn with_before_restart_hook<H, F>(mut self, hook: H) -> Self
+    where
+        H: Fn(Snapshot) -> F + Send + Sync + 'static,
+        F: Future<Output = Option<Snapshot>> + Send + 'static,
+    {
+        trace!("Children({}): Setting before restart hook.", self.id());
+        let hook = Box::new(move |snapshot: Snapshot| {
+            let fut: Pin<Box<dyn Future<Output = Option<Snapshot>> + Send>> =
+                Box::pin(hook(snapshot));
+            fut
+        });
+
+        self.restart_hook = Some(RestartHook(hook));
+        self
+    }
+
+    /// Sets a hook called on the group's task when it is started,
+    /// before any of its elements starts running, which can be
+    /// used to acquire resources shared by the elements (eg. a
+    /// connection pool) instead of doing it in every element.
+    ///
+    /// Unlike the closures passed to [`Callbacks`], the hook is
+    /// asynchronous: the elements only start once the future it
+    /// returns completed. When the group is restarted, the hook
+    /// set with [`with_after_restart_hook`] is called instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The closure taking a [`ChildrenRef`]
+    ///     referencing the group and returning the future to
+    ///     complete before its elements start.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_before_start_hook(|children_ref: ChildrenRef| {
+    ///             async move {
+    ///                 // Open the connections used by the elements...
+    ///             }
+    ///         })
+    ///         .with_after_stop_hook(|children_ref: ChildrenRef| {
+    ///             async move {
+    ///                 // ...and close them once they stopped.
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Callbacks`]: struct.Callbacks.html
+    /// [`ChildrenRef`]: children_ref/struct.ChildrenRef.html
+    /// [`with_after_restart_hook`]: #method.with_after_restart_hook
+    pub fn with_before_start_hook<H, F>(mut self, hook: H) -> Self
+    where
+        H: Fn(ChildrenRef) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        trace!("Children({}): Setting before start hook.", self.id());
+        self.before_start_hook = Some(LifecycleHook::new(hook));
+        self
+    }
+
+    /// Sets a hook called on the group's task when it is started
+    /// again after being restarted by its supervisor, before any
+    /// of its new elements starts running.
+    ///
+    /// The elements only start once the future returned by the
+    /// hook completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The closure taking a [`ChildrenRef`]
+    ///     referencing the restarted group and returning the
+    ///     future to complete before its elements start.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_after_restart_hook(|children_ref: ChildrenRef| {
+    ///         async move {
+    ///             // Reopen the connections used by the elements...
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: children_ref/struct.ChildrenRef.html
+    pub fn with_after_restart_hook<H, F>(mut self, hook: H) -> Self
+    where
+        H: Fn(ChildrenRef) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        trace!("Children({}): Setting after restart hook.", self.id());
+        self.after_restart_hook = Some(LifecycleHook::new(hook));
+        self
+    }
+
+    /// Sets a hook called on the group's task once all its
+    /// elements stopped or were killed, whether the group itself
+    /// stopped, was killed or faulted (in which case it is called
+    /// before the group is restarted).
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The closure taking a [`ChildrenRef`]
+    ///     referencing the group and returning the future to
+    ///     complete before the group's task finishes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_after_stop_hook(|children_ref: ChildrenRef| {
+    ///         async move {
+    ///             // Release the resources used by the elements...
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: children_ref/struct.ChildrenRef.html
+    pub fn with_after_stop_hook<H, F>(mut self, hook: H) -> Self
+    where
+        H: Fn(ChildrenRef) -> F + Send + Sync + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        trace!("Children({}): Setting after stop hook.", self.id());
+        self.after_stop_hook = Some(LifecycleHook::new(hook));
+        self
+    }
+
+    // Takes the snapshot saved by the element and passes it
+    // through the restart hook (if any), keeping the result to
+    // be restored by the element replacing it (which has the
+    // same index).
+    async fn save_snapshot(&mut self, id: BastionId, index: usize) {
+        let snapshot = match self.saved.remove(&id).and_then(|saved| saved.take()) {
+            Some(snapshot) => snapshot,
+            None => return,
+        };
+
+        let snapshot = match &self.restart_hook {
+            Some(hook) => {
+                trace!(
+                    "Children({}): Calling before restart hook for Child({}).",
+                    self.id(),
+                    id
+                );
+                (hook.0)(snapshot).await
+            }
+            None => Some(snapshot),
+        };
+
+        if let Some(snapshot) = snapshot {
+            debug!(
+                "Children({}): Keeping snapshot of Child({}).",
+                self.id(),
+                id
+            );
+            self.snapshots.insert(index, snapshot);
+        }
+    }
+
+    async fn save_snapshots(&mut self) {
+        let ids = self.saved.keys().cloned().collect::<Vec<_>>();
+        for id in ids {
+            if let Some(index) = self.indices.get(&id).copied() {
+                self.save_snapshot(id, index).await;
+            }
+        }
+    }
+
+    // Keeps the messages that the elements that were stopped
+    // didn't handle, to deliver them to the elements replacing
+    // them.
+    fn save_undelivered_msgs(&mut self) {
+        let undelivered = std::mem::replace(&mut self.undelivered, FxHashMap::default());
+        for (id, undelivered) in undelivered {
+            let index = match self.indices.get(&id) {
+                Some(index) => *index,
+                None => continue,
+            };
+
+            let msgs = undelivered.take();
+            if !msgs.is_empty() {
+                debug!(
+                    "Children({}): Keeping {} messages of Child({}).",
+                    self.id(),
+                    msgs.len(),
+                    id
+                );
+                self.leftover_msgs.insert(index, msgs);
+            }
+        }
+    }
+
+    // Delivers the messages kept for the element with the given
+    // index to the element replacing it.
+    fn restore_undelivered_msgs(&mut self, index: usize, state: &ContextState) {
+        let msgs = match self.leftover_msgs.remove(&index) {
+            Some(msgs) => msgs,
+            None => return,
+        };
+
+        for msg in msgs {
+            state.push_msg(msg.msg, msg.sign);
+        }
+    }
+
+    // Takes the snapshot kept for the element with the given
+    // index, for the element replacing it.
+    fn take_snapshot(&mut self, index: usize) -> Option<Snapshot> {
+        self.snapshots.remove(&index)
+    }
+
+    // Keeps the broadcasts of the elements that stopped to
+    // relaunch them once the group is restarted (when using
+    // `with_stable_ids`).
+    fn keep_stopped<I>(&mut self, stopped: I)
+    where
+        I: IntoIterator<Item = (ChildRef, Option<Broadcast>)>,
+    {
+        if !self.stable_ids {
+            return;
+        }
+
+        for (child_ref, bcast) in stopped {
+            match bcast {
+                Some(bcast) => self.stopped_elems.push((child_ref, bcast)),
+                None => debug!(
+                    "Children({}): Child({}) can't be relaunched.",
+                    self.id(),
+                    child_ref.id()
+                ),
+            }
+        }
+    }
+
+    // Asks the launched elements to prepare to stop and waits
+    // until all of them answered or the deadline passed.
+    async fn prepare_stop(&mut self, deadline: Duration) {
+        debug!("Children({}): Preparing elements to stop.", self.id());
+        let mut answers = Vec::new();
+        for (child_ref, _) in self.launched.values() {
+            let (msg, answer) = BastionMessage::prepare_stop();
+            let env = Envelope::from_dead_letters(msg);
+            if child_ref.send(env).is_ok() {
+                answers.push(answer);
+            }
+        }
+
+        let answered = future::join_all(answers);
+        if let Either::Right(_) = future::select(answered, Delay::new(deadline)).await {
+            warn!(
+                "Children({}): Elements still preparing after {:?}, stopping them.",
+                self.id(),
+                deadline
+            );
+        }
+    }
+
+    async fn stop(&mut self) {
+        if let Some(deadline) = self.prepare_stop {
+            self.prepare_stop(deadline).await;
+        }
+
+        match self.config.shutdown_mode {
+            ShutdownMode::Immediate => (),
+            ShutdownMode::Drain => {
+                self.drain(None).await;
+                return;
+            }
+            ShutdownMode::Timeout(timeout) => {
+                self.drain(Some(timeout)).await;
+                return;
+            }
+        }
+
+        debug!("Children({}): Stopping.", self.id());
+        self.bcast.stop_children();
+        self.idle.clear();
+
+        let launched = self
+            .launched
+            .drain()
+            .map(|(_, (child_ref, launched))| launched.map(|bcast| (child_ref, bcast)))
+            .collect::<Vec<_>>();
+        self.refresh_ref();
+
+        let stopped = future::join_all(launched).await;
+        self.keep_stopped(stopped);
+    }
+
+    // Kills the elements right away, whatever the shutdown
+    // mode (which only applies when they are stopped).
     async fn kill(&mut self) {
         debug!("Children({}): Killing.", self.id());
         self.bcast.kill_children();
+        self.idle.clear();
 
-        let mut children = FuturesOrdered::new();
-        for (_, (_, launched)) in self.launched.drain() {
-            launched.cancel();
+        let mut children = Vec::new();
+        for (_, (child_ref, launched)) in self.launched.drain() {
+            // The elements that should keep their identity are
+            // given the time to handle the kill message.
+            if !self.stable_ids {
+                launched.cancel();
+            }
 
-            children.push(launched);
+            children.push(launched.map(|bcast| (child_ref, bcast)));
         }
+        self.refresh_ref();
 
-        children
-            .for_each_concurrent(None, |_| async {
-                trace!("Children({}): Unknown child stopped.", self.id());
-            })
-            .await;
+        let stopped = future::join_all(children).await;
+        self.keep_stopped(stopped);
+    }
+
+    // Tells the elements to stop once their mailbox is empty and
+    // waits for them to do so, killing them if they didn't after
+    // the timeout (if any), returning whether they all stopped
+    // in time.
+    async fn drain(&mut self, timeout: Option<Duration>) -> bool {
+        debug!("Children({}): Draining.", self.id());
+        self.bcast.stop_children();
+        self.idle.clear();
+
+        let (child_refs, mut launched): (Vec<_>, Vec<_>) = self
+            .launched
+            .drain()
+            .map(|(_, (child_ref, launched))| (child_ref, launched))
+            .unzip();
+        self.refresh_ref();
+
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => {
+                let stopped = future::join_all(launched).await;
+                self.keep_stopped(child_refs.into_iter().zip(stopped));
+                return true;
+            }
+        };
+
+        let drained = future::join_all(launched.iter_mut());
+        let stopped = match future::select(drained, Delay::new(timeout)).await {
+            Either::Left((stopped, _)) => Some(stopped),
+            Either::Right(_) => None,
+        };
+
+        match stopped {
+            Some(stopped) => {
+                self.keep_stopped(child_refs.into_iter().zip(stopped));
+                true
+            }
+            None => {
+                warn!(
+                    "Children({}): Elements still draining after {:?}, killing them.",
+                    self.id(),
+                    timeout
+                );
+                for launched in launched.iter() {
+                    launched.cancel();
+                }
+
+                future::join_all(launched).await;
+                false
+            }
+        }
     }
 
     fn stopped(&mut self) {
-        debug!("Children({}): Stopped.", self.id());
-        self.bcast.stopped();
+        self.terminated(TerminationReason::Normal);
+    }
+
+    fn faulted(&mut self, reason: FaultReason) {
+        self.terminated(TerminationReason::Fault(reason));
+    }
+
+    fn terminated(&mut self, reason: TerminationReason) {
+        self.notify_terminated(&reason);
+        self.bcast.terminated(reason);
     }
 
-    fn faulted(&mut self) {
-        debug!("Children({}): Faulted.", self.id());
-        self.bcast.faulted();
+    // Tells those waiting for the group to terminate that it did.
+    fn notify_terminated(&mut self, reason: &TerminationReason) {
+        debug!("Children({}): Terminated: {:?}", self.id(), reason);
+        for sender in self.stopped_senders.drain(..) {
+            sender.send(Terminated::new(self.bcast.id().clone(), reason.clone()));
+        }
+        // The group can't get ready anymore.
+        self.ready_senders.clear();
     }
 
     async fn handle(&mut self, env: Envelope) -> Result<(), ()> {
@@ -406,25 +3148,128 @@ impl Children {
                 ..
             } => {
                 self.kill().await;
+                self.terminated(TerminationReason::Killed);
+
+                return Err(());
+            }
+            Envelope {
+                msg: BastionMessage::StopWithin { deadline, sender },
+                ..
+            } => {
+                let gracefully = self.drain(Some(deadline)).await;
                 self.stopped();
+                sender.send(gracefully);
 
                 return Err(());
             }
-            // FIXME
+            // Children groups can't supervise what is deployed to
+            // them, so it is dropped.
             Envelope {
-                msg: BastionMessage::Deploy(_),
+                msg: BastionMessage::Deploy(deployment),
                 ..
-            } => unimplemented!(),
-            // FIXME
+            } => {
+                warn!(
+                    "Children({}): Dropping deployment: {:?}",
+                    self.id(),
+                    deployment
+                );
+            }
+            // Only elements can be watched.
+            Envelope {
+                msg: BastionMessage::Watch { watcher },
+                ..
+            } => {
+                debug!(
+                    "Children({}): Ignoring watch of Child({}).",
+                    self.id(),
+                    watcher.id()
+                );
+            }
+            Envelope {
+                msg: BastionMessage::Unwatch { watcher },
+                ..
+            } => {
+                debug!(
+                    "Children({}): Ignoring unwatch of Child({}).",
+                    self.id(),
+                    watcher
+                );
+            }
+            // Children groups don't supervise anything, so there is
+            // nothing to prune.
+            Envelope {
+                msg: BastionMessage::Prune { id },
+                ..
+            } => {
+                debug!("Children({}): Ignoring pruning of {}.", self.id(), id);
+            }
+            Envelope {
+                msg: BastionMessage::Scale(scaling),
+                ..
+            } => self.scale(scaling),
+            Envelope {
+                msg: BastionMessage::NotifyStopped { sender },
+                ..
+            } => self.stopped_senders.push(sender),
+            // Only elements notify when they exited.
+            Envelope {
+                msg: BastionMessage::NotifyExited { .. },
+                ..
+            } => {
+                debug!("Children({}): Ignoring exit notification.", self.id());
+            }
+            Envelope {
+                msg: BastionMessage::ElemReady { id },
+                ..
+            } => {
+                // FIXME: Err if false?
+                if self.launched.contains_key(&id) {
+                    debug!("Children({}): Child({}) is ready.", self.id(), id);
+                    self.ready.insert(id);
+                }
+            }
+            Envelope {
+                msg: BastionMessage::NotifyReady { sender },
+                ..
+            } => self.ready_senders.push(sender),
+            Envelope {
+                msg: BastionMessage::Pause,
+                ..
+            } => self.set_paused(true),
+            Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => self.set_paused(false),
+            Envelope {
+                msg: BastionMessage::RestartElem { id },
+                ..
+            } => self.restart_elem(id),
+            // Only elements can be pinged.
+            Envelope {
+                msg: BastionMessage::Ping { .. },
+                ..
+            } => {
+                debug!("Children({}): Ignoring ping.", self.id());
+            }
+            // Children groups prepare their elements to stop on
+            // their own.
             Envelope {
-                msg: BastionMessage::Prune { .. },
+                msg: BastionMessage::PrepareStop { .. },
                 ..
-            } => unimplemented!(),
-            // FIXME
+            } => {
+                debug!("Children({}): Ignoring stop preparation.", self.id());
+            }
+            // The strategy is only used by the group's supervisor,
+            // which reads it from the group's `ChildrenRef` the next
+            // time the group faults.
             Envelope {
-                msg: BastionMessage::SuperviseWith(_),
+                msg: BastionMessage::SuperviseWith(strategy),
                 ..
-            } => unimplemented!(),
+            } => {
+                debug!("Children({}): Setting strategy: {:?}", self.id(), strategy);
+                // FIXME: panics?
+                *self.strategy.write().unwrap() = Some(strategy);
+            }
             Envelope {
                 msg: BastionMessage::Message(ref message),
                 ..
@@ -434,30 +3279,108 @@ impl Children {
                     self.id(),
                     message
                 );
-                self.bcast.send_children(env);
+                self.bcast.send_children_except(env, &self.standbys);
+            }
+            Envelope {
+                msg: BastionMessage::SendOne(msg),
+                sign,
+            } => self.send_one(msg, sign),
+            Envelope {
+                msg: BastionMessage::SendSiblings { msg, except },
+                sign,
+            } => {
+                debug!(
+                    "Children({}): Broadcasting a message to the siblings of Child({}): {:?}",
+                    self.id(),
+                    except,
+                    msg
+                );
+                let mut excluded = self.standbys.clone();
+                excluded.insert(except);
+                let env = Envelope {
+                    msg: BastionMessage::Message(msg),
+                    sign,
+                };
+                self.bcast.send_children_except(env, &excluded);
             }
             Envelope {
-                msg: BastionMessage::Stopped { id },
+                msg:
+                    BastionMessage::Termination {
+                        id,
+                        reason: TerminationReason::Fault(reason),
+                    },
                 ..
             } => {
                 // FIXME: Err if false?
                 if self.launched.contains_key(&id) {
-                    debug!("Children({}): Child({}) stopped.", self.id(), id);
-                    self.stop().await;
-                    self.stopped();
+                    warn!(
+                        "Children({}): Child({}) faulted: {:?}",
+                        self.id(),
+                        id,
+                        reason
+                    );
+                    if self.retiring.remove(&id) {
+                        return self.recover_elem(id, false).await;
+                    }
+
+                    if self.restarting.remove(&id) {
+                        return self.recover_elem(id, true).await;
+                    }
+
+                    // Standbys are replaced on their own, and replace
+                    // the elements that fault.
+                    if self.standbys.contains(&id) || self.promote_standby(&id) {
+                        let restart = self.restart_policy != GroupRestartPolicy::Temporary;
+                        return self.recover_elem(id, restart).await;
+                    }
+
+                    if self.isolated {
+                        let restart = self.restart_policy != GroupRestartPolicy::Temporary;
+                        return self.recover_elem(id, restart).await;
+                    }
+
+                    self.kill().await;
+                    // The supervisor doesn't restart the group if
+                    // its restart policy is `Temporary`.
+                    self.faulted(reason);
 
                     return Err(());
                 }
             }
             Envelope {
-                msg: BastionMessage::Faulted { id },
+                msg: BastionMessage::Termination { id, reason },
                 ..
             } => {
                 // FIXME: Err if false?
                 if self.launched.contains_key(&id) {
-                    warn!("Children({}): Child({}) faulted.", self.id(), id);
-                    self.kill().await;
-                    self.faulted();
+                    debug!(
+                        "Children({}): Child({}) stopped: {:?}",
+                        self.id(),
+                        id,
+                        reason
+                    );
+                    if self.retiring.remove(&id) {
+                        return self.recover_elem(id, false).await;
+                    }
+
+                    if self.restarting.remove(&id) {
+                        return self.recover_elem(id, true).await;
+                    }
+
+                    if self.isolated || self.standbys.contains(&id) {
+                        let restart = self.restart_policy == GroupRestartPolicy::Permanent;
+                        return self.recover_elem(id, restart).await;
+                    }
+
+                    self.stop().await;
+                    // Faulting makes the supervisor restart the group.
+                    if self.restart_policy == GroupRestartPolicy::Permanent {
+                        self.faulted(FaultReason::Stopped);
+                    } else {
+                        // The group stops for the same reason as
+                        // its element.
+                        self.terminated(reason);
+                    }
 
                     return Err(());
                 }
@@ -467,11 +3390,36 @@ impl Children {
         Ok(())
     }
 
-    async fn run(mut self) -> Self {
+    async fn run(self) -> Self {
+        let children = self.run_loop().await;
+        if let Some(hook) = &children.after_stop_hook {
+            trace!("Children({}): Calling after stop hook.", children.id());
+            (hook.0)(children.as_ref()).await;
+        }
+
+        children
+    }
+
+    async fn run_loop(mut self) -> Self {
         debug!("Children({}): Launched.", self.id());
         loop {
-            for (_, launched) in self.launched.values_mut() {
-                let _ = poll!(launched);
+            // The elements that keep their identity give their
+            // broadcast back once they stop, which polling them
+            // here would lose.
+            if !self.stable_ids {
+                for (_, launched) in self.launched.values_mut() {
+                    let _ = poll!(launched);
+                }
+            }
+
+            if self.launch_idle().await.is_err() {
+                return self;
+            }
+
+            if self.started {
+                self.autoscale().await;
+                self.check_heartbeats().await;
+                self.notify_ready();
             }
 
             match poll!(&mut self.bcast.next()) {
@@ -486,15 +3434,30 @@ impl Children {
                         BastionMessage::Start
                     );
                     debug!("Children({}): Starting.", self.id());
+                    let hook = if self.restarted {
+                        &self.after_restart_hook
+                    } else {
+                        &self.before_start_hook
+                    };
+                    if let Some(hook) = hook {
+                        trace!("Children({}): Calling lifecycle hook.", self.id());
+                        (hook.0)(self.as_ref()).await;
+                    }
+
                     self.started = true;
+                    self.restarted = false;
+                    // The elements' heartbeats are only required
+                    // once they are started.
+                    for heartbeat in self.heartbeats.values() {
+                        heartbeat.beat();
+                    }
 
                     let msg = BastionMessage::start();
                     let env =
                         Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
                     self.bcast.send_children(env);
 
-                    let msgs = self.pre_start_msgs.drain(..).collect::<Vec<_>>();
-                    self.pre_start_msgs.shrink_to_fit();
+                    let msgs = self.pre_start_msgs.take();
 
                     debug!(
                         "Children({}): Replaying messages received before starting.",
@@ -513,7 +3476,15 @@ impl Children {
                         self.id(),
                         msg
                     );
-                    self.pre_start_msgs.push(msg);
+                    if let Err((env, policy)) = self.pre_start_msgs.push(msg) {
+                        warn!(
+                            "Children({}): Too many messages received before starting ({:?}): {:?}",
+                            self.id(),
+                            policy,
+                            env
+                        );
+                        policy.apply(env);
+                    }
                 }
                 Poll::Ready(Some(msg)) => {
                     trace!(
@@ -536,39 +3507,739 @@ impl Children {
 
     pub(crate) fn launch_elems(&mut self) {
         debug!("Children({}): Launching elements.", self.id());
-        for _ in 0..self.redundancy {
-            let parent = Parent::children(self.as_ref());
-            let bcast = Broadcast::new(parent, BastionPathElement::Child(BastionId::new()));
+        let parent = Parent::children(self.as_ref());
+
+        // The elements that stopped when the group was restarted
+        // are relaunched first, keeping their identity.
+        let stopped = std::mem::replace(&mut self.stopped_elems, Vec::new());
+        let count = self.redundancy + self.standby_count;
+        let relaunched = stopped.len().min(count);
+        let stopped = stopped.into_iter().take(relaunched).collect::<Vec<_>>();
+        // The relaunched elements keep their index.
+        self.indices
+            .retain(|id, _| stopped.iter().any(|(child_ref, _)| child_ref.id() == id));
+        for (child_ref, bcast) in stopped {
+            self.reuse_elem(&parent, child_ref, bcast);
+        }
+
+        for index in self.free_indices(count - relaunched) {
+            self.create_elem(&parent, index);
+        }
+
+        // The elements with the last indices are the standbys.
+        let redundancy = self.redundancy;
+        let standbys = self
+            .indices
+            .iter()
+            .filter(|(_, index)| **index >= redundancy)
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>();
+        self.standbys.extend(standbys);
+
+        // The snapshot is only rebuilt once all the elements were
+        // created, so that they all share the same one.
+        self.refresh_ref();
+
+        if self.lazy {
+            debug!(
+                "Children({}): {} elements will be launched on their first message.",
+                self.id(),
+                self.idle.len() - self.standbys.len()
+            );
+        }
+
+        self.launch_created();
+    }
+
+    // Returns the lowest index that no element of the group has.
+    fn next_index(&self) -> usize {
+        self.free_indices(1)[0]
+    }
+
+    // Returns the `count` lowest indices that no element of the
+    // group has.
+    fn free_indices(&self, count: usize) -> Vec<usize> {
+        let used = self.indices.values().copied().collect::<FxHashSet<_>>();
+        (0..)
+            .filter(|index| !used.contains(index))
+            .take(count)
+            .collect()
+    }
+
+    // Creates a new idle element with the given index.
+    fn create_elem(&mut self, parent: &Parent, index: usize) {
+        let element = BastionPathElement::Child(BastionId::new());
+        let mut bcast = Broadcast::with_channel(parent.clone(), element, &self.channel);
+        let mailbox = self.mailbox.create();
+        let status = Arc::new(MailboxStatus::new(
+            self.overflow_policy,
+            mailbox.max_len(),
+        ));
+        bcast.bind_mailbox(status.clone());
+
+        // TODO: clone or ref?
+        let id = bcast.id().clone();
+        let sender = bcast.sender().clone();
+        let path = bcast.path().clone();
+        let usage = Arc::new(UsageStatus::new());
+        let child_ref = ChildRef::new(
+            id.clone(),
+            index,
+            sender,
+            path,
+            self.config.validation.clone(),
+            status.clone(),
+            usage,
+        );
+
+        let state = ContextState::new(mailbox, status, self.poll_budget);
+        state.restore(self.take_snapshot(index));
+        state.seed(self.seed.as_ref().map(|seed| (seed.0)()));
+        self.restore_undelivered_msgs(index, &state);
+        let state = Arc::new(state);
+
+        self.bcast.register(&bcast);
+        self.indices.insert(id.clone(), index);
+        self.idle.insert(id, (child_ref, bcast, state));
+    }
+
+    // Creates an idle element reusing the identity (and index) of
+    // an element that stopped.
+    fn reuse_elem(&mut self, parent: &Parent, child_ref: ChildRef, mut bcast: Broadcast) {
+        bcast.reparent(parent.clone());
+        bcast.drop_system_msgs();
+
+        let id = bcast.id().clone();
+        let status = child_ref.mailbox_status().clone();
+        status.reopen();
+        let usage = child_ref.usage_status().clone();
+        let index = match self.indices.get(&id) {
+            Some(index) => *index,
+            None => {
+                let index = self.next_index();
+                self.indices.insert(id.clone(), index);
+                index
+            }
+        };
+        let child_ref = ChildRef::new(
+            id.clone(),
+            index,
+            bcast.sender().clone(),
+            bcast.path().clone(),
+            self.config.validation.clone(),
+            status.clone(),
+            usage,
+        );
+
+        let state = ContextState::new(self.mailbox.create(), status, self.poll_budget);
+        state.restore(self.take_snapshot(index));
+        state.seed(self.seed.as_ref().map(|seed| (seed.0)()));
+        self.restore_undelivered_msgs(index, &state);
+        let state = Arc::new(state);
+
+        self.bcast.register(&bcast);
+        self.idle.insert(id, (child_ref, bcast, state));
+    }
+
+    // Removes the element that stopped or faulted from the group
+    // and restarts it on its own if `restart` is true (when using
+    // `with_isolated_elems`), returning `Err(())` if the group
+    // stopped because it had no elements left.
+    async fn recover_elem(&mut self, id: BastionId, restart: bool) -> Result<(), ()> {
+        // FIXME: panics?
+        let (child_ref, launched) = self.launched.remove(&id).unwrap();
+        self.bcast.unregister(&id);
+        self.ready.remove(&id);
+        // The element already stopped, so this only waits for
+        // its future to be dropped.
+        let bcast = launched.await;
+        let index = self.indices.remove(&id);
+        self.heartbeats.remove(&id);
+        self.hung.remove(&id);
+        let undelivered = self.undelivered.remove(&id);
+        let standby = self.standbys.remove(&id);
+
+        if !restart {
+            debug!("Children({}): Removing Child({}).", self.id(), id);
+            self.saved.remove(&id);
+            self.refresh_ref();
+            // A group scaled down to no elements keeps running
+            // until elements are added to it again.
+            if self.launched.is_empty() && self.idle.is_empty() && self.redundancy > 0 {
+                self.stopped();
+                return Err(());
+            }
+
+            return Ok(());
+        }
+
+        debug!("Children({}): Restarting Child({}).", self.id(), id);
+        let index = index.unwrap_or_else(|| self.next_index());
+        self.save_snapshot(id.clone(), index).await;
+        if let Some(undelivered) = undelivered {
+            self.leftover_msgs.insert(index, undelivered.take());
+        }
+        // The snapshot needs to be rebuilt for the restarted
+        // element to get an up-to-date one.
+        self.refresh_ref();
+        let parent = Parent::children(self.as_ref());
+        match bcast {
+            Some(bcast) if self.stable_ids => {
+                self.indices.insert(id, index);
+                self.reuse_elem(&parent, child_ref, bcast)
+            }
+            _ => self.create_elem(&parent, index),
+        }
+        if standby {
+            // The element replacing a standby is a standby too.
+            let replacement = self
+                .indices
+                .iter()
+                .find(|(_, used)| **used == index)
+                .map(|(id, _)| id.clone());
+            self.standbys.extend(replacement);
+        }
+        self.refresh_ref();
+        self.launch_created();
+
+        Ok(())
+    }
+
+    // Promotes a standby to replace the element that faulted,
+    // which becomes a standby itself (to be restarted as one),
+    // returning whether the group had a standby.
+    fn promote_standby(&mut self, id: &BastionId) -> bool {
+        let promoted = match self.standbys.iter().next() {
+            Some(promoted) => promoted.clone(),
+            None => return false,
+        };
+
+        debug!(
+            "Children({}): Promoting Child({}) to replace Child({}).",
+            self.id(),
+            promoted,
+            id
+        );
+        self.standbys.remove(&promoted);
+        self.standbys.insert(id.clone());
+        true
+    }
+
+    // Launches the elements that were created (unless they are
+    // lazy and not standbys).
+    fn launch_created(&mut self) {
+        let lazy = self.lazy;
+        let standbys = &self.standbys;
+        let ids = self
+            .idle
+            .keys()
+            .filter(|id| !lazy || standbys.contains(id))
+            .cloned()
+            .collect::<Vec<_>>();
+        for id in ids {
+            // FIXME: panics?
+            let (child_ref, bcast, state) = self.idle.remove(&id).unwrap();
+            if self.started {
+                let msg = BastionMessage::start();
+                let start =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                // FIXME: Err(msg)
+                bcast.sender().try_send(start).ok();
+            }
+
+            self.launch_elem(child_ref, bcast, state, None);
+        }
+    }
+
+    // Tells the launched elements to stop or start polling their
+    // future again (the idle ones aren't woken up, and will be
+    // paused when launched if needed).
+    fn set_paused(&mut self, paused: bool) {
+        if paused == self.paused {
+            return;
+        }
+
+        if paused {
+            debug!("Children({}): Pausing.", self.id());
+        } else {
+            debug!("Children({}): Resuming.", self.id());
+            // The elements didn't send heartbeats while paused.
+            for heartbeat in self.heartbeats.values() {
+                heartbeat.beat();
+            }
+        }
+
+        self.paused = paused;
+        for id in self.launched.keys() {
+            let msg = if paused {
+                BastionMessage::pause()
+            } else {
+                BastionMessage::resume()
+            };
+            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+            self.bcast.send_child(id, env);
+        }
+    }
+
+    // Delivers the message to a single element of the group,
+    // picked according to the group's dispatch mode.
+    fn send_one(&mut self, msg: Msg, sign: RefAddr) {
+        let elems = self.children_ref.elems();
+        if elems.is_empty() {
+            warn!(
+                "Children({}): No element to send the message to: {:?}",
+                self.id(),
+                msg
+            );
+            return;
+        }
+
+        let first = self.next_elem % elems.len();
+        let pos = match self.dispatch_mode {
+            DispatchMode::RoundRobin => first,
+            // The elements are checked starting from the next one
+            // in turn, so that equally loaded elements (eg. when
+            // they didn't retrieve the last messages yet) still
+            // receive the messages in turn.
+            DispatchMode::LeastLoaded => (first..elems.len())
+                .chain(0..first)
+                .min_by_key(|pos| elems[*pos].mailbox_status().depth())
+                .unwrap_or(first),
+        };
+
+        let child_ref = &elems[pos];
+        self.next_elem = pos.wrapping_add(1);
+        debug!(
+            "Children({}): Sending a message to Child({}): {:?}",
+            self.id(),
+            child_ref.id(),
+            msg
+        );
+        let mut env = Envelope {
+            msg: BastionMessage::Message(msg),
+            sign,
+        };
+
+        // The other elements are tried in turn if the picked one
+        // has no room left (when the group's overflow policy is
+        // `Block` or `Fail`), the message being sent to the dead
+        // letters if none of them has.
+        for pos in (pos..elems.len()).chain(0..pos) {
+            env = match elems[pos].send(env) {
+                Ok(()) => return,
+                Err(env) => env,
+            };
+        }
+
+        debug!(
+            "Children({}): No element has room for the message, sending it to the dead letters: {:?}",
+            self.id(),
+            env
+        );
+        // FIXME: Err(env)
+        SYSTEM.dead_letters().send(env).ok();
+    }
+
+    // Returns how many elements the group has, without the ones
+    // that are retiring.
+    fn elems_count(&self) -> usize {
+        self.launched.len() + self.idle.len() - self.retiring.len() - self.standbys.len()
+    }
+
+    // Starts or retires elements as requested through the
+    // group's `ChildrenRef`.
+    fn scale(&mut self, scaling: Scaling) {
+        match scaling {
+            Scaling::To(redundancy) => {
+                let redundancy = if self.elastic {
+                    redundancy
+                } else {
+                    redundancy.max(1)
+                };
+                let count = self.elems_count();
+                debug!(
+                    "Children({}): Scaling from {} to {} elements.",
+                    self.id(),
+                    count,
+                    redundancy
+                );
+                if redundancy > count {
+                    self.add_elems(redundancy - count);
+                } else {
+                    // The idle elements are retired first, since
+                    // they don't have anything to finish.
+                    let standbys = &self.standbys;
+                    let mut retired = self
+                        .idle
+                        .keys()
+                        .filter(|id| !standbys.contains(id))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    for id in self.launched.keys() {
+                        if !self.retiring.contains(id) && !standbys.contains(id) {
+                            retired.push(id.clone());
+                        }
+                    }
+
+                    for id in retired.into_iter().take(count - redundancy) {
+                        self.retire_elem(id);
+                    }
+                }
+            }
+            Scaling::Add => self.add_elems(1),
+            Scaling::Remove(id) => {
+                let known = self.idle.contains_key(&id) || self.launched.contains_key(&id);
+                if !known || self.retiring.contains(&id) || self.standbys.contains(&id) {
+                    debug!(
+                        "Children({}): Unknown Child({}) can't be removed.",
+                        self.id(),
+                        id
+                    );
+                    return;
+                }
+
+                if self.elems_count() == 1 && !self.elastic {
+                    warn!(
+                        "Children({}): Child({}) can't be removed, it is the last element.",
+                        self.id(),
+                        id
+                    );
+                    return;
+                }
+
+                self.retire_elem(id);
+            }
+        }
+
+        // The group keeps its new size when it is restarted.
+        self.redundancy = self.elems_count();
+        self.refresh_ref();
+    }
+
+    // Resizes the group depending on how many messages are
+    // waiting in its elements' mailboxes, if it has an
+    // autoscaler and it is time to check them.
+    async fn autoscale(&mut self) {
+        let elems = self.elems_count();
+        let target = match &mut self.autoscaler {
+            Some((autoscaler, delay)) => {
+                if poll!(&mut *delay).is_pending() {
+                    return;
+                }
+
+                // The reset delay needs to be polled to wake the
+                // group up once it elapses.
+                delay.reset(autoscaler.interval());
+                let _ = poll!(&mut *delay);
+                let depth = self
+                    .children_ref
+                    .elems()
+                    .iter()
+                    .map(|child_ref| child_ref.mailbox_stats().depth())
+                    .sum();
+
+                autoscaler.target(elems, depth)
+            }
+            None => return,
+        };
+
+        if target != elems {
+            debug!(
+                "Children({}): Autoscaling to {} elements.",
+                self.id(),
+                target
+            );
+            self.scale(Scaling::To(target));
+        }
+    }
+
+    // Acts on the elements that stopped sending heartbeats, if
+    // the group requires them and it is time to check them.
+    async fn check_heartbeats(&mut self) {
+        // The elements of a paused group can't send heartbeats.
+        if self.paused {
+            return;
+        }
+
+        let (max_silence, action) = match &mut self.heartbeat {
+            Some((interval, max_missed, action, delay)) => {
+                if poll!(&mut *delay).is_pending() {
+                    return;
+                }
+
+                // The reset delay needs to be polled to wake the
+                // group up once it elapses.
+                delay.reset(*interval);
+                let _ = poll!(&mut *delay);
+                (*interval * *max_missed as u32, *action)
+            }
+            None => return,
+        };
+
+        let mut hung = Vec::new();
+        for (id, (child_ref, _)) in self.launched.iter() {
+            if self.retiring.contains(id) || self.restarting.contains(id) {
+                continue;
+            }
+
+            let elapsed = match self.heartbeats.get(id) {
+                Some(heartbeat) => heartbeat.elapsed(),
+                None => continue,
+            };
+
+            if elapsed <= max_silence {
+                self.hung.remove(id);
+            } else if self.hung.insert(id.clone()) {
+                hung.push((child_ref.clone(), elapsed));
+            }
+        }
+
+        for (child_ref, elapsed) in hung {
+            let id = child_ref.id().clone();
+            warn!(
+                "Children({}): Child({}) didn't send a heartbeat for {:?}, it is hung.",
+                self.id(),
+                id,
+                elapsed
+            );
+            if let Some(hook) = &self.hang_hook {
+                trace!(
+                    "Children({}): Calling hang hook for Child({}).",
+                    self.id(),
+                    id
+                );
+                (hook.0)(child_ref);
+            }
+
+            match action {
+                HangAction::Warn => (),
+                HangAction::Kill => {
+                    debug!("Children({}): Killing hung Child({}).", self.id(), id);
+                    self.bcast.kill_child(&id);
+                }
+                HangAction::Restart => self.restart_elem(id),
+            }
+        }
+    }
+
+    // Tells the futures waiting for the group to be ready (see
+    // `ChildrenRef::ready`) that it is, once all its launched
+    // elements told they finished initializing.
+    fn notify_ready(&mut self) {
+        if self.ready_senders.is_empty() {
+            return;
+        }
+
+        if !self.launched.keys().all(|id| self.ready.contains(id)) {
+            return;
+        }
+
+        debug!("Children({}): All elements are ready.", self.id());
+        for sender in self.ready_senders.drain(..) {
+            sender.send(());
+        }
+    }
+
+    fn add_elems(&mut self, count: usize) {
+        debug!("Children({}): Adding {} elements.", self.id(), count);
+        let parent = Parent::children(self.as_ref());
+        for index in self.free_indices(count) {
+            self.create_elem(&parent, index);
+        }
+
+        self.refresh_ref();
+        self.launch_created();
+    }
+
+    // Tells the element to stop, after which it is restarted on
+    // its own (as requested through its `ChildRef`).
+    fn restart_elem(&mut self, id: BastionId) {
+        if !self.launched.contains_key(&id) || self.retiring.contains(&id) {
+            debug!(
+                "Children({}): Child({}) isn't running, not restarting it.",
+                self.id(),
+                id
+            );
+            return;
+        }
+
+        debug!(
+            "Children({}): Stopping Child({}) to restart it.",
+            self.id(),
+            id
+        );
+        self.restarting.insert(id.clone());
+        self.bcast.stop_child(&id);
+    }
+
+    // Tells the element to stop, after which it is removed from
+    // the group (or removes it right away if it is idle).
+    fn retire_elem(&mut self, id: BastionId) {
+        debug!("Children({}): Retiring Child({}).", self.id(), id);
+        if self.retire_idle(&id) {
+            return;
+        }
+
+        self.retiring.insert(id.clone());
+        self.bcast.stop_child(&id);
+    }
+
+    // Removes the element from the group if it is idle,
+    // returning whether it was.
+    fn retire_idle(&mut self, id: &BastionId) -> bool {
+        if self.idle.remove(id).is_none() {
+            return false;
+        }
+
+        self.bcast.unregister(id);
+        self.indices.remove(id);
+        true
+    }
+
+    // Launches the idle elements that received a message,
+    // returning `Err(())` if the group stopped because one of
+    // them was told to stop (only this element is removed when
+    // using `with_isolated_elems`, unless it was the last one).
+    async fn launch_idle(&mut self) -> Result<(), ()> {
+        let mut woken = Vec::new();
+        let mut stopped = Vec::new();
+        for (id, (_, bcast, _)) in self.idle.iter_mut() {
+            loop {
+                match poll!(&mut bcast.next()) {
+                    Poll::Ready(Some(Envelope {
+                        msg: BastionMessage::Start,
+                        ..
+                    })) => (),
+                    Poll::Ready(Some(Envelope {
+                        msg: BastionMessage::Stop,
+                        ..
+                    }))
+                    | Poll::Ready(Some(Envelope {
+                        msg: BastionMessage::Kill,
+                        ..
+                    })) => {
+                        debug!("Children({}): Idle Child({}) stopped.", self.bcast.id(), id);
+                        stopped.push(id.clone());
+                        break;
+                    }
+                    Poll::Ready(Some(env)) => {
+                        woken.push((id.clone(), env));
+                        break;
+                    }
+                    // NOTE: because `Broadcast` always holds both a `Sender` and
+                    //      `Receiver` of the same channel, this would only be
+                    //      possible if the channel was closed, which never happens.
+                    Poll::Ready(None) => unreachable!(),
+                    Poll::Pending => break,
+                }
+            }
+        }
 
-            // TODO: clone or ref?
-            let id = bcast.id().clone();
-            let sender = bcast.sender().clone();
-            let path = bcast.path().clone();
-            let child_ref = ChildRef::new(id.clone(), sender.clone(), path);
+        if !stopped.is_empty() && !self.isolated {
+            self.stop().await;
+            self.stopped();
 
-            let children = self.as_ref();
-            let supervisor = self.bcast.parent().clone().into_supervisor();
+            return Err(());
+        }
 
-            let state = ContextState::new();
-            let state = Qutex::new(state);
+        if !stopped.is_empty() {
+            for id in stopped {
+                self.retire_idle(&id);
+                self.saved.remove(&id);
+            }
 
-            let ctx = BastionContext::new(id, child_ref, children, supervisor, state.clone());
-            let exec = (self.init.0)(ctx);
+            self.refresh_ref();
+            if self.launched.is_empty() && self.idle.is_empty() {
+                self.stopped();
+                return Err(());
+            }
+        }
 
-            self.bcast.register(&bcast);
+        for (id, env) in woken {
+            let (child_ref, bcast, state) = self.idle.remove(&id).unwrap();
+            if self.started {
+                let msg = BastionMessage::start();
+                let start =
+                    Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+                // FIXME: Err(msg)
+                bcast.sender().try_send(start).ok();
+            }
 
             debug!(
-                "Children({}): Initializing Child({}).",
+                "Children({}): Child({}) received its first message.",
                 self.id(),
-                bcast.id()
+                id
             );
-            let child = Child::new(exec, bcast, state);
-            debug!("Children({}): Launching Child({}).", self.id(), child.id());
-            let id = child.id().clone();
-            let launched = child.launch();
+            self.launch_elem(child_ref, bcast, state, Some(env));
+        }
+
+        Ok(())
+    }
+
+    fn launch_elem(
+        &mut self,
+        child_ref: ChildRef,
+        bcast: Broadcast,
+        state: Arc<ContextState>,
+        first_msg: Option<Envelope>,
+    ) {
+        let children = self.as_ref();
+        let supervisor = self.bcast.parent().clone().into_supervisor();
+
+        let ctx = BastionContext::new(
+            bcast.id().clone(),
+            child_ref.clone(),
+            children,
+            supervisor,
+            state.clone(),
+        );
+        // FIXME: panics?
+        let index = self.indices[bcast.id()];
+        let exec = (self.init.0)(index, ctx);
+        let saved = state.saved().clone();
+        // The element is given a full interval to send its first
+        // heartbeat once it is launched.
+        let heartbeat = state.heartbeat().clone();
+        heartbeat.beat();
+        let undelivered = if self.preserve_mailboxes {
+            Some(Undelivered::default())
+        } else {
+            None
+        };
+
+        debug!(
+            "Children({}): Initializing Child({}).",
+            self.id(),
+            bcast.id()
+        );
+        let mut child = Child::new(
+            exec,
+            bcast,
+            state,
+            child_ref.usage_status().clone(),
+            self.config.clone(),
+            undelivered.clone(),
+        );
+        if let Some(env) = first_msg {
+            child.push_pre_start_msg(env);
+        }
+        if self.paused {
+            child.pause();
+        }
 
-            self.launched.insert(id, (sender, launched));
+        debug!("Children({}): Launching Child({}).", self.id(), child.id());
+        let id = child.id().clone();
+        let launched = child.launch();
+
+        // The element needs to tell it is ready again if it
+        // is relaunched.
+        self.ready.remove(&id);
+        self.saved.insert(id.clone(), saved);
+        self.heartbeats.insert(id.clone(), heartbeat);
+        if let Some(undelivered) = undelivered {
+            self.undelivered.insert(id.clone(), undelivered);
         }
+        self.launched.insert(id, (child_ref, launched));
     }
 
     pub(crate) fn launch(self) -> RecoverableHandle<Self> {
@@ -577,3 +4248,45 @@ impl Children {
         pool::spawn(self.run(), stack)
     }
 }
+
+impl Debug for RestartHook {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("RestartHook").finish()
+    }
+}
+
+impl Debug for Seed {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Seed").finish()
+    }
+}
+
+impl Debug for HangHook {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("HangHook").finish()
+    }
+}
+
+impl Debug for LifecycleHook {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("LifecycleHook").finish()
+    }
+}
+
+impl Default for GroupRestartPolicy {
+    fn default() -> Self {
+        GroupRestartPolicy::Transient
+    }
+}
+
+impl Default for ShutdownMode {
+    fn default() -> Self {
+        ShutdownMode::Immediate
+    }
+}
+
+impl Default for DispatchMode {
+    fn default() -> Self {
+        DispatchMode::RoundRobin
+    }
+}
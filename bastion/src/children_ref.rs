@@ -2,37 +2,181 @@
 //! Allows users to communicate with children through the mailboxes.
 use crate::broadcast::Sender;
 use crate::child_ref::ChildRef;
+use crate::children::{DispatchMode, GroupRestartPolicy};
 use crate::context::BastionId;
-use crate::envelope::Envelope;
-use crate::message::{BastionMessage, Message};
+use crate::envelope::{Envelope, RefAddr};
+use crate::mailbox::{MailboxStats, OverflowPolicy};
+use crate::message::{BastionMessage, Message, Ready, Scaling, Shutdown, Stopped};
 use crate::path::BastionPath;
+use crate::supervisor::SupervisionStrategy;
+use crate::validation::Validation;
+use fxhash::FxHashMap;
 use std::cmp::{Eq, PartialEq};
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 /// A "reference" to a children group, allowing to communicate
 /// with it.
 pub struct ChildrenRef {
+    // The group's state is a snapshot shared by all the clones
+    // of a `ChildrenRef` (eg. one per element's context), so that
+    // cloning it is only a reference count increment.
+    inner: Arc<ChildrenRefInner>,
+}
+
+#[derive(Debug)]
+struct ChildrenRefInner {
     id: BastionId,
+    name: Option<String>,
     sender: Sender,
     path: Arc<BastionPath>,
     children: Vec<ChildRef>,
+    // The index of every element in `children`.
+    indices: FxHashMap<BastionId, usize>,
+    // The index in `children` of the element with every ordinal
+    // (see `ChildRef::index`).
+    ordinals: FxHashMap<usize, usize>,
+    // The validation that messages sent to the children
+    // group need to pass to be accepted.
+    validation: Validation,
+    // The strategy the group's supervisor uses when it faults,
+    // instead of its own (if any), shared with the group.
+    strategy: Arc<RwLock<Option<SupervisionStrategy>>>,
+    // Whether the group's supervisor may restart it when it
+    // faults.
+    restart_policy: GroupRestartPolicy,
+    // How `send_one` picks the element receiving a message when
+    // it sends it directly (see `is_bounded`), and the position
+    // in `children` of the next element in turn.
+    dispatch_mode: DispatchMode,
+    next_elem: AtomicUsize,
+}
+
+#[derive(Debug, Default, Clone)]
+/// Which elements of a children group received a message sent
+/// using [`ChildrenRef::broadcast_with_report`], and why the
+/// others didn't.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+///     # let children_ref = Bastion::children(|children| children).unwrap();
+/// let report = children_ref
+///     .broadcast_with_report("A message containing data.")
+///     .expect("Couldn't send the message.");
+/// for (id, failure) in report.failed() {
+///     println!("Child({}) didn't receive the message: {:?}", id, failure);
+/// }
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildrenRef::broadcast_with_report`]: struct.ChildrenRef.html#method.broadcast_with_report
+pub struct BroadcastReport {
+    delivered: Vec<BastionId>,
+    failed: Vec<(BastionId, DeliveryFailure)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why an element of a children group didn't receive a message
+/// (see [`BroadcastReport::failed`]).
+///
+/// [`BroadcastReport::failed`]: struct.BroadcastReport.html#method.failed
+pub enum DeliveryFailure {
+    /// The element stopped.
+    Stopped,
+    /// The element has no room left and the group's overflow
+    /// policy is [`OverflowPolicy::Fail`] or [`OverflowPolicy::Block`],
+    /// or the element is refusing messages (see
+    /// [`Children::with_pre_start_limit`]).
+    ///
+    /// [`OverflowPolicy::Fail`]: ../mailbox/enum.OverflowPolicy.html#variant.Fail
+    /// [`OverflowPolicy::Block`]: ../mailbox/enum.OverflowPolicy.html#variant.Block
+    /// [`Children::with_pre_start_limit`]: ../children/struct.Children.html#method.with_pre_start_limit
+    Full,
+}
+
+impl BroadcastReport {
+    /// Returns the identifiers of the elements whose mailbox
+    /// accepted the message.
+    pub fn delivered(&self) -> &[BastionId] {
+        &self.delivered
+    }
+
+    /// Returns the identifiers of the elements that didn't
+    /// receive the message, along with the reason why.
+    pub fn failed(&self) -> &[(BastionId, DeliveryFailure)] {
+        &self.failed
+    }
+
+    /// Returns whether all the elements received the message.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
 }
 
 impl ChildrenRef {
     pub(crate) fn new(
         id: BastionId,
+        name: Option<String>,
         sender: Sender,
         path: Arc<BastionPath>,
         children: Vec<ChildRef>,
+        validation: Validation,
+        strategy: Arc<RwLock<Option<SupervisionStrategy>>>,
     ) -> Self {
-        ChildrenRef {
+        let indices = children
+            .iter()
+            .enumerate()
+            .map(|(index, child_ref)| (child_ref.id().clone(), index))
+            .collect();
+        let ordinals = children
+            .iter()
+            .enumerate()
+            .map(|(index, child_ref)| (child_ref.index(), index))
+            .collect();
+        let inner = Arc::new(ChildrenRefInner {
             id,
+            name,
             sender,
             path,
             children,
-        }
+            indices,
+            ordinals,
+            validation,
+            strategy,
+            restart_policy: GroupRestartPolicy::default(),
+            dispatch_mode: DispatchMode::default(),
+            next_elem: AtomicUsize::new(0),
+        });
+
+        ChildrenRef { inner }
+    }
+
+    pub(crate) fn with_dispatch_mode(mut self, mode: DispatchMode) -> Self {
+        // The snapshot was just created, so it isn't shared yet.
+        // FIXME: panics?
+        Arc::get_mut(&mut self.inner).unwrap().dispatch_mode = mode;
+        self
+    }
+
+    pub(crate) fn with_restart_policy(mut self, policy: GroupRestartPolicy) -> Self {
+        // The snapshot was just created, so it isn't shared yet.
+        // FIXME: panics?
+        Arc::get_mut(&mut self.inner).unwrap().restart_policy = policy;
+        self
     }
 
     /// Returns the identifier of the children group this `ChildrenRef`
@@ -62,7 +206,16 @@ impl ChildrenRef {
     /// # }
     /// ```
     pub fn id(&self) -> &BastionId {
-        &self.id
+        &self.inner.id
+    }
+
+    /// Returns the name of the children group this `ChildrenRef`
+    /// is referencing, if it was given one with
+    /// [`Children::with_name`].
+    ///
+    /// [`Children::with_name`]: children/struct.Children.html#method.with_name
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name.as_ref().map(String::as_str)
     }
 
     /// Returns a list of [`ChildRef`] referencing the elements
@@ -87,7 +240,125 @@ impl ChildrenRef {
     ///
     /// [`ChildRef`]: children/struct.ChildRef.html
     pub fn elems(&self) -> &[ChildRef] {
-        &self.children
+        &self.inner.children
+    }
+
+    /// Returns the [`ChildRef`] of the element of the children
+    /// group this `ChildrenRef` is referencing with the given
+    /// identifier, if it has one.
+    ///
+    /// Like [`elems`], this looks at the elements that the
+    /// children group had when this `ChildrenRef` was created.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let id = children_ref.elems()[0].id().clone();
+    /// let child_ref: Option<&ChildRef> = children_ref.find_elem(&id);
+    /// assert!(child_ref.is_some());
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`elems`]: #method.elems
+    pub fn find_elem(&self, id: &BastionId) -> Option<&ChildRef> {
+        let index = *self.inner.indices.get(id)?;
+        self.inner.children.get(index)
+    }
+
+    /// Returns the [`ChildRef`] of the element of the children
+    /// group this `ChildrenRef` is referencing with the given
+    /// ordinal (see [`ChildRef::index`]), if it has one.
+    ///
+    /// Since elements keep their ordinal when they are restarted,
+    /// this allows to deterministically map the shards of a
+    /// workload to elements (eg. `shard % elems().len()`). Like
+    /// [`elems`], this looks at the elements that the children
+    /// group had when this `ChildrenRef` was created.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The ordinal of the element.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| children.with_redundancy(4))
+    ///     .expect("Couldn't create the children group.");
+    ///
+    /// let shard = 42;
+    /// let child_ref = children_ref
+    ///     .elem_by_index(shard % children_ref.elems().len())
+    ///     .expect("Couldn't find the element.");
+    /// child_ref.tell_anonymously(shard).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`ChildRef::index`]: ../child_ref/struct.ChildRef.html#method.index
+    /// [`elems`]: #method.elems
+    pub fn elem_by_index(&self, index: usize) -> Option<&ChildRef> {
+        let index = *self.inner.ordinals.get(&index)?;
+        self.inner.children.get(index)
+    }
+
+    /// Returns statistics about the mailboxes of all the elements
+    /// of the children group this `ChildrenRef` is referencing
+    /// (see [`ChildRef::mailbox_stats`]), combined: the depths and
+    /// numbers of messages are summed up, the mean latency is the
+    /// one of all the retrieved messages and the highest latency
+    /// is the highest of all the elements.
+    ///
+    /// This allows to tell whether the group needs more elements
+    /// or larger mailboxes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children.with_redundancy(4)).unwrap();
+    /// let stats: MailboxStats = children_ref.mailbox_stats();
+    /// if stats.mean_latency() > Duration::from_millis(100) {
+    ///     children_ref.add_elem().expect("Couldn't send the message.");
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::mailbox_stats`]: ../child_ref/struct.ChildRef.html#method.mailbox_stats
+    pub fn mailbox_stats(&self) -> MailboxStats {
+        MailboxStats::sum(self.elems().iter().map(ChildRef::mailbox_stats))
     }
 
     /// Sends a message to the children group this `ChildrenRef`
@@ -99,7 +370,12 @@ impl ChildrenRef {
     /// of them.
     ///
     /// This method returns `()` if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// otherwise (including when the message was rejected by the
+    /// children group's validation, see [`Children::with_validator`],
+    /// or when one of the elements has no room left and the group's
+    /// overflow policy is [`OverflowPolicy::Fail`] or
+    /// [`OverflowPolicy::Block`], in which case none of them
+    /// receives the message).
     ///
     /// # Arguments
     ///
@@ -142,22 +418,448 @@ impl ChildrenRef {
     /// ```
     ///
     /// [`elems`]: #method.elems
+    /// [`Children::with_validator`]: children/struct.Children.html#method.with_validator
+    /// [`OverflowPolicy::Fail`]: mailbox/enum.OverflowPolicy.html#variant.Fail
+    /// [`OverflowPolicy::Block`]: mailbox/enum.OverflowPolicy.html#variant.Block
     pub fn broadcast<M: Message>(&self, msg: M) -> Result<(), M> {
         debug!(
             "ChildrenRef({}): Broadcasting message: {:?}",
             self.id(),
             msg
         );
+        let msg = self.inner.validation.check(msg)?;
         let msg = BastionMessage::broadcast(msg);
+        if self.is_bounded() {
+            let elems = match self.try_reserve_all(None) {
+                Some(elems) => elems,
+                // FIXME: panics?
+                None => return Err(msg.into_msg().unwrap()),
+            };
+
+            Self::send_reserved(elems, msg, RefAddr::dead_letters());
+            return Ok(());
+        }
+
+        let env = Envelope::from_dead_letters(msg);
+        // FIXME: panics?
+        self.send(env).map_err(|err| err.into_msg().unwrap())
+    }
+
+    // Broadcasts a message to all the elements of the group but
+    // the given one (see `BastionContext::broadcast_siblings`).
+    pub(crate) fn send_siblings<M: Message>(
+        &self,
+        msg: M,
+        except: BastionId,
+        sign: RefAddr,
+    ) -> Result<(), M> {
+        debug!(
+            "ChildrenRef({}): Broadcasting message to the siblings of Child({}): {:?}",
+            self.id(),
+            except,
+            msg
+        );
+        let msg = self.inner.validation.check(msg)?;
+        let msg = BastionMessage::send_siblings(msg, except);
+        if self.is_bounded() {
+            let (msg, except) = match msg {
+                BastionMessage::SendSiblings { msg, except } => (msg, except),
+                _ => unreachable!(),
+            };
+            let msg = BastionMessage::Message(msg);
+            let elems = match self.try_reserve_all(Some(&except)) {
+                Some(elems) => elems,
+                // FIXME: panics?
+                None => return Err(msg.into_msg().unwrap()),
+            };
+
+            Self::send_reserved(elems, msg, sign);
+            return Ok(());
+        }
+
+        let env = Envelope::new_with_sign(msg, sign);
+        // FIXME: panics?
+        self.send(env).map_err(|err| err.into_msg().unwrap())
+    }
+
+    /// Sends a message to only one of the elements of the
+    /// children group this `ChildrenRef` is referencing, instead
+    /// of all of them like [`broadcast`] does, which allows to
+    /// use the group as a work queue.
+    ///
+    /// By default, the elements receive the messages sent using
+    /// this method in turn (round-robin), without taking into
+    /// account the ones that are retiring (see
+    /// [`Children::with_dispatch_mode`]).
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise (including when the message was rejected by the
+    /// children group's validation, see [`Children::with_validator`],
+    /// or when none of the elements has room left and the group's
+    /// overflow policy is [`OverflowPolicy::Fail`] or
+    /// [`OverflowPolicy::Block`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children.with_redundancy(4)).unwrap();
+    /// // Each job is handled by a single element of the group...
+    /// for job in 0..16u64 {
+    ///     children_ref.send_one(job).expect("Couldn't send the message.");
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    /// [`Children::with_dispatch_mode`]: children/struct.Children.html#method.with_dispatch_mode
+    /// [`Children::with_validator`]: children/struct.Children.html#method.with_validator
+    /// [`OverflowPolicy::Fail`]: mailbox/enum.OverflowPolicy.html#variant.Fail
+    /// [`OverflowPolicy::Block`]: mailbox/enum.OverflowPolicy.html#variant.Block
+    pub fn send_one<M: Message>(&self, msg: M) -> Result<(), M> {
+        debug!("ChildrenRef({}): Sending message: {:?}", self.id(), msg);
+        let msg = self.inner.validation.check(msg)?;
+        let msg = BastionMessage::send_one(msg);
+        if self.is_bounded() {
+            let msg = match msg {
+                BastionMessage::SendOne(msg) => BastionMessage::Message(msg),
+                _ => unreachable!(),
+            };
+            let child = match self.try_reserve_one() {
+                Some(child) => child,
+                // FIXME: panics?
+                None => return Err(msg.into_msg().unwrap()),
+            };
+
+            let env = Envelope::from_dead_letters(msg);
+            // FIXME: panics?
+            return child
+                .sender()
+                .send_reserved(env)
+                .map_err(|env| env.into_msg().unwrap());
+        }
+
         let env = Envelope::from_dead_letters(msg);
         // FIXME: panics?
         self.send(env).map_err(|err| err.into_msg().unwrap())
     }
 
+    /// Sends a message to all the elements of the children group
+    /// this `ChildrenRef` is referencing like [`broadcast`] does,
+    /// but returns a [`BroadcastReport`] listing the elements
+    /// whose mailbox accepted the message and the ones that
+    /// didn't receive it (because they stopped or their mailbox
+    /// is full), instead of hiding partial failures.
+    ///
+    /// The message is delivered to the elements of the snapshot
+    /// returned by [`elems`] directly, instead of going through
+    /// the group.
+    ///
+    /// This method returns the report if the message was sent,
+    /// or `Err(msg)` if it was rejected by the children group's
+    /// validation (see [`Children::with_validator`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// let report = children_ref
+    ///     .broadcast_with_report("A message containing data.")
+    ///     .expect("Couldn't send the message.");
+    /// if !report.is_complete() {
+    ///     // Some elements didn't receive the message...
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    /// [`elems`]: #method.elems
+    /// [`BroadcastReport`]: struct.BroadcastReport.html
+    /// [`Children::with_validator`]: children/struct.Children.html#method.with_validator
+    pub fn broadcast_with_report<M: Message>(&self, msg: M) -> Result<BroadcastReport, M> {
+        debug!(
+            "ChildrenRef({}): Broadcasting message with report: {:?}",
+            self.id(),
+            msg
+        );
+        let msg = self.inner.validation.check(msg)?;
+        let msg = BastionMessage::broadcast(msg);
+
+        let mut report = BroadcastReport::default();
+        for child_ref in &self.inner.children {
+            let id = child_ref.id().clone();
+            if !child_ref.is_alive() {
+                report.failed.push((id, DeliveryFailure::Stopped));
+                continue;
+            }
+
+            if child_ref.mailbox_status().rejects() {
+                report.failed.push((id, DeliveryFailure::Full));
+                continue;
+            }
+
+            // Cloning a broadcasted message only clones the
+            // pointer to its (shared) payload.
+            // FIXME: panics?
+            let env = Envelope::from_dead_letters(msg.try_clone().unwrap());
+            match child_ref.send(env) {
+                Ok(()) => report.delivered.push(id),
+                // The element is still running, so it had no room
+                // left (see `OverflowPolicy::Fail`).
+                Err(_) if child_ref.is_alive() => {
+                    report.failed.push((id, DeliveryFailure::Full))
+                }
+                Err(_) => report.failed.push((id, DeliveryFailure::Stopped)),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Sends a message to all the elements of the children group
+    /// this `ChildrenRef` is referencing like [`broadcast`] does,
+    /// except that when the group's overflow policy is
+    /// [`OverflowPolicy::Block`], this method waits (asynchronously)
+    /// until all the elements have room for the message instead
+    /// of failing, slowing the caller down while the group can't
+    /// keep up.
+    ///
+    /// Room is reserved for the message in the mailboxes of all
+    /// the elements of the snapshot returned by [`elems`] (waiting
+    /// for the ones that have none, but not for the ones that
+    /// stopped) before it is sent to any of them, so that the
+    /// caller is slowed down to the pace of the slowest element
+    /// and no message is dropped.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise (see [`broadcast`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children
+    ///         .with_mailbox_limit(16, OverflowPolicy::Block)
+    ///         # .with_exec(|ctx: BastionContext| {
+    ///             # async move {
+    ///                 # loop {
+    ///                     # ctx.recv().await?;
+    ///                 # }
+    ///             # }
+    ///         # })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    ///     # Bastion::start();
+    ///     #
+    /// run!(async {
+    ///     for i in 0..64 {
+    ///         // This will wait while an element has 16 messages waiting...
+    ///         children_ref.broadcast_async(i).await.expect("Couldn't send the message.");
+    ///     }
+    /// });
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`broadcast`]: #method.broadcast
+    /// [`elems`]: #method.elems
+    /// [`OverflowPolicy::Block`]: mailbox/enum.OverflowPolicy.html#variant.Block
+    pub async fn broadcast_async<M: Message>(&self, msg: M) -> Result<(), M> {
+        if !self.is_blocking() {
+            return self.broadcast(msg);
+        }
+
+        debug!(
+            "ChildrenRef({}): Broadcasting message asynchronously: {:?}",
+            self.id(),
+            msg
+        );
+        let msg = self.inner.validation.check(msg)?;
+        let msg = BastionMessage::broadcast(msg);
+
+        trace!(
+            "ChildrenRef({}): Waiting for the elements to have room.",
+            self.id()
+        );
+        let mut elems = Vec::with_capacity(self.inner.children.len());
+        for child in &self.inner.children {
+            // The room reserved in an element's mailbox is only
+            // released once it received the message, so that the
+            // other elements can't take it meanwhile.
+            if child.reserve().await {
+                elems.push(child);
+            }
+        }
+
+        Self::send_reserved(elems, msg, RefAddr::dead_letters());
+        Ok(())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to start or retire elements so
+    /// that it has the given number of them, instead of the
+    /// number it was created with (see [`Children::with_redundancy`]).
+    ///
+    /// The retired elements are told to stop (respecting the
+    /// group's [`ShutdownMode`]) and removed from the group once
+    /// they did. A group keeps its new number of elements when it
+    /// is restarted, and always has at least one element unless
+    /// it was created without any.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `redundancy` - The number of elements the group should
+    ///     have.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref.scale_to(8).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_redundancy`]: children/struct.Children.html#method.with_redundancy
+    /// [`ShutdownMode`]: children/enum.ShutdownMode.html
+    pub fn scale_to(&self, redundancy: usize) -> Result<(), ()> {
+        debug!(
+            "ChildrenRef({}): Scaling to {} elements.",
+            self.id(),
+            redundancy
+        );
+        self.scale(Scaling::To(redundancy))
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to start one more element.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref.add_elem().expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn add_elem(&self) -> Result<(), ()> {
+        debug!("ChildrenRef({}): Adding an element.", self.id());
+        self.scale(Scaling::Add)
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to retire the element with the
+    /// given identifier, like [`scale_to`] does.
+    ///
+    /// The group ignores the message if it doesn't have such an
+    /// element or if it is its last one (unless it was created
+    /// without any).
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the element to retire.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children.with_redundancy(2)).unwrap();
+    /// let id = children_ref.elems()[0].id();
+    /// children_ref.remove_elem(id).expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`scale_to`]: #method.scale_to
+    pub fn remove_elem(&self, id: &BastionId) -> Result<(), ()> {
+        debug!("ChildrenRef({}): Removing Child({}).", self.id(), id);
+        self.scale(Scaling::Remove(id.clone()))
+    }
+
+    fn scale(&self, scaling: Scaling) -> Result<(), ()> {
+        let msg = BastionMessage::scale(scaling);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
     /// Sends a message to the children group this `ChildrenRef`
     /// is referencing to tell it to stop all of its running
     /// elements.
     ///
+    /// The elements receive this message before the messages that
+    /// were sent to them but that they didn't receive yet (eg.
+    /// because their mailbox is full), which are only handled if
+    /// the group drains their mailboxes when stopping them (see
+    /// [`ShutdownMode`]).
+    ///
     /// This method returns `()` if it succeeded, or `Err(())`
     /// otherwise.
     ///
@@ -177,6 +879,8 @@ impl ChildrenRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
+    ///
+    /// [`ShutdownMode`]: children/enum.ShutdownMode.html
     pub fn stop(&self) -> Result<(), ()> {
         debug!("ChildrenRef({}): Stopping.", self.id());
         let msg = BastionMessage::stop();
@@ -184,10 +888,303 @@ impl ChildrenRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to stop all of its running
+    /// elements, killing those which didn't stop once the deadline
+    /// passed.
+    ///
+    /// This method returns a [`Shutdown`] resolving once the
+    /// children group stopped if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - How long to wait for the elements to stop
+    ///     before killing them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # Bastion::start();
+    /// let shutdown: Shutdown = children_ref
+    ///     .stop_with_deadline(Duration::from_secs(5))
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// // Waiting for it to stop...
+    /// if let Ok(false) = run!(shutdown) {
+    ///     // Some elements had to be killed...
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Shutdown`]: ../message/struct.Shutdown.html
+    pub fn stop_with_deadline(&self, deadline: Duration) -> Result<Shutdown, ()> {
+        debug!(
+            "ChildrenRef({}): Stopping within {:?}.",
+            self.id(),
+            deadline
+        );
+        let (sender, shutdown) = Shutdown::new();
+        let msg = BastionMessage::stop_within(deadline, sender);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())?;
+
+        Ok(shutdown)
+    }
+
+    /// Returns a [`Stopped`] resolving once the children group
+    /// this `ChildrenRef` is referencing terminated, ie. once all
+    /// of its elements stopped or were killed (because the group
+    /// was stopped, killed or faulted), to a [`Terminated`]
+    /// describing why.
+    ///
+    /// This allows waiting for a children group to stop (eg. in
+    /// tests or shutdown sequences) instead of sleeping. Note
+    /// that a children group that faulted might be restarted by
+    /// its supervisor, with a new identifier.
+    ///
+    /// This method returns `Err(())` if the message couldn't be
+    /// sent to the children group (eg. because it already
+    /// terminated).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # Bastion::start();
+    /// let stopped: Stopped = children_ref.stopped().expect("Couldn't send the message.");
+    /// children_ref.stop().expect("Couldn't send the message.");
+    ///
+    /// // Waiting for it to stop...
+    /// let terminated: Terminated = run!(stopped).expect("The group was dropped.");
+    /// assert!(!terminated.reason().is_fault());
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Stopped`]: ../message/struct.Stopped.html
+    /// [`Terminated`]: ../message/struct.Terminated.html
+    pub fn stopped(&self) -> Result<Stopped, ()> {
+        debug!("ChildrenRef({}): Waiting for it to stop.", self.id());
+        let (sender, stopped) = Stopped::new();
+        let msg = BastionMessage::notify_stopped(sender);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())?;
+
+        Ok(stopped)
+    }
+
+    /// Returns a [`Ready`] resolving once all the elements of the
+    /// children group this `ChildrenRef` is referencing told that
+    /// they finished initializing (using
+    /// [`BastionContext::notify_started`]), after the group was
+    /// started.
+    ///
+    /// Note that the group never gets ready if one of its elements
+    /// never tells it is, and that the elements launched lazily
+    /// (see [`Children::with_lazy_elems`]) aren't waited for until
+    /// they are launched.
+    ///
+    /// This method returns `Err(())` if the message couldn't be
+    /// sent to the children group (eg. because it already
+    /// terminated).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let children_ref = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Initializing...
+    ///             ctx.notify_started().expect("Couldn't send the message.");
+    ///
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// let ready: Ready = children_ref.ready().expect("Couldn't send the message.");
+    /// Bastion::start();
+    ///
+    /// // Waiting for its elements to be initialized...
+    /// run!(ready).expect("The group was dropped.");
+    /// children_ref.broadcast("A message containing data.").ok();
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Ready`]: ../message/struct.Ready.html
+    /// [`BastionContext::notify_started`]: ../context/struct.BastionContext.html#method.notify_started
+    /// [`Children::with_lazy_elems`]: ../children/struct.Children.html#method.with_lazy_elems
+    pub fn ready(&self) -> Result<Ready, ()> {
+        debug!("ChildrenRef({}): Waiting for it to be ready.", self.id());
+        let (sender, ready) = Ready::new();
+        let msg = BastionMessage::notify_ready(sender);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())?;
+
+        Ok(ready)
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to pause its elements until it
+    /// is resumed (see [`resume`]), without stopping them.
+    ///
+    /// While the group is paused, the futures of its elements
+    /// aren't polled, so the messages sent to them queue up in
+    /// their mailbox, while the group and its elements still
+    /// handle system messages (eg. to be stopped or killed). The
+    /// elements launched while the group is paused (eg. because
+    /// one of them was restarted) are paused too.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref.pause().expect("Couldn't send the message.");
+    ///
+    /// // Messages queue up in the elements' mailboxes...
+    /// children_ref.broadcast("A message containing data.").expect("Couldn't send the message.");
+    ///
+    /// // ...until the group is resumed.
+    /// children_ref.resume().expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`resume`]: #method.resume
+    pub fn pause(&self) -> Result<(), ()> {
+        debug!("ChildrenRef({}): Pausing.", self.id());
+        let msg = BastionMessage::pause();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to resume its elements after it
+    /// was paused (see [`pause`]), which then handle the messages
+    /// that queued up in their mailbox.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    ///     # children_ref.pause().unwrap();
+    /// children_ref.resume().expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`pause`]: #method.pause
+    pub fn resume(&self) -> Result<(), ()> {
+        debug!("ChildrenRef({}): Resuming.", self.id());
+        let msg = BastionMessage::resume();
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the children group this `ChildrenRef`
+    /// is referencing to tell it to change the strategy its
+    /// supervisor uses when it faults (see
+    /// [`Children::with_supervision_strategy`]).
+    ///
+    /// The new strategy is used from the next time the group
+    /// faults, including after it was restarted.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy to use when the group faults.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let children_ref = Bastion::children(|children| children).unwrap();
+    /// children_ref
+    ///     .strategy(SupervisionStrategy::OneForAll)
+    ///     .expect("Couldn't send the message.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_supervision_strategy`]: children/struct.Children.html#method.with_supervision_strategy
+    pub fn strategy(&self, strategy: SupervisionStrategy) -> Result<(), ()> {
+        debug!(
+            "ChildrenRef({}): Setting strategy: {:?}",
+            self.id(),
+            strategy
+        );
+        let msg = BastionMessage::supervise_with(strategy);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
     /// Sends a message to the children group this `ChildrenRef`
     /// is referencing to tell it to kill all of its running
     /// elements.
     ///
+    /// The elements receive this message before the messages that
+    /// were sent to them but that they didn't receive yet, which
+    /// are dropped.
+    ///
     /// This method returns `()` if it succeeded, or `Err(())`
     /// otherwise.
     ///
@@ -216,25 +1213,134 @@ impl ChildrenRef {
 
     pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
         trace!("ChildrenRef({}): Sending message: {:?}", self.id(), env);
-        self.sender
-            .unbounded_send(env)
-            .map_err(|err| err.into_inner())
+        self.inner.sender.try_send(env)
+    }
+
+    // Whether the elements of the group can only have a limited
+    // number of messages waiting for them (when the group's
+    // overflow policy is `Block` or `Fail`), in which case users'
+    // messages are sent to them directly instead of going through
+    // the group, so that the senders know whether they had room.
+    fn is_bounded(&self) -> bool {
+        self.inner
+            .children
+            .iter()
+            .any(|child| child.mailbox_status().is_bounded())
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.inner
+            .children
+            .iter()
+            .any(|child| child.mailbox_status().policy() == OverflowPolicy::Block)
+    }
+
+    // Reserves room for a message in the mailboxes of all the
+    // elements that didn't stop (but the given one), or in none
+    // of them if one has no room left.
+    fn try_reserve_all(&self, except: Option<&BastionId>) -> Option<Vec<&ChildRef>> {
+        let mut elems: Vec<&ChildRef> = Vec::with_capacity(self.inner.children.len());
+        for child in &self.inner.children {
+            let status = child.mailbox_status();
+            if Some(child.id()) == except || status.is_closed() {
+                continue;
+            }
+
+            if !status.try_reserve() {
+                trace!(
+                    "ChildrenRef({}): Child({}) has no room left.",
+                    self.id(),
+                    child.id()
+                );
+                for child in elems {
+                    child.mailbox_status().release();
+                }
+
+                return None;
+            }
+
+            elems.push(child);
+        }
+
+        Some(elems)
+    }
+
+    // Reserves room for a message in the mailbox of the element
+    // picked by the group's dispatch mode, or of the next ones in
+    // turn if it has no room left.
+    fn try_reserve_one(&self) -> Option<&ChildRef> {
+        let elems = &self.inner.children;
+        if elems.is_empty() {
+            return None;
+        }
+
+        let first = self.inner.next_elem.fetch_add(1, Ordering::Relaxed) % elems.len();
+        let pos = match self.inner.dispatch_mode {
+            DispatchMode::RoundRobin => first,
+            DispatchMode::LeastLoaded => (first..elems.len())
+                .chain(0..first)
+                .min_by_key(|pos| elems[*pos].mailbox_status().depth())
+                .unwrap_or(first),
+        };
+
+        (pos..elems.len())
+            .chain(0..pos)
+            .map(|pos| &elems[pos])
+            .find(|child| child.mailbox_status().try_reserve())
+    }
+
+    // Sends a message to the given elements, for which room was
+    // reserved beforehand.
+    fn send_reserved(elems: Vec<&ChildRef>, msg: BastionMessage, sign: RefAddr) {
+        let mut elems = elems.into_iter().peekable();
+        let mut msg = Some(msg);
+        while let Some(child) = elems.next() {
+            let msg = if elems.peek().is_none() {
+                // The last element gets the original message.
+                // FIXME: panics?
+                msg.take().unwrap()
+            } else {
+                // Cloning a broadcasted message only clones the
+                // pointer to its (shared) payload.
+                // FIXME: panics?
+                msg.as_ref().and_then(BastionMessage::try_clone).unwrap()
+            };
+
+            let env = Envelope::new_with_sign(msg, sign.clone());
+            // The element stopped since its room was reserved.
+            child.sender().send_reserved(env).ok();
+        }
     }
 
     /// Returns the [`BastionPath`] of this ChildrenRef
     pub fn path(&self) -> &Arc<BastionPath> {
-        &self.path
+        &self.inner.path
+    }
+
+    pub(crate) fn overridden_strategy(&self) -> Option<SupervisionStrategy> {
+        // FIXME: panics?
+        self.inner.strategy.read().unwrap().clone()
+    }
+
+    pub(crate) fn restart_policy(&self) -> GroupRestartPolicy {
+        self.inner.restart_policy
     }
 
     pub(crate) fn sender(&self) -> &Sender {
-        &self.sender
+        &self.inner.sender
     }
 }
 
 impl PartialEq for ChildrenRef {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
+        self.inner.id == other.inner.id
     }
 }
 
 impl Eq for ChildrenRef {}
+
+impl Hash for ChildrenRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner.id.hash(state);
+    }
+}
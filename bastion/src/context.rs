@@ -2,15 +2,33 @@
 //! A context allows a child's future to access its received
 //! messages, parent and supervisor.
 
+use crate::bastion::Bastion;
+use crate::child::yield_now;
 use crate::child_ref::ChildRef;
+use crate::children::Children;
 use crate::children_ref::ChildrenRef;
-use crate::envelope::{Envelope, RefAddr, SignedMessage};
-use crate::message::{Answer, BastionMessage, Message, Msg};
-use crate::supervisor::SupervisorRef;
+use crate::envelope::{Envelope, Recipient, RefAddr, SignedMessage};
+use crate::mailbox::{Mailbox, MailboxStats, MailboxStatus, OverflowPolicy};
+use crate::message::{Answer, AskError, BastionMessage, Message, Msg, Terminated};
+use crate::path::BastionPath;
+use crate::supervisor::{SupervisorRef, TerminationReason};
+use crate::system::SYSTEM;
+use bastion_executor::pool;
+use crossbeam_queue::SegQueue;
+use futures::future::{self, AbortHandle, Abortable, Aborted, Either};
 use futures::pending;
-use qutex::{Guard, Qutex};
-use std::collections::VecDeque;
-use std::fmt::{self, Display, Formatter};
+use futures::prelude::*;
+use futures_timer::Delay;
+use fxhash::FxHashMap;
+use lightproc::prelude::*;
+use std::any::{Any, TypeId};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// Identifier for a root supervisor and dead-letters children.
@@ -102,12 +120,184 @@ pub struct BastionContext {
     child: ChildRef,
     children: ChildrenRef,
     supervisor: Option<SupervisorRef>,
-    state: Qutex<ContextState>,
+    state: Arc<ContextState>,
+    // The values stored by the child's future (see `set` and
+    // `get`), which only live as long as this context.
+    extensions: Mutex<FxHashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+/// A snapshot of the state of a children group's element, saved
+/// using [`BastionContext::save_state`] and handed to the element
+/// replacing it when its children group is restarted (see
+/// [`BastionContext::take_restored_state`] and
+/// [`Children::with_before_restart_hook`]).
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// let snapshot = Snapshot::new(42usize);
+/// assert_eq!(snapshot.downcast_ref::<usize>(), Some(&42));
+/// assert_eq!(snapshot.downcast::<usize>().ok(), Some(42));
+/// ```
+///
+/// [`BastionContext::save_state`]: struct.BastionContext.html#method.save_state
+/// [`BastionContext::take_restored_state`]: struct.BastionContext.html#method.take_restored_state
+/// [`Children::with_before_restart_hook`]: ../children/struct.Children.html#method.with_before_restart_hook
+pub struct Snapshot(Box<dyn Any + Send + Sync>);
+
+/// A [`Future`] returned when spawning a task using
+/// [`BastionContext::spawn`], which resolves to the task's output
+/// or to a [`TaskError`] if it was cancelled or if it panicked.
+///
+/// Dropping it detaches the task, which keeps running until it
+/// completes or until the child that spawned it stops or faults.
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`BastionContext::spawn`]: struct.BastionContext.html#method.spawn
+/// [`TaskError`]: enum.TaskError.html
+pub struct TaskHandle<T> {
+    handle: RecoverableHandle<Result<T, TaskError>>,
+    abort: AbortHandle,
+}
+
+/// A guard returned when registering a periodic tick using
+/// [`BastionContext::tick_every`], which stops the tick once it
+/// is dropped or cancelled.
+///
+/// [`BastionContext::tick_every`]: struct.BastionContext.html#method.tick_every
+#[must_use = "the tick stops once its guard is dropped"]
+pub struct TickGuard {
+    id: usize,
+    ticks: Ticks,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Why a task spawned using [`BastionContext::spawn`] didn't
+/// complete.
+///
+/// [`BastionContext::spawn`]: struct.BastionContext.html#method.spawn
+pub enum TaskError {
+    /// The task was cancelled, either using [`TaskHandle::cancel`]
+    /// or because the child that spawned it stopped or faulted.
+    ///
+    /// [`TaskHandle::cancel`]: struct.TaskHandle.html#method.cancel
+    Cancelled,
+    /// The task panicked.
+    Panicked,
+}
+
+#[derive(Debug, Clone, Default)]
+// The last snapshot saved by a child, shared with its children
+// group (which doesn't keep the child's whole state alive).
+pub(crate) struct SavedState(Arc<Mutex<Option<Snapshot>>>);
+
+#[derive(Debug, Clone, Default)]
+// The message that a child asked to send to an element if it
+// faults or is killed, shared with the child (which doesn't keep
+// its context's whole state alive).
+pub(crate) struct LastWill(Arc<Mutex<Option<(ChildRef, Msg)>>>);
+
+#[derive(Debug, Clone, Default)]
+// The messages that a child didn't handle before stopping or
+// faulting, shared with its children group (which delivers
+// them to the element replacing it).
+pub(crate) struct Undelivered(Arc<Mutex<Vec<SignedMessage>>>);
+
+#[derive(Debug, Clone)]
+// The last time a child sent a heartbeat, shared with its
+// children group (which checks that it keeps sending them).
+pub(crate) struct Heartbeat(Arc<Mutex<Instant>>);
+
+#[derive(Debug, Clone, Default)]
+// The tasks that a child spawned (using `BastionContext::spawn`)
+// and that didn't complete yet, shared with the child (which
+// cancels them once it is dropped) and with the tasks themselves
+// (which remove themselves once they complete).
+pub(crate) struct Tasks(Arc<Mutex<TasksInner>>);
+
+#[derive(Debug, Default)]
+struct TasksInner {
+    next_id: usize,
+    handles: FxHashMap<usize, AbortHandle>,
+    // Whether the child was dropped, in which case the tasks
+    // it still spawns are cancelled right away.
+    cancelled: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+// The periodic ticks that a child registered (using
+// `BastionContext::tick_every`), shared with the child (which
+// polls their timers and delivers their messages to itself) and
+// with their guards (which remove them once dropped).
+pub(crate) struct Ticks(Arc<Mutex<TicksInner>>);
+
+#[derive(Debug, Default)]
+struct TicksInner {
+    next_id: usize,
+    ticks: FxHashMap<usize, Tick>,
+}
+
+struct Tick {
+    interval: Duration,
+    delay: Delay,
+    msg: Box<dyn Fn() -> BastionMessage + Send>,
 }
 
 #[derive(Debug)]
+// The state shared by a child and its context, storing the
+// messages it received until its future retrieves them.
 pub(crate) struct ContextState {
-    msgs: VecDeque<SignedMessage>,
+    // The messages that were delivered to the child but not
+    // moved to its mailbox yet. This is a lock-free queue so
+    // that delivering a message never contends with the child's
+    // receive path.
+    inbox: SegQueue<SignedMessage>,
+    // The child's mailbox, which is only accessed while moving
+    // the messages of the inbox to it or while retrieving them.
+    msgs: Mutex<Msgs>,
+    // Whether a message is held back because the mailbox is
+    // full (when using `OverflowPolicy::Block` or `Fail`).
+    blocked: AtomicBool,
+    // Whether the child was told to stop, and whether its future
+    // waits for it to be (see `BastionContext::shutdown_requested`),
+    // in which case it isn't stopped until it returns.
+    shutdown: AtomicBool,
+    handles_shutdown: AtomicBool,
+    status: Arc<MailboxStatus>,
+    // The number of messages that the child's future can still
+    // retrieve before being forced to yield, and the number it
+    // gets every time it is polled.
+    budget: AtomicUsize,
+    poll_budget: usize,
+    // The state restored from the previous incarnation of the
+    // child (if any), and the one it saved for the next one.
+    restored: Mutex<Option<Snapshot>>,
+    saved: SavedState,
+    // A clone of the state that the child's group gives to all
+    // of its elements (see `Children::with_seed_state`).
+    seed: Mutex<Option<Snapshot>>,
+    // The signature of the last message retrieved by the
+    // child (see `BastionContext::reply`), and the deadline it
+    // was asked with (see `BastionContext::current_deadline`).
+    current_sender: Mutex<Option<RefAddr>>,
+    current_deadline: Mutex<Option<Instant>>,
+    // The custom reason the child gives when it stops (if any).
+    exit_reason: Mutex<Option<Msg>>,
+    last_will: LastWill,
+    heartbeat: Heartbeat,
+    tasks: Tasks,
+    ticks: Ticks,
+}
+
+#[derive(Debug)]
+struct Msgs {
+    mailbox: Box<dyn Mailbox>,
+    // The message that the mailbox refused while using
+    // `OverflowPolicy::Block` or `Fail`, which will be the next
+    // one moved to it.
+    held: Option<SignedMessage>,
 }
 
 impl BastionId {
@@ -124,15 +314,18 @@ impl BastionContext {
         child: ChildRef,
         children: ChildrenRef,
         supervisor: Option<SupervisorRef>,
-        state: Qutex<ContextState>,
+        state: Arc<ContextState>,
     ) -> Self {
         debug!("BastionContext({}): Creating.", id);
+        let extensions = Mutex::new(FxHashMap::default());
+
         BastionContext {
             id,
             child,
             children,
             supervisor,
             state,
+            extensions,
         }
     }
 
@@ -170,6 +363,118 @@ impl BastionContext {
         &self.child
     }
 
+    /// Returns the identifier of the children group's element that
+    /// is linked to this `BastionContext` (the same as
+    /// `ctx.current().id()`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             println!("Child({}): Started in Children({}).", ctx.id(), ctx.group_id());
+    ///             assert_eq!(ctx.id(), ctx.current().id());
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns the identifier of the children group of the element
+    /// that is linked to this `BastionContext` (the same as
+    /// `ctx.parent().id()`).
+    ///
+    /// See [`id`] for an example.
+    ///
+    /// [`id`]: #method.id
+    pub fn group_id(&self) -> &BastionId {
+        self.children.id()
+    }
+
+    /// Returns the [`BastionPath`] of the children group's element
+    /// that is linked to this `BastionContext`, which contains the
+    /// identifiers of all its ancestors.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let path: &BastionPath = ctx.path();
+    ///             assert_eq!(path.id(), ctx.id());
+    ///             assert!(path.elem().as_ref().unwrap().is_child());
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`BastionPath`]: ../path/struct.BastionPath.html
+    pub fn path(&self) -> &BastionPath {
+        self.child.path()
+    }
+
+    /// Returns the ordinal of the element that is linked to this
+    /// `BastionContext` within its children group, which it keeps
+    /// when it is restarted (see [`ChildRef::index`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let index: usize = ctx.elem_index();
+    ///             // Handle the shards mapped to this element...
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::index`]: ../child_ref/struct.ChildRef.html#method.index
+    pub fn elem_index(&self) -> usize {
+        self.child.index()
+    }
+
     /// Returns a [`ChildrenRef`] referencing the children group
     /// of the element that is linked to this `BastionContext`.
     ///
@@ -261,6 +566,157 @@ impl BastionContext {
         self.supervisor.as_ref()
     }
 
+    /// Returns [`SupervisorRef`]s referencing the supervisor that
+    /// supervises the element that is linked to this
+    /// `BastionContext` (see [`supervisor`]) followed by all its
+    /// ancestors, up to (but excluding) the system supervisor.
+    ///
+    /// The ancestors are looked up in the supervision tree, so
+    /// if one of them can't currently be reached (eg. because
+    /// it is being restarted), only the element's supervisor is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.supervisor(|sp| {
+    ///         sp.children(|children| {
+    ///             children.with_exec(|ctx: BastionContext| {
+    ///                 async move {
+    ///                     // The inner supervisor, then the outer one...
+    ///                     let chain: Vec<SupervisorRef> = ctx.supervisor_chain();
+    ///                     // ...which could be told to stop everything.
+    ///                     if let Some(root) = chain.last() {
+    ///                         root.stop().ok();
+    ///                     }
+    ///
+    ///                     Ok(())
+    ///                 }
+    ///             })
+    ///         })
+    ///     })
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SupervisorRef`]: supervisor/struct.SupervisorRef.html
+    /// [`supervisor`]: #method.supervisor
+    pub fn supervisor_chain(&self) -> Vec<SupervisorRef> {
+        let supervisor = match &self.supervisor {
+            Some(supervisor) => supervisor.clone(),
+            None => return vec![],
+        };
+
+        // The path starts with the top-level supervisor and ends
+        // with the element's supervisor.
+        let path = supervisor.path().clone();
+        let ids: Vec<&BastionId> = path.iter().collect();
+        let mut ancestors = Vec::new();
+        let mut supervisors = SYSTEM.supervisors();
+        for id in &ids[..ids.len() - 1] {
+            match supervisors.into_iter().find(|sp| sp.id() == *id) {
+                Some(sp) => {
+                    supervisors = sp.supervisors();
+                    ancestors.push(sp);
+                }
+                None => {
+                    debug!(
+                        "BastionContext({}): Couldn't find supervisor: {}",
+                        self.id, id
+                    );
+                    ancestors.clear();
+                    break;
+                }
+            }
+        }
+
+        let mut chain = vec![supervisor];
+        chain.extend(ancestors.into_iter().rev());
+        chain
+    }
+
+    /// Creates a new [`Children`] with the given redundancy, passes
+    /// it through the specified `init` closure and then sends it to
+    /// the supervisor of the element linked to this `BastionContext`
+    /// (or to the system supervisor if it has none, see
+    /// [`supervisor`]) for it to start supervising it.
+    ///
+    /// This method returns a [`ChildrenRef`] referencing the newly
+    /// created children group if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `init` - The closure taking the new [`Children`] as an
+    ///     argument and returning it once configured.
+    /// * `redundancy` - The number of elements the children group
+    ///     starts with (see [`Children::with_redundancy`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             let jobs: usize = 4;
+    ///             // Spinning up a worker per job...
+    ///             let workers: ChildrenRef = ctx.children(
+    ///                 |children| {
+    ///                     children.with_exec(|ctx: BastionContext| {
+    ///                         async move {
+    ///                             // ...
+    ///                             Ok(())
+    ///                         }
+    ///                     })
+    ///                 },
+    ///                 jobs,
+    ///             )?;
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children`]: ../children/struct.Children.html
+    /// [`Children::with_redundancy`]: ../children/struct.Children.html#method.with_redundancy
+    /// [`ChildrenRef`]: ../children_ref/struct.ChildrenRef.html
+    /// [`supervisor`]: #method.supervisor
+    pub fn children<C>(&self, init: C, redundancy: usize) -> Result<ChildrenRef, ()>
+    where
+        C: FnOnce(Children) -> Children,
+    {
+        debug!(
+            "BastionContext({}): Creating children group with redundancy: {}",
+            self.id, redundancy
+        );
+        let init = move |children: Children| init(children.with_redundancy(redundancy));
+        match &self.supervisor {
+            Some(supervisor) => supervisor.children(init),
+            None => SYSTEM.supervisor().children(init),
+        }
+    }
+
     /// Tries to retrieve asynchronously a message received by
     /// the element this `BastionContext` is linked to.
     ///
@@ -300,10 +756,9 @@ impl BastionContext {
     /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
     pub async fn try_recv(&self) -> Option<SignedMessage> {
         debug!("BastionContext({}): Trying to receive message.", self.id);
-        // TODO: Err(Error)
-        let mut state = self.state.clone().lock_async().await.ok()?;
+        self.consume_budget().await;
 
-        if let Some(msg) = state.msgs.pop_front() {
+        if let Some(msg) = self.state.pop_msg() {
             trace!("BastionContext({}): Received message: {:?}", self.id, msg);
             Some(msg)
         } else {
@@ -351,22 +806,27 @@ impl BastionContext {
     /// [`SignedMessage`]: ../prelude/struct.SignedMessage.html
     pub async fn recv(&self) -> Result<SignedMessage, ()> {
         debug!("BastionContext({}): Waiting to receive message.", self.id);
-        loop {
-            // TODO: Err(Error)
-            let mut state = self.state.clone().lock_async().await.unwrap();
+        self.consume_budget().await;
 
-            if let Some(msg) = state.msgs.pop_front() {
+        loop {
+            if let Some(msg) = self.state.pop_msg() {
                 trace!("BastionContext({}): Received message: {:?}", self.id, msg);
                 return Ok(msg);
             }
 
-            Guard::unlock(state);
-
             pending!();
         }
     }
 
-    /// Returns [`RefAddr`] of the current `BastionContext`
+    /// Retrieves asynchronously all the messages received by the
+    /// element this `BastionContext` is linked to that weren't
+    /// retrieved yet, in the order they would have been retrieved
+    /// using [`try_recv`], without waiting for one if none has been
+    /// received.
+    ///
+    /// If you need to wait (always asynchronously) until at
+    /// least one message can be retrieved, use [`recv_all`]
+    /// instead.
     ///
     /// # Example
     ///
@@ -376,37 +836,45 @@ impl BastionContext {
     /// # fn main() {
     ///     # Bastion::init();
     ///     #
-    ///
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             ctx.tell(&ctx.signature(), "Hello to myself");
+    ///             let backlog: Vec<SignedMessage> = ctx.drain().await;
+    ///             // Handle the messages as a batch...
     ///
-    ///             # Bastion::stop();
     ///             Ok(())
     ///         }
     ///     })
     /// }).expect("Couldn't create the children group.");
     ///     #
     ///     # Bastion::start();
+    ///     # Bastion::stop();
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
     ///
-    /// [`RefAddr`]: /prelude/struct.Answer.html
-    pub fn signature(&self) -> RefAddr {
-        RefAddr::new(
-            self.current().path().clone(),
-            self.current().sender().clone(),
-        )
+    /// [`try_recv`]: #method.try_recv
+    /// [`recv_all`]: #method.recv_all
+    pub async fn drain(&self) -> Vec<SignedMessage> {
+        debug!("BastionContext({}): Draining messages.", self.id);
+        self.consume_budget().await;
+
+        let msgs = self.state.pop_msgs();
+        trace!(
+            "BastionContext({}): Received {} messages.",
+            self.id,
+            msgs.len()
+        );
+        msgs
     }
 
-    /// Sends a message to the specified [`RefAddr`]
-    ///
-    /// # Arguments
+    /// Retrieves asynchronously all the messages received by the
+    /// element this `BastionContext` is linked to that weren't
+    /// retrieved yet (like [`drain`]), waiting (always
+    /// asynchronously) for one if none has been received yet.
     ///
-    /// * `to` – the [`RefAddr`] to send the message to
-    /// * `msg` – The actual message to send
+    /// This method returns the (non-empty) list of messages if it
+    /// succeeded, or `Err(())` otherwise.
     ///
     /// # Example
     ///
@@ -419,13 +887,11 @@ impl BastionContext {
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             // Wait for a message to be received...
-    ///             let smsg: SignedMessage = ctx.recv().await?;
-    ///             // Obtain address of this message sender...
-    ///             let sender_addr = smsg.signature();
-    ///             // And send something back
-    ///             ctx.tell(&sender_addr, "Ack").expect("Unable to acknowledge");
-    ///             Ok(())
+    ///             loop {
+    ///                 for msg in ctx.recv_all().await? {
+    ///                     // Handle every message of the batch...
+    ///                 }
+    ///             }
     ///         }
     ///     })
     /// }).expect("Couldn't create the children group.");
@@ -436,61 +902,260 @@ impl BastionContext {
     /// # }
     /// ```
     ///
-    /// [`RefAddr`]: ../prelude/struct.RefAddr.html
-    pub fn tell<M: Message>(&self, to: &RefAddr, msg: M) -> Result<(), M> {
-        debug!(
-            "{:?}: Telling message: {:?} to: {:?}",
-            self.current().path(),
-            msg,
-            to.path()
-        );
-        let msg = BastionMessage::tell(msg);
-        let env = Envelope::new_with_sign(msg, self.signature());
-        // FIXME: panics?
-        to.sender()
-            .unbounded_send(env)
-            .map_err(|err| err.into_inner().into_msg().unwrap())
+    /// [`drain`]: #method.drain
+    pub async fn recv_all(&self) -> Result<Vec<SignedMessage>, ()> {
+        debug!("BastionContext({}): Waiting to receive messages.", self.id);
+        self.consume_budget().await;
+
+        loop {
+            let msgs = self.state.pop_msgs();
+            if !msgs.is_empty() {
+                trace!(
+                    "BastionContext({}): Received {} messages.",
+                    self.id,
+                    msgs.len()
+                );
+                return Ok(msgs);
+            }
+
+            pending!();
+        }
     }
 
-    /// Sends a message from behalf of current context to the addr,
-    /// allowing to addr owner answer.
-    ///
-    /// This method returns [`Answer`] if it succeeded, or `Err(msg)`
-    /// otherwise.
+    /// Returns whether the element linked to this `BastionContext`
+    /// was told to stop (eg. because its children group is being
+    /// stopped).
     ///
-    /// # Argument
-    ///
-    /// * `msg` - The message to send.
+    /// Once this method (or [`shutdown_requested`]) was called, the
+    /// element isn't stopped when told to anymore: it keeps running
+    /// until its future returns (unless it gets killed, eg. because
+    /// it was told to stop within a deadline).
     ///
     /// # Example
     ///
-    /// ```
+    /// ```rust
     /// # use bastion::prelude::*;
     /// #
     /// # fn main() {
     ///     # Bastion::init();
-    /// // The message that will be "asked"...
-    /// const ASK_MSG: &'static str = "A message containing data (ask).";
-    /// // The message the will be "answered"...
-    /// const ANSWER_MSG: &'static str = "A message containing data (answer).";
-    ///
-    ///     # let children_ref =
-    /// // Create a new child...
+    ///     #
     /// Bastion::children(|children| {
     ///     children.with_exec(|ctx: BastionContext| {
     ///         async move {
-    ///             // ...which will receive the message asked...
-    ///             msg! { ctx.recv().await?,
-    ///                 msg: &'static str =!> {
-    ///                     assert_eq!(msg, ASK_MSG);
+    ///             while !ctx.is_shutdown_requested() {
+    ///                 if let Some(msg) = ctx.try_recv().await {
     ///                     // Handle the message...
-    ///
-    ///                     // ...and eventually answer to it...
-    ///                     answer!(ctx, ANSWER_MSG);
-    ///                 };
-    ///                 // This won't happen because this example
-    ///                 // only "asks" a `&'static str`...
-    ///                 _: _ => ();
+    ///                 }
+    ///                 // Do some work...
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`shutdown_requested`]: #method.shutdown_requested
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.state.is_shutdown_requested()
+    }
+
+    /// Waits (always asynchronously) until the element linked to
+    /// this `BastionContext` is told to stop, allowing its future
+    /// to finish its current work and return `Ok(())` instead of
+    /// being stopped in the middle of it (see
+    /// [`is_shutdown_requested`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use futures::future::{self, Either};
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 let recv = Box::pin(ctx.recv());
+    ///                 let shutdown = Box::pin(ctx.shutdown_requested());
+    ///                 match future::select(recv, shutdown).await {
+    ///                     Either::Left((msg, _)) => {
+    ///                         let msg = msg?;
+    ///                         // Handle the message...
+    ///                     }
+    ///                     Either::Right(_) => return Ok(()),
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`is_shutdown_requested`]: #method.is_shutdown_requested
+    pub async fn shutdown_requested(&self) {
+        loop {
+            if self.is_shutdown_requested() {
+                debug!("BastionContext({}): Shutdown requested.", self.id);
+                return;
+            }
+
+            pending!();
+        }
+    }
+
+    /// Returns [`RefAddr`] of the current `BastionContext`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.tell(&ctx.signature(), "Hello to myself");
+    ///
+    ///             # Bastion::stop();
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`RefAddr`]: /prelude/struct.Answer.html
+    pub fn signature(&self) -> RefAddr {
+        RefAddr::new(
+            self.current().path().clone(),
+            self.current().sender().clone(),
+        )
+    }
+
+    /// Sends a message to the specified [`RefAddr`] or [`ChildRef`]
+    ///
+    /// When sent to a [`ChildRef`], the message is validated and
+    /// respects the overflow policy of the element's group like
+    /// with [`ChildRef::tell_anonymously`], and is given back as
+    /// an error if it is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` – the [`RefAddr`] or [`ChildRef`] to send the message to
+    /// * `msg` – The actual message to send
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Wait for a message to be received...
+    ///             let smsg: SignedMessage = ctx.recv().await?;
+    ///             // Obtain address of this message sender...
+    ///             let sender_addr = smsg.signature();
+    ///             // And send something back
+    ///             ctx.tell(&sender_addr, "Ack").expect("Unable to acknowledge");
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`RefAddr`]: ../prelude/struct.RefAddr.html
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    /// [`ChildRef::tell_anonymously`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously
+    pub fn tell<M: Message, R: Recipient + ?Sized>(&self, to: &R, msg: M) -> Result<(), M> {
+        // The messages sent to a `ChildRef` are validated and
+        // respect the overflow policy of the element's group.
+        if let Some(child_ref) = to.child_ref() {
+            return child_ref.tell_signed(msg, self.signature());
+        }
+
+        let to = to.ref_addr();
+        debug!(
+            "{:?}: Telling message: {:?} to: {:?}",
+            self.current().path(),
+            msg,
+            to.path()
+        );
+        let msg = BastionMessage::tell(msg);
+        let env = Envelope::new_with_sign(msg, self.signature());
+        to.sender().try_send(env).map_err(Envelope::take_back)
+    }
+
+    /// Sends a message from behalf of current context to the addr,
+    /// allowing to addr owner answer.
+    ///
+    /// If the last message retrieved by the element linked to this
+    /// `BastionContext` was asked with a deadline (see
+    /// [`current_deadline`]), the message carries the same one.
+    ///
+    /// This method returns [`Answer`] if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    /// // The message that will be "asked"...
+    /// const ASK_MSG: &'static str = "A message containing data (ask).";
+    /// // The message the will be "answered"...
+    /// const ANSWER_MSG: &'static str = "A message containing data (answer).";
+    ///
+    ///     # let children_ref =
+    /// // Create a new child...
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // ...which will receive the message asked...
+    ///             msg! { ctx.recv().await?,
+    ///                 msg: &'static str =!> {
+    ///                     assert_eq!(msg, ASK_MSG);
+    ///                     // Handle the message...
+    ///
+    ///                     // ...and eventually answer to it...
+    ///                     answer!(ctx, ANSWER_MSG);
+    ///                 };
+    ///                 // This won't happen because this example
+    ///                 // only "asks" a `&'static str`...
+    ///                 _: _ => ();
     ///             }
     ///
     ///             Ok(())
@@ -528,38 +1193,1761 @@ impl BastionContext {
     /// ```
     ///
     /// [`Answer`]: /message/struct.Answer.html
-    pub fn ask<M: Message>(&self, to: &RefAddr, msg: M) -> Result<Answer, M> {
+    /// [`current_deadline`]: #method.current_deadline
+    pub fn ask<M: Message, R: Recipient + ?Sized>(&self, to: &R, msg: M) -> Result<Answer, M> {
+        self.ask_within(to, msg, self.current_deadline())
+    }
+
+    fn ask_within<M: Message, R: Recipient + ?Sized>(
+        &self,
+        to: &R,
+        msg: M,
+        deadline: Option<Instant>,
+    ) -> Result<Answer, M> {
+        if let Some(child_ref) = to.child_ref() {
+            return child_ref.ask_signed(msg, deadline, self.signature());
+        }
+
+        let to = to.ref_addr();
         debug!(
             "{:?}: Asking message: {:?} to: {:?}",
             self.current().path(),
             msg,
             to
         );
-        let (msg, answer) = BastionMessage::ask(msg);
+        let (msg, answer) = BastionMessage::ask_within(msg, deadline);
         let env = Envelope::new_with_sign(msg, self.signature());
-        // FIXME: panics?
-        to.sender()
-            .unbounded_send(env)
-            .map_err(|err| err.into_inner().into_msg().unwrap())?;
+        to.sender().try_send(env).map_err(Envelope::take_back)?;
 
         Ok(answer)
     }
-}
 
-impl ContextState {
-    pub(crate) fn new() -> Self {
-        let msgs = VecDeque::new();
+    /// Sends a message from behalf of current context to the addr
+    /// (like [`ask`]) and waits for its answer, giving up after
+    /// the given timeout.
+    ///
+    /// The message carries the deadline after which its answer
+    /// isn't waited for anymore, for the recipient to skip work
+    /// that can't be completed in time (see [`current_deadline`]).
+    /// This deadline is the one of the last message retrieved by
+    /// the element linked to this `BastionContext` if it is
+    /// earlier, in which case the message isn't even sent if it
+    /// already passed.
+    ///
+    /// This method returns the answer if it was received in time,
+    /// or an [`AskError`] otherwise (`AskError::Rejected(msg)` if
+    /// the message couldn't be sent, `AskError::NoAnswer` if it was
+    /// dropped without being answered or `AskError::TimedOut`).
+    ///
+    /// # Arguments
+    ///
+    /// * `to` – the [`RefAddr`] or [`ChildRef`] to send the message to
+    /// * `msg` - The message to send.
+    /// * `timeout` - How long to wait for the answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let doubler = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     msg: usize =!> {
+    ///                         answer!(ctx, msg * 2).ok();
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    /// let doubler_ref = doubler.elems()[0].clone();
+    ///
+    /// Bastion::children(move |children| {
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let doubler_ref = doubler_ref.clone();
+    ///         async move {
+    ///             let answer = ctx
+    ///                 .ask_with_timeout(&doubler_ref, 21usize, Duration::from_secs(1))
+    ///                 .await
+    ///                 .map_err(|_| ())?;
+    ///
+    ///             msg! { answer,
+    ///                 msg: usize => {
+    ///                     assert_eq!(msg, 42);
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ask`]: #method.ask
+    /// [`current_deadline`]: #method.current_deadline
+    /// [`AskError`]: ../message/enum.AskError.html
+    /// [`RefAddr`]: ../prelude/struct.RefAddr.html
+    /// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+    pub async fn ask_with_timeout<M: Message, R: Recipient + ?Sized>(
+        &self,
+        to: &R,
+        msg: M,
+        timeout: Duration,
+    ) -> Result<SignedMessage, AskError<M>> {
+        let now = Instant::now();
+        let deadline = match self.current_deadline() {
+            Some(current) => current.min(now + timeout),
+            None => now + timeout,
+        };
+        if deadline <= now {
+            debug!(
+                "BastionContext({}): Deadline already passed, not asking: {:?}",
+                self.id, msg
+            );
+            return Err(AskError::TimedOut);
+        }
 
-        ContextState { msgs }
+        let answer = self
+            .ask_within(to, msg, Some(deadline))
+            .map_err(AskError::Rejected)?;
+        match future::select(answer, Delay::new(deadline - now)).await {
+            Either::Left((answer, _)) => answer.map_err(|()| AskError::NoAnswer),
+            Either::Right(_) => {
+                debug!(
+                    "BastionContext({}): No answer after {:?}.",
+                    self.id,
+                    deadline - now
+                );
+                Err(AskError::TimedOut)
+            }
+        }
+    }
+
+    /// Sends a message to all the other elements of the children
+    /// group of the element linked to this `BastionContext` (the
+    /// message is shared, like when using [`ChildrenRef::broadcast`]).
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise.
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_redundancy(3)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 // Telling the other elements that this one is up...
+    ///                 ctx.broadcast_siblings(ctx.elem_index())
+    ///                     .expect("Couldn't send the message.");
+    ///
+    ///                 Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef::broadcast`]: ../children_ref/struct.ChildrenRef.html#method.broadcast
+    pub fn broadcast_siblings<M: Message>(&self, msg: M) -> Result<(), M> {
+        self.parent()
+            .send_siblings(msg, self.id.clone(), self.signature())
     }
 
-    pub(crate) fn push_msg(&mut self, msg: Msg, sign: RefAddr) {
-        self.msgs.push_back(SignedMessage::new(msg, sign))
+    /// Returns the [`RefAddr`] of the sender of the last message
+    /// retrieved using [`recv`] or [`try_recv`], or `None` if no
+    /// message has been retrieved yet.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             assert!(ctx.current_sender().is_none());
+    ///
+    ///             ctx.recv().await?;
+    ///             let sender: RefAddr = ctx.current_sender().unwrap();
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`RefAddr`]: ../prelude/struct.RefAddr.html
+    /// [`recv`]: #method.recv
+    /// [`try_recv`]: #method.try_recv
+    pub fn current_sender(&self) -> Option<RefAddr> {
+        self.state.current_sender()
+    }
+
+    /// Returns when the sender of the last message retrieved using
+    /// [`recv`] or [`try_recv`] stops waiting for its answer, if
+    /// it was asked with a deadline (see [`ask_with_timeout`]),
+    /// allowing to skip work that can't be completed in time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Instant;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     query: &'static str =!> {
+    ///                         // Nobody is waiting for the answer anymore...
+    ///                         let expired = ctx
+    ///                             .current_deadline()
+    ///                             .map(|deadline| deadline <= Instant::now())
+    ///                             .unwrap_or(false);
+    ///
+    ///                         // ...so the query isn't even run.
+    ///                         if !expired {
+    ///                             answer!(ctx, query.len()).ok();
+    ///                         }
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv`]: #method.recv
+    /// [`try_recv`]: #method.try_recv
+    /// [`ask_with_timeout`]: #method.ask_with_timeout
+    pub fn current_deadline(&self) -> Option<Instant> {
+        self.state.current_deadline()
+    }
+
+    /// Sends a message to the sender of the last message retrieved
+    /// using [`recv`] or [`try_recv`] (see [`current_sender`]).
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// if no message has been retrieved yet or if the message
+    /// couldn't be sent.
+    ///
+    /// # Argument
+    ///
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     msg: &'static str => {
+    ///                         // Acknowledge the message without
+    ///                         // knowing who sent it...
+    ///                         ctx.reply("Ack").ok();
+    ///                     };
+    ///                     _: _ => ();
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`recv`]: #method.recv
+    /// [`try_recv`]: #method.try_recv
+    /// [`current_sender`]: #method.current_sender
+    pub fn reply<M: Message>(&self, msg: M) -> Result<(), M> {
+        match self.current_sender() {
+            Some(sender) => self.tell(&sender, msg),
+            None => {
+                debug!(
+                    "BastionContext({}): No sender to reply to: {:?}",
+                    self.id, msg
+                );
+                Err(msg)
+            }
+        }
+    }
+
+    /// Runs the given closure on the blocking thread pool and
+    /// waits for its result, without blocking the executor's
+    /// threads (and thus the other children) in the meantime.
+    ///
+    /// This method returns the closure's result if it succeeded,
+    /// or `Err(())` if it panicked.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure to run on the blocking thread pool.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Reading a file without stalling the other children...
+    ///             let file = ctx
+    ///                 .blocking(|| std::fs::read_to_string("Cargo.toml"))
+    ///                 .await?
+    ///                 .map_err(|_| ())?;
+    ///             println!("Read {} bytes.", file.len());
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub async fn blocking<F, R>(&self, f: F) -> Result<R, ()>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        trace!("BastionContext({}): Running blocking task.", self.id);
+        Bastion::spawn_blocking(f).await.ok_or(())
+    }
+
+    /// Spawns a task running the given future alongside the
+    /// element linked to this `BastionContext`, which is cancelled
+    /// once the element stops or faults (so that it never outlives
+    /// it).
+    ///
+    /// If the task panics, the element isn't affected and the
+    /// returned [`TaskHandle`] resolves to `Err(TaskError::Panicked)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `future` - The future the task runs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Polling something while handling messages...
+    ///             let poller: TaskHandle<()> = ctx.spawn(async {
+    ///                 // ...
+    ///             });
+    ///
+    ///             msg! { ctx.recv().await?,
+    ///                 msg: &'static str => {
+    ///                     // ...until told to stop.
+    ///                     poller.cancel();
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`TaskHandle`]: struct.TaskHandle.html
+    pub fn spawn<F, T>(&self, future: F) -> TaskHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        trace!("BastionContext({}): Spawning task.", self.id);
+        let (abort, registration) = AbortHandle::new_pair();
+        let tasks = self.state.tasks().clone();
+        let task_id = tasks.push(abort.clone());
+
+        let id = self.id.clone();
+        let task = async move {
+            let res = AssertUnwindSafe(Abortable::new(future, registration))
+                .catch_unwind()
+                .await;
+            tasks.remove(task_id);
+
+            match res {
+                Ok(Ok(output)) => Ok(output),
+                Ok(Err(Aborted)) => {
+                    debug!("BastionContext({}): Task cancelled.", id);
+                    Err(TaskError::Cancelled)
+                }
+                Err(_) => {
+                    warn!("BastionContext({}): Task panicked.", id);
+                    Err(TaskError::Panicked)
+                }
+            }
+        };
+
+        let handle = pool::spawn(task, ProcStack::default());
+        TaskHandle { handle, abort }
+    }
+
+    /// Delivers a copy of the given message to the element linked
+    /// to this `BastionContext` every time the given interval
+    /// elapses, as if it was told to it by the element itself,
+    /// until the returned [`TickGuard`] is dropped or cancelled.
+    ///
+    /// The ticks rely on the same timers as the rest of the system
+    /// instead of on a spawned task, and stop when the element
+    /// stops, faults or gets restarted.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - How often the message is delivered.
+    /// * `msg` - The message to deliver.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Receiving "Tick" every second...
+    ///             let ticks = ctx.tick_every(Duration::from_secs(1), "Tick");
+    ///
+    ///             loop {
+    ///                 msg! { ctx.recv().await?,
+    ///                     msg: &'static str => {
+    ///                         // ...
+    ///                     };
+    ///                     _: _ => break;
+    ///                 }
+    ///             }
+    ///
+    ///             // ...until something else is received.
+    ///             ticks.cancel();
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`TickGuard`]: struct.TickGuard.html
+    pub fn tick_every<M: Message + Clone>(&self, interval: Duration, msg: M) -> TickGuard {
+        trace!(
+            "BastionContext({}): Ticking every {:?}: {:?}",
+            self.id,
+            interval,
+            msg
+        );
+        let ticks = self.state.ticks().clone();
+        let id = ticks.push(
+            interval,
+            Box::new(move || BastionMessage::tell(msg.clone())),
+        );
+
+        TickGuard { id, ticks }
+    }
+
+    /// Sets the custom reason given by the element linked to this
+    /// `BastionContext` when its future returns `Ok(())`, which
+    /// its supervisor and the elements watching it will receive
+    /// as a [`TerminationReason::Custom`].
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - The reason to give when stopping.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Stop once the work is done...
+    ///             ctx.exit_with("Done");
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`TerminationReason::Custom`]: ../supervisor/enum.TerminationReason.html#variant.Custom
+    pub fn exit_with<M: Message>(&self, reason: M) {
+        debug!(
+            "BastionContext({}): Setting exit reason: {:?}",
+            self.id, reason
+        );
+        // Broadcasted messages can be cloned, allowing to give
+        // the reason to several elements.
+        self.state.set_exit_reason(Msg::broadcast(reason));
+    }
+
+    /// Sets the "last will" of the element linked to this
+    /// `BastionContext`: a message sent to the element referenced
+    /// by the given [`ChildRef`] if this element faults (because
+    /// its future returned an error or panicked) or is killed,
+    /// allowing it to clean up or compensate without having to
+    /// be watched. The message isn't sent if the element stops
+    /// normally.
+    ///
+    /// Setting a last will replaces the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - The element the message will be sent to.
+    /// * `msg` - The message to send.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let compensator = Bastion::children(|children| children)
+    ///     .expect("Couldn't create the children group.");
+    /// let compensator = compensator.elems()[0].clone();
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let compensator = compensator.clone();
+    ///         async move {
+    ///             ctx.set_last_will(&compensator, "Rollback the transaction");
+    ///             // Work that might fail...
+    ///             ctx.recv().await?;
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../children/struct.ChildRef.html
+    pub fn set_last_will<M: Message>(&self, recipient: &ChildRef, msg: M) {
+        debug!(
+            "BastionContext({}): Setting last will for Child({}): {:?}",
+            self.id,
+            recipient.id(),
+            msg
+        );
+        self.state
+            .last_will()
+            .set(recipient.clone(), Msg::tell(msg));
+    }
+
+    /// Starts watching the element referenced by the given
+    /// [`ChildRef`], making the element linked to this
+    /// `BastionContext` receive a [`Terminated`] message once the
+    /// watched element stops or faults (or right away if it
+    /// already did).
+    ///
+    /// Unlike supervision, watching an element doesn't affect the
+    /// lifecycle of the watcher, which is only notified. The watch
+    /// ends once the [`Terminated`] message was sent, so an element
+    /// that was restarted needs to be watched again.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `child_ref` - The element to watch.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let watched = Bastion::children(|children| children)
+    ///     .expect("Couldn't create the children group.");
+    /// let watched = watched.elems()[0].clone();
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let watched = watched.clone();
+    ///         async move {
+    ///             ctx.watch(&watched)?;
+    ///             // Wait for a `Terminated` message...
+    ///             ctx.recv().await?;
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../children/struct.ChildRef.html
+    /// [`Terminated`]: ../message/struct.Terminated.html
+    pub fn watch(&self, child_ref: &ChildRef) -> Result<(), ()> {
+        debug!(
+            "BastionContext({}): Watching Child({}).",
+            self.id,
+            child_ref.id()
+        );
+        let msg = BastionMessage::watch(self.current().clone());
+        let env = Envelope::new_with_sign(msg, self.signature());
+        if !child_ref.mailbox_status().is_closed() && child_ref.send(env).is_ok() {
+            return Ok(());
+        }
+
+        debug!(
+            "BastionContext({}): Child({}) already terminated.",
+            self.id,
+            child_ref.id()
+        );
+        let msg = Terminated::new(child_ref.id().clone(), TerminationReason::Normal);
+        self.current().tell_anonymously(msg).map_err(|_| ())
+    }
+
+    /// Stops watching the element referenced by the given
+    /// [`ChildRef`] (see [`watch`]).
+    ///
+    /// Note that a [`Terminated`] message might still be received
+    /// if the watched element terminated before being told to
+    /// stop being watched.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise (eg. if the watched element already terminated).
+    ///
+    /// # Arguments
+    ///
+    /// * `child_ref` - The element to stop watching.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let watched = Bastion::children(|children| children)
+    ///     .expect("Couldn't create the children group.");
+    /// let watched = watched.elems()[0].clone();
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(move |ctx: BastionContext| {
+    ///         let watched = watched.clone();
+    ///         async move {
+    ///             ctx.watch(&watched)?;
+    ///             // ...
+    ///             ctx.unwatch(&watched).ok();
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef`]: ../children/struct.ChildRef.html
+    /// [`Terminated`]: ../message/struct.Terminated.html
+    /// [`watch`]: #method.watch
+    pub fn unwatch(&self, child_ref: &ChildRef) -> Result<(), ()> {
+        debug!(
+            "BastionContext({}): Unwatching Child({}).",
+            self.id,
+            child_ref.id()
+        );
+        let msg = BastionMessage::unwatch(self.id.clone());
+        let env = Envelope::new_with_sign(msg, self.signature());
+        child_ref.send(env).map_err(|_| ())
+    }
+
+    /// Saves a snapshot of the state of the element linked to this
+    /// `BastionContext`, replacing the previous one. If its children
+    /// group is restarted, the last saved snapshot is handed to the
+    /// element replacing it, which can retrieve it using
+    /// [`take_restored_state`].
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to save.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Resume counting from where the previous
+    ///             // incarnation left off...
+    ///             let mut count = ctx.take_restored_state::<usize>().unwrap_or(0);
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///                 count += 1;
+    ///                 ctx.save_state(count);
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`take_restored_state`]: #method.take_restored_state
+    pub fn save_state<S: Any + Send + Sync>(&self, state: S) {
+        trace!("BastionContext({}): Saving state.", self.id);
+        self.state.save(Snapshot::new(state));
+    }
+
+    /// Takes the snapshot of the state saved by the previous
+    /// incarnation of the element linked to this `BastionContext`
+    /// (using [`save_state`]) before its children group was
+    /// restarted.
+    ///
+    /// This method returns `None` if there is no snapshot or if it
+    /// isn't of type `S` (in which case it can still be taken with
+    /// another type).
+    ///
+    /// See [`save_state`] for an example.
+    ///
+    /// [`save_state`]: #method.save_state
+    pub fn take_restored_state<S: Any + Send + Sync>(&self) -> Option<S> {
+        trace!("BastionContext({}): Taking restored state.", self.id);
+        match self.state.take_restored()?.downcast() {
+            Ok(state) => Some(state),
+            Err(snapshot) => {
+                self.state.restore(Some(snapshot));
+                None
+            }
+        }
+    }
+
+    /// Returns a clone of the snapshot of the state saved by the
+    /// previous incarnation of the element linked to this
+    /// `BastionContext` (like [`take_restored_state`], but leaving
+    /// the snapshot in place so that it can be read again).
+    ///
+    /// This method returns `None` if there is no snapshot or if it
+    /// isn't of type `S`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             if let Some(checkpoint) = ctx.restored_state::<Vec<u8>>() {
+    ///                 // Recover from the checkpoint...
+    ///             }
+    ///
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///                 ctx.save_state(vec![0u8; 16]);
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`take_restored_state`]: #method.take_restored_state
+    pub fn restored_state<S: Any + Send + Sync + Clone>(&self) -> Option<S> {
+        trace!("BastionContext({}): Reading restored state.", self.id);
+        // FIXME: panics?
+        let restored = self.state.restored.lock().unwrap();
+        restored.as_ref()?.downcast_ref().cloned()
+    }
+
+    /// Takes the clone of the state that the children group of
+    /// the element linked to this `BastionContext` gives to all of
+    /// its elements when they are launched (see
+    /// [`Children::with_seed_state`]).
+    ///
+    /// This method returns `None` if there is no seed state (eg.
+    /// because it was already taken) or if it isn't of type `S`
+    /// (in which case it can still be taken with another type).
+    ///
+    /// [`Children::with_seed_state`]: ../children/struct.Children.html#method.with_seed_state
+    pub fn take_seed_state<S: Any + Send + Sync>(&self) -> Option<S> {
+        trace!("BastionContext({}): Taking seed state.", self.id);
+        match self.state.take_seed()?.downcast() {
+            Ok(state) => Some(state),
+            Err(snapshot) => {
+                self.state.seed(Some(snapshot));
+                None
+            }
+        }
+    }
+
+    /// Stores a value of type `T` in this `BastionContext`,
+    /// replacing (and returning) the one that was previously
+    /// stored (if any).
+    ///
+    /// Stored values are kept until the element linked to this
+    /// `BastionContext` stops or faults (unlike the state saved
+    /// with [`save_state`], they aren't handed to the element
+    /// replacing it).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to store.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// struct Handled(usize);
+    ///
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.set(Handled(0));
+    ///
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///                 ctx.update(|handled: &mut Handled| handled.0 += 1);
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`save_state`]: #method.save_state
+    pub fn set<T: Any + Send + Sync>(&self, value: T) -> Option<T> {
+        trace!("BastionContext({}): Storing value.", self.id);
+        // FIXME: panics?
+        let mut extensions = self.extensions.lock().unwrap();
+        let prev = extensions.insert(TypeId::of::<T>(), Box::new(value))?;
+        prev.downcast().ok().map(|prev| *prev)
+    }
+
+    /// Returns a clone of the value of type `T` stored in this
+    /// `BastionContext` using [`set`] (if any).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.set(String::from("greeter"));
+    ///             ctx.recv().await?;
+    ///
+    ///             let name: Option<String> = ctx.get();
+    ///             assert_eq!(name.as_deref(), Some("greeter"));
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`set`]: #method.set
+    pub fn get<T: Any + Send + Sync + Clone>(&self) -> Option<T> {
+        // FIXME: panics?
+        let extensions = self.extensions.lock().unwrap();
+        extensions.get(&TypeId::of::<T>())?.downcast_ref().cloned()
+    }
+
+    /// Calls the given closure with a mutable reference to the
+    /// value of type `T` stored in this `BastionContext` using
+    /// [`set`], returning its result or `None` if there is no
+    /// such value.
+    ///
+    /// Note that the closure shouldn't call the other methods
+    /// accessing the stored values.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure to call with the stored value.
+    ///
+    /// # Example
+    ///
+    /// See [`set`].
+    ///
+    /// [`set`]: #method.set
+    pub fn update<T, F, R>(&self, f: F) -> Option<R>
+    where
+        T: Any + Send + Sync,
+        F: FnOnce(&mut T) -> R,
+    {
+        // FIXME: panics?
+        let mut extensions = self.extensions.lock().unwrap();
+        extensions
+            .get_mut(&TypeId::of::<T>())?
+            .downcast_mut()
+            .map(f)
+    }
+
+    /// Removes and returns the value of type `T` stored in this
+    /// `BastionContext` using [`set`] (if any).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             ctx.set(42usize);
+    ///             assert_eq!(ctx.remove::<usize>(), Some(42));
+    ///             assert_eq!(ctx.get::<usize>(), None);
+    ///
+    ///             Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`set`]: #method.set
+    pub fn remove<T: Any + Send + Sync>(&self) -> Option<T> {
+        trace!("BastionContext({}): Removing value.", self.id);
+        // FIXME: panics?
+        let mut extensions = self.extensions.lock().unwrap();
+        let value = extensions.remove(&TypeId::of::<T>())?;
+        value.downcast().ok().map(|value| *value)
+    }
+
+    /// Returns statistics about the messages received by the
+    /// element linked to this `BastionContext` (how many were
+    /// stored in its mailbox, how many it retrieved, how long they
+    /// waited to be, etc.), like [`ChildRef::mailbox_stats`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///
+    ///                 let stats: MailboxStats = ctx.stats();
+    ///                 if stats.mean_latency() > Duration::from_millis(100) {
+    ///                     // Messages wait too long to be handled...
+    ///                 }
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildRef::mailbox_stats`]: ../child_ref/struct.ChildRef.html#method.mailbox_stats
+    pub fn stats(&self) -> MailboxStats {
+        self.state.stats()
+    }
+
+    /// Tells the children group of the element linked to this
+    /// `BastionContext` that it finished initializing, which
+    /// resolves the [`Ready`]s waiting for it once all the other
+    /// elements of the group did the same (see [`ChildrenRef::ready`]
+    /// and [`Bastion::ready`]).
+    ///
+    /// An element that is restarted needs to call this method
+    /// again for its group to be considered as ready.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Opening connections, loading data...
+    ///
+    ///             // ...and telling that messages can be sent.
+    ///             ctx.notify_started().expect("Couldn't send the message.");
+    ///
+    ///             loop {
+    ///                 ctx.recv().await?;
+    ///             }
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Ready`]: ../message/struct.Ready.html
+    /// [`ChildrenRef::ready`]: ../children_ref/struct.ChildrenRef.html#method.ready
+    /// [`Bastion::ready`]: ../struct.Bastion.html#method.ready
+    pub fn notify_started(&self) -> Result<(), ()> {
+        debug!("BastionContext({}): Notifying it is ready.", self.id);
+        let msg = BastionMessage::elem_ready(self.id.clone());
+        let env = Envelope::new_with_sign(msg, self.signature());
+        self.children.send(env).map_err(|_| ())
+    }
+
+    /// Tells the children group of the element linked to this
+    /// `BastionContext` that it is still making progress.
+    ///
+    /// When the group was created using [`Children::with_heartbeat`],
+    /// its elements need to call this method regularly, otherwise
+    /// they are considered as hung.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children
+    ///         .with_heartbeat(Duration::from_secs(1), 3, HangAction::Restart)
+    ///         .with_exec(|ctx: BastionContext| {
+    ///             async move {
+    ///                 // Regularly, while doing some long work...
+    ///                 ctx.heartbeat();
+    ///
+    ///                 Ok(())
+    ///             }
+    ///         })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_heartbeat`]: children/struct.Children.html#method.with_heartbeat
+    pub fn heartbeat(&self) {
+        trace!("BastionContext({}): Sending heartbeat.", self.id);
+        self.state.heartbeat().beat();
+    }
+
+    // Forces the child's future to yield if it retrieved too many
+    // messages without doing so, preventing it from starving the
+    // other children if messages are always available.
+    async fn consume_budget(&self) {
+        if !self.state.consume_budget() {
+            trace!("BastionContext({}): Budget exhausted, yielding.", self.id);
+            yield_now().await;
+        }
+    }
+}
+
+impl ContextState {
+    pub(crate) fn new(
+        mailbox: Box<dyn Mailbox>,
+        status: Arc<MailboxStatus>,
+        poll_budget: usize,
+    ) -> Self {
+        let inbox = SegQueue::new();
+        let msgs = Mutex::new(Msgs {
+            mailbox,
+            held: None,
+        });
+        let blocked = AtomicBool::new(false);
+        let shutdown = AtomicBool::new(false);
+        let handles_shutdown = AtomicBool::new(false);
+        let budget = AtomicUsize::new(poll_budget);
+        let restored = Mutex::new(None);
+        let saved = SavedState::default();
+        let seed = Mutex::new(None);
+        let current_sender = Mutex::new(None);
+        let current_deadline = Mutex::new(None);
+        let exit_reason = Mutex::new(None);
+        let last_will = LastWill::default();
+        let heartbeat = Heartbeat::new();
+        let tasks = Tasks::default();
+        let ticks = Ticks::default();
+
+        ContextState {
+            inbox,
+            msgs,
+            blocked,
+            shutdown,
+            handles_shutdown,
+            status,
+            budget,
+            poll_budget,
+            restored,
+            saved,
+            seed,
+            current_sender,
+            current_deadline,
+            exit_reason,
+            last_will,
+            heartbeat,
+            tasks,
+            ticks,
+        }
+    }
+
+    pub(crate) fn restore(&self, snapshot: Option<Snapshot>) {
+        // FIXME: panics?
+        *self.restored.lock().unwrap() = snapshot;
+    }
+
+    fn take_restored(&self) -> Option<Snapshot> {
+        // FIXME: panics?
+        self.restored.lock().unwrap().take()
+    }
+
+    pub(crate) fn seed(&self, seed: Option<Snapshot>) {
+        // FIXME: panics?
+        *self.seed.lock().unwrap() = seed;
+    }
+
+    fn take_seed(&self) -> Option<Snapshot> {
+        // FIXME: panics?
+        self.seed.lock().unwrap().take()
+    }
+
+    fn save(&self, snapshot: Snapshot) {
+        // FIXME: panics?
+        *(self.saved.0).lock().unwrap() = Some(snapshot);
+    }
+
+    pub(crate) fn saved(&self) -> &SavedState {
+        &self.saved
+    }
+
+    pub(crate) fn last_will(&self) -> &LastWill {
+        &self.last_will
+    }
+
+    pub(crate) fn heartbeat(&self) -> &Heartbeat {
+        &self.heartbeat
+    }
+
+    pub(crate) fn tasks(&self) -> &Tasks {
+        &self.tasks
+    }
+
+    pub(crate) fn ticks(&self) -> &Ticks {
+        &self.ticks
+    }
+
+    fn set_exit_reason(&self, reason: Msg) {
+        // FIXME: panics?
+        *self.exit_reason.lock().unwrap() = Some(reason);
+    }
+
+    pub(crate) fn take_exit_reason(&self) -> Option<Msg> {
+        // FIXME: panics?
+        self.exit_reason.lock().unwrap().take()
+    }
+
+    pub(crate) fn overflow_policy(&self) -> OverflowPolicy {
+        self.status.policy()
+    }
+
+    // Whether the senders reserve room in the child's mailbox
+    // before sending it messages (see `MailboxStatus::try_reserve`).
+    pub(crate) fn reserves_room(&self) -> bool {
+        self.status.is_bounded()
+    }
+
+    pub(crate) fn stats(&self) -> MailboxStats {
+        self.status.stats()
+    }
+
+    // Makes the `ChildRef`s referencing the child fail to send
+    // messages to it, or stop doing so.
+    pub(crate) fn refuse_msgs(&self, refusing: bool) {
+        self.status.refuse(refusing);
+    }
+
+    // Gives the child's future its whole budget back (once it
+    // yielded).
+    pub(crate) fn reset_budget(&self) {
+        self.budget.store(self.poll_budget, Ordering::Relaxed);
+    }
+
+    // Consumes one unit of the budget, returning whether there
+    // was any left (otherwise, the child's future should yield).
+    pub(crate) fn consume_budget(&self) -> bool {
+        let budget = self.budget.load(Ordering::Relaxed);
+        if budget == 0 {
+            return false;
+        }
+
+        self.budget.store(budget - 1, Ordering::Relaxed);
+        true
+    }
+
+    // Delivers a message to the child, which only pushes it
+    // to the inbox and moves it to the mailbox if the receive
+    // path isn't currently accessing it (and never waits for
+    // it to be done).
+    pub(crate) fn push_msg(&self, msg: Msg, sign: RefAddr) {
+        self.inbox.push(SignedMessage::new(msg, sign));
+        self.status.delivered();
+
+        if let Ok(mut msgs) = self.msgs.try_lock() {
+            self.transfer(&mut msgs);
+        }
+    }
+
+    pub(crate) fn request_shutdown(&self) {
+        self.shutdown.store(true, Ordering::Release);
+    }
+
+    fn is_shutdown_requested(&self) -> bool {
+        self.handles_shutdown.store(true, Ordering::Release);
+        self.shutdown.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn handles_shutdown(&self) -> bool {
+        self.handles_shutdown.load(Ordering::Acquire)
+    }
+
+    // Whether a message is held back because the mailbox
+    // is full (when using `OverflowPolicy::Block` or `Fail`).
+    pub(crate) fn is_blocked(&self) -> bool {
+        self.blocked.load(Ordering::Acquire)
+    }
+
+    // Tries to move the messages that were held back to the
+    // mailbox, returning whether all of them were.
+    pub(crate) fn flush(&self) -> bool {
+        if let Ok(mut msgs) = self.msgs.try_lock() {
+            self.transfer(&mut msgs);
+        }
+
+        !self.is_blocked()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        // FIXME: panics?
+        let msgs = self.msgs.lock().unwrap();
+        self.inbox.is_empty() && msgs.held.is_none() && msgs.mailbox.is_empty()
+    }
+
+    pub(crate) fn pop_msg(&self) -> Option<SignedMessage> {
+        // FIXME: panics?
+        let mut msgs = self.msgs.lock().unwrap();
+        self.transfer(&mut msgs);
+
+        let msg = msgs.mailbox.dequeue();
+        if let Some(msg) = &msg {
+            self.status.dequeued(msg);
+            self.set_current(msg);
+            // Dequeuing the message might have made room for the
+            // message that was held back.
+            self.transfer(&mut msgs);
+        }
+
+        msg
+    }
+
+    // Retrieves all the messages that are currently in the
+    // mailbox, locking it only once.
+    pub(crate) fn pop_msgs(&self) -> Vec<SignedMessage> {
+        // FIXME: panics?
+        let mut msgs = self.msgs.lock().unwrap();
+        self.transfer(&mut msgs);
+
+        let mut popped = Vec::new();
+        while let Some(msg) = msgs.mailbox.dequeue() {
+            self.status.dequeued(&msg);
+            popped.push(msg);
+        }
+
+        if let Some(msg) = popped.last() {
+            self.set_current(msg);
+            // Dequeuing the messages might have made room for the
+            // message that was held back.
+            self.transfer(&mut msgs);
+        }
+
+        popped
+    }
+
+    // Remembers who sent the last retrieved message and until
+    // when the answer to it is waited for.
+    fn set_current(&self, msg: &SignedMessage) {
+        // FIXME: panics?
+        *self.current_sender.lock().unwrap() = Some(msg.sign.clone());
+        // FIXME: panics?
+        *self.current_deadline.lock().unwrap() = msg.deadline();
+    }
+
+    fn current_sender(&self) -> Option<RefAddr> {
+        // FIXME: panics?
+        self.current_sender.lock().unwrap().clone()
+    }
+
+    fn current_deadline(&self) -> Option<Instant> {
+        // FIXME: panics?
+        *self.current_deadline.lock().unwrap()
+    }
+
+    // Removes all the messages that the child's future didn't
+    // retrieve, for them to be delivered to the element replacing
+    // it (when using `Children::with_preserved_mailboxes`).
+    pub(crate) fn take_msgs(&self) -> Vec<SignedMessage> {
+        // FIXME: panics?
+        let mut msgs = self.msgs.lock().unwrap();
+        let mut taken = Vec::new();
+        while let Some(msg) = msgs.mailbox.dequeue() {
+            self.status.dropped();
+            taken.push(msg);
+        }
+
+        if let Some(msg) = msgs.held.take() {
+            self.status.dropped();
+            taken.push(msg);
+        }
+
+        while let Ok(msg) = self.inbox.pop() {
+            self.status.dropped();
+            taken.push(msg);
+        }
+
+        self.blocked.store(false, Ordering::Release);
+        taken
+    }
+
+    // Moves the messages of the inbox to the mailbox, applying
+    // the overflow policy when the mailbox refuses one.
+    fn transfer(&self, msgs: &mut Msgs) {
+        while let Some(msg) = msgs.held.take().or_else(|| self.inbox.pop().ok()) {
+            let msg = match msgs.mailbox.enqueue(msg) {
+                Ok(()) => {
+                    self.status.enqueued();
+                    continue;
+                }
+                Err(msg) => msg,
+            };
+
+            match self.overflow_policy() {
+                // The senders wait or fail once the element has no
+                // room left, so the messages that it received
+                // anyway are held back instead of dropped.
+                OverflowPolicy::Block | OverflowPolicy::Fail => {
+                    msgs.held = Some(msg);
+                    self.blocked.store(true, Ordering::Release);
+                    return;
+                }
+                OverflowPolicy::DropOldest => {
+                    if let Some(oldest) = msgs.mailbox.dequeue() {
+                        debug!("ContextState: Dropping oldest message: {:?}", oldest);
+                        self.status.dropped();
+                    }
+
+                    if let Err(msg) = msgs.mailbox.enqueue(msg) {
+                        warn!("ContextState: Mailbox refused a message: {:?}", msg);
+                        self.status.dropped();
+                    } else {
+                        self.status.enqueued();
+                    }
+                }
+                OverflowPolicy::DropNewest => {
+                    warn!("ContextState: Mailbox refused a message: {:?}", msg);
+                    self.status.dropped();
+                }
+                OverflowPolicy::DeadLetters => {
+                    debug!(
+                        "ContextState: Sending refused message to the dead letters: {:?}",
+                        msg
+                    );
+                    self.status.dropped();
+                    let (msg, sign) = msg.extract();
+                    let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                    // FIXME: Err(env)
+                    SYSTEM.dead_letters().send(env).ok();
+                }
+            }
+        }
+
+        self.blocked.store(false, Ordering::Release);
+    }
+}
+
+impl Drop for ContextState {
+    fn drop(&mut self) {
+        // Nobody will retrieve the messages anymore.
+        self.status.close();
+    }
+}
+
+impl Snapshot {
+    /// Creates a new snapshot containing the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to put in the snapshot.
+    pub fn new<S: Any + Send + Sync>(state: S) -> Self {
+        Snapshot(Box::new(state))
+    }
+
+    /// Returns a reference to the state contained in this
+    /// snapshot if it is of type `S`, or `None` otherwise.
+    pub fn downcast_ref<S: Any + Send + Sync>(&self) -> Option<&S> {
+        self.0.downcast_ref()
+    }
+
+    /// Returns the state contained in this snapshot if it is of
+    /// type `S`, or the snapshot itself otherwise.
+    pub fn downcast<S: Any + Send + Sync>(self) -> Result<S, Self> {
+        match self.0.downcast() {
+            Ok(state) => Ok(*state),
+            Err(state) => Err(Snapshot(state)),
+        }
+    }
+}
+
+impl SavedState {
+    pub(crate) fn take(&self) -> Option<Snapshot> {
+        // FIXME: panics?
+        self.0.lock().unwrap().take()
+    }
+}
+
+impl LastWill {
+    fn set(&self, recipient: ChildRef, msg: Msg) {
+        // FIXME: panics?
+        *self.0.lock().unwrap() = Some((recipient, msg));
+    }
+
+    pub(crate) fn take(&self) -> Option<(ChildRef, Msg)> {
+        // FIXME: panics?
+        self.0.lock().unwrap().take()
+    }
+}
+
+impl Undelivered {
+    pub(crate) fn extend(&self, msgs: Vec<SignedMessage>) {
+        // FIXME: panics?
+        self.0.lock().unwrap().extend(msgs);
+    }
+
+    pub(crate) fn take(&self) -> Vec<SignedMessage> {
+        // FIXME: panics?
+        std::mem::replace(&mut *self.0.lock().unwrap(), Vec::new())
+    }
+}
+
+impl Tasks {
+    // Tracks a task, returning the identifier it removes itself
+    // with once it completes.
+    fn push(&self, handle: AbortHandle) -> usize {
+        // FIXME: panics?
+        let mut inner = self.0.lock().unwrap();
+        if inner.cancelled {
+            handle.abort();
+        }
+
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.handles.insert(id, handle);
+        id
+    }
+
+    fn remove(&self, id: usize) {
+        // FIXME: panics?
+        self.0.lock().unwrap().handles.remove(&id);
+    }
+
+    // Cancels all the tasks that didn't complete yet and the
+    // ones that will be spawned.
+    pub(crate) fn cancel(&self) {
+        // FIXME: panics?
+        let mut inner = self.0.lock().unwrap();
+        inner.cancelled = true;
+        for (_, handle) in inner.handles.drain() {
+            handle.abort();
+        }
+    }
+}
+
+impl Ticks {
+    // Registers a tick, returning the identifier its guard
+    // removes it with.
+    fn push(&self, interval: Duration, msg: Box<dyn Fn() -> BastionMessage + Send>) -> usize {
+        let delay = Delay::new(interval);
+        let tick = Tick {
+            interval,
+            delay,
+            msg,
+        };
+
+        // FIXME: panics?
+        let mut inner = self.0.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.ticks.insert(id, tick);
+        id
+    }
+
+    fn remove(&self, id: usize) {
+        // FIXME: panics?
+        self.0.lock().unwrap().ticks.remove(&id);
+    }
+
+    // Returns the messages of the ticks whose timers fired,
+    // resetting those timers for their next interval (which
+    // get polled, and thus wake the child, the next time this
+    // is called).
+    pub(crate) fn poll_fired(&self, ctx: &mut TaskContext) -> Vec<BastionMessage> {
+        // FIXME: panics?
+        let mut inner = self.0.lock().unwrap();
+        let mut msgs = Vec::new();
+        for tick in inner.ticks.values_mut() {
+            if Pin::new(&mut tick.delay).poll(ctx).is_ready() {
+                tick.delay.reset(tick.interval);
+                msgs.push((tick.msg)());
+            }
+        }
+
+        msgs
+    }
+}
+
+impl Heartbeat {
+    fn new() -> Self {
+        Heartbeat(Arc::new(Mutex::new(Instant::now())))
+    }
+
+    pub(crate) fn beat(&self) {
+        // FIXME: panics?
+        *self.0.lock().unwrap() = Instant::now();
+    }
+
+    // Returns how long ago the last heartbeat was sent.
+    pub(crate) fn elapsed(&self) -> Duration {
+        // FIXME: panics?
+        self.0.lock().unwrap().elapsed()
+    }
+}
+
+impl<T> TaskHandle<T> {
+    /// Cancels the task, which makes this `TaskHandle` resolve
+    /// to `Err(TaskError::Cancelled)` unless the task already
+    /// completed.
+    pub fn cancel(&self) {
+        self.abort.abort();
+    }
+}
+
+impl<T> Future for TaskHandle<T> {
+    type Output = Result<T, TaskError>;
+
+    fn poll(mut self: Pin<&mut Self>, ctx: &mut TaskContext) -> Poll<Self::Output> {
+        match Pin::new(&mut self.handle).poll(ctx) {
+            Poll::Ready(Some(res)) => Poll::Ready(res),
+            // The task can only fail this way if it panicked
+            // while completing.
+            Poll::Ready(None) => Poll::Ready(Err(TaskError::Panicked)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl TickGuard {
+    /// Stops the tick, which won't deliver any other message
+    /// (this is the same as dropping this `TickGuard`).
+    pub fn cancel(self) {
+        self.ticks.remove(self.id);
+    }
+}
+
+impl Drop for TickGuard {
+    fn drop(&mut self) {
+        self.ticks.remove(self.id);
+    }
+}
+
+impl Debug for Tick {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Tick")
+            .field("interval", &self.interval)
+            .field("delay", &self.delay)
+            .finish()
+    }
+}
+
+impl Debug for TickGuard {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("TickGuard").finish()
+    }
+}
+
+impl<T> Debug for TaskHandle<T> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("TaskHandle").finish()
+    }
+}
+
+impl Debug for Snapshot {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Snapshot").finish()
     }
 }
 
 impl Display for BastionId {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        self.0.fmt(fmt)
+        Display::fmt(&self.0, fmt)
     }
 }
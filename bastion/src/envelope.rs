@@ -3,12 +3,18 @@
 //! and instruct Bastion how to send messages back to them
 
 use crate::broadcast::Sender;
+use crate::child_ref::ChildRef;
 use crate::message::{BastionMessage, Message, Msg};
 use crate::path::BastionPath;
 use crate::system::SYSTEM;
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Debug)]
+// An envelope isn't allocated on its own: it is moved (inline)
+// into the channels it is sent through, so broadcasting one only
+// needs its message's payload and its signature to be shared by
+// the clones (see `Envelope::try_clone`), not to be pooled.
 pub(crate) struct Envelope {
     pub(crate) msg: BastionMessage,
     pub(crate) sign: RefAddr,
@@ -43,11 +49,20 @@ pub(crate) struct Envelope {
 pub struct SignedMessage {
     pub(crate) msg: Msg,
     pub(crate) sign: RefAddr,
+    // When the message was received, used to compute the
+    // time it spent in a mailbox.
+    pub(crate) received_at: Instant,
 }
 
 impl SignedMessage {
     pub(crate) fn new(msg: Msg, sign: RefAddr) -> Self {
-        SignedMessage { msg, sign }
+        let received_at = Instant::now();
+
+        SignedMessage {
+            msg,
+            sign,
+            received_at,
+        }
     }
 
     #[doc(hidden)]
@@ -55,6 +70,24 @@ impl SignedMessage {
         (self.msg, self.sign)
     }
 
+    /// Returns a reference to the message itself, allowing to
+    /// inspect it without consuming it (eg. in a [`Mailbox`]
+    /// implementation).
+    ///
+    /// [`Mailbox`]: ../mailbox/trait.Mailbox.html
+    pub fn msg(&self) -> &Msg {
+        &self.msg
+    }
+
+    /// Returns when the sender stops waiting for the answer to
+    /// this message, if it was asked with a deadline (see
+    /// [`BastionContext::ask_with_timeout`]).
+    ///
+    /// [`BastionContext::ask_with_timeout`]: ../context/struct.BastionContext.html#method.ask_with_timeout
+    pub fn deadline(&self) -> Option<Instant> {
+        self.msg.deadline()
+    }
+
     /// Returns a message signature to identify the message sender
     ///
     /// # Example
@@ -114,13 +147,23 @@ impl SignedMessage {
 /// # }
 /// ```
 pub struct RefAddr {
+    // The path and sender are behind a single `Arc` so that
+    // cloning a signature (eg. once per element when a message
+    // is broadcasted) is only a reference count increment.
+    inner: Arc<RefAddrInner>,
+}
+
+#[derive(Debug)]
+struct RefAddrInner {
     path: Arc<BastionPath>,
     sender: Sender,
 }
 
 impl RefAddr {
     pub(crate) fn new(path: Arc<BastionPath>, sender: Sender) -> Self {
-        RefAddr { path, sender }
+        let inner = Arc::new(RefAddrInner { path, sender });
+
+        RefAddr { inner }
     }
 
     pub(crate) fn dead_letters() -> Self {
@@ -170,7 +213,7 @@ impl RefAddr {
     /// # }
     /// ```
     pub fn is_sender_identified(&self) -> bool {
-        self.path.is_dead_letters()
+        self.inner.path.is_dead_letters()
     }
 
     /// Returns `BastionPath` of a sender
@@ -211,11 +254,89 @@ impl RefAddr {
     /// # }
     /// ```
     pub fn path(&self) -> &Arc<BastionPath> {
-        &self.path
+        &self.inner.path
     }
 
     pub(crate) fn sender(&self) -> &Sender {
-        &self.sender
+        &self.inner.sender
+    }
+}
+
+/// A trait implemented by everything that messages can be sent
+/// to using [`BastionContext::tell`] and [`BastionContext::ask`]
+/// (a [`RefAddr`], a [`ChildRef`] or a reference to one of them).
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+///     # let children_ref = Bastion::children(|children| children).unwrap();
+///     # let child_ref = children_ref.elems()[0].clone();
+///     #
+/// Bastion::children(|children| {
+///     children.with_exec(move |ctx: BastionContext| {
+///         let child_ref = child_ref.clone();
+///         async move {
+///             ctx.tell(&child_ref, "Hello").expect("Couldn't send the message.");
+///             ctx.tell(&child_ref.addr(), "Hello").expect("Couldn't send the message.");
+///
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`BastionContext::tell`]: ../context/struct.BastionContext.html#method.tell
+/// [`BastionContext::ask`]: ../context/struct.BastionContext.html#method.ask
+/// [`RefAddr`]: struct.RefAddr.html
+/// [`ChildRef`]: ../child_ref/struct.ChildRef.html
+pub trait Recipient {
+    /// Returns the [`RefAddr`] messages should be sent to.
+    ///
+    /// [`RefAddr`]: struct.RefAddr.html
+    fn ref_addr(&self) -> RefAddr;
+
+    #[doc(hidden)]
+    // Returns the `ChildRef` messages should be sent through (to
+    // be validated and to respect the overflow policy of the
+    // element's group), if any.
+    fn child_ref(&self) -> Option<&ChildRef> {
+        None
+    }
+}
+
+impl Recipient for RefAddr {
+    fn ref_addr(&self) -> RefAddr {
+        self.clone()
+    }
+}
+
+impl Recipient for ChildRef {
+    fn ref_addr(&self) -> RefAddr {
+        self.addr()
+    }
+
+    fn child_ref(&self) -> Option<&ChildRef> {
+        Some(self)
+    }
+}
+
+impl<R: Recipient + ?Sized> Recipient for &R {
+    fn ref_addr(&self) -> RefAddr {
+        (**self).ref_addr()
+    }
+
+    fn child_ref(&self) -> Option<&ChildRef> {
+        (**self).child_ref()
     }
 }
 
@@ -248,4 +369,17 @@ impl Envelope {
     pub(crate) fn into_msg<M: Message>(self) -> Option<M> {
         self.msg.into_msg()
     }
+
+    // Gives back the message of an envelope that couldn't be sent
+    // after having been created with `BastionMessage::tell` or
+    // `BastionMessage::ask_within`.
+    pub(crate) fn take_back<M: Message>(self) -> M {
+        match self.into_msg() {
+            Some(msg) => msg,
+            // NOTE: the message is only owned by the `Msg` of the
+            //      envelope (unlike a broadcasted one), which can
+            //      thus always give it back.
+            None => unreachable!(),
+        }
+    }
 }
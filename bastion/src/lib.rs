@@ -65,6 +65,7 @@ pub use self::bastion::Bastion;
 pub use self::callbacks::Callbacks;
 pub use self::config::Config;
 
+mod address;
 mod bastion;
 mod broadcast;
 mod callbacks;
@@ -72,33 +73,57 @@ mod child;
 mod config;
 mod macros;
 mod system;
+mod validation;
 
+pub mod autoscale;
+pub mod channel;
 pub mod child_ref;
 pub mod children;
 pub mod children_ref;
 pub mod context;
 pub mod envelope;
+pub mod mailbox;
 pub mod message;
 pub mod path;
+pub mod rate_limit;
 pub mod supervisor;
+pub mod tree;
+pub mod usage;
 
 ///
 /// Prelude of Bastion
 pub mod prelude {
+    pub use crate::autoscale::Autoscaler;
     pub use crate::bastion::Bastion;
     pub use crate::callbacks::Callbacks;
+    pub use crate::channel::{BoundedChannel, Channel, UnboundedChannel};
     pub use crate::child_ref::ChildRef;
-    pub use crate::children::Children;
-    pub use crate::children_ref::ChildrenRef;
+    pub use crate::children::{
+        Children, DispatchMode, GroupRestartPolicy, HangAction, PreStartOverflow, ShutdownMode,
+        SlowPollAction,
+    };
+    pub use crate::children_ref::{BroadcastReport, ChildrenRef, DeliveryFailure};
     pub use crate::config::Config;
-    pub use crate::context::{BastionContext, BastionId, NIL_ID};
-    pub use crate::envelope::{RefAddr, SignedMessage};
-    pub use crate::message::{Answer, AnswerSender, Message, Msg};
+    pub use crate::context::{
+        BastionContext, BastionId, Snapshot, TaskError, TaskHandle, TickGuard, NIL_ID,
+    };
+    pub use crate::envelope::{Recipient, RefAddr, SignedMessage};
+    pub use crate::mailbox::{
+        ElasticMailbox, FifoMailbox, Mailbox, MailboxStats, OverflowPolicy, RingMailbox,
+    };
+    pub use crate::message::{
+        Answer, AnswerSender, AskError, Exit, Exited, Message, MessageSize, Msg, PrepareStop,
+        Ready, Shutdown, Stopped, Terminated,
+    };
     pub use crate::msg;
     pub use crate::path::{BastionPath, BastionPathElement};
+    pub use crate::rate_limit::RateLimitPolicy;
     pub use crate::supervisor::{
-        ActorRestartStrategy, RestartPolicy, RestartStrategy, SupervisionStrategy, Supervisor,
-        SupervisorRef,
+        ActorRestartStrategy, ChildError, Decision, Fault, FaultInfo, FaultKind, FaultReason,
+        RestartPolicy, RestartStrategy, Strategy, SupervisedState, SupervisedStats,
+        SupervisionStrategy, Supervisor, SupervisorRef, TerminationReason,
     };
+    pub use crate::tree::{ChildrenNode, SupervisionTree, SupervisorNode};
+    pub use crate::usage::Usage;
     pub use crate::{blocking, children, run, spawn, supervisor};
 }
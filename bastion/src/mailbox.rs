@@ -0,0 +1,1074 @@
+//!
+//! Mailboxes are storing the messages received by the elements
+//! of children groups until their future retrieves them.
+//!
+//! The mailbox used by the elements of a children group can be
+//! changed using [`Children::with_mailbox`].
+//!
+//! [`Children::with_mailbox`]: ../children/struct.Children.html#method.with_mailbox
+use crate::envelope::SignedMessage;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// A trait that every mailbox needs to implement, allowing to
+/// customize how the messages received by the elements of a
+/// children group are stored and in which order they are
+/// retrieved (eg. by priority, by deduplicating them, by
+/// only keeping a defined amount of them, etc.).
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// // A mailbox which always returns the most recent message first...
+/// #[derive(Debug, Default)]
+/// struct LifoMailbox {
+///     msgs: Vec<SignedMessage>,
+/// }
+///
+/// impl Mailbox for LifoMailbox {
+///     fn enqueue(&mut self, msg: SignedMessage) -> Result<(), SignedMessage> {
+///         self.msgs.push(msg);
+///         Ok(())
+///     }
+///
+///     fn dequeue(&mut self) -> Option<SignedMessage> {
+///         self.msgs.pop()
+///     }
+///
+///     fn len(&self) -> usize {
+///         self.msgs.len()
+///     }
+/// }
+///
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// // ...which will be used by every element of a children group.
+/// Bastion::children(|children| {
+///     children.with_mailbox(LifoMailbox::default)
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub trait Mailbox: Send + Debug + 'static {
+    /// Stores a message received by the element that this
+    /// mailbox belongs to.
+    ///
+    /// This method returns `()` if the message was stored, or
+    /// `Err(msg)` if the mailbox refused it (in which case the
+    /// message is dropped).
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - The message to store.
+    fn enqueue(&mut self, msg: SignedMessage) -> Result<(), SignedMessage>;
+
+    /// Returns the next message that the element that this
+    /// mailbox belongs to should handle, or `None` if the
+    /// mailbox is empty.
+    fn dequeue(&mut self) -> Option<SignedMessage>;
+
+    /// Returns the number of messages currently stored in
+    /// this mailbox.
+    fn len(&self) -> usize;
+
+    /// Returns whether this mailbox currently stores no
+    /// messages.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether this mailbox is full, in which case the
+    /// messages it receives will be refused and the children
+    /// group's [`OverflowPolicy`] will be applied.
+    ///
+    /// The default implementation always returns `false`.
+    ///
+    /// [`OverflowPolicy`]: enum.OverflowPolicy.html
+    fn is_full(&self) -> bool {
+        false
+    }
+
+    /// Returns the maximum number of messages that this mailbox
+    /// can store, if it is bounded.
+    ///
+    /// When the children group's overflow policy is
+    /// [`OverflowPolicy::Block`] or [`OverflowPolicy::Fail`], this
+    /// is also the number of messages that can be waiting for the
+    /// element to retrieve them before the messages sent to it
+    /// are refused.
+    ///
+    /// The default implementation always returns `None`.
+    ///
+    /// [`OverflowPolicy::Block`]: enum.OverflowPolicy.html#variant.Block
+    /// [`OverflowPolicy::Fail`]: enum.OverflowPolicy.html#variant.Fail
+    fn max_len(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The policy applied by the elements of a children group when
+/// their mailbox refuses a message because it is full.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children
+///         .with_mailbox(|| RingMailbox::with_capacity(1024))
+///         // Only the 1024 most recent messages will be kept...
+///         .with_overflow_policy(OverflowPolicy::DropOldest)
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub enum OverflowPolicy {
+    /// The message that was refused is dropped. This is the
+    /// default policy.
+    DropNewest,
+    /// The oldest message stored in the mailbox is dropped to
+    /// make room for the message that was refused.
+    DropOldest,
+    /// Sending a message to an element which has as many
+    /// messages waiting for it as its mailbox can store (see
+    /// [`Mailbox::max_len`]) fails, eg. [`ChildRef::tell_anonymously`]
+    /// and [`ChildrenRef::broadcast`] return `Err(msg)` (a
+    /// broadcasted message is only sent if all the elements have
+    /// room for it). The messages that were accepted are never
+    /// dropped.
+    ///
+    /// [`Mailbox::max_len`]: trait.Mailbox.html#method.max_len
+    /// [`ChildRef::tell_anonymously`]: ../child_ref/struct.ChildRef.html#method.tell_anonymously
+    /// [`ChildrenRef::broadcast`]: ../children_ref/struct.ChildrenRef.html#method.broadcast
+    Fail,
+    /// The message that was refused is sent to the dead letters
+    /// instead of being dropped.
+    DeadLetters,
+    /// Like [`OverflowPolicy::Fail`], except that the senders that
+    /// can wait for the elements to have room again do so instead
+    /// of failing (eg. [`ChildRef::tell_async`] and
+    /// [`ChildrenRef::broadcast_async`]). System messages (eg. to
+    /// stop or kill the elements) are still received.
+    ///
+    /// [`OverflowPolicy::Fail`]: #variant.Fail
+    /// [`ChildRef::tell_async`]: ../child_ref/struct.ChildRef.html#method.tell_async
+    /// [`ChildrenRef::broadcast_async`]: ../children_ref/struct.ChildrenRef.html#method.broadcast_async
+    Block,
+}
+
+#[derive(Debug, Default)]
+/// The mailbox used by default by the elements of children
+/// groups, which returns messages in the order they were
+/// received and stores an unlimited amount of them, unless it
+/// was created using [`FifoMailbox::with_limit`].
+///
+/// [`FifoMailbox::with_limit`]: #method.with_limit
+pub struct FifoMailbox {
+    msgs: VecDeque<SignedMessage>,
+    // The maximum number of messages stored (if any), after
+    // which messages are refused.
+    limit: Option<usize>,
+}
+
+#[derive(Debug)]
+/// A bounded mailbox backed by a ring buffer, whose memory is
+/// allocated once when it is created.
+///
+/// Because a `RingMailbox` never needs to grow or allocate while
+/// storing messages, it is better suited than [`FifoMailbox`] for
+/// elements handling a high volume of messages. Messages received
+/// while the mailbox is full are refused (and the children
+/// group's [`OverflowPolicy`] is applied).
+///
+/// Note that the mailbox only stores the messages that were
+/// received by an element; the channel carrying them to it can
+/// be replaced by a lock-free ring buffer using
+/// [`Children::with_channel`] and [`BoundedChannel`].
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     // Every element will be able to store up to 1024 messages...
+///     children.with_mailbox(|| RingMailbox::with_capacity(1024))
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`FifoMailbox`]: struct.FifoMailbox.html
+/// [`OverflowPolicy`]: enum.OverflowPolicy.html
+/// [`Children::with_channel`]: ../children/struct.Children.html#method.with_channel
+/// [`BoundedChannel`]: ../channel/struct.BoundedChannel.html
+pub struct RingMailbox {
+    msgs: VecDeque<SignedMessage>,
+    capacity: usize,
+}
+
+#[derive(Debug)]
+/// A bounded mailbox whose memory grows when it fills up during
+/// bursts of messages, and gets released when it empties again.
+///
+/// An `ElasticMailbox` starts with room for `min_capacity`
+/// messages. Its capacity is doubled (up to `max_capacity`)
+/// whenever the number of messages it stores reaches the high
+/// watermark, and halved (down to `min_capacity`) whenever it
+/// goes below the low watermark. Both watermarks are fractions
+/// of its current capacity (`0.75` and `0.25` by default).
+///
+/// Messages received while the mailbox stores `max_capacity`
+/// messages are refused (and dropped).
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     // Every element will usually have room for 64 messages,
+///     // and for up to 65536 of them during bursts...
+///     children.with_mailbox(|| ElasticMailbox::new(64, 65536))
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub struct ElasticMailbox {
+    msgs: VecDeque<SignedMessage>,
+    // The number of messages that the mailbox currently has
+    // room for (`msgs` might have more room than that).
+    capacity: usize,
+    min_capacity: usize,
+    max_capacity: usize,
+    high_watermark: f64,
+    low_watermark: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Statistics about the mailbox of an element of a children
+/// group, as returned by [`ChildRef::mailbox_stats`].
+///
+/// The latency of a message is the time between the moment it
+/// was received by the element and the moment its future
+/// retrieved it from the mailbox (eg. using
+/// [`BastionContext::recv`]).
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// let children_ref = Bastion::children(|children| children)
+///     .expect("Couldn't create the children group.");
+///
+/// for child_ref in children_ref.elems() {
+///     let stats: MailboxStats = child_ref.mailbox_stats();
+///     if stats.depth() > 1024 || stats.max_latency() > Duration::from_secs(1) {
+///         // The child isn't keeping up with the messages it receives...
+///     }
+/// }
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildRef::mailbox_stats`]: ../children/struct.ChildRef.html#method.mailbox_stats
+/// [`BastionContext::recv`]: ../struct.BastionContext.html#method.recv
+pub struct MailboxStats {
+    depth: usize,
+    enqueued: u64,
+    dequeued: u64,
+    mean_latency: Duration,
+    max_latency: Duration,
+}
+
+#[derive(Debug, Default)]
+// The status of an element's mailbox, shared between the
+// element and the `ChildRef`s referencing it.
+//
+// NOTE: the latencies are only updated while the element's
+//       mailbox is locked, so loading and storing them
+//       separately is enough.
+pub(crate) struct MailboxStatus {
+    policy: OverflowPolicy,
+    // The number of messages that can be waiting for the element
+    // before the messages sent to it are refused, if the overflow
+    // policy bounds its user lane (see `try_reserve`).
+    capacity: Option<usize>,
+    // The number of user messages that were sent to the element
+    // but that it didn't receive yet (when `capacity` is set).
+    queued: AtomicUsize,
+    // Whether the element stopped, in which case its mailbox
+    // won't ever have room again.
+    closed: AtomicBool,
+    // The tasks waiting for the element to have room.
+    waiters: Mutex<Vec<Waker>>,
+    waiting: AtomicBool,
+    // Whether the element refuses messages regardless of its
+    // mailbox (eg. because it buffered too many of them before
+    // being started).
+    refusing: AtomicBool,
+    depth: AtomicUsize,
+    enqueued: AtomicU64,
+    dequeued: AtomicU64,
+    // The sums and maximum of the latencies of the dequeued
+    // messages, in nanoseconds.
+    total_latency: AtomicU64,
+    max_latency: AtomicU64,
+}
+
+#[derive(Clone)]
+pub(crate) struct MailboxFactory(Arc<dyn Fn() -> Box<dyn Mailbox> + Send + Sync>);
+
+impl FifoMailbox {
+    /// Creates a new empty `FifoMailbox`.
+    pub fn new() -> Self {
+        FifoMailbox::default()
+    }
+
+    /// Creates a new empty `FifoMailbox` storing up to `limit`
+    /// messages, after which the messages it receives are refused
+    /// (and the children group's [`OverflowPolicy`] is applied).
+    ///
+    /// Unlike a [`RingMailbox`], its memory is only allocated as
+    /// messages are received.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of messages that the
+    ///     mailbox will store.
+    ///
+    /// [`OverflowPolicy`]: enum.OverflowPolicy.html
+    /// [`RingMailbox`]: struct.RingMailbox.html
+    pub fn with_limit(limit: usize) -> Self {
+        let msgs = VecDeque::new();
+        let limit = Some(limit);
+
+        FifoMailbox { msgs, limit }
+    }
+
+    /// Returns the maximum number of messages that this mailbox
+    /// stores, if it is limited.
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+}
+
+impl Mailbox for FifoMailbox {
+    fn enqueue(&mut self, msg: SignedMessage) -> Result<(), SignedMessage> {
+        if self.is_full() {
+            return Err(msg);
+        }
+
+        self.msgs.push_back(msg);
+        Ok(())
+    }
+
+    fn dequeue(&mut self) -> Option<SignedMessage> {
+        self.msgs.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        self.msgs.len()
+    }
+
+    fn is_full(&self) -> bool {
+        match self.limit {
+            Some(limit) => self.msgs.len() >= limit,
+            None => false,
+        }
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        self.limit
+    }
+}
+
+impl RingMailbox {
+    /// Creates a new empty `RingMailbox` able to store up to
+    /// `capacity` messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of messages that the
+    ///     mailbox will be able to store (it needs to be greater
+    ///     than `0`).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is `0`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+
+        let msgs = VecDeque::with_capacity(capacity);
+
+        RingMailbox { msgs, capacity }
+    }
+
+    /// Returns the maximum number of messages that this mailbox
+    /// can store.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl Mailbox for RingMailbox {
+    fn enqueue(&mut self, msg: SignedMessage) -> Result<(), SignedMessage> {
+        if self.is_full() {
+            return Err(msg);
+        }
+
+        self.msgs.push_back(msg);
+        Ok(())
+    }
+
+    fn dequeue(&mut self) -> Option<SignedMessage> {
+        self.msgs.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        self.msgs.len()
+    }
+
+    fn is_full(&self) -> bool {
+        self.msgs.len() >= self.capacity
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(self.capacity)
+    }
+}
+
+impl ElasticMailbox {
+    /// Creates a new empty `ElasticMailbox` with room for
+    /// `min_capacity` messages, able to grow to store up to
+    /// `max_capacity` messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_capacity` - The number of messages that the mailbox
+    ///     has room for when it isn't under pressure (it needs to
+    ///     be greater than `0`).
+    /// * `max_capacity` - The maximum number of messages that the
+    ///     mailbox will be able to store (it can't be lower than
+    ///     `min_capacity`).
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `min_capacity` is `0` or if
+    /// `max_capacity` is lower than `min_capacity`.
+    pub fn new(min_capacity: usize, max_capacity: usize) -> Self {
+        assert!(min_capacity > 0, "capacity must be non-zero");
+        assert!(
+            max_capacity >= min_capacity,
+            "max_capacity must not be lower than min_capacity"
+        );
+
+        let msgs = VecDeque::with_capacity(min_capacity);
+
+        ElasticMailbox {
+            msgs,
+            capacity: min_capacity,
+            min_capacity,
+            max_capacity,
+            high_watermark: 0.75,
+            low_watermark: 0.25,
+        }
+    }
+
+    /// Sets the fractions of its current capacity that the number
+    /// of messages stored by the mailbox needs to reach for it to
+    /// grow (`high`) or to go below for it to shrink (`low`).
+    ///
+    /// # Arguments
+    ///
+    /// * `low` - The low watermark, which needs to be lower than
+    ///     half the high watermark for the mailbox to not keep
+    ///     growing and shrinking back.
+    /// * `high` - The high watermark, which needs to be greater
+    ///     than `0.0` and not greater than `1.0`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the watermarks don't respect the
+    /// conditions above.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let mailbox = ElasticMailbox::new(64, 1024).with_watermarks(0.1, 0.9);
+    /// ```
+    pub fn with_watermarks(mut self, low: f64, high: f64) -> Self {
+        assert!(
+            high > 0.0 && high <= 1.0,
+            "high watermark must be in (0, 1]"
+        );
+        assert!(
+            low >= 0.0 && low * 2.0 < high,
+            "low watermark must be in [0, high / 2)"
+        );
+
+        self.low_watermark = low;
+        self.high_watermark = high;
+        self
+    }
+
+    /// Returns the number of messages that this mailbox currently
+    /// has room for.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the maximum number of messages that this mailbox
+    /// can store.
+    pub fn max_capacity(&self) -> usize {
+        self.max_capacity
+    }
+
+    fn grow(&mut self) {
+        while self.capacity < self.max_capacity
+            && self.msgs.len() as f64 >= self.capacity as f64 * self.high_watermark
+        {
+            self.capacity = (self.capacity * 2).min(self.max_capacity);
+        }
+
+        self.msgs.reserve_exact(self.capacity - self.msgs.len());
+    }
+
+    fn shrink(&mut self) {
+        let capacity = self.capacity;
+        while self.capacity > self.min_capacity
+            && (self.msgs.len() as f64) < self.capacity as f64 * self.low_watermark
+        {
+            self.capacity = (self.capacity / 2).max(self.min_capacity);
+        }
+
+        if self.capacity < capacity {
+            // Moving the messages to a smaller buffer to release
+            // the memory of the current one.
+            let mut msgs = VecDeque::with_capacity(self.capacity);
+            msgs.extend(self.msgs.drain(..));
+            self.msgs = msgs;
+        }
+    }
+}
+
+impl Mailbox for ElasticMailbox {
+    fn enqueue(&mut self, msg: SignedMessage) -> Result<(), SignedMessage> {
+        if self.is_full() {
+            return Err(msg);
+        }
+
+        self.msgs.push_back(msg);
+        self.grow();
+
+        Ok(())
+    }
+
+    fn dequeue(&mut self) -> Option<SignedMessage> {
+        let msg = self.msgs.pop_front()?;
+        self.shrink();
+
+        Some(msg)
+    }
+
+    fn len(&self) -> usize {
+        self.msgs.len()
+    }
+
+    fn is_full(&self) -> bool {
+        self.msgs.len() >= self.max_capacity
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(self.max_capacity)
+    }
+}
+
+impl Default for OverflowPolicy {
+    fn default() -> Self {
+        OverflowPolicy::DropNewest
+    }
+}
+
+impl MailboxStats {
+    /// Returns the number of messages that were waiting to be
+    /// retrieved when those statistics were retrieved.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of messages that were stored in the
+    /// mailbox since the element was launched.
+    pub fn enqueued(&self) -> u64 {
+        self.enqueued
+    }
+
+    /// Returns the number of messages that were retrieved from
+    /// the mailbox since the element was launched.
+    pub fn dequeued(&self) -> u64 {
+        self.dequeued
+    }
+
+    /// Returns the mean latency of the messages that were
+    /// retrieved from the mailbox.
+    pub fn mean_latency(&self) -> Duration {
+        self.mean_latency
+    }
+
+    /// Returns the highest latency of the messages that were
+    /// retrieved from the mailbox.
+    pub fn max_latency(&self) -> Duration {
+        self.max_latency
+    }
+
+    // Combines the statistics of the mailboxes of several
+    // elements (eg. of all the elements of a children group).
+    pub(crate) fn sum<I: IntoIterator<Item = MailboxStats>>(stats: I) -> Self {
+        let mut sum = MailboxStats {
+            depth: 0,
+            enqueued: 0,
+            dequeued: 0,
+            mean_latency: Duration::default(),
+            max_latency: Duration::default(),
+        };
+        // The sum of the latencies of the dequeued messages, in
+        // nanoseconds.
+        let mut total_latency = 0u128;
+        for stats in stats {
+            sum.depth += stats.depth;
+            sum.enqueued += stats.enqueued;
+            sum.dequeued += stats.dequeued;
+            sum.max_latency = sum.max_latency.max(stats.max_latency);
+            total_latency += stats.mean_latency.as_nanos() * u128::from(stats.dequeued);
+        }
+
+        if sum.dequeued > 0 {
+            sum.mean_latency =
+                Duration::from_nanos((total_latency / u128::from(sum.dequeued)) as u64);
+        }
+
+        sum
+    }
+}
+
+impl MailboxStatus {
+    // Creates the status of the mailbox of an element, whose user
+    // lane is bounded by the mailbox's maximum length when the
+    // policy is `Block` or `Fail`.
+    pub(crate) fn new(policy: OverflowPolicy, max_len: Option<usize>) -> Self {
+        let capacity = match policy {
+            OverflowPolicy::Block | OverflowPolicy::Fail => max_len,
+            _ => None,
+        };
+
+        MailboxStatus {
+            policy,
+            capacity,
+            ..MailboxStatus::default()
+        }
+    }
+
+    pub(crate) fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    // Whether the messages sent to the element are refused once
+    // it has `capacity` of them waiting.
+    pub(crate) fn is_bounded(&self) -> bool {
+        self.capacity.is_some()
+    }
+
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.wake_waiters();
+    }
+
+    // Reopens the mailbox of an element relaunched with the same
+    // identity, whose new mailbox is empty.
+    pub(crate) fn reopen(&self) {
+        self.depth.store(0, Ordering::Relaxed);
+        self.closed.store(false, Ordering::SeqCst);
+    }
+
+    // Whether the element stopped.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    // Reserves room for a user message sent to the element,
+    // returning whether it had any. The room is given back by
+    // `release` once the element received the message (or if
+    // it couldn't be sent).
+    //
+    // The messages that the element received but didn't retrieve
+    // yet are counted too, so that the messages it accepts can
+    // always be moved to its mailbox (at least once its future
+    // retrieved the ones it stores).
+    pub(crate) fn try_reserve(&self) -> bool {
+        let capacity = match self.capacity {
+            Some(capacity) => capacity,
+            None => return true,
+        };
+
+        let mut queued = self.queued.load(Ordering::SeqCst);
+        loop {
+            if queued + self.depth() >= capacity {
+                return false;
+            }
+
+            match self.queued.compare_exchange(
+                queued,
+                queued + 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return true,
+                Err(current) => queued = current,
+            }
+        }
+    }
+
+    // Reserves room for a user message sent to the element,
+    // returning `Poll::Ready(true)` once it did or
+    // `Poll::Ready(false)` if the element stopped.
+    pub(crate) fn poll_reserve(&self, ctx: &mut Context) -> Poll<bool> {
+        if self.is_closed() {
+            return Poll::Ready(false);
+        } else if self.try_reserve() {
+            return Poll::Ready(true);
+        }
+
+        self.waiters.lock().unwrap().push(ctx.waker().clone());
+        self.waiting.store(true, Ordering::SeqCst);
+
+        // The element might have made room (or stopped) before
+        // the waker was registered.
+        if self.is_closed() {
+            Poll::Ready(false)
+        } else if self.try_reserve() {
+            Poll::Ready(true)
+        } else {
+            Poll::Pending
+        }
+    }
+
+    // Gives back the room reserved for a message (see
+    // `try_reserve`).
+    pub(crate) fn release(&self) {
+        if self.capacity.is_some() {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            self.wake_waiters();
+        }
+    }
+
+    fn wake_waiters(&self) {
+        if !self.waiting.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let mut waiters = self.waiters.lock().unwrap();
+        self.waiting.store(false, Ordering::SeqCst);
+        for waker in waiters.drain(..) {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn delivered(&self) {
+        self.depth.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn enqueued(&self) {
+        self.enqueued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dropped(&self) {
+        self.depth.fetch_sub(1, Ordering::Relaxed);
+        self.wake_waiters();
+    }
+
+    pub(crate) fn dequeued(&self, msg: &SignedMessage) {
+        self.depth.fetch_sub(1, Ordering::Relaxed);
+        self.dequeued.fetch_add(1, Ordering::Relaxed);
+
+        let latency = msg.received_at.elapsed().as_nanos() as u64;
+        let total_latency = self.total_latency.load(Ordering::Relaxed);
+        self.total_latency
+            .store(total_latency.saturating_add(latency), Ordering::Relaxed);
+        if latency > self.max_latency.load(Ordering::Relaxed) {
+            self.max_latency.store(latency, Ordering::Relaxed);
+        }
+
+        self.wake_waiters();
+    }
+
+    // Returns how many messages were delivered to the element
+    // without having been retrieved yet.
+    pub(crate) fn depth(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn stats(&self) -> MailboxStats {
+        let dequeued = self.dequeued.load(Ordering::Relaxed);
+        let total_latency = self.total_latency.load(Ordering::Relaxed);
+        let mean_latency = if dequeued == 0 {
+            Duration::default()
+        } else {
+            Duration::from_nanos(total_latency / dequeued)
+        };
+
+        MailboxStats {
+            depth: self.depth.load(Ordering::Relaxed),
+            enqueued: self.enqueued.load(Ordering::Relaxed),
+            dequeued,
+            mean_latency,
+            max_latency: Duration::from_nanos(self.max_latency.load(Ordering::Relaxed)),
+        }
+    }
+
+    pub(crate) fn refuse(&self, refusing: bool) {
+        self.refusing.store(refusing, Ordering::SeqCst);
+    }
+
+    // Whether messages sent to the element should fail to
+    // be sent regardless of whether it has room for them.
+    pub(crate) fn rejects(&self) -> bool {
+        self.refusing.load(Ordering::SeqCst)
+    }
+}
+
+impl MailboxFactory {
+    pub(crate) fn new<F, M>(factory: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+        M: Mailbox,
+    {
+        let factory = Arc::new(move || {
+            let mailbox: Box<dyn Mailbox> = Box::new(factory());
+            mailbox
+        });
+
+        MailboxFactory(factory)
+    }
+
+    pub(crate) fn create(&self) -> Box<dyn Mailbox> {
+        (self.0)()
+    }
+}
+
+impl Default for MailboxFactory {
+    fn default() -> Self {
+        MailboxFactory::new(FifoMailbox::new)
+    }
+}
+
+impl Debug for MailboxFactory {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("MailboxFactory").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ElasticMailbox, FifoMailbox, Mailbox, MailboxStats, MailboxStatus, OverflowPolicy,
+        RingMailbox,
+    };
+    use crate::broadcast;
+    use crate::envelope::{RefAddr, SignedMessage};
+    use crate::message::Msg;
+    use crate::path::BastionPath;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn signed(msg: usize) -> SignedMessage {
+        // need manual construction because SYSTEM is not running in this test
+        let (sender, _) = broadcast::channel();
+        let sign = RefAddr::new(Arc::new(BastionPath::root()), sender);
+
+        SignedMessage::new(Msg::tell(msg), sign)
+    }
+
+    fn dequeue(mailbox: &mut dyn Mailbox) -> Option<usize> {
+        mailbox
+            .dequeue()
+            .map(|smsg| smsg.extract().0.downcast().unwrap())
+    }
+
+    #[test]
+    fn fifo_mailbox() {
+        let mut mailbox = FifoMailbox::new();
+        assert!(mailbox.is_empty());
+
+        for i in 0..4 {
+            assert!(mailbox.enqueue(signed(i)).is_ok());
+        }
+        assert_eq!(mailbox.len(), 4);
+
+        for i in 0..4 {
+            assert_eq!(dequeue(&mut mailbox), Some(i));
+        }
+        assert_eq!(dequeue(&mut mailbox), None);
+    }
+
+    #[test]
+    fn limited_fifo_mailbox() {
+        let mut mailbox = FifoMailbox::with_limit(2);
+        assert_eq!(mailbox.limit(), Some(2));
+
+        assert!(mailbox.enqueue(signed(0)).is_ok());
+        assert!(mailbox.enqueue(signed(1)).is_ok());
+        assert!(mailbox.is_full());
+        assert!(mailbox.enqueue(signed(2)).is_err());
+
+        assert_eq!(dequeue(&mut mailbox), Some(0));
+        assert!(!mailbox.is_full());
+        assert!(mailbox.enqueue(signed(3)).is_ok());
+        assert_eq!(dequeue(&mut mailbox), Some(1));
+        assert_eq!(dequeue(&mut mailbox), Some(3));
+    }
+
+    #[test]
+    fn ring_mailbox() {
+        let mut mailbox = RingMailbox::with_capacity(2);
+        assert_eq!(mailbox.capacity(), 2);
+
+        assert!(mailbox.enqueue(signed(0)).is_ok());
+        assert!(mailbox.enqueue(signed(1)).is_ok());
+        assert!(mailbox.is_full());
+        assert!(mailbox.enqueue(signed(2)).is_err());
+
+        assert_eq!(dequeue(&mut mailbox), Some(0));
+        assert!(mailbox.enqueue(signed(3)).is_ok());
+        assert_eq!(dequeue(&mut mailbox), Some(1));
+        assert_eq!(dequeue(&mut mailbox), Some(3));
+        assert!(mailbox.is_empty());
+    }
+
+    #[test]
+    fn elastic_mailbox() {
+        let mut mailbox = ElasticMailbox::new(2, 8);
+        assert_eq!(mailbox.capacity(), 2);
+
+        for i in 0..8 {
+            assert!(mailbox.enqueue(signed(i)).is_ok());
+        }
+        assert_eq!(mailbox.capacity(), 8);
+        assert!(mailbox.is_full());
+        assert!(mailbox.enqueue(signed(8)).is_err());
+
+        for i in 0..7 {
+            assert_eq!(dequeue(&mut mailbox), Some(i));
+        }
+        assert_eq!(mailbox.capacity(), 4);
+        assert_eq!(dequeue(&mut mailbox), Some(7));
+        assert_eq!(mailbox.capacity(), 2);
+        assert!(mailbox.is_empty());
+    }
+
+    #[test]
+    fn mailbox_status() {
+        let mailbox = RingMailbox::with_capacity(2);
+        let fail = MailboxStatus::new(OverflowPolicy::Fail, mailbox.max_len());
+        let drop = MailboxStatus::new(OverflowPolicy::DropNewest, mailbox.max_len());
+        assert!(fail.is_bounded());
+        assert!(!drop.is_bounded());
+
+        // One message was received but not retrieved yet, and
+        // another one wasn't received yet...
+        fail.delivered();
+        assert!(fail.try_reserve());
+        assert!(!fail.try_reserve());
+        assert!(drop.try_reserve());
+
+        // ...until it is.
+        fail.release();
+        fail.delivered();
+        assert!(!fail.try_reserve());
+
+        fail.dequeued(&signed(0));
+        assert!(fail.try_reserve());
+    }
+
+    #[test]
+    fn mailbox_stats() {
+        let mut mailbox = FifoMailbox::new();
+        let status = MailboxStatus::default();
+
+        for i in 0..4 {
+            status.delivered();
+            assert!(mailbox.enqueue(signed(i)).is_ok());
+            status.enqueued();
+        }
+
+        let msg = mailbox.dequeue().unwrap();
+        status.dequeued(&msg);
+
+        let stats = status.stats();
+        assert_eq!(stats.depth(), 3);
+        assert_eq!(stats.enqueued(), 4);
+        assert_eq!(stats.dequeued(), 1);
+        assert!(stats.max_latency() >= stats.mean_latency());
+    }
+
+    #[test]
+    fn summed_mailbox_stats() {
+        let stats = |dequeued, mean, max| MailboxStats {
+            depth: 1,
+            enqueued: dequeued + 1,
+            dequeued,
+            mean_latency: Duration::from_millis(mean),
+            max_latency: Duration::from_millis(max),
+        };
+
+        let sum = MailboxStats::sum(vec![stats(1, 10, 10), stats(3, 30, 50), stats(0, 0, 0)]);
+        assert_eq!(sum.depth(), 3);
+        assert_eq!(sum.enqueued(), 7);
+        assert_eq!(sum.dequeued(), 4);
+        assert_eq!(sum.mean_latency(), Duration::from_millis(25));
+        assert_eq!(sum.max_latency(), Duration::from_millis(50));
+
+        let empty = MailboxStats::sum(Vec::new());
+        assert_eq!(empty.dequeued(), 0);
+        assert_eq!(empty.mean_latency(), Duration::default());
+    }
+}
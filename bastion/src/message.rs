@@ -6,17 +6,19 @@
 //! * All message communication relies on at-most-once delivery guarantee.
 //! * Messages are not guaranteed to be ordered, all message's order is causal.
 //!
+use crate::child_ref::ChildRef;
 use crate::children::Children;
-use crate::context::BastionId;
+use crate::context::{BastionContext, BastionId};
 use crate::envelope::{RefAddr, SignedMessage};
-use crate::supervisor::{SupervisionStrategy, Supervisor};
+use crate::supervisor::{SupervisionStrategy, Supervisor, TerminationReason};
 use futures::channel::oneshot::{self, Receiver};
 use std::any::{type_name, Any};
 use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 /// A trait that any message sent needs to implement (it is
 /// already automatically implemented but forces message to
@@ -30,9 +32,177 @@ use std::task::{Context, Poll};
 pub trait Message: Any + Send + Sync + Debug {}
 impl<T> Message for T where T: Any + Send + Sync + Debug {}
 
-#[derive(Debug)]
-#[doc(hidden)]
-pub struct AnswerSender(oneshot::Sender<SignedMessage>);
+/// A trait that messages can implement to tell how many bytes of
+/// data they hold, including the heap allocations they own (eg. the
+/// length of a [`String`] or the sizes of the items of a [`Vec`]),
+/// which is close to the size they would have once serialized.
+///
+/// This allows children groups to reject the messages that are too
+/// large (see [`Children::with_max_message_size`]).
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// #[derive(Debug)]
+/// struct Upload {
+///     name: String,
+///     data: Vec<u8>,
+/// }
+///
+/// impl MessageSize for Upload {
+///     fn message_size(&self) -> usize {
+///         self.name.message_size() + self.data.message_size()
+///     }
+/// }
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+/// [`Vec`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+/// [`Children::with_max_message_size`]: ../children/struct.Children.html#method.with_max_message_size
+pub trait MessageSize {
+    /// Returns how many bytes of data the message holds.
+    fn message_size(&self) -> usize;
+}
+
+macro_rules! fixed_message_size {
+    ($($ty:ty),*) => {
+        $(
+            impl MessageSize for $ty {
+                fn message_size(&self) -> usize {
+                    std::mem::size_of::<$ty>()
+                }
+            }
+        )*
+    };
+}
+
+fixed_message_size!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64
+);
+
+impl MessageSize for String {
+    fn message_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl MessageSize for &'static str {
+    fn message_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: MessageSize> MessageSize for Vec<T> {
+    fn message_size(&self) -> usize {
+        self.iter().map(MessageSize::message_size).sum()
+    }
+}
+
+impl<T: MessageSize> MessageSize for Box<T> {
+    fn message_size(&self) -> usize {
+        (**self).message_size()
+    }
+}
+
+impl<T: MessageSize> MessageSize for Option<T> {
+    fn message_size(&self) -> usize {
+        self.as_ref().map_or(0, MessageSize::message_size)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A handle allowing to answer a message that was "asked"
+/// (using [`ChildRef::ask_anonymously`] or
+/// [`BastionContext::ask`]).
+///
+/// An `AnswerSender` can be retrieved with the `answer_sender!`
+/// macro generated by the [`msg!`] macro for "asked" messages
+/// (when using `=!>`) and can then be put inside of another
+/// message, allowing a different child to answer the original
+/// sender later on (using [`reply`]), without routing the answer
+/// back through the intermediaries.
+///
+/// An `AnswerSender` can be cloned but the asked message can only
+/// be answered once: only the first answer will be received.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+/// #[derive(Debug)]
+/// struct Job {
+///     data: &'static str,
+///     reply_to: AnswerSender,
+/// }
+///
+/// // A worker that will answer on behalf of the frontend...
+/// let workers = Bastion::children(|children| {
+///     children.with_exec(|ctx: BastionContext| {
+///         async move {
+///             msg! { ctx.recv().await?,
+///                 job: Job => {
+///                     // ...by replying to the original sender.
+///                     job.reply_to.reply(&ctx, job.data).ok();
+///                 };
+///                 _: _ => ();
+///             }
+///
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///
+/// // A frontend receiving the asked messages...
+/// Bastion::children(|children| {
+///     children.with_exec(move |ctx: BastionContext| {
+///         let worker = workers.elems()[0].clone();
+///         async move {
+///             msg! { ctx.recv().await?,
+///                 data: &'static str =!> {
+///                     // ...and forwarding them to the worker.
+///                     let job = Job { data, reply_to: answer_sender!() };
+///                     ctx.tell(&worker.addr(), job).ok();
+///                 };
+///                 _: _ => ();
+///             }
+///
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildRef::ask_anonymously`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+/// [`BastionContext::ask`]: ../context/struct.BastionContext.html#method.ask
+/// [`msg!`]: ../macro.msg.html
+/// [`reply`]: #method.reply
+pub struct AnswerSender(Arc<Mutex<Option<oneshot::Sender<SignedMessage>>>>);
 
 #[derive(Debug)]
 /// A [`Future`] returned when successfully "asking" a
@@ -112,6 +282,236 @@ pub struct AnswerSender(oneshot::Sender<SignedMessage>);
 /// [`msg!`]: macro.msg.html
 pub struct Answer(Receiver<SignedMessage>);
 
+#[derive(Debug)]
+/// Why a message asked using [`ChildRef::ask`] or
+/// [`BastionContext::ask_with_timeout`] didn't get an answer
+/// (of the expected type).
+///
+/// [`ChildRef::ask`]: ../child_ref/struct.ChildRef.html#method.ask
+/// [`BastionContext::ask_with_timeout`]: ../context/struct.BastionContext.html#method.ask_with_timeout
+pub enum AskError<M> {
+    /// The message couldn't be sent (eg. because it was rejected
+    /// by the children group's validation or because the child's
+    /// mailbox is full, see [`ChildRef::ask_anonymously`]).
+    ///
+    /// [`ChildRef::ask_anonymously`]: ../child_ref/struct.ChildRef.html#method.ask_anonymously
+    Rejected(M),
+    /// The child stopped or dropped the message without
+    /// answering it.
+    NoAnswer,
+    /// The child answered with a message of another type, which
+    /// is given back.
+    UnexpectedAnswer(SignedMessage),
+    /// No answer was received before the timeout (see
+    /// [`BastionContext::ask_with_timeout`]).
+    ///
+    /// [`BastionContext::ask_with_timeout`]: ../context/struct.BastionContext.html#method.ask_with_timeout
+    TimedOut,
+}
+
+#[derive(Debug)]
+/// A [`Future`] returned when successfully telling a children
+/// group, a supervisor or an element to stop using
+/// [`ChildrenRef::stop_with_deadline`],
+/// [`SupervisorRef::stop_with_deadline`] or
+/// [`ChildRef::stop_with_timeout`], and which resolves once it
+/// stopped.
+///
+/// It resolves to `Ok(true)` if everything stopped before the
+/// deadline, `Ok(false)` if some elements had to be killed, and
+/// `Err(())` if the children group, supervisor or element was
+/// dropped without stopping (eg. because it was killed meanwhile).
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`ChildRef::stop_with_timeout`]: ../child_ref/struct.ChildRef.html#method.stop_with_timeout
+/// [`ChildrenRef::stop_with_deadline`]: ../children_ref/struct.ChildrenRef.html#method.stop_with_deadline
+/// [`SupervisorRef::stop_with_deadline`]: ../supervisor/struct.SupervisorRef.html#method.stop_with_deadline
+pub struct Shutdown(Receiver<bool>);
+
+#[derive(Debug)]
+/// A [`Future`] returned by [`ChildrenRef::stopped`], which
+/// resolves once the children group terminated (ie. once all
+/// its elements did).
+///
+/// It resolves to a [`Terminated`] describing why the children
+/// group terminated, or to `Err(())` if it was dropped before
+/// (eg. because the system was shut down).
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`ChildrenRef::stopped`]: ../children_ref/struct.ChildrenRef.html#method.stopped
+/// [`Terminated`]: struct.Terminated.html
+pub struct Stopped(Receiver<Terminated>);
+
+#[derive(Debug)]
+/// A [`Future`] returned by [`ChildRef::exited`], which resolves
+/// once the element terminated (ie. once it stopped, faulted or
+/// was killed).
+///
+/// It resolves to the [`TerminationReason`] describing why the
+/// element terminated, or to `Err(())` if it was dropped before
+/// (eg. because the system was shut down).
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`ChildRef::exited`]: ../child_ref/struct.ChildRef.html#method.exited
+/// [`TerminationReason`]: ../supervisor/enum.TerminationReason.html
+pub struct Exited(Receiver<TerminationReason>);
+
+#[derive(Debug)]
+/// A [`Future`] returned by [`ChildrenRef::ready`] or
+/// [`Bastion::ready`], which resolves once all the elements of
+/// the children groups it waits for told that they are ready
+/// (using [`BastionContext::notify_started`]).
+///
+/// It resolves to `Ok(())`, or to `Err(())` if one of those
+/// children groups terminated before (eg. because it was stopped,
+/// restarted or because the system was shut down).
+///
+/// [`Future`]: https://doc.rust-lang.org/std/future/trait.Future.html
+/// [`ChildrenRef::ready`]: ../children_ref/struct.ChildrenRef.html#method.ready
+/// [`Bastion::ready`]: ../struct.Bastion.html#method.ready
+/// [`BastionContext::notify_started`]: ../context/struct.BastionContext.html#method.notify_started
+pub struct Ready(Vec<Receiver<()>>);
+
+#[derive(Debug, Clone, PartialEq)]
+/// The message received by an element of a children group
+/// trapping exits (see [`Children::with_trapped_exits`]) when it
+/// is told to stop, instead of being stopped right away.
+///
+/// The element can then clean up and stop by returning from
+/// its future whenever it wants to.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children
+///         .with_trapped_exits()
+///         .with_exec(|ctx: BastionContext| {
+///             async move {
+///                 loop {
+///                     msg! { ctx.recv().await?,
+///                         _msg: Exit => {
+///                             // Clean up and stop...
+///                             return Ok(());
+///                         };
+///                         _: _ => ();
+///                     }
+///                 }
+///             }
+///         })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Children::with_trapped_exits`]: ../children/struct.Children.html#method.with_trapped_exits
+pub struct Exit;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The message asked to the elements of a children group
+/// preparing to stop (see [`Children::with_prepare_stop`])
+/// before they are told to, giving them a chance to finish their
+/// in-flight work and flush their buffers.
+///
+/// The children group only stops its elements once all of them
+/// answered (or dropped) it, or once its deadline passed.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     children
+///         .with_prepare_stop(Duration::from_secs(5))
+///         .with_exec(|ctx: BastionContext| {
+///             async move {
+///                 loop {
+///                     msg! { ctx.recv().await?,
+///                         _msg: PrepareStop =!> {
+///                             // Flush the buffers and acknowledge...
+///                             answer!(ctx, ()).ok();
+///                         };
+///                         _: _ => ();
+///                     }
+///                 }
+///             }
+///         })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Children::with_prepare_stop`]: ../children/struct.Children.html#method.with_prepare_stop
+pub struct PrepareStop;
+
+#[derive(Debug, Clone)]
+/// The message received by an element watching another one
+/// (using [`BastionContext::watch`]) once the watched element
+/// stopped or faulted.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+///     # let children_ref = Bastion::children(|children| children).unwrap();
+///     # let watched = children_ref.elems()[0].clone();
+/// Bastion::children(|children| {
+///     children.with_exec(move |ctx: BastionContext| {
+///         let watched = watched.clone();
+///         async move {
+///             ctx.watch(&watched).expect("Couldn't watch the element.");
+///
+///             msg! { ctx.recv().await?,
+///                 terminated: Terminated => {
+///                     // The watched element stopped or faulted...
+///                     println!("{} terminated: {:?}", terminated.id(), terminated.reason());
+///                 };
+///                 _: _ => ();
+///             }
+///
+///             Ok(())
+///         }
+///     })
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`BastionContext::watch`]: ../context/struct.BastionContext.html#method.watch
+pub struct Terminated {
+    id: BastionId,
+    reason: TerminationReason,
+}
+
+#[derive(Debug)]
+// The sender of a oneshot channel that can be cloned along with
+// the message carrying it, only the first call to `send` sending
+// the value.
+pub(crate) struct OneshotSender<T>(Arc<Mutex<Option<oneshot::Sender<T>>>>);
+
 #[derive(Debug)]
 /// A message returned by [`BastionContext::recv`] or
 /// [`BastionContext::try_recv`] that should be passed to the
@@ -176,24 +576,166 @@ pub struct Msg(MsgInner);
 #[derive(Debug)]
 enum MsgInner {
     Broadcast(Arc<dyn Any + Send + Sync + 'static>),
-    Tell(Box<dyn Any + Send + Sync + 'static>),
+    Tell(Payload),
     Ask {
-        msg: Box<dyn Any + Send + Sync + 'static>,
+        msg: Payload,
         sender: Option<AnswerSender>,
+        // When the answer stops being waited for (see
+        // `BastionContext::ask_with_timeout`), if ever.
+        deadline: Option<Instant>,
     },
 }
 
+#[derive(Debug)]
+// The payload of a message that was "told" or "asked", which
+// is stored inline instead of being allocated on the heap when
+// it is of one of the primitive types listed in `Inline`. Any
+// other type is boxed, however small it is (eg. users' enums
+// or structs).
+enum Payload {
+    Inline(Inline),
+    Boxed(Box<dyn Any + Send + Sync + 'static>),
+}
+
+macro_rules! inline_payload {
+    ($($variant:ident($ty:ty),)*) => {
+        #[derive(Debug)]
+        // The types of payloads that are stored inline. Those are
+        // found using `Any` to swap the payload in or out of an
+        // `Option` of the right type, which is why they need to be
+        // listed (storing any small enough type inline would
+        // require unsafe code, which the crate denies).
+        enum Inline {
+            $($variant($ty),)*
+        }
+
+        impl Inline {
+            fn new<M: Message>(msg: M) -> Result<Self, M> {
+                let mut msg = Some(msg);
+                let any: &mut dyn Any = &mut msg;
+                $(
+                    if let Some(msg) = any.downcast_mut::<Option<$ty>>() {
+                        return Ok(Inline::$variant(msg.take().unwrap()));
+                    }
+                )*
+
+                Err(msg.unwrap())
+            }
+
+            fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+                match self {
+                    $(Inline::$variant(msg) => msg,)*
+                }
+            }
+
+            fn take<M: Message>(self) -> Result<M, Self> {
+                let mut taken: Option<M> = None;
+                let any: &mut dyn Any = &mut taken;
+                match self {
+                    $(
+                        Inline::$variant(msg) => match any.downcast_mut::<Option<$ty>>() {
+                            Some(taken) => *taken = Some(msg),
+                            None => return Err(Inline::$variant(msg)),
+                        },
+                    )*
+                }
+
+                Ok(taken.unwrap())
+            }
+        }
+    };
+}
+
+inline_payload! {
+    Unit(()),
+    Bool(bool),
+    Char(char),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Usize(usize),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Isize(isize),
+    F32(f32),
+    F64(f64),
+    Str(&'static str),
+}
+
 #[derive(Debug)]
 pub(crate) enum BastionMessage {
     Start,
     Stop,
     Kill,
     Deploy(Deployment),
-    Prune { id: BastionId },
+    Prune {
+        id: BastionId,
+    },
     SuperviseWith(SupervisionStrategy),
     Message(Msg),
-    Stopped { id: BastionId },
-    Faulted { id: BastionId },
+    StopWithin {
+        deadline: Duration,
+        sender: OneshotSender<bool>,
+    },
+    Watch {
+        watcher: ChildRef,
+    },
+    Unwatch {
+        watcher: BastionId,
+    },
+    Termination {
+        id: BastionId,
+        reason: TerminationReason,
+    },
+    Scale(Scaling),
+    NotifyStopped {
+        sender: OneshotSender<Terminated>,
+    },
+    NotifyExited {
+        sender: OneshotSender<TerminationReason>,
+    },
+    // Tells a children group that one of its elements finished
+    // initializing.
+    ElemReady {
+        id: BastionId,
+    },
+    NotifyReady {
+        sender: OneshotSender<()>,
+    },
+    RestartElem {
+        id: BastionId,
+    },
+    Ping {
+        sender: OneshotSender<()>,
+    },
+    PrepareStop {
+        msg: Msg,
+    },
+    // A message that should be delivered to only one of the
+    // elements of a children group.
+    SendOne(Msg),
+    // A message that should be delivered to all the elements
+    // of a children group but the one that sent it.
+    SendSiblings {
+        msg: Msg,
+        except: BastionId,
+    },
+    Pause,
+    Resume,
+}
+
+#[derive(Debug, Clone)]
+// How a children group should change its number of elements.
+pub(crate) enum Scaling {
+    // Start or retire elements to have the given number of them.
+    To(usize),
+    // Start one more element.
+    Add,
+    // Retire the element with the given identifier.
+    Remove(BastionId),
 }
 
 #[derive(Debug)]
@@ -202,18 +744,141 @@ pub(crate) enum Deployment {
     Children(Children),
 }
 
+impl Shutdown {
+    pub(crate) fn new() -> (OneshotSender<bool>, Self) {
+        let (sender, recver) = OneshotSender::new();
+        (sender, Shutdown(recver))
+    }
+}
+
+impl Stopped {
+    pub(crate) fn new() -> (OneshotSender<Terminated>, Self) {
+        let (sender, recver) = OneshotSender::new();
+        (sender, Stopped(recver))
+    }
+}
+
+impl Exited {
+    pub(crate) fn new() -> (OneshotSender<TerminationReason>, Self) {
+        let (sender, recver) = OneshotSender::new();
+        (sender, Exited(recver))
+    }
+}
+
+impl Ready {
+    pub(crate) fn new() -> (OneshotSender<()>, Self) {
+        let (sender, recver) = OneshotSender::new();
+        (sender, Ready(vec![recver]))
+    }
+
+    // Waits for all the given children groups to be ready.
+    pub(crate) fn all(readies: Vec<Ready>) -> Self {
+        Ready(readies.into_iter().flat_map(|ready| ready.0).collect())
+    }
+}
+
+impl Terminated {
+    pub(crate) fn new(id: BastionId, reason: TerminationReason) -> Self {
+        Terminated { id, reason }
+    }
+
+    /// Returns the identifier of the element that terminated.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns why the element terminated.
+    ///
+    /// Note that the reason is [`TerminationReason::Normal`] if
+    /// the element had already terminated when it started being
+    /// watched.
+    ///
+    /// [`TerminationReason::Normal`]: ../supervisor/enum.TerminationReason.html#variant.Normal
+    pub fn reason(&self) -> &TerminationReason {
+        &self.reason
+    }
+}
+
+impl<T> OneshotSender<T> {
+    pub(crate) fn new() -> (Self, Receiver<T>) {
+        let (sender, recver) = oneshot::channel();
+        let sender = OneshotSender(Arc::new(Mutex::new(Some(sender))));
+
+        (sender, recver)
+    }
+
+    // Sends the value, unless it was already sent by a clone of
+    // this sender.
+    pub(crate) fn send(&self, value: T) {
+        // FIXME: panics?
+        if let Some(sender) = self.0.lock().unwrap().take() {
+            sender.send(value).ok();
+        }
+    }
+}
+
+impl<T> Clone for OneshotSender<T> {
+    fn clone(&self) -> Self {
+        OneshotSender(self.0.clone())
+    }
+}
+
 impl AnswerSender {
+    fn new(sender: oneshot::Sender<SignedMessage>) -> Self {
+        AnswerSender(Arc::new(Mutex::new(Some(sender))))
+    }
+
     // FIXME: we can't let manipulating Signature in a public API
     // but now it's being called only by a macro so we are trusting it
     #[doc(hidden)]
     pub fn send<M: Message>(self, msg: M, sign: RefAddr) -> Result<(), M> {
         debug!("{:?}: Sending answer: {:?}", self, msg);
+        // FIXME: panics?
+        let sender = match self.0.lock().unwrap().take() {
+            Some(sender) => sender,
+            None => {
+                debug!("{:?}: Already answered.", self);
+                return Err(msg);
+            }
+        };
+
         let msg = Msg::tell(msg);
         trace!("{:?}: Sending message: {:?}", self, msg);
-        self.0
+        sender
             .send(SignedMessage::new(msg, sign))
             .map_err(|smsg| smsg.msg.try_unwrap().unwrap())
     }
+
+    /// Answers the message this `AnswerSender` was created for,
+    /// signing the answer with the given context's signature.
+    ///
+    /// This method returns `()` if it succeeded, or `Err(msg)`
+    /// otherwise (if the message was already answered using
+    /// another clone of this `AnswerSender` or if the asker isn't
+    /// waiting for the answer anymore).
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of the child answering the message.
+    /// * `msg` - The answer to send.
+    ///
+    /// See [`AnswerSender`]'s documentation for an example.
+    ///
+    /// [`AnswerSender`]: struct.AnswerSender.html
+    pub fn reply<M: Message>(self, ctx: &BastionContext, msg: M) -> Result<(), M> {
+        self.send(msg, ctx.signature())
+    }
+
+    /// Returns whether the message this `AnswerSender` was created
+    /// for can't be answered anymore, either because it was already
+    /// answered or because the asker stopped waiting for an answer.
+    pub fn is_closed(&self) -> bool {
+        // FIXME: panics?
+        match &*self.0.lock().unwrap() {
+            Some(sender) => sender.is_canceled(),
+            None => true,
+        }
+    }
 }
 
 impl Msg {
@@ -223,22 +888,57 @@ impl Msg {
     }
 
     pub(crate) fn tell<M: Message>(msg: M) -> Self {
-        let inner = MsgInner::Tell(Box::new(msg));
+        let inner = MsgInner::Tell(Payload::new(msg));
         Msg(inner)
     }
 
+    // Lets `f` look at the message as a `Msg` (eg. to validate it
+    // before it gets sent), giving it back afterwards.
+    pub(crate) fn inspect<M, R, F>(msg: M, f: F) -> (M, R)
+    where
+        M: Message,
+        F: FnOnce(&Msg) -> R,
+    {
+        let msg = Msg::tell(msg);
+        let res = f(&msg);
+        match msg.downcast() {
+            Ok(msg) => (msg, res),
+            // NOTE: the message was just wrapped as a `M` and `f`
+            //      only borrowed it, so it can always be taken back.
+            Err(_) => unreachable!(),
+        }
+    }
+
     pub(crate) fn ask<M: Message>(msg: M) -> (Self, Answer) {
-        let msg = Box::new(msg);
+        Msg::ask_within(msg, None)
+    }
+
+    pub(crate) fn ask_within<M: Message>(msg: M, deadline: Option<Instant>) -> (Self, Answer) {
+        let msg = Payload::new(msg);
         let (sender, recver) = oneshot::channel();
-        let sender = AnswerSender(sender);
+        let sender = AnswerSender::new(sender);
         let answer = Answer(recver);
 
         let sender = Some(sender);
-        let inner = MsgInner::Ask { msg, sender };
+        let inner = MsgInner::Ask {
+            msg,
+            sender,
+            deadline,
+        };
 
         (Msg(inner), answer)
     }
 
+    // Returns when the answer to the message stops being waited
+    // for, if it was asked with a deadline.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        if let MsgInner::Ask { deadline, .. } = self.0 {
+            deadline
+        } else {
+            None
+        }
+    }
+
     #[doc(hidden)]
     pub fn is_broadcast(&self) -> bool {
         if let MsgInner::Broadcast(_) = self.0 {
@@ -289,28 +989,77 @@ impl Msg {
     pub fn downcast<M: Message>(self) -> Result<M, Self> {
         trace!("{:?}: Downcasting to {}.", self, type_name::<M>());
         match self.0 {
-            MsgInner::Tell(msg) => {
-                if msg.is::<M>() {
-                    let msg: Box<dyn Any + 'static> = msg;
-                    Ok(*msg.downcast().unwrap())
-                } else {
-                    let inner = MsgInner::Tell(msg);
-                    Err(Msg(inner))
-                }
-            }
-            MsgInner::Ask { msg, sender } => {
-                if msg.is::<M>() {
-                    let msg: Box<dyn Any + 'static> = msg;
-                    Ok(*msg.downcast().unwrap())
-                } else {
-                    let inner = MsgInner::Ask { msg, sender };
-                    Err(Msg(inner))
-                }
-            }
+            MsgInner::Tell(msg) => msg.take().map_err(|msg| {
+                let inner = MsgInner::Tell(msg);
+                Msg(inner)
+            }),
+            MsgInner::Ask {
+                msg,
+                sender,
+                deadline,
+            } => msg.take().map_err(|msg| {
+                let inner = MsgInner::Ask {
+                    msg,
+                    sender,
+                    deadline,
+                };
+                Msg(inner)
+            }),
             _ => Err(self),
         }
     }
 
+    /// Returns a reference to the message's payload if it is
+    /// of type `M`, or `None` otherwise.
+    ///
+    /// This is mostly useful to inspect a message without
+    /// consuming it, eg. in a validator set with
+    /// [`Children::with_validator`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::children(|children| {
+    ///     children.with_validator(|msg: &Msg| {
+    ///         // Only accept non-empty `&'static str`s...
+    ///         match msg.peek::<&'static str>() {
+    ///             Some(msg) => !msg.is_empty(),
+    ///             None => false,
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::with_validator`]: children/struct.Children.html#method.with_validator
+    pub fn peek<M: Message>(&self) -> Option<&M> {
+        match &self.0 {
+            MsgInner::Tell(msg) => msg.downcast_ref(),
+            MsgInner::Ask { msg, .. } => msg.downcast_ref(),
+            MsgInner::Broadcast(msg) => msg.downcast_ref(),
+        }
+    }
+
+    // Returns the in-memory size of the message's payload,
+    // without the heap allocations it might own (which is only
+    // used as an approximation, see `Usage::allocated`).
+    pub(crate) fn size(&self) -> usize {
+        match &self.0 {
+            MsgInner::Tell(msg) => std::mem::size_of_val(msg.as_any()),
+            MsgInner::Ask { msg, .. } => std::mem::size_of_val(msg.as_any()),
+            MsgInner::Broadcast(msg) => std::mem::size_of_val(&**msg),
+        }
+    }
+
     #[doc(hidden)]
     pub fn downcast_ref<M: Message>(&self) -> Option<Arc<M>> {
         trace!("{:?}: Downcasting to ref of {}.", self, type_name::<M>());
@@ -355,6 +1104,44 @@ impl Msg {
     }
 }
 
+impl Payload {
+    fn new<M: Message>(msg: M) -> Self {
+        match Inline::new(msg) {
+            Ok(msg) => Payload::Inline(msg),
+            Err(msg) => Payload::Boxed(Box::new(msg)),
+        }
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        match self {
+            Payload::Inline(msg) => msg.as_any(),
+            Payload::Boxed(msg) => &**msg,
+        }
+    }
+
+    fn is<M: Message>(&self) -> bool {
+        self.as_any().is::<M>()
+    }
+
+    fn downcast_ref<M: Message>(&self) -> Option<&M> {
+        self.as_any().downcast_ref()
+    }
+
+    fn take<M: Message>(self) -> Result<M, Self> {
+        match self {
+            Payload::Inline(msg) => msg.take().map_err(Payload::Inline),
+            Payload::Boxed(msg) => {
+                if msg.is::<M>() {
+                    let msg: Box<dyn Any + 'static> = msg;
+                    Ok(*msg.downcast().unwrap())
+                } else {
+                    Err(Payload::Boxed(msg))
+                }
+            }
+        }
+    }
+}
+
 impl BastionMessage {
     pub(crate) fn start() -> Self {
         BastionMessage::Start
@@ -368,6 +1155,14 @@ impl BastionMessage {
         BastionMessage::Kill
     }
 
+    pub(crate) fn pause() -> Self {
+        BastionMessage::Pause
+    }
+
+    pub(crate) fn resume() -> Self {
+        BastionMessage::Resume
+    }
+
     pub(crate) fn deploy_supervisor(supervisor: Supervisor) -> Self {
         let deployment = Deployment::Supervisor(supervisor);
 
@@ -380,10 +1175,55 @@ impl BastionMessage {
         BastionMessage::Deploy(deployment)
     }
 
+    pub(crate) fn stop_within(deadline: Duration, sender: OneshotSender<bool>) -> Self {
+        BastionMessage::StopWithin { deadline, sender }
+    }
+
+    pub(crate) fn watch(watcher: ChildRef) -> Self {
+        BastionMessage::Watch { watcher }
+    }
+
+    pub(crate) fn unwatch(watcher: BastionId) -> Self {
+        BastionMessage::Unwatch { watcher }
+    }
+
     pub(crate) fn prune(id: BastionId) -> Self {
         BastionMessage::Prune { id }
     }
 
+    pub(crate) fn scale(scaling: Scaling) -> Self {
+        BastionMessage::Scale(scaling)
+    }
+
+    pub(crate) fn notify_stopped(sender: OneshotSender<Terminated>) -> Self {
+        BastionMessage::NotifyStopped { sender }
+    }
+
+    pub(crate) fn notify_exited(sender: OneshotSender<TerminationReason>) -> Self {
+        BastionMessage::NotifyExited { sender }
+    }
+
+    pub(crate) fn elem_ready(id: BastionId) -> Self {
+        BastionMessage::ElemReady { id }
+    }
+
+    pub(crate) fn notify_ready(sender: OneshotSender<()>) -> Self {
+        BastionMessage::NotifyReady { sender }
+    }
+
+    pub(crate) fn restart_elem(id: BastionId) -> Self {
+        BastionMessage::RestartElem { id }
+    }
+
+    pub(crate) fn ping(sender: OneshotSender<()>) -> Self {
+        BastionMessage::Ping { sender }
+    }
+
+    pub(crate) fn prepare_stop() -> (Self, Answer) {
+        let (msg, answer) = Msg::ask(PrepareStop);
+        (BastionMessage::PrepareStop { msg }, answer)
+    }
+
     pub(crate) fn supervise_with(strategy: SupervisionStrategy) -> Self {
         BastionMessage::SuperviseWith(strategy)
     }
@@ -398,17 +1238,23 @@ impl BastionMessage {
         BastionMessage::Message(msg)
     }
 
-    pub(crate) fn ask<M: Message>(msg: M) -> (Self, Answer) {
-        let (msg, answer) = Msg::ask(msg);
+    pub(crate) fn ask_within<M: Message>(msg: M, deadline: Option<Instant>) -> (Self, Answer) {
+        let (msg, answer) = Msg::ask_within(msg, deadline);
         (BastionMessage::Message(msg), answer)
     }
 
-    pub(crate) fn stopped(id: BastionId) -> Self {
-        BastionMessage::Stopped { id }
+    pub(crate) fn send_one<M: Message>(msg: M) -> Self {
+        let msg = Msg::tell(msg);
+        BastionMessage::SendOne(msg)
     }
 
-    pub(crate) fn faulted(id: BastionId) -> Self {
-        BastionMessage::Faulted { id }
+    pub(crate) fn send_siblings<M: Message>(msg: M, except: BastionId) -> Self {
+        let msg = Msg::broadcast(msg);
+        BastionMessage::SendSiblings { msg, except }
+    }
+
+    pub(crate) fn termination(id: BastionId, reason: TerminationReason) -> Self {
+        BastionMessage::Termination { id, reason }
     }
 
     pub(crate) fn try_clone(&self) -> Option<Self> {
@@ -417,25 +1263,65 @@ impl BastionMessage {
             BastionMessage::Start => BastionMessage::start(),
             BastionMessage::Stop => BastionMessage::stop(),
             BastionMessage::Kill => BastionMessage::kill(),
-            // FIXME
-            BastionMessage::Deploy(_) => unimplemented!(),
+            // The deployed children group or supervisor can only be
+            // launched once.
+            BastionMessage::Deploy(_) => return None,
             BastionMessage::Prune { id } => BastionMessage::prune(id.clone()),
             BastionMessage::SuperviseWith(strategy) => {
                 BastionMessage::supervise_with(strategy.clone())
             }
             BastionMessage::Message(msg) => BastionMessage::Message(msg.try_clone()?),
-            BastionMessage::Stopped { id } => BastionMessage::stopped(id.clone()),
-            BastionMessage::Faulted { id } => BastionMessage::faulted(id.clone()),
+            BastionMessage::StopWithin { deadline, sender } => {
+                BastionMessage::stop_within(*deadline, sender.clone())
+            }
+            BastionMessage::Watch { watcher } => BastionMessage::watch(watcher.clone()),
+            BastionMessage::Unwatch { watcher } => BastionMessage::unwatch(watcher.clone()),
+            BastionMessage::Termination { id, reason } => {
+                BastionMessage::termination(id.clone(), reason.clone())
+            }
+            BastionMessage::Scale(scaling) => BastionMessage::scale(scaling.clone()),
+            BastionMessage::NotifyStopped { sender } => {
+                BastionMessage::notify_stopped(sender.clone())
+            }
+            BastionMessage::NotifyExited { sender } => {
+                BastionMessage::notify_exited(sender.clone())
+            }
+            BastionMessage::ElemReady { id } => BastionMessage::elem_ready(id.clone()),
+            BastionMessage::NotifyReady { sender } => BastionMessage::notify_ready(sender.clone()),
+            BastionMessage::RestartElem { id } => BastionMessage::restart_elem(id.clone()),
+            BastionMessage::Ping { sender } => BastionMessage::ping(sender.clone()),
+            BastionMessage::PrepareStop { msg } => BastionMessage::PrepareStop {
+                msg: msg.try_clone()?,
+            },
+            BastionMessage::SendOne(msg) => BastionMessage::SendOne(msg.try_clone()?),
+            BastionMessage::SendSiblings { msg, except } => BastionMessage::SendSiblings {
+                msg: msg.try_clone()?,
+                except: except.clone(),
+            },
+            BastionMessage::Pause => BastionMessage::pause(),
+            BastionMessage::Resume => BastionMessage::resume(),
         };
 
         Some(clone)
     }
 
+    // Whether this message should be delivered through the
+    // system lane (instead of the user lane).
+    pub(crate) fn is_system(&self) -> bool {
+        match self {
+            BastionMessage::Message(_)
+            | BastionMessage::SendOne(_)
+            | BastionMessage::SendSiblings { .. } => false,
+            _ => true,
+        }
+    }
+
     pub(crate) fn into_msg<M: Message>(self) -> Option<M> {
-        if let BastionMessage::Message(msg) = self {
-            msg.try_unwrap().ok()
-        } else {
-            None
+        match self {
+            BastionMessage::Message(msg)
+            | BastionMessage::SendOne(msg)
+            | BastionMessage::SendSiblings { msg, .. } => msg.try_unwrap().ok(),
+            _ => None,
         }
     }
 }
@@ -449,6 +1335,58 @@ impl Future for Answer {
     }
 }
 
+impl Future for Shutdown {
+    type Output = Result<bool, ()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        debug!("{:?}: Polling.", self);
+        Pin::new(&mut self.get_mut().0).poll(ctx).map_err(|_| ())
+    }
+}
+
+impl Future for Stopped {
+    type Output = Result<Terminated, ()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        debug!("{:?}: Polling.", self);
+        Pin::new(&mut self.get_mut().0).poll(ctx).map_err(|_| ())
+    }
+}
+
+impl Future for Exited {
+    type Output = Result<TerminationReason, ()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        debug!("{:?}: Polling.", self);
+        Pin::new(&mut self.get_mut().0).poll(ctx).map_err(|_| ())
+    }
+}
+
+impl Future for Ready {
+    type Output = Result<(), ()>;
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        debug!("{:?}: Polling.", self);
+        let recvers = &mut self.get_mut().0;
+        let mut i = 0;
+        while i < recvers.len() {
+            match Pin::new(&mut recvers[i]).poll(ctx) {
+                Poll::Ready(Ok(())) => {
+                    let _ = recvers.swap_remove(i);
+                }
+                Poll::Ready(Err(_)) => return Poll::Ready(Err(())),
+                Poll::Pending => i += 1,
+            }
+        }
+
+        if recvers.is_empty() {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
 #[macro_export]
 /// Matches a [`Msg`] (as returned by [`BastionContext::recv`]
 /// or [`BastionContext::try_recv`]) with different types.
@@ -469,7 +1407,9 @@ impl Future for Answer {
 /// If the message can be answered (when using `=!>` instead
 /// of `=>` as said above), an answer can be sent by passing
 /// it to the `answer!` macro that will be generated for this
-/// use.
+/// use. An [`AnswerSender`] allowing to answer the message
+/// later on (eventually from another child) can also be
+/// retrieved using the `answer_sender!` macro.
 ///
 /// A default case is required, which is defined in the same
 /// way as any other case but with its type set as `_` (note
@@ -536,6 +1476,7 @@ impl Future for Answer {
 /// [`Msg`]: children/struct.Msg.html
 /// [`BastionContext::recv`]: context/struct.BastionContext.html#method.recv
 /// [`BastionContext::try_recv`]: context/struct.BastionContext.html#method.try_recv
+/// [`AnswerSender`]: message/struct.AnswerSender.html
 macro_rules! msg {
     ($msg:expr, $($tokens:tt)+) => {
         msg!(@internal $msg, (), (), (), $($tokens)+)
@@ -647,6 +1588,12 @@ macro_rules! msg {
                 };
             }
 
+            macro_rules! answer_sender {
+                () => {
+                    sender.clone()
+                };
+            }
+
             if false {
                 unreachable!();
             }
@@ -675,3 +1622,58 @@ macro_rules! msg {
         }
     } };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Msg, MsgInner, Payload};
+
+    #[test]
+    fn inline_payload() {
+        let msg = Msg::tell(42u64);
+        match &msg.0 {
+            MsgInner::Tell(Payload::Inline(_)) => (),
+            _ => panic!(),
+        }
+        assert!(msg.is::<u64>());
+        assert_eq!(msg.peek::<u64>(), Some(&42));
+
+        let msg = msg.downcast::<u32>().unwrap_err();
+        assert_eq!(msg.downcast::<u64>().ok(), Some(42));
+
+        let (msg, _) = Msg::ask("A message containing data.");
+        match &msg.0 {
+            MsgInner::Ask {
+                msg: Payload::Inline(_),
+                ..
+            } => (),
+            _ => panic!(),
+        }
+        assert_eq!(
+            msg.downcast::<&'static str>().ok(),
+            Some("A message containing data.")
+        );
+    }
+
+    #[test]
+    fn boxed_payload() {
+        let msg = Msg::tell(vec![1, 2, 3]);
+        match &msg.0 {
+            MsgInner::Tell(Payload::Boxed(_)) => (),
+            _ => panic!(),
+        }
+
+        let msg = msg.downcast::<u64>().unwrap_err();
+        assert_eq!(msg.downcast::<Vec<i32>>().ok(), Some(vec![1, 2, 3]));
+
+        // Only the listed primitive types are stored inline.
+        #[derive(Debug, PartialEq)]
+        struct Small(u8);
+
+        let msg = Msg::tell(Small(42));
+        match &msg.0 {
+            MsgInner::Tell(Payload::Boxed(_)) => (),
+            _ => panic!(),
+        }
+        assert_eq!(msg.downcast::<Small>().ok(), Some(Small(42)));
+    }
+}
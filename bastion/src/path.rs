@@ -58,6 +58,15 @@ impl BastionPath {
         }
     }
 
+    // An element that isn't part of the tree yet (eg. a children
+    // group that was built but not deployed)
+    pub(crate) fn detached(this: BastionPathElement) -> BastionPath {
+        BastionPath {
+            parent_chain: vec![],
+            this: Some(this),
+        }
+    }
+
     /// iterates over path elements
     pub(crate) fn iter(&self) -> impl Iterator<Item = &BastionId> {
         let parent_iter = self.parent_chain.iter();
@@ -0,0 +1,133 @@
+//!
+//! Rate limiting of the messages received by the elements of
+//! children groups, protecting slow elements from bursty
+//! producers.
+//!
+//! The rate at which the elements of a children group receive
+//! messages can be limited using [`Children::with_rate_limit`].
+//!
+//! [`Children::with_rate_limit`]: ../children/struct.Children.html#method.with_rate_limit
+use futures::prelude::*;
+use futures_timer::Delay;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What an element of a children group does with the messages
+/// it receives while exceeding its rate limit.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::children(|children| {
+///     // Every element will receive at most 100 messages per second,
+///     // delaying the others...
+///     children.with_rate_limit(100, RateLimitPolicy::Delay)
+/// }).expect("Couldn't create the children group.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub enum RateLimitPolicy {
+    /// The messages are held back until the rate limit allows
+    /// them to be received. The element doesn't receive any
+    /// other message in the meantime (except system messages,
+    /// eg. to stop or kill it).
+    Delay,
+    /// The messages are dropped.
+    Reject,
+}
+
+#[derive(Debug)]
+// A token bucket refilled at `rate` tokens per second and able
+// to hold up to one second worth of tokens.
+pub(crate) struct RateLimiter {
+    policy: RateLimitPolicy,
+    rate: f64,
+    tokens: f64,
+    refilled_at: Instant,
+    // The timer waking the element up once a token will be
+    // available.
+    delay: Option<Delay>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(max_per_second: u32, policy: RateLimitPolicy) -> Self {
+        let rate = f64::from(max_per_second.max(1));
+
+        RateLimiter {
+            policy,
+            rate,
+            tokens: rate,
+            refilled_at: Instant::now(),
+            delay: None,
+        }
+    }
+
+    pub(crate) fn policy(&self) -> RateLimitPolicy {
+        self.policy
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.refilled_at = now;
+    }
+
+    // Takes a token if one is available, returning whether it
+    // was.
+    pub(crate) fn try_acquire(&mut self) -> bool {
+        self.refill();
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Takes a token once one is available, registering a timer
+    // to wake the current task up until then.
+    pub(crate) fn poll_acquire(&mut self, ctx: &mut Context) -> Poll<()> {
+        loop {
+            if self.try_acquire() {
+                self.delay = None;
+                return Poll::Ready(());
+            }
+
+            let wait = Duration::from_secs_f64((1.0 - self.tokens) / self.rate);
+            let delay = self.delay.get_or_insert_with(|| Delay::new(wait));
+            if Pin::new(delay).poll(ctx).is_pending() {
+                return Poll::Pending;
+            }
+
+            self.delay = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RateLimitPolicy, RateLimiter};
+
+    #[test]
+    fn rate_limiter() {
+        let mut limiter = RateLimiter::new(4, RateLimitPolicy::Reject);
+
+        for _ in 0..4 {
+            assert!(limiter.try_acquire());
+        }
+        assert!(!limiter.try_acquire());
+    }
+}
@@ -3,11 +3,12 @@
 //! or other supervisor trees under themselves.
 use crate::broadcast::{Broadcast, Parent, Sender};
 use crate::callbacks::Callbacks;
-use crate::children::Children;
+use crate::child_ref::ChildRef;
+use crate::children::{Children, GroupRestartPolicy};
 use crate::children_ref::ChildrenRef;
 use crate::context::BastionId;
 use crate::envelope::Envelope;
-use crate::message::{BastionMessage, Deployment, Message};
+use crate::message::{BastionMessage, Deployment, Message, Msg, Shutdown};
 use crate::path::{BastionPath, BastionPathElement};
 use bastion_executor::pool;
 use futures::prelude::*;
@@ -18,11 +19,14 @@ use fxhash::FxHashMap;
 use lightproc::prelude::*;
 use log::Level;
 use std::cmp::{Eq, PartialEq};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::task::Poll;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
 
 #[derive(Debug)]
 /// A supervisor that can supervise both [`Children`] and other
@@ -58,14 +62,56 @@ use std::time::Duration;
 /// # }
 /// ```
 ///
+/// Whole supervision trees can be declared in a single
+/// expression by chaining calls to [`supervisor`] and
+/// [`children`], which return the supervisor they were called
+/// on:
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::supervisor(|sp| {
+///     sp.with_strategy(SupervisionStrategy::OneForAll)
+///         .children(|children| {
+///             // ...
+///             # children
+///         })
+///         .supervisor(|sp| {
+///             sp.with_strategy(SupervisionStrategy::RestForOne)
+///                 .children(|children| {
+///                     // ...
+///                     # children
+///                 })
+///                 .children(|children| {
+///                     // ...
+///                     # children
+///                 })
+///         })
+/// }).expect("Couldn't create the supervision tree.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
 /// [`Children`]: children/struct.Children.html
 /// [`SupervisionStrategy`]: supervisor/enum.SupervisionStrategy.html
 /// [`with_strategy`]: #method.with_strategy
+/// [`supervisor`]: #method.supervisor
+/// [`children`]: #method.children
 /// [`Bastion::children`]: struct.Bastion.html#method.children
 pub struct Supervisor {
     bcast: Broadcast,
+    // The name used instead of the supervisor's identifier in
+    // the addresses of its elements (if any).
+    name: Option<String>,
     // The order in which children and supervisors were added.
-    // It is only updated when at least one of those is resat.
+    // Restarted elements keep their position (and thus stay
+    // before the ones added after them, for "rest-for-one").
     order: Vec<BastionId>,
     // The currently launched supervised children and supervisors.
     // The last value is the amount of times a given actor has restarted.
@@ -79,9 +125,33 @@ pub struct Supervisor {
     killed: FxHashMap<BastionId, Supervised>,
     strategy: SupervisionStrategy,
     restart_strategy: RestartStrategy,
+    // When every supervised children group and supervisor
+    // faulted within the restart intensity's window (if any),
+    // which is thus tracked separately for each of them.
+    restarts: FxHashMap<BastionId, VecDeque<Instant>>,
+    // When the supervised children and supervisors last faulted.
+    last_faults: FxHashMap<BastionId, Instant>,
+    // The supervised children and supervisors whose circuit
+    // breaker is open or half-open, with the end of its cooldown.
+    circuits: FxHashMap<BastionId, (CircuitState, Delay)>,
+    // The statistics about the supervised children and
+    // supervisors, shared with the `SupervisorRef`s.
+    stats: Arc<RwLock<Vec<SupervisedStats>>>,
+    // The references to the launched supervised children and
+    // supervisors, and their snapshot shared with the
+    // `SupervisorRef`s.
+    refs: FxHashMap<BastionId, SupervisedRef>,
+    shared_refs: Arc<RwLock<Vec<SupervisedRef>>>,
     // The callbacks called at the supervisor's different
     // lifecycle events.
     callbacks: Callbacks,
+    // The strategy deciding what to do with faulted elements
+    // instead of the restart strategy (if any).
+    custom_strategy: Option<Box<dyn Strategy>>,
+    // The callbacks called and the elements notified every time
+    // the supervisor decides what to do with a faulted element.
+    fault_callbacks: Vec<FaultCallback>,
+    fault_subscribers: Vec<ChildRef>,
     // Whether this supervisor was started by the system (in
     // which case, users shouldn't be able to get a reference
     // to it).
@@ -93,6 +163,226 @@ pub struct Supervisor {
     started: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A notification of a supervised children group or supervisor
+/// that faulted, passed to the callbacks set with
+/// [`Supervisor::with_fault_callback`] and sent to the elements
+/// subscribed with [`Supervisor::with_fault_subscriber`] once the
+/// supervisor decided what to do with it.
+///
+/// [`Supervisor::with_fault_callback`]: supervisor/struct.Supervisor.html#method.with_fault_callback
+/// [`Supervisor::with_fault_subscriber`]: supervisor/struct.Supervisor.html#method.with_fault_subscriber
+pub struct Fault {
+    info: FaultInfo,
+    decision: Decision,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A description of a supervised children group or supervisor
+/// that faulted, passed to [`Strategy::decide`].
+///
+/// [`Strategy::decide`]: supervisor/trait.Strategy.html#tymethod.decide
+pub struct FaultInfo {
+    id: BastionId,
+    restarts: usize,
+    reason: FaultReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why a supervised children group or supervisor faulted.
+pub enum FaultReason {
+    /// The future of one of the elements of the children group
+    /// returned an error.
+    Error,
+    /// The future of one of the elements of the children group
+    /// returned the given error (when using
+    /// [`Children::with_fallible_exec`]).
+    ///
+    /// [`Children::with_fallible_exec`]: children/struct.Children.html#method.with_fallible_exec
+    Failure(ChildError),
+    /// The future of one of the elements of the children group
+    /// panicked, with the panic's message if it was a string.
+    Panic(Option<String>),
+    /// The polls of the future of one of the elements of the
+    /// children group kept exceeding their deadline (see
+    /// [`Children::with_poll_deadline`]).
+    ///
+    /// [`Children::with_poll_deadline`]: children/struct.Children.html#method.with_poll_deadline
+    TooSlow,
+    /// The future of one of the elements of the children group
+    /// didn't complete within the group's maximum runtime (see
+    /// [`Children::with_max_runtime`]).
+    ///
+    /// [`Children::with_max_runtime`]: children/struct.Children.html#method.with_max_runtime
+    Timeout,
+    /// One of the elements of the children group stopped while
+    /// its restart policy was [`GroupRestartPolicy::Permanent`].
+    ///
+    /// [`GroupRestartPolicy::Permanent`]: children/enum.GroupRestartPolicy.html#variant.Permanent
+    Stopped,
+    /// The supervisor faulted because of the fault of one of
+    /// its supervised children groups or supervisors, whose
+    /// reason is given.
+    Escalated(Box<FaultReason>),
+}
+
+#[derive(Clone)]
+/// The error returned by the future of an element of a children
+/// group (see [`Children::with_fallible_exec`]), carried by
+/// [`FaultReason::Failure`] for supervisors and watchers to
+/// inspect it.
+///
+/// Two `ChildError`s are equal if their messages are equal.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// # use std::io;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// Bastion::supervisor(|sp| {
+///     sp.with_fault_callback(|fault: &Fault| {
+///         if let FaultReason::Failure(err) = fault.reason() {
+///             if let Some(err) = err.downcast_ref::<io::Error>() {
+///                 println!("I/O error: {:?}", err.kind());
+///             }
+///         }
+///     })
+/// }).expect("Couldn't create the supervisor.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Children::with_fallible_exec`]: children/struct.Children.html#method.with_fallible_exec
+/// [`FaultReason::Failure`]: supervisor/enum.FaultReason.html#variant.Failure
+pub struct ChildError(Arc<dyn Error + Send + Sync>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Whether a supervised children group or supervisor faulted
+/// because of a panic or of an error, allowing strategies to
+/// handle them differently without caring about the details
+/// of the [`FaultReason`].
+///
+/// [`FaultReason`]: supervisor/enum.FaultReason.html
+pub enum FaultKind {
+    /// The future of one of the elements panicked.
+    Panic,
+    /// The future of one of the elements returned an error.
+    Error,
+    /// The fault wasn't caused by the future of one of the
+    /// elements (eg. it was too slow or stopped).
+    Other,
+}
+
+#[derive(Debug)]
+/// Why an element, a children group or a supervisor terminated,
+/// as told to its supervisor and to the elements watching it
+/// (see [`Terminated`]), and returned by
+/// [`Bastion::block_until_stopped`] for the system.
+///
+/// [`Terminated`]: message/struct.Terminated.html
+/// [`Bastion::block_until_stopped`]: struct.Bastion.html#method.block_until_stopped
+pub enum TerminationReason {
+    /// It stopped normally, either because it was told to or
+    /// because the future of an element returned `Ok(())`.
+    Normal,
+    /// It was killed.
+    Killed,
+    /// It faulted for the given reason.
+    Fault(FaultReason),
+    /// The future of an element returned `Ok(())` after setting
+    /// this custom reason using [`BastionContext::exit_with`].
+    ///
+    /// [`BastionContext::exit_with`]: context/struct.BastionContext.html#method.exit_with
+    Custom(Msg),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What a supervisor decided to do with a supervised children
+/// group or supervisor that faulted.
+///
+/// Unless a [`Strategy`] was set with
+/// [`Supervisor::with_custom_strategy`], the decision is
+/// `Escalate` if it restarted too many times (see
+/// [`RestartStrategy::with_restart_intensity`]), `Restart` if
+/// its [`RestartPolicy`] allows it and `Stop` otherwise.
+///
+/// [`Strategy`]: supervisor/trait.Strategy.html
+/// [`Supervisor::with_custom_strategy`]: supervisor/struct.Supervisor.html#method.with_custom_strategy
+/// [`RestartStrategy::with_restart_intensity`]: supervisor/struct.RestartStrategy.html#method.with_restart_intensity
+/// [`RestartPolicy`]: supervisor/enum.RestartPolicy.html
+pub enum Decision {
+    /// It is restarted, along with the other children groups
+    /// and supervisors required by the supervision strategy.
+    Restart,
+    /// It isn't restarted and the supervisor stops supervising
+    /// it.
+    Stop,
+    /// The supervisor faults itself, letting its own supervisor
+    /// decide what to do.
+    Escalate,
+    /// The fault is ignored and it is considered stopped, so it
+    /// is only restarted if the supervision strategy requires it
+    /// when another children group or supervisor faults.
+    Resume,
+}
+
+/// A trait that custom strategies need to implement to decide
+/// what a supervisor does with the supervised children groups
+/// and supervisors that fault.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// // A strategy only restarting the children groups whose
+/// // elements panicked...
+/// #[derive(Debug)]
+/// struct RestartOnPanic;
+///
+/// impl Strategy for RestartOnPanic {
+///     fn decide(&mut self, fault: &FaultInfo) -> Decision {
+///         match fault.kind() {
+///             FaultKind::Panic => Decision::Restart,
+///             _ => Decision::Stop,
+///         }
+///     }
+/// }
+///
+/// # fn main() {
+///     # Bastion::init();
+///     #
+/// // ...which will be used by a supervisor.
+/// Bastion::supervisor(|sp| {
+///     sp.with_custom_strategy(RestartOnPanic)
+/// }).expect("Couldn't create the supervisor.");
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+pub trait Strategy: Send + Sync + Debug + 'static {
+    /// Decides what to do with a supervised children group or
+    /// supervisor that faulted.
+    ///
+    /// # Arguments
+    ///
+    /// * `fault` - The description of the children group or
+    ///     supervisor that faulted.
+    fn decide(&mut self, fault: &FaultInfo) -> Decision;
+}
+
+#[derive(Clone)]
+struct FaultCallback(Arc<dyn Fn(&Fault) + Send + Sync>);
+
 #[derive(Debug, Clone)]
 /// A "reference" to a [`Supervisor`], allowing to
 /// communicate with it.
@@ -100,8 +390,60 @@ pub struct Supervisor {
 /// [`Supervisor`]: supervisor/struct.Supervisor.html
 pub struct SupervisorRef {
     id: BastionId,
+    name: Option<String>,
     sender: Sender,
     path: Arc<BastionPath>,
+    stats: Arc<RwLock<Vec<SupervisedStats>>>,
+    supervised: Arc<RwLock<Vec<SupervisedRef>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Statistics about a children group or supervisor supervised
+/// by a supervisor, returned by [`SupervisorRef::stats`].
+///
+/// [`SupervisorRef::stats`]: supervisor/struct.SupervisorRef.html#method.stats
+pub struct SupervisedStats {
+    id: BastionId,
+    state: SupervisedState,
+    restarts: usize,
+    last_fault: Option<Instant>,
+    backoff: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The state of a children group or supervisor supervised by a
+/// supervisor, as returned by [`SupervisedStats::state`].
+///
+/// [`SupervisedStats::state`]: supervisor/struct.SupervisedStats.html#method.state
+pub enum SupervisedState {
+    /// It is running.
+    Started,
+    /// It stopped and won't be restarted unless the supervision
+    /// strategy requires it when another children group or
+    /// supervisor faults.
+    Stopped,
+    /// It faulted and its supervisor decided not to restart it
+    /// (see [`Decision::Resume`]).
+    ///
+    /// [`Decision::Resume`]: supervisor/enum.Decision.html#variant.Resume
+    Faulted,
+    /// It kept faulting and its circuit breaker is open: it isn't
+    /// running until its supervisor tries to restart it again once
+    /// the cooldown elapsed (see
+    /// [`RestartStrategy::with_circuit_breaker`]).
+    ///
+    /// [`RestartStrategy::with_circuit_breaker`]: supervisor/struct.RestartStrategy.html#method.with_circuit_breaker
+    Open,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitState {
+    // The element isn't running until the cooldown elapses.
+    Open,
+    // The element was restarted once the cooldown elapsed and
+    // the circuit is opened again if it faults before another
+    // cooldown elapses.
+    HalfOpen,
 }
 
 #[derive(Debug, Clone)]
@@ -137,6 +479,12 @@ enum Supervised {
     Children(Children),
 }
 
+#[derive(Debug, Clone)]
+pub(crate) enum SupervisedRef {
+    Supervisor(SupervisorRef),
+    Children(ChildrenRef),
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 /// The restart policy which is used during restoring failed
 /// actors by the supervisor.
@@ -163,6 +511,16 @@ pub enum RestartPolicy {
 pub struct RestartStrategy {
     restart_policy: RestartPolicy,
     strategy: ActorRestartStrategy,
+    // The maximum amount of times an actor can be restarted
+    // within a duration before the supervisor faults.
+    intensity: Option<(usize, Duration)>,
+    // The maximum random delay added before restarting an actor,
+    // so that actors failing at the same time don't restart
+    // in lockstep.
+    jitter: Option<Duration>,
+    // The amount of restarts after which an actor isn't restarted
+    // anymore until a cooldown elapses.
+    circuit_breaker: Option<(usize, Duration)>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -196,26 +554,46 @@ pub enum ActorRestartStrategy {
 impl Supervisor {
     pub(crate) fn new(bcast: Broadcast) -> Self {
         debug!("Supervisor({}): Initializing.", bcast.id());
+        let name = None;
         let order = Vec::new();
         let launched = FxHashMap::default();
         let stopped = FxHashMap::default();
         let killed = FxHashMap::default();
         let strategy = SupervisionStrategy::default();
         let restart_strategy = RestartStrategy::default();
+        let restarts = FxHashMap::default();
+        let last_faults = FxHashMap::default();
+        let circuits = FxHashMap::default();
+        let stats = Arc::default();
+        let refs = FxHashMap::default();
+        let shared_refs = Arc::default();
         let callbacks = Callbacks::new();
+        let custom_strategy = None;
+        let fault_callbacks = Vec::new();
+        let fault_subscribers = Vec::new();
         let is_system_supervisor = false;
         let pre_start_msgs = Vec::new();
         let started = false;
 
         Supervisor {
             bcast,
+            name,
             order,
             launched,
             stopped,
             killed,
             strategy,
             restart_strategy,
+            restarts,
+            last_faults,
+            circuits,
+            stats,
+            refs,
+            shared_refs,
             callbacks,
+            custom_strategy,
+            fault_callbacks,
+            fault_subscribers,
             is_system_supervisor,
             pre_start_msgs,
             started,
@@ -224,6 +602,7 @@ impl Supervisor {
 
     pub(crate) fn system(bcast: Broadcast) -> Self {
         let mut supervisor = Supervisor::new(bcast);
+        supervisor.name = Some("user".to_string());
         supervisor.is_system_supervisor = true;
 
         supervisor
@@ -269,7 +648,7 @@ impl Supervisor {
         self.pre_start_msgs.clear();
         self.pre_start_msgs.shrink_to_fit();
 
-        self.restart(0..self.order.len()).await;
+        self.restart(0..self.order.len(), false).await;
 
         debug!(
             "Supervisor({}): Removing {} stopped elements.",
@@ -335,10 +714,51 @@ impl Supervisor {
         );
         // TODO: clone or ref?
         let id = self.bcast.id().clone();
+        let name = self.name.clone();
         let sender = self.bcast.sender().clone();
         let path = self.bcast.path().clone();
+        let stats = self.stats.clone();
+        let supervised = self.shared_refs.clone();
+
+        SupervisorRef::new(id, name, sender, path, stats, supervised)
+    }
+
+    // Updates the statistics and references shared with the
+    // `SupervisorRef`s.
+    fn refresh_shared(&self) {
+        let refs = self
+            .order
+            .iter()
+            .filter_map(|id| self.refs.get(id))
+            .cloned()
+            .collect();
+
+        // FIXME: panics?
+        *self.shared_refs.write().unwrap() = refs;
+
+        let stats = self
+            .order
+            .iter()
+            .map(|id| {
+                let (state, restarts) = match self.launched.get(id) {
+                    Some((_, _, restarts)) => (SupervisedState::Started, *restarts),
+                    None if self.circuits.contains_key(id) => (SupervisedState::Open, 0),
+                    None if self.last_faults.contains_key(id) => (SupervisedState::Faulted, 0),
+                    None => (SupervisedState::Stopped, 0),
+                };
+
+                SupervisedStats {
+                    id: id.clone(),
+                    state,
+                    restarts,
+                    last_fault: self.last_faults.get(id).cloned(),
+                    backoff: self.restart_strategy.restart_delay(restarts + 1),
+                }
+            })
+            .collect();
 
-        SupervisorRef::new(id, sender, path)
+        // FIXME: panics?
+        *self.stats.write().unwrap() = stats;
     }
 
     /// Creates a new supervisor, passes it through the specified
@@ -627,6 +1047,46 @@ impl Supervisor {
         children_ref
     }
 
+    /// Sets the name of the supervisor, used instead of its
+    /// identifier in its address and in the addresses of the
+    /// children groups and supervisors it supervises (see
+    /// [`Bastion::actor_at`]).
+    ///
+    /// Unlike its identifier, the supervisor's name is kept when
+    /// it is restarted.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the supervisor, which shouldn't
+    ///     contain any `/`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     // Its address will be "bastion://sys/payment"...
+    ///     sp.with_name("payment")
+    /// }).expect("Couldn't create the supervisor");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Bastion::actor_at`]: struct.Bastion.html#method.actor_at
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Self {
+        let name = name.into();
+        trace!("Supervisor({}): Setting name: {}", self.id(), name);
+        self.name = Some(name);
+        self
+    }
+
     /// Sets the strategy the supervisor should use when one
     /// of its supervised children groups or supervisors dies
     /// (in the case of a children group, it could be because one
@@ -771,7 +1231,180 @@ impl Supervisor {
         self
     }
 
-    async fn restart(&mut self, range: Range<usize>) {
+    /// Sets a custom strategy deciding what this supervisor does
+    /// with the supervised children groups and supervisors that
+    /// fault, instead of its restart strategy's restart policy
+    /// and intensity.
+    ///
+    /// Note that the supervision strategy set with
+    /// [`with_strategy`] is still used to know which children
+    /// groups and supervisors to restart.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The strategy deciding what to do with the
+    ///     children groups and supervisors that fault.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// #[derive(Debug)]
+    /// struct NeverRestart;
+    ///
+    /// impl Strategy for NeverRestart {
+    ///     fn decide(&mut self, _: &FaultInfo) -> Decision {
+    ///         Decision::Stop
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_custom_strategy(NeverRestart)
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`with_strategy`]: #method.with_strategy
+    pub fn with_custom_strategy<S: Strategy>(mut self, strategy: S) -> Self {
+        trace!(
+            "Supervisor({}): Setting custom strategy: {:?}",
+            self.id(),
+            strategy
+        );
+        self.custom_strategy = Some(Box::new(strategy));
+        self
+    }
+
+    /// Adds a callback that will get called every time one of
+    /// this supervisor's supervised children groups or supervisors
+    /// faults, once the supervisor decided what to do with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The closure taking the [`Fault`] describing
+    ///     the faulted children group or supervisor and the
+    ///     supervisor's decision.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_fault_callback(|fault: &Fault| {
+    ///         println!(
+    ///             "{} faulted after {} restarts: {:?}",
+    ///             fault.id(),
+    ///             fault.restarts(),
+    ///             fault.decision(),
+    ///         );
+    ///     })
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Fault`]: supervisor/struct.Fault.html
+    pub fn with_fault_callback<C>(mut self, callback: C) -> Self
+    where
+        C: Fn(&Fault) + Send + Sync + 'static,
+    {
+        trace!("Supervisor({}): Adding a fault callback.", self.id());
+        self.fault_callbacks.push(FaultCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Adds an element that will be sent a [`Fault`] every time
+    /// one of this supervisor's supervised children groups or
+    /// supervisors faults, once the supervisor decided what to do
+    /// with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscriber` - A reference to the element that will be
+    ///     sent the faults.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let alerts = Bastion::children(|children| {
+    ///     children.with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             msg! { ctx.recv().await?,
+    ///                 ref fault: Fault => {
+    ///                     println!("{} faulted.", fault.id());
+    ///                 };
+    ///                 _: _ => ();
+    ///             }
+    ///             # Ok(())
+    ///         }
+    ///     })
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// Bastion::supervisor(|sp| {
+    ///     sp.with_fault_subscriber(alerts.elems()[0].clone())
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Fault`]: supervisor/struct.Fault.html
+    pub fn with_fault_subscriber(mut self, subscriber: ChildRef) -> Self {
+        trace!(
+            "Supervisor({}): Adding fault subscriber: {:?}",
+            self.id(),
+            subscriber
+        );
+        self.fault_subscribers.push(subscriber);
+        self
+    }
+
+    // Calls the fault callbacks and notifies the fault
+    // subscribers.
+    fn notify_fault(&self, fault: Fault) {
+        debug!("Supervisor({}): Notifying fault: {:?}", self.id(), fault);
+        for callback in &self.fault_callbacks {
+            (callback.0)(&fault);
+        }
+
+        for subscriber in &self.fault_subscribers {
+            if subscriber.tell_anonymously(fault.clone()).is_err() {
+                warn!(
+                    "Supervisor({}): Couldn't notify ChildRef({}) of a fault.",
+                    self.id(),
+                    subscriber.id()
+                );
+            }
+        }
+    }
+
+    // Restarts the elements in `range`, removing those that can't
+    // be restarted anymore according to the restart policy unless
+    // `forced` is true (when the restart was asked for by a custom
+    // strategy or a half-open circuit).
+    async fn restart(&mut self, range: Range<usize>, forced: bool) {
         let mut tracked_actors = HashMap::new();
         for index in range.clone() {
             let bastion_id = self.order[index].clone();
@@ -791,7 +1424,9 @@ impl Supervisor {
         let supervisor_id = &self.id().clone();
         let parent = Parent::supervisor(self.as_ref());
         let mut reset = FuturesOrdered::new();
-        for id in self.order.drain(range) {
+        let mut removed = Vec::new();
+        for index in range {
+            let id = self.order[index].clone();
             let (killed, supervised) = if let Some(supervised) = self.stopped.remove(&id) {
                 (false, supervised)
             } else if let Some(supervised) = self.killed.remove(&id) {
@@ -800,6 +1435,24 @@ impl Supervisor {
                 // FIXME
                 unimplemented!();
             };
+            self.bcast.unregister(&id);
+
+            let actor_restarts_count = match tracked_actors.get(&id) {
+                Some(count) => *count + 1,
+                None => 1,
+            };
+
+            if !forced && !restart_strategy.allows_restart(actor_restarts_count) {
+                debug!(
+                    "Supervisor({}): Removing Supervised({}) after {} restarts.",
+                    self.id(),
+                    id,
+                    actor_restarts_count - 1
+                );
+                self.forget(&id);
+                removed.push(id);
+                continue;
+            }
 
             if killed {
                 supervised.callbacks().before_restart();
@@ -810,46 +1463,31 @@ impl Supervisor {
                 supervised.elem().clone().with_id(BastionId::new()),
             );
 
-            let actor_restarts_count = match tracked_actors.get(&id.clone()) {
-                Some(count) => *count + 1,
-                None => 1,
-            };
-
-            let restart_required = match restart_strategy.restart_policy() {
-                RestartPolicy::Always => true,
-                RestartPolicy::Never => false,
-                RestartPolicy::Tries(max_retries) => actor_restarts_count < max_retries,
-            };
-
-            if restart_required {
-                let restart_strategy_inner = restart_strategy.clone();
-
-                reset.push(async move {
-                    debug!(
-                        "Supervisor({}): Resetting Supervised({}) (killed={}) to Supervised({}).",
-                        supervisor_id,
-                        supervised.id(),
-                        killed,
-                        bcast.id()
-                    );
-
-                    let old_bastion_id = supervised.id().clone();
-                    restart_strategy_inner
-                        .apply_strategy(actor_restarts_count)
-                        .await;
+            let restart_strategy_inner = restart_strategy.clone();
+            reset.push(async move {
+                debug!(
+                    "Supervisor({}): Resetting Supervised({}) (killed={}) to Supervised({}).",
+                    supervisor_id,
+                    supervised.id(),
+                    killed,
+                    bcast.id()
+                );
 
-                    // FIXME: panics?
-                    let supervised = supervised.reset(bcast).await.unwrap();
-                    // FIXME: might not keep order
-                    if killed {
-                        supervised.callbacks().after_restart();
-                    } else {
-                        supervised.callbacks().before_start();
-                    }
+                restart_strategy_inner
+                    .apply_strategy(actor_restarts_count)
+                    .await;
+
+                // FIXME: panics?
+                let supervised = supervised.reset(bcast).await.unwrap();
+                // FIXME: might not keep order
+                if killed {
+                    supervised.callbacks().after_restart();
+                } else {
+                    supervised.callbacks().before_start();
+                }
 
-                    (old_bastion_id, supervised)
-                })
-            }
+                (index, actor_restarts_count, supervised)
+            })
         }
 
         trace!(
@@ -857,7 +1495,7 @@ impl Supervisor {
             self.id(),
             reset.len()
         );
-        while let Some((old_bastion_id, supervised)) = reset.next().await {
+        while let Some((index, restart_count, supervised)) = reset.next().await {
             self.bcast.register(supervised.bcast());
             if self.started {
                 let msg = BastionMessage::start();
@@ -872,61 +1510,183 @@ impl Supervisor {
                 supervised.id()
             );
             let id = supervised.id().clone();
-            let restart_count = match tracked_actors.get(&old_bastion_id) {
-                Some(count) => *count + 1,
-                None => 1,
-            };
+            self.refs.insert(id.clone(), supervised.as_ref());
             let launched = supervised.launch();
             self.launched
-                .insert(id.clone(), (self.order.len(), launched, restart_count));
-            self.order.push(id);
+                .insert(id.clone(), (index, launched, restart_count));
+            // The restarted element takes the place of the one it
+            // replaces.
+            let old_id = std::mem::replace(&mut self.order[index], id.clone());
+            self.refs.remove(&old_id);
+            if let Some(restarts) = self.restarts.remove(&old_id) {
+                self.restarts.insert(id.clone(), restarts);
+            }
+            if let Some(last_fault) = self.last_faults.remove(&old_id) {
+                self.last_faults.insert(id.clone(), last_fault);
+            }
+            if let Some(circuit) = self.circuits.remove(&old_id) {
+                self.circuits.insert(id, circuit);
+            }
+        }
+
+        if !removed.is_empty() {
+            self.order.retain(|id| !removed.contains(id));
+            self.reindex();
         }
     }
 
-    async fn stop(&mut self, range: Range<usize>) {
-        debug!("Supervisor({}): Stopping range: {:?}", self.id(), range);
-        if range.start == 0 {
-            self.bcast.stop_children();
-        } else {
-            // FIXME: panics
-            for id in self.order.get(range.clone()).unwrap() {
-                trace!("Supervised({}): Stopping Supervised({}).", self.id(), id);
-                self.bcast.stop_child(id);
+    // Updates the position of the launched elements after
+    // elements were removed from `order`.
+    fn reindex(&mut self) {
+        for (index, id) in self.order.iter().enumerate() {
+            if let Some((start, _, _)) = self.launched.get_mut(id) {
+                *start = index;
             }
         }
+    }
 
-        let mut supervised = FuturesOrdered::new();
+    // Removes the supervised element, which was stopped or
+    // killed, from the supervisor.
+    fn detach(&mut self, index: usize) {
+        let id = self.order.remove(index);
+        self.reindex();
+
+        self.bcast.unregister(&id);
+        self.forget(&id);
+        self.stopped.remove(&id);
+        self.killed.remove(&id);
+    }
+
+    // Removes what the supervisor tracks about the supervised
+    // element (its faults, circuit breaker and reference), once
+    // it was removed from the supervisor.
+    fn forget(&mut self, id: &BastionId) {
+        self.restarts.remove(id);
+        self.last_faults.remove(id);
+        self.circuits.remove(id);
+        self.refs.remove(id);
+    }
+
+    // Stops the supervised element and detaches it from the
+    // supervisor, which won't restart it anymore.
+    async fn prune(&mut self, id: BastionId) {
+        let index = match self.order.iter().position(|elem| elem == &id) {
+            Some(index) => index,
+            None => {
+                debug!(
+                    "Supervisor({}): Unknown Supervised({}) can't be pruned.",
+                    self.id(),
+                    id
+                );
+                return;
+            }
+        };
+
+        debug!("Supervisor({}): Pruning Supervised({}).", self.id(), id);
+        self.stop(index..index + 1).await;
+        self.detach(index);
+    }
+
+    async fn stop(&mut self, range: Range<usize>) {
+        debug!("Supervisor({}): Stopping range: {:?}", self.id(), range);
+        // The supervised elements are stopped one after the other,
+        // in the reverse order in which they were added, so that
+        // the ones depending on the previous ones stop first.
         // FIXME: panics?
-        for id in self.order.get(range.clone()).unwrap() {
+        let ids = self.order.get(range).unwrap().to_vec();
+        for id in ids.into_iter().rev() {
+            trace!("Supervisor({}): Stopping Supervised({}).", self.id(), id);
+            self.bcast.stop_child(&id);
+
             // TODO: Err if None?
             if let Some((_, launched, _)) = self.launched.remove(&id) {
-                // TODO: add a "stopped" list and poll from it instead of awaiting
-                supervised.push(launched);
+                match launched.await {
+                    Some(supervised) => {
+                        trace!(
+                            "Supervisor({}): Supervised({}) stopped.",
+                            self.id(),
+                            supervised.id()
+                        );
+                        supervised.callbacks().after_stop();
+
+                        let id = supervised.id().clone();
+                        self.stopped.insert(id, supervised);
+                    }
+                    // The element was cancelled while stopping (eg.
+                    // because the system is shutting down).
+                    None => {
+                        debug!(
+                            "Supervisor({}): Supervised({}) cancelled instead of stopped.",
+                            self.id(),
+                            id
+                        );
+                    }
+                }
             }
         }
+    }
 
-        while let Some(supervised) = supervised.next().await {
-            match supervised {
-                Some(supervised) => {
-                    trace!(
-                        "Supervisor({}): Supervised({}) stopped.",
-                        self.id(),
-                        supervised.id()
-                    );
-                    supervised.callbacks().after_stop();
+    // Stops the supervised elements like `stop` but tells them
+    // to kill what didn't stop before the deadline, returning
+    // whether everything stopped in time.
+    async fn stop_within(&mut self, range: Range<usize>, deadline: Duration) -> bool {
+        debug!(
+            "Supervisor({}): Stopping range within {:?}: {:?}",
+            self.id(),
+            deadline,
+            range
+        );
+        let deadline = Instant::now() + deadline;
+        let mut gracefully = true;
+        // FIXME: panics?
+        let ids = self.order.get(range).unwrap().to_vec();
+        for id in ids.into_iter().rev() {
+            trace!("Supervisor({}): Stopping Supervised({}).", self.id(), id);
+            // The elements stopped later get what remains of the
+            // time the previous ones didn't use.
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let (sender, shutdown) = Shutdown::new();
+            let msg = BastionMessage::stop_within(remaining, sender);
+            let env = Envelope::new(msg, self.bcast.path().clone(), self.bcast.sender().clone());
+            self.bcast.send_child(&id, env);
 
-                    let id = supervised.id().clone();
-                    self.stopped.insert(id, supervised);
+            // TODO: Err if None?
+            if let Some((_, launched, _)) = self.launched.remove(&id) {
+                match launched.await {
+                    Some(supervised) => {
+                        trace!(
+                            "Supervisor({}): Supervised({}) stopped.",
+                            self.id(),
+                            supervised.id()
+                        );
+                        supervised.callbacks().after_stop();
+
+                        let id = supervised.id().clone();
+                        self.stopped.insert(id, supervised);
+                    }
+                    // The element was cancelled while stopping (eg.
+                    // because the system is shutting down).
+                    None => {
+                        debug!(
+                            "Supervisor({}): Supervised({}) cancelled instead of stopped.",
+                            self.id(),
+                            id
+                        );
+                    }
+                }
+
+                if shutdown.await != Ok(true) {
+                    gracefully = false;
                 }
-                // FIXME
-                None => unimplemented!(),
             }
         }
+
+        gracefully
     }
 
     async fn kill(&mut self, range: Range<usize>) {
         debug!("Supervisor({}): Killing range: {:?}", self.id(), range);
-        if range.start == 0 {
+        if range.start == 0 && range.end == self.order.len() {
             self.bcast.kill_children();
         } else {
             // FIXME: panics
@@ -964,40 +1724,249 @@ impl Supervisor {
     }
 
     fn stopped(&mut self) {
-        debug!("Supervisor({}): Stopped.", self.id());
-        self.bcast.stopped();
+        self.terminated(TerminationReason::Normal);
+    }
+
+    fn faulted(&mut self, reason: FaultReason) {
+        self.terminated(TerminationReason::Fault(reason));
     }
 
-    fn faulted(&mut self) {
-        debug!("Supervisor({}): Faulted.", self.id());
-        self.bcast.faulted();
+    fn terminated(&mut self, reason: TerminationReason) {
+        debug!("Supervisor({}): Terminated: {:?}", self.id(), reason);
+        self.bcast.terminated(reason);
     }
 
-    async fn recover(&mut self, id: BastionId) -> Result<(), ()> {
+    // Records that the supervised element faulted, returning
+    // whether it exceeded the restart intensity (if any) on its
+    // own (the faults of the other elements aren't counted).
+    fn exceeds_intensity(&mut self, id: &BastionId) -> bool {
+        let (max_restarts, within) = match self.restart_strategy.restart_intensity() {
+            Some(intensity) => intensity,
+            None => return false,
+        };
+
+        let now = Instant::now();
+        let restarts = self.restarts.entry(id.clone()).or_default();
+        while let Some(restart) = restarts.front() {
+            if now.duration_since(*restart) <= within {
+                break;
+            }
+
+            restarts.pop_front();
+        }
+        restarts.push_back(now);
+
+        restarts.len() > max_restarts
+    }
+
+    // Returns whether the fault of the supervised element should
+    // open its circuit breaker (if any), either because it kept
+    // faulting or because it faulted again while half-open.
+    fn trips_circuit(&self, id: &BastionId, restarts: usize) -> bool {
+        match self.restart_strategy.circuit_breaker() {
+            Some((max_failures, _)) => self.circuits.contains_key(id) || restarts >= max_failures,
+            None => false,
+        }
+    }
+
+    // Kills the supervised element and keeps it stopped until
+    // the circuit breaker's cooldown elapses.
+    async fn open_circuit(&mut self, index: usize, id: BastionId) {
+        let cooldown = match self.restart_strategy.circuit_breaker() {
+            Some((_, cooldown)) => cooldown,
+            None => return,
+        };
+
+        warn!(
+            "Supervisor({}): Opening the circuit of Supervised({}) for {:?}.",
+            self.id(),
+            id,
+            cooldown
+        );
+        self.kill(index..index + 1).await;
+        self.circuits
+            .insert(id, (CircuitState::Open, Delay::new(cooldown)));
+    }
+
+    // Restarts the supervised elements whose open circuit's
+    // cooldown elapsed (making it half-open) and closes the
+    // half-open circuits whose element didn't fault again
+    // during the cooldown.
+    async fn check_circuits(&mut self) -> bool {
+        let mut elapsed = Vec::new();
+        for (id, (state, delay)) in self.circuits.iter_mut() {
+            if let Poll::Ready(()) = poll!(delay) {
+                elapsed.push((id.clone(), *state));
+            }
+        }
+
+        if elapsed.is_empty() {
+            return false;
+        }
+
+        let cooldown = self
+            .restart_strategy
+            .circuit_breaker()
+            .map(|(_, cooldown)| cooldown)
+            .unwrap_or_default();
+        for (id, state) in elapsed {
+            match state {
+                CircuitState::HalfOpen => {
+                    debug!(
+                        "Supervisor({}): Closing the circuit of Supervised({}).",
+                        self.id(),
+                        id
+                    );
+                    self.circuits.remove(&id);
+                    if let Some((_, _, restarts)) = self.launched.get_mut(&id) {
+                        *restarts = 0;
+                    }
+                }
+                CircuitState::Open => {
+                    // The reset delay needs to be polled to wake
+                    // the supervisor up once it elapses.
+                    if let Some((state, delay)) = self.circuits.get_mut(&id) {
+                        *state = CircuitState::HalfOpen;
+                        delay.reset(cooldown);
+                        let _ = poll!(delay);
+                    }
+                    // It might have been restarted along with
+                    // another element that faulted.
+                    if self.launched.contains_key(&id) {
+                        continue;
+                    }
+
+                    let index = match self.order.iter().position(|elem| elem == &id) {
+                        Some(index) => index,
+                        None => {
+                            self.circuits.remove(&id);
+                            continue;
+                        }
+                    };
+
+                    debug!(
+                        "Supervisor({}): Half-opening the circuit of Supervised({}).",
+                        self.id(),
+                        id
+                    );
+                    self.restart(index..index + 1, true).await;
+                }
+            }
+        }
+
+        true
+    }
+
+    async fn recover(&mut self, id: BastionId, reason: FaultReason) -> Result<(), ()> {
+        self.last_faults.insert(id.clone(), Instant::now());
+        let restarts = match self.launched.get(&id) {
+            Some((_, _, restarts)) => *restarts,
+            None => 0,
+        };
+        let info = FaultInfo {
+            id: id.clone(),
+            restarts,
+            reason,
+        };
+
+        let mut opens_circuit = false;
+        let decision = if let Some(strategy) = &mut self.custom_strategy {
+            strategy.decide(&info)
+        } else if self.exceeds_intensity(&id) {
+            Decision::Escalate
+        } else if self.trips_circuit(&id, restarts) {
+            // The element will be restarted once the cooldown
+            // elapsed.
+            opens_circuit = true;
+            Decision::Restart
+        } else if self.restart_strategy.allows_restart(restarts + 1) {
+            Decision::Restart
+        } else {
+            Decision::Stop
+        };
+
+        // Temporary children groups are never restarted.
+        let decision = match (decision, self.refs.get(&id)) {
+            (Decision::Restart, Some(SupervisedRef::Children(children_ref)))
+                if children_ref.restart_policy() == GroupRestartPolicy::Temporary =>
+            {
+                opens_circuit = false;
+                Decision::Stop
+            }
+            (decision, _) => decision,
+        };
+
+        self.notify_fault(Fault { info, decision });
+
+        let (index, _, _) = self.launched.get(&id).ok_or(())?;
+        let index = *index;
+        match decision {
+            Decision::Restart if opens_circuit => {
+                self.open_circuit(index, id).await;
+
+                return Ok(());
+            }
+            Decision::Restart => (),
+            Decision::Stop => {
+                debug!(
+                    "Supervisor({}): Not restarting Supervised({}) anymore.",
+                    self.id(),
+                    id
+                );
+                self.kill(index..index + 1).await;
+                self.detach(index);
+
+                return Ok(());
+            }
+            Decision::Escalate => {
+                warn!(
+                    "Supervisor({}): Escalating the fault of Supervised({}).",
+                    self.id(),
+                    id
+                );
+                return Err(());
+            }
+            Decision::Resume => {
+                debug!(
+                    "Supervisor({}): Ignoring the fault of Supervised({}).",
+                    self.id(),
+                    id
+                );
+                self.stop(index..index + 1).await;
+                self.bcast.unregister(&id);
+
+                return Ok(());
+            }
+        }
+
+        // Children groups can override the supervisor's strategy.
+        let strategy = match self.refs.get(&id) {
+            Some(SupervisedRef::Children(children_ref)) => children_ref.overridden_strategy(),
+            _ => None,
+        }
+        .unwrap_or_else(|| self.strategy.clone());
+
         debug!(
             "Supervisor({}): Recovering using strategy: {:?}",
             self.id(),
-            self.strategy
+            strategy
         );
-        match self.strategy {
+        // Custom strategies decide on their own whether the
+        // element can still be restarted.
+        let forced = self.custom_strategy.is_some();
+        match strategy {
             SupervisionStrategy::OneForOne => {
-                let (start, _, _) = self.launched.get(&id).ok_or(())?;
-                let start = *start;
-
-                self.restart(start..start + 1).await;
+                self.restart(index..index + 1, forced).await;
             }
             SupervisionStrategy::OneForAll => {
-                self.restart(0..self.order.len()).await;
+                self.restart(0..self.order.len(), forced).await;
 
                 // TODO: should be empty
                 self.stopped.shrink_to_fit();
                 self.killed.shrink_to_fit();
             }
             SupervisionStrategy::RestForOne => {
-                let (start, _, _) = self.launched.get(&id).ok_or(())?;
-                let start = *start;
-
-                self.restart(start..self.order.len()).await;
+                self.restart(index..self.order.len(), forced).await;
             }
         }
 
@@ -1024,7 +1993,7 @@ impl Supervisor {
                 ..
             } => {
                 self.kill(0..self.order.len()).await;
-                self.stopped();
+                self.terminated(TerminationReason::Killed);
 
                 return Err(());
             }
@@ -1067,16 +2036,26 @@ impl Supervisor {
                     supervised.id()
                 );
                 let id = supervised.id().clone();
+                self.refs.insert(id.clone(), supervised.as_ref());
                 let launched = supervised.launch();
                 self.launched
                     .insert(id.clone(), (self.order.len(), launched, 0));
                 self.order.push(id);
             }
-            // FIXME
             Envelope {
-                msg: BastionMessage::Prune { .. },
+                msg: BastionMessage::Prune { id },
+                ..
+            } => self.prune(id).await,
+            Envelope {
+                msg: BastionMessage::StopWithin { deadline, sender },
                 ..
-            } => unimplemented!(),
+            } => {
+                let gracefully = self.stop_within(0..self.order.len(), deadline).await;
+                self.stopped();
+                sender.send(gracefully);
+
+                return Err(());
+            }
             Envelope {
                 msg: BastionMessage::SuperviseWith(strategy),
                 ..
@@ -1088,6 +2067,124 @@ impl Supervisor {
                 );
                 self.strategy = strategy;
             }
+            // Only elements can be watched.
+            Envelope {
+                msg: BastionMessage::Watch { watcher },
+                ..
+            } => {
+                debug!(
+                    "Supervisor({}): Ignoring watch of Child({}).",
+                    self.id(),
+                    watcher.id()
+                );
+            }
+            Envelope {
+                msg: BastionMessage::Unwatch { watcher },
+                ..
+            } => {
+                debug!(
+                    "Supervisor({}): Ignoring unwatch of Child({}).",
+                    self.id(),
+                    watcher
+                );
+            }
+            // Only children groups can be scaled.
+            Envelope {
+                msg: BastionMessage::Scale(scaling),
+                ..
+            } => {
+                debug!("Supervisor({}): Ignoring scaling: {:?}", self.id(), scaling);
+            }
+            // Only children groups notify when they stopped.
+            Envelope {
+                msg: BastionMessage::NotifyStopped { .. },
+                ..
+            } => {
+                debug!("Supervisor({}): Ignoring stop notification.", self.id());
+            }
+            // Only elements notify when they exited.
+            Envelope {
+                msg: BastionMessage::NotifyExited { .. },
+                ..
+            } => {
+                debug!("Supervisor({}): Ignoring exit notification.", self.id());
+            }
+            // Only children groups track whether their elements
+            // are ready.
+            Envelope {
+                msg: BastionMessage::ElemReady { id },
+                ..
+            } => {
+                debug!(
+                    "Supervisor({}): Ignoring readiness of Child({}).",
+                    self.id(),
+                    id
+                );
+            }
+            Envelope {
+                msg: BastionMessage::NotifyReady { .. },
+                ..
+            } => {
+                debug!("Supervisor({}): Ignoring ready notification.", self.id());
+            }
+            // Only children groups can be paused.
+            Envelope {
+                msg: BastionMessage::Pause,
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => {
+                debug!("Supervisor({}): Ignoring pause or resume.", self.id());
+            }
+            // Only elements can be pinged.
+            Envelope {
+                msg: BastionMessage::Ping { .. },
+                ..
+            } => {
+                debug!("Supervisor({}): Ignoring ping.", self.id());
+            }
+            // Only elements are prepared to stop.
+            Envelope {
+                msg: BastionMessage::PrepareStop { .. },
+                ..
+            } => {
+                debug!("Supervisor({}): Ignoring stop preparation.", self.id());
+            }
+            // Only children groups deliver messages to one of their
+            // elements.
+            Envelope {
+                msg: BastionMessage::SendOne(msg),
+                ..
+            } => {
+                debug!(
+                    "Supervisor({}): Ignoring anycast message: {:?}",
+                    self.id(),
+                    msg
+                );
+            }
+            Envelope {
+                msg: BastionMessage::SendSiblings { msg, .. },
+                ..
+            } => {
+                debug!(
+                    "Supervisor({}): Ignoring siblings message: {:?}",
+                    self.id(),
+                    msg
+                );
+            }
+            // Only children groups restart their elements.
+            Envelope {
+                msg: BastionMessage::RestartElem { id },
+                ..
+            } => {
+                debug!(
+                    "Supervisor({}): Ignoring restart of Child({}).",
+                    self.id(),
+                    id
+                );
+            }
             Envelope {
                 msg: BastionMessage::Message(ref message),
                 ..
@@ -1100,12 +2197,51 @@ impl Supervisor {
                 self.bcast.send_children(env);
             }
             Envelope {
-                msg: BastionMessage::Stopped { id },
+                msg:
+                    BastionMessage::Termination {
+                        id,
+                        reason: TerminationReason::Fault(reason),
+                    },
+                ..
+            } => {
+                // The element might have already been restarted
+                // (eg. when using "one-for-all" and several
+                // elements faulted at the same time).
+                if !self.launched.contains_key(&id) {
+                    debug!(
+                        "Supervisor({}): Unknown Supervised({}) faulted.",
+                        self.id(),
+                        id
+                    );
+                    return Ok(());
+                }
+
+                warn!(
+                    "Supervisor({}): Supervised({}) faulted: {:?}",
+                    self.id(),
+                    id,
+                    reason
+                );
+                if self.recover(id, reason.clone()).await.is_err() {
+                    // TODO: stop or kill?
+                    self.kill(0..self.order.len()).await;
+                    self.faulted(FaultReason::Escalated(Box::new(reason)));
+
+                    return Err(());
+                }
+            }
+            Envelope {
+                msg: BastionMessage::Termination { id, reason },
                 ..
             } => {
                 // FIXME: Err if None?
                 if let Some((_, launched, _)) = self.launched.remove(&id) {
-                    debug!("Supervisor({}): Supervised({}) stopped.", self.id(), id);
+                    debug!(
+                        "Supervisor({}): Supervised({}) stopped: {:?}",
+                        self.id(),
+                        id,
+                        reason
+                    );
                     // TODO: add a "waiting" list an poll from it instead of awaiting
                     // FIXME: panics?
                     let supervised = launched.await.unwrap();
@@ -1115,22 +2251,6 @@ impl Supervisor {
                     self.stopped.insert(id, supervised);
                 }
             }
-            Envelope {
-                msg: BastionMessage::Faulted { id },
-                ..
-            } => {
-                if self.launched.contains_key(&id) {
-                    warn!("Supervisor({}): Supervised({}) faulted.", self.id(), id);
-                }
-
-                if self.recover(id).await.is_err() {
-                    // TODO: stop or kill?
-                    self.kill(0..self.order.len()).await;
-                    self.faulted();
-
-                    return Err(());
-                }
-            }
         }
 
         Ok(())
@@ -1139,6 +2259,10 @@ impl Supervisor {
     async fn run(mut self) -> Self {
         debug!("Supervisor({}): Launched.", self.id());
         loop {
+            if self.started && self.check_circuits().await {
+                self.refresh_shared();
+            }
+
             match poll!(&mut self.bcast.next()) {
                 // TODO: Err if started == true?
                 Poll::Ready(Some(Envelope {
@@ -1171,6 +2295,21 @@ impl Supervisor {
                             return self;
                         }
                     }
+
+                    self.refresh_shared();
+                }
+                // The strategy is changed right away so that it is
+                // used even if an element faults while starting.
+                Poll::Ready(Some(Envelope {
+                    msg: BastionMessage::SuperviseWith(strategy),
+                    ..
+                })) => {
+                    debug!(
+                        "Supervisor({}): Setting strategy: {:?}",
+                        self.id(),
+                        strategy
+                    );
+                    self.strategy = strategy;
                 }
                 Poll::Ready(Some(msg)) if !self.started => {
                     trace!(
@@ -1189,6 +2328,8 @@ impl Supervisor {
                     if self.handle(msg).await.is_err() {
                         return self;
                     }
+
+                    self.refresh_shared();
                 }
                 // NOTE: because `Broadcast` always holds both a `Sender` and
                 //      `Receiver` of the same channel, this would only be
@@ -1207,8 +2348,22 @@ impl Supervisor {
 }
 
 impl SupervisorRef {
-    pub(crate) fn new(id: BastionId, sender: Sender, path: Arc<BastionPath>) -> Self {
-        SupervisorRef { id, sender, path }
+    pub(crate) fn new(
+        id: BastionId,
+        name: Option<String>,
+        sender: Sender,
+        path: Arc<BastionPath>,
+        stats: Arc<RwLock<Vec<SupervisedStats>>>,
+        supervised: Arc<RwLock<Vec<SupervisedRef>>>,
+    ) -> Self {
+        SupervisorRef {
+            id,
+            name,
+            sender,
+            path,
+            stats,
+            supervised,
+        }
     }
 
     /// Returns the identifier of the supervisor this `SupervisorRef`
@@ -1241,6 +2396,145 @@ impl SupervisorRef {
         &self.id
     }
 
+    /// Returns the name of the supervisor this `SupervisorRef` is
+    /// referencing, if it was given one with
+    /// [`Supervisor::with_name`].
+    ///
+    /// [`Supervisor::with_name`]: supervisor/struct.Supervisor.html#method.with_name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(String::as_str)
+    }
+
+    /// Returns statistics about the children groups and
+    /// supervisors supervised by the supervisor this
+    /// `SupervisorRef` is referencing, in the order they were
+    /// added to it.
+    ///
+    /// Note that the statistics are updated every time the
+    /// supervisor handles a message, and are thus empty until
+    /// it started.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let supervisor_ref = Bastion::supervisor(|sp| {
+    ///     // ...
+    ///     # sp
+    /// }).expect("Couldn't create the supervisor.");
+    ///
+    /// for stats in supervisor_ref.stats() {
+    ///     println!("{} restarted {} times", stats.id(), stats.restarts());
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn stats(&self) -> Vec<SupervisedStats> {
+        // FIXME: panics?
+        self.stats.read().unwrap().clone()
+    }
+
+    /// Returns [`ChildrenRef`]s referencing the children groups
+    /// supervised by the supervisor this `SupervisorRef` is
+    /// referencing, in the order they were added to it.
+    ///
+    /// Note that, like [`stats`], the returned references are
+    /// updated every time the supervisor handles a message, and
+    /// that each of them is a snapshot of its children group at
+    /// the time it was launched.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let supervisor_ref = Bastion::supervisor(|sp| {
+    ///     // ...
+    ///     # sp
+    /// }).expect("Couldn't create the supervisor.");
+    ///
+    /// for children_ref in supervisor_ref.children_groups() {
+    ///     children_ref.broadcast("Hello!").ok();
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`ChildrenRef`]: children/struct.ChildrenRef.html
+    /// [`stats`]: #method.stats
+    pub fn children_groups(&self) -> Vec<ChildrenRef> {
+        // FIXME: panics?
+        self.supervised
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|supervised| match supervised {
+                SupervisedRef::Children(children_ref) => Some(children_ref.clone()),
+                SupervisedRef::Supervisor(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns [`SupervisorRef`]s referencing the supervisors
+    /// supervised by the supervisor this `SupervisorRef` is
+    /// referencing, in the order they were added to it.
+    ///
+    /// Note that, like [`stats`], the returned references are
+    /// updated every time the supervisor handles a message.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let supervisor_ref = Bastion::supervisor(|sp| {
+    ///     // ...
+    ///     # sp
+    /// }).expect("Couldn't create the supervisor.");
+    ///
+    /// // Reaches all the children groups of the subtree...
+    /// let mut children_groups = supervisor_ref.children_groups();
+    /// for sp_ref in supervisor_ref.supervisors() {
+    ///     children_groups.extend(sp_ref.children_groups());
+    /// }
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SupervisorRef`]: supervisor/struct.SupervisorRef.html
+    /// [`stats`]: #method.stats
+    pub fn supervisors(&self) -> Vec<SupervisorRef> {
+        // FIXME: panics?
+        self.supervised
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|supervised| match supervised {
+                SupervisedRef::Supervisor(supervisor_ref) => Some(supervisor_ref.clone()),
+                SupervisedRef::Children(_) => None,
+            })
+            .collect()
+    }
+
     /// Creates a new [`Supervisor`], passes it through the specified
     /// `init` closure and then sends it to the supervisor this
     /// `SupervisorRef` is referencing to supervise it.
@@ -1371,8 +2665,68 @@ impl SupervisorRef {
             bcast.id()
         );
         let children = Children::new(bcast);
-        let mut children = init(children);
+        let children = init(children);
         debug!("Children({}): Initialized.", children.id());
+
+        self.deploy_children(children)
+    }
+
+    /// Sends a children group created using [`Children::builder`]
+    /// and configured by chaining its `with_*` methods to the
+    /// supervisor this `SupervisorRef` is referencing, for it to
+    /// start supervising it.
+    ///
+    /// This method returns a [`ChildrenRef`] referencing the
+    /// children group if it succeeded, or `Err(())` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `children` - The configured children group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let sp_ref = Bastion::supervisor(|sp| sp).unwrap();
+    /// let children = Children::builder()
+    ///     .with_redundancy(4)
+    ///     .with_exec(|ctx: BastionContext| {
+    ///         async move {
+    ///             // Send and receive messages...
+    ///             let opt_msg: Option<SignedMessage> = ctx.try_recv().await;
+    ///             // ...and return `Ok(())` or `Err(())` when you are done...
+    ///             Ok(())
+    ///         }
+    ///     });
+    ///
+    /// let children_ref: ChildrenRef = sp_ref
+    ///     .children_from(children)
+    ///     .expect("Couldn't create the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`Children::builder`]: children/struct.Children.html#method.builder
+    /// [`ChildrenRef`]: children/struct.ChildrenRef.html
+    pub fn children_from(&self, mut children: Children) -> Result<ChildrenRef, ()> {
+        debug!(
+            "SupervisorRef({}): Adopting Children({}).",
+            self.id(),
+            children.id()
+        );
+        children.reparent(Parent::supervisor(self.clone()));
+
+        self.deploy_children(children)
+    }
+
+    fn deploy_children(&self, mut children: Children) -> Result<ChildrenRef, ()> {
         // FIXME: children group elems launched without the group itself being launched
         children.launch_elems();
 
@@ -1436,17 +2790,59 @@ impl SupervisorRef {
     ///     # Bastion::block_until_stopped();
     /// # }
     /// ```
-    ///
-    /// [`SupervisionStrategy::OneForOne`]: supervisor/enum.SupervisionStrategy.html#variant.OneForOne
-    /// [`SupervisionStrategy::OneForAll`]: supervisor/enum.SupervisionStrategy.html#variant.OneForAll
-    /// [`SupervisionStrategy::RestForOne`]: supervisor/enum.SupervisionStrategy.html#variant.RestForOne
-    pub fn strategy(&self, strategy: SupervisionStrategy) -> Result<(), ()> {
-        debug!(
-            "SupervisorRef({}): Setting strategy: {:?}",
-            self.id(),
-            strategy
-        );
-        let msg = BastionMessage::supervise_with(strategy);
+    ///
+    /// [`SupervisionStrategy::OneForOne`]: supervisor/enum.SupervisionStrategy.html#variant.OneForOne
+    /// [`SupervisionStrategy::OneForAll`]: supervisor/enum.SupervisionStrategy.html#variant.OneForAll
+    /// [`SupervisionStrategy::RestForOne`]: supervisor/enum.SupervisionStrategy.html#variant.RestForOne
+    pub fn strategy(&self, strategy: SupervisionStrategy) -> Result<(), ()> {
+        debug!(
+            "SupervisorRef({}): Setting strategy: {:?}",
+            self.id(),
+            strategy
+        );
+        let msg = BastionMessage::supervise_with(strategy);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())
+    }
+
+    /// Sends a message to the supervisor this `SupervisorRef` is
+    /// referencing to tell it to stop one of its supervised
+    /// children groups or supervisors and to stop supervising
+    /// it (it won't be restarted anymore, even if the supervision
+    /// strategy requires it).
+    ///
+    /// This method returns `()` if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the supervised children group or
+    ///     supervisor to stop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bastion::prelude::*;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// let sp_ref = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    /// let children_ref = sp_ref.children(|children| {
+    ///     // ...
+    ///     # children
+    /// }).expect("Couldn't create the children group.");
+    ///
+    /// sp_ref.prune(children_ref.id()).expect("Couldn't prune the children group.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn prune(&self, id: &BastionId) -> Result<(), ()> {
+        debug!("SupervisorRef({}): Pruning Supervised({}).", self.id(), id);
+        let msg = BastionMessage::prune(id.clone());
         let env = Envelope::from_dead_letters(msg);
         self.send(env).map_err(|_| ())
     }
@@ -1515,6 +2911,10 @@ impl SupervisorRef {
     /// is referencing to tell it to stop every running children
     /// groups and supervisors that it is supervising.
     ///
+    /// They are stopped one after the other, in the reverse order
+    /// in which they were added to the supervisor, each of them
+    /// being stopped only once the previous one stopped.
+    ///
     /// This method returns `()` if it succeeded, or `Err(())`
     /// otherwise.
     ///
@@ -1541,6 +2941,65 @@ impl SupervisorRef {
         self.send(env).map_err(|_| ())
     }
 
+    /// Sends a message to the supervisor this `SupervisorRef`
+    /// is referencing to tell it to stop every running children
+    /// groups and supervisors that it is supervising, killing
+    /// those which didn't stop once the deadline passed.
+    ///
+    /// Like with [`stop`], they are stopped one after the other,
+    /// in the reverse order in which they were added to the
+    /// supervisor, sharing the deadline.
+    ///
+    /// This method returns a [`Shutdown`] resolving once the
+    /// supervisor stopped if it succeeded, or `Err(())`
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - How long to wait for the supervised elements
+    ///     to stop before killing them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    ///     # let sp_ref = Bastion::supervisor(|sp| sp).unwrap();
+    ///     # Bastion::start();
+    /// let shutdown: Shutdown = sp_ref
+    ///     .stop_with_deadline(Duration::from_secs(5))
+    ///     .expect("Couldn't send the message.");
+    ///
+    /// // Waiting for it to stop...
+    /// if let Ok(false) = run!(shutdown) {
+    ///     // Some elements had to be killed...
+    /// }
+    ///     #
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`stop`]: #method.stop
+    /// [`Shutdown`]: ../message/struct.Shutdown.html
+    pub fn stop_with_deadline(&self, deadline: Duration) -> Result<Shutdown, ()> {
+        debug!(
+            "SupervisorRef({}): Stopping within {:?}.",
+            self.id(),
+            deadline
+        );
+        let (sender, shutdown) = Shutdown::new();
+        let msg = BastionMessage::stop_within(deadline, sender);
+        let env = Envelope::from_dead_letters(msg);
+        self.send(env).map_err(|_| ())?;
+
+        Ok(shutdown)
+    }
+
     /// Sends a message to the supervisor this `SupervisorRef`
     /// is referencing to tell it to kill every running children
     /// groups and supervisors that it is supervising.
@@ -1573,9 +3032,7 @@ impl SupervisorRef {
 
     pub(crate) fn send(&self, env: Envelope) -> Result<(), Envelope> {
         trace!("SupervisorRef({}): Sending message: {:?}", self.id(), env);
-        self.sender
-            .unbounded_send(env)
-            .map_err(|err| err.into_inner())
+        self.sender.try_send(env)
     }
 
     pub(crate) fn path(&self) -> &Arc<BastionPath> {
@@ -1637,6 +3094,13 @@ impl Supervised {
         }
     }
 
+    fn as_ref(&self) -> SupervisedRef {
+        match self {
+            Supervised::Supervisor(supervisor) => SupervisedRef::Supervisor(supervisor.as_ref()),
+            Supervised::Children(children) => SupervisedRef::Children(children.as_ref()),
+        }
+    }
+
     pub(crate) fn elem(&self) -> &BastionPathElement {
         match self {
             // FIXME
@@ -1730,6 +3194,9 @@ impl RestartStrategy {
         RestartStrategy {
             restart_policy,
             strategy,
+            intensity: None,
+            jitter: None,
+            circuit_breaker: None,
         }
     }
 
@@ -1744,6 +3211,25 @@ impl RestartStrategy {
         self.strategy.clone()
     }
 
+    /// Returns the maximum amount of times a failed actor can be
+    /// restarted within a duration, if any.
+    pub fn restart_intensity(&self) -> Option<(usize, Duration)> {
+        self.intensity
+    }
+
+    /// Returns the maximum random delay added before restarting a
+    /// failed actor, if any.
+    pub fn restart_jitter(&self) -> Option<Duration> {
+        self.jitter
+    }
+
+    /// Returns the amount of restarts after which a failed actor
+    /// isn't restarted until a cooldown elapses and the cooldown,
+    /// if any.
+    pub fn circuit_breaker(&self) -> Option<(usize, Duration)> {
+        self.circuit_breaker
+    }
+
     /// Sets the limit of attempts for restoring failed actors.
     pub fn with_restart_policy(mut self, restart_policy: RestartPolicy) -> Self {
         self.restart_policy = restart_policy;
@@ -1758,11 +3244,167 @@ impl RestartStrategy {
         self
     }
 
-    pub(crate) async fn apply_strategy(&self, restarts_count: usize) {
+    /// Sets the maximum amount of times a failed actor can be
+    /// restarted within a duration.
+    ///
+    /// If an actor fails more than `max_restarts` times within
+    /// `within`, the supervisor stops restarting it and faults
+    /// itself, escalating the failure to its own supervisor.
+    ///
+    /// The restarts are counted separately for every children
+    /// group or supervisor of the supervisor (and not for the
+    /// supervisor as a whole), so that only an actor failing
+    /// repeatedly escalates its failure. When using
+    /// [`SupervisionStrategy::OneForAll`] or
+    /// [`SupervisionStrategy::RestForOne`], only the failure
+    /// of the actor that faulted is counted, not the restarts of
+    /// the other actors it caused.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_restarts` - The maximum amount of restarts within
+    ///     the duration.
+    /// * `within` - The duration during which restarts are counted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     // At most 3 restarts of the same actor every 5 seconds...
+    ///     sp.with_restart_strategy(
+    ///         RestartStrategy::default()
+    ///             .with_restart_intensity(3, Duration::from_secs(5))
+    ///     )
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SupervisionStrategy::OneForAll`]: supervisor/enum.SupervisionStrategy.html#variant.OneForAll
+    /// [`SupervisionStrategy::RestForOne`]: supervisor/enum.SupervisionStrategy.html#variant.RestForOne
+    pub fn with_restart_intensity(mut self, max_restarts: usize, within: Duration) -> Self {
+        self.intensity = Some((max_restarts, within));
+        self
+    }
+
+    /// Sets the maximum random delay added before restarting a
+    /// failed actor (on top of the delay of the actor restart
+    /// strategy), so that actors failing at the same time (eg.
+    /// because of the outage of a dependency) don't all restart
+    /// at once and overload it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum delay added before each restart, a
+    ///     random delay between zero and it being picked every
+    ///     time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     // Restarts are spread over half a second...
+    ///     sp.with_restart_strategy(
+    ///         RestartStrategy::default()
+    ///             .with_restart_jitter(Duration::from_millis(500))
+    ///     )
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    pub fn with_restart_jitter(mut self, max: Duration) -> Self {
+        self.jitter = Some(max);
+        self
+    }
+
+    /// Sets a circuit breaker for failed actors: instead of
+    /// restarting an actor that keeps failing forever or giving
+    /// up on it, the supervisor stops restarting it for a cooldown
+    /// period, during which its statistics report it as
+    /// [`SupervisedState::Open`] and the messages sent to its
+    /// elements are rejected.
+    ///
+    /// Once the cooldown elapsed, the actor is restarted once
+    /// more. If it fails again before another cooldown elapses,
+    /// the circuit is opened again; otherwise it is closed and the
+    /// actor's restarts count is reset.
+    ///
+    /// This is only used when the supervisor doesn't use a
+    /// custom [`Strategy`], and takes precedence over the restart
+    /// policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_failures` - The amount of restarts after which the
+    ///     circuit is opened the next time the actor fails.
+    /// * `cooldown` - How long the circuit stays open (and
+    ///     half-open).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    ///     # Bastion::init();
+    ///     #
+    /// Bastion::supervisor(|sp| {
+    ///     // After 3 restarts, wait 10 seconds before trying again...
+    ///     sp.with_restart_strategy(
+    ///         RestartStrategy::default()
+    ///             .with_circuit_breaker(3, Duration::from_secs(10))
+    ///     )
+    /// }).expect("Couldn't create the supervisor.");
+    ///     #
+    ///     # Bastion::start();
+    ///     # Bastion::stop();
+    ///     # Bastion::block_until_stopped();
+    /// # }
+    /// ```
+    ///
+    /// [`SupervisedState::Open`]: supervisor/enum.SupervisedState.html#variant.Open
+    /// [`Strategy`]: supervisor/trait.Strategy.html
+    pub fn with_circuit_breaker(mut self, max_failures: usize, cooldown: Duration) -> Self {
+        self.circuit_breaker = Some((max_failures, cooldown));
+        self
+    }
+
+    // Returns whether the restart policy allows an actor to be
+    // restarted for the given time.
+    pub(crate) fn allows_restart(&self, restarts_count: usize) -> bool {
+        match self.restart_policy {
+            RestartPolicy::Always => true,
+            RestartPolicy::Never => false,
+            RestartPolicy::Tries(max_retries) => restarts_count < max_retries,
+        }
+    }
+
+    // Returns the delay to wait for before restarting an element
+    // for the given time (if any).
+    pub(crate) fn restart_delay(&self, restarts_count: usize) -> Option<Duration> {
         match self.strategy {
             ActorRestartStrategy::LinearBackOff { timeout } => {
                 let start_in = timeout.as_secs() + (timeout.as_secs() * restarts_count as u64);
-                Delay::new(Duration::from_secs(start_in)).await;
+                Some(Duration::from_secs(start_in))
             }
             ActorRestartStrategy::ExponentialBackOff {
                 timeout,
@@ -1770,10 +3412,239 @@ impl RestartStrategy {
             } => {
                 let start_in =
                     timeout.as_secs() + (timeout.as_secs() * multiplier * restarts_count as u64);
-                Delay::new(Duration::from_secs(start_in)).await;
+                Some(Duration::from_secs(start_in))
             }
-            _ => {}
+            _ => None,
+        }
+    }
+
+    // Returns a random delay between zero and the jitter.
+    fn random_jitter(&self) -> Duration {
+        let max = match self.jitter {
+            Some(max) => max.as_nanos() as u64,
+            None => return Duration::default(),
         };
+
+        if max == 0 {
+            return Duration::default();
+        }
+
+        // The identifiers are random, so this avoids depending
+        // on a random number generator.
+        let random = Uuid::new_v4().as_u128() as u64;
+        Duration::from_nanos(random % max)
+    }
+
+    pub(crate) async fn apply_strategy(&self, restarts_count: usize) {
+        let delay = self.restart_delay(restarts_count).unwrap_or_default() + self.random_jitter();
+        if delay > Duration::default() {
+            Delay::new(delay).await;
+        }
+    }
+}
+
+impl SupervisedStats {
+    /// Returns the identifier of the children group or supervisor.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns whether the children group or supervisor is
+    /// running, stopped or faulted.
+    pub fn state(&self) -> SupervisedState {
+        self.state
+    }
+
+    /// Returns how many times the children group or supervisor
+    /// was restarted.
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// Returns when the children group or supervisor last
+    /// faulted, if it ever did.
+    pub fn last_fault(&self) -> Option<Instant> {
+        self.last_fault
+    }
+
+    /// Returns the delay the supervisor will wait for before
+    /// restarting the children group or supervisor the next time
+    /// it faults, if its [`ActorRestartStrategy`] is a back off.
+    ///
+    /// [`ActorRestartStrategy`]: supervisor/enum.ActorRestartStrategy.html
+    pub fn backoff(&self) -> Option<Duration> {
+        self.backoff
+    }
+}
+
+impl Fault {
+    /// Returns the identifier of the children group or supervisor
+    /// that faulted.
+    pub fn id(&self) -> &BastionId {
+        self.info.id()
+    }
+
+    /// Returns how many times the children group or supervisor
+    /// was restarted before faulting.
+    pub fn restarts(&self) -> usize {
+        self.info.restarts()
+    }
+
+    /// Returns why the children group or supervisor faulted.
+    pub fn reason(&self) -> &FaultReason {
+        self.info.reason()
+    }
+
+    /// Returns whether the children group or supervisor faulted
+    /// because of a panic or of an error.
+    pub fn kind(&self) -> FaultKind {
+        self.info.kind()
+    }
+
+    /// Returns what the supervisor decided to do with the children
+    /// group or supervisor.
+    pub fn decision(&self) -> Decision {
+        self.decision
+    }
+}
+
+impl FaultInfo {
+    /// Returns the identifier of the children group or supervisor
+    /// that faulted.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns how many times the children group or supervisor
+    /// was restarted before faulting.
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// Returns why the children group or supervisor faulted.
+    pub fn reason(&self) -> &FaultReason {
+        &self.reason
+    }
+
+    /// Returns whether the children group or supervisor faulted
+    /// because of a panic or of an error.
+    pub fn kind(&self) -> FaultKind {
+        self.reason.kind()
+    }
+}
+
+impl ChildError {
+    pub(crate) fn new<E: Into<Box<dyn Error + Send + Sync>>>(err: E) -> Self {
+        ChildError(Arc::from(err.into()))
+    }
+
+    /// Returns the error returned by the element's future.
+    pub fn get(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.0
+    }
+
+    /// Returns the error returned by the element's future if it
+    /// is of type `E`.
+    pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
+        self.0.downcast_ref()
+    }
+}
+
+impl Debug for ChildError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, fmt)
+    }
+}
+
+impl Display for ChildError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.0, fmt)
+    }
+}
+
+impl PartialEq for ChildError {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl Eq for ChildError {}
+
+impl FaultReason {
+    /// Returns whether this fault was caused by a panic or by an
+    /// error, looking through the escalated faults.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let reason = FaultReason::Escalated(Box::new(FaultReason::Panic(None)));
+    /// assert_eq!(reason.kind(), FaultKind::Panic);
+    /// ```
+    pub fn kind(&self) -> FaultKind {
+        match self {
+            FaultReason::Error | FaultReason::Failure(_) => FaultKind::Error,
+            FaultReason::Panic(_) => FaultKind::Panic,
+            FaultReason::TooSlow | FaultReason::Timeout | FaultReason::Stopped => FaultKind::Other,
+            FaultReason::Escalated(reason) => reason.kind(),
+        }
+    }
+}
+
+impl TerminationReason {
+    /// Returns whether it faulted.
+    pub fn is_fault(&self) -> bool {
+        self.fault().is_some()
+    }
+
+    /// Returns why it faulted, or `None` if it didn't.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use bastion::prelude::*;
+    /// #
+    /// let reason = TerminationReason::Fault(FaultReason::Error);
+    /// assert_eq!(reason.fault(), Some(&FaultReason::Error));
+    /// assert_eq!(TerminationReason::Killed.fault(), None);
+    /// ```
+    pub fn fault(&self) -> Option<&FaultReason> {
+        match self {
+            TerminationReason::Fault(reason) => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the custom reason if it is of
+    /// type `M`, or `None` otherwise.
+    pub fn custom<M: Message>(&self) -> Option<&M> {
+        match self {
+            TerminationReason::Custom(msg) => msg.peek(),
+            _ => None,
+        }
+    }
+}
+
+impl Clone for TerminationReason {
+    fn clone(&self) -> Self {
+        match self {
+            TerminationReason::Normal => TerminationReason::Normal,
+            TerminationReason::Killed => TerminationReason::Killed,
+            TerminationReason::Fault(reason) => TerminationReason::Fault(reason.clone()),
+            // Custom reasons are always broadcasted messages,
+            // which can be cloned.
+            TerminationReason::Custom(msg) => match msg.try_clone() {
+                Some(msg) => TerminationReason::Custom(msg),
+                None => TerminationReason::Normal,
+            },
+        }
+    }
+}
+
+impl Debug for FaultCallback {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("FaultCallback").finish()
     }
 }
 
@@ -1788,6 +3659,9 @@ impl Default for RestartStrategy {
         RestartStrategy {
             restart_policy: RestartPolicy::Always,
             strategy: ActorRestartStrategy::default(),
+            intensity: None,
+            jitter: None,
+            circuit_breaker: None,
         }
     }
 }
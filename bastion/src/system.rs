@@ -4,7 +4,7 @@ use crate::context::{BastionContext, BastionId, NIL_ID};
 use crate::envelope::Envelope;
 use crate::message::{BastionMessage, Deployment};
 use crate::path::{BastionPath, BastionPathElement};
-use crate::supervisor::{Supervisor, SupervisorRef};
+use crate::supervisor::{Supervisor, SupervisorRef, TerminationReason};
 use bastion_executor::pool;
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
@@ -13,7 +13,7 @@ use fxhash::{FxHashMap, FxHashSet};
 use lazy_static::lazy_static;
 use lightproc::prelude::*;
 use qutex::Qutex;
-use std::sync::{Arc, Condvar, Mutex};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::task::Poll;
 
 lazy_static! {
@@ -24,9 +24,12 @@ pub(crate) struct GlobalSystem {
     sender: Sender,
     supervisor: SupervisorRef,
     dead_letters: ChildrenRef,
+    // The supervisors launched by the system, shared with it.
+    supervisors: Arc<RwLock<Vec<SupervisorRef>>>,
     path: Arc<BastionPath>,
     handle: Qutex<Option<RecoverableHandle<()>>>,
-    running: Mutex<bool>,
+    // Why the system terminated, or `None` while it is running.
+    termination: Mutex<Option<TerminationReason>>,
     stopping_cvar: Condvar,
 }
 
@@ -34,6 +37,9 @@ pub(crate) struct GlobalSystem {
 struct System {
     bcast: Broadcast,
     launched: FxHashMap<BastionId, RecoverableHandle<Supervisor>>,
+    // The references to the launched supervisors, shared with
+    // the `GlobalSystem`.
+    supervisors: Arc<RwLock<Vec<SupervisorRef>>>,
     // TODO: set limit
     restart: FxHashSet<BastionId>,
     waiting: FuturesUnordered<RecoverableHandle<Supervisor>>,
@@ -46,21 +52,23 @@ impl GlobalSystem {
         sender: Sender,
         supervisor: SupervisorRef,
         dead_letters: ChildrenRef,
+        supervisors: Arc<RwLock<Vec<SupervisorRef>>>,
         handle: RecoverableHandle<()>,
     ) -> Self {
         let handle = Some(handle);
         let handle = Qutex::new(handle);
         let path = Arc::new(BastionPath::root());
-        let running = Mutex::new(true);
+        let termination = Mutex::new(None);
         let stopping_cvar = Condvar::new();
 
         GlobalSystem {
             sender,
             supervisor,
             dead_letters,
+            supervisors,
             path,
             handle,
-            running,
+            termination,
             stopping_cvar,
         }
     }
@@ -77,6 +85,11 @@ impl GlobalSystem {
         &self.dead_letters
     }
 
+    pub(crate) fn supervisors(&self) -> Vec<SupervisorRef> {
+        // FIXME: panics?
+        self.supervisors.read().unwrap().clone()
+    }
+
     pub(crate) fn handle(&self) -> Qutex<Option<RecoverableHandle<()>>> {
         self.handle.clone()
     }
@@ -85,17 +98,27 @@ impl GlobalSystem {
         &self.path
     }
 
-    pub(crate) fn notify_stopped(&self) {
+    pub(crate) fn notify_stopped(&self, reason: TerminationReason) {
+        info!("System: Terminated: {:?}", reason);
         // FIXME: panics
-        *self.running.lock().unwrap() = false;
+        let mut termination = self.termination.lock().unwrap();
+        // The system might have stopped before being killed.
+        if termination.is_none() {
+            *termination = Some(reason);
+        }
+
         self.stopping_cvar.notify_all();
     }
 
-    pub(crate) fn wait_until_stopped(&self) {
+    pub(crate) fn wait_until_stopped(&self) -> TerminationReason {
         // FIXME: panics
-        let mut running = self.running.lock().unwrap();
-        while *running {
-            running = self.stopping_cvar.wait(running).unwrap();
+        let mut termination = self.termination.lock().unwrap();
+        loop {
+            if let Some(reason) = &*termination {
+                return reason.clone();
+            }
+
+            termination = self.stopping_cvar.wait(termination).unwrap();
         }
     }
 }
@@ -106,6 +129,7 @@ impl System {
         let parent = Parent::none();
         let bcast = Broadcast::new_root(parent);
         let launched = FxHashMap::default();
+        let supervisors = Arc::new(RwLock::new(Vec::new()));
         let restart = FxHashSet::default();
         let waiting = FuturesUnordered::new();
         let pre_start_msgs = Vec::new();
@@ -116,6 +140,7 @@ impl System {
         let system = System {
             bcast,
             launched,
+            supervisors: supervisors.clone(),
             restart,
             waiting,
             pre_start_msgs,
@@ -144,7 +169,13 @@ impl System {
         let dead_letters_ref =
             Self::spawn_dead_letters(&supervisor_ref).expect("Can't spawn dead letters");
 
-        GlobalSystem::new(sender, supervisor_ref, dead_letters_ref, handle)
+        GlobalSystem::new(
+            sender,
+            supervisor_ref,
+            dead_letters_ref,
+            supervisors,
+            handle,
+        )
     }
 
     fn stack(&self) -> ProcStack {
@@ -154,12 +185,14 @@ impl System {
 
     fn spawn_dead_letters(root_sv: &SupervisorRef) -> Result<ChildrenRef, ()> {
         root_sv.children_with_id(NIL_ID, |children| {
-            children.with_exec(|ctx: BastionContext| async move {
-                loop {
-                    let smsg = ctx.recv().await?;
-                    debug!("Received dead letter: {:?}", smsg);
-                }
-            })
+            children
+                .with_name("dead_letters")
+                .with_exec(|ctx: BastionContext| async move {
+                    loop {
+                        let smsg = ctx.recv().await?;
+                        debug!("Received dead letter: {:?}", smsg);
+                    }
+                })
         })
     }
 
@@ -185,14 +218,30 @@ impl System {
 
         info!("System: Launching Supervisor({}).", supervisor.id());
         let id = supervisor.id().clone();
+        self.add_ref(supervisor.as_ref());
         let launched = supervisor.launch();
         self.launched.insert(id, launched);
     }
 
+    fn add_ref(&self, supervisor_ref: SupervisorRef) {
+        // FIXME: panics?
+        self.supervisors.write().unwrap().push(supervisor_ref);
+    }
+
+    fn remove_ref(&self, id: &BastionId) {
+        // FIXME: panics?
+        self.supervisors
+            .write()
+            .unwrap()
+            .retain(|supervisor_ref| supervisor_ref.id() != id);
+    }
+
     async fn stop(&mut self) -> Vec<Supervisor> {
         self.bcast.stop_children();
 
-        for (_, launched) in self.launched.drain() {
+        let launched = self.launched.drain().collect::<Vec<_>>();
+        for (id, launched) in launched {
+            self.remove_ref(&id);
             self.waiting.push(launched);
         }
 
@@ -219,7 +268,9 @@ impl System {
             launched.cancel();
         }
 
-        for (_, launched) in self.launched.drain() {
+        let launched = self.launched.drain().collect::<Vec<_>>();
+        for (id, launched) in launched {
+            self.remove_ref(&id);
             launched.cancel();
 
             self.waiting.push(launched);
@@ -239,7 +290,9 @@ impl System {
         }
     }
 
-    async fn handle(&mut self, env: Envelope) -> Result<(), ()> {
+    // Handles the message, returning why the system terminated
+    // if it did.
+    async fn handle(&mut self, env: Envelope) -> Result<(), TerminationReason> {
         match env {
             Envelope {
                 msg: BastionMessage::Start,
@@ -254,7 +307,7 @@ impl System {
                     supervisor.callbacks().after_stop();
                 }
 
-                return Err(());
+                return Err(TerminationReason::Normal);
             }
             Envelope {
                 msg: BastionMessage::Kill,
@@ -263,7 +316,7 @@ impl System {
                 info!("System: Killing.");
                 self.kill().await;
 
-                return Err(());
+                return Err(TerminationReason::Killed);
             }
             Envelope {
                 msg: BastionMessage::Deploy(deployment),
@@ -286,6 +339,7 @@ impl System {
 
                     info!("System: Launching Supervisor({}).", supervisor.id());
                     let id = supervisor.id().clone();
+                    self.add_ref(supervisor.as_ref());
                     let launched = supervisor.launch();
                     self.launched.insert(id, launched);
                 }
@@ -298,6 +352,7 @@ impl System {
             } => {
                 // TODO: Err if None?
                 if let Some(launched) = self.launched.remove(&id) {
+                    self.remove_ref(&id);
                     // TODO: stop or kill?
                     self.bcast.kill_child(&id);
 
@@ -309,6 +364,122 @@ impl System {
                 msg: BastionMessage::SuperviseWith(_),
                 ..
             } => unimplemented!(),
+            // The system is stopped with `Bastion::stop` and the
+            // sender is dropped, so the caller gets `Err(())`.
+            Envelope {
+                msg: BastionMessage::StopWithin { deadline, .. },
+                ..
+            } => {
+                debug!("System: Ignoring stop within {:?}.", deadline);
+            }
+            // Only elements can be watched.
+            Envelope {
+                msg: BastionMessage::Watch { watcher },
+                ..
+            } => {
+                debug!("System: Ignoring watch of Child({}).", watcher.id());
+            }
+            Envelope {
+                msg: BastionMessage::Unwatch { watcher },
+                ..
+            } => {
+                debug!("System: Ignoring unwatch of Child({}).", watcher);
+            }
+            // Only children groups can be scaled.
+            Envelope {
+                msg: BastionMessage::Scale(scaling),
+                ..
+            } => {
+                debug!("System: Ignoring scaling: {:?}", scaling);
+            }
+            // Only children groups notify when they stopped, and the
+            // sender is dropped so that the caller gets `Err(())`.
+            Envelope {
+                msg: BastionMessage::NotifyStopped { .. },
+                ..
+            } => {
+                debug!("System: Ignoring stop notification.");
+            }
+            // Only elements notify when they exited.
+            Envelope {
+                msg: BastionMessage::NotifyExited { .. },
+                ..
+            } => {
+                debug!("System: Ignoring exit notification.");
+            }
+            // Only children groups track whether their elements
+            // are ready.
+            Envelope {
+                msg: BastionMessage::ElemReady { id },
+                ..
+            } => {
+                debug!("System: Ignoring readiness of Child({}).", id);
+            }
+            // The sender is dropped so that the caller gets `Err(())`.
+            Envelope {
+                msg: BastionMessage::NotifyReady { .. },
+                ..
+            } => {
+                debug!("System: Ignoring ready notification.");
+            }
+            // Only children groups restart their elements.
+            Envelope {
+                msg: BastionMessage::RestartElem { id },
+                ..
+            } => {
+                debug!("System: Ignoring restart of Child({}).", id);
+            }
+            // Only elements can be pinged.
+            Envelope {
+                msg: BastionMessage::Ping { .. },
+                ..
+            } => {
+                debug!("System: Ignoring ping.");
+            }
+            // Only elements are prepared to stop.
+            Envelope {
+                msg: BastionMessage::PrepareStop { .. },
+                ..
+            } => {
+                debug!("System: Ignoring stop preparation.");
+            }
+            // Only children groups deliver messages to one of their
+            // elements, so the message is sent to the dead letters.
+            Envelope {
+                msg: BastionMessage::SendOne(msg),
+                sign,
+            } => {
+                debug!(
+                    "System: Sending anycast message to the dead letters: {:?}",
+                    msg
+                );
+                let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                // FIXME: Err(env)
+                SYSTEM.dead_letters().send(env).ok();
+            }
+            Envelope {
+                msg: BastionMessage::SendSiblings { msg, .. },
+                sign,
+            } => {
+                debug!(
+                    "System: Sending siblings message to the dead letters: {:?}",
+                    msg
+                );
+                let env = Envelope::new_with_sign(BastionMessage::Message(msg), sign);
+                // FIXME: Err(env)
+                SYSTEM.dead_letters().send(env).ok();
+            }
+            // Only children groups can be paused.
+            Envelope {
+                msg: BastionMessage::Pause,
+                ..
+            }
+            | Envelope {
+                msg: BastionMessage::Resume,
+                ..
+            } => {
+                debug!("System: Ignoring pause or resume.");
+            }
             Envelope {
                 msg: BastionMessage::Message(ref message),
                 ..
@@ -317,25 +488,31 @@ impl System {
                 self.bcast.send_children(env);
             }
             Envelope {
-                msg: BastionMessage::Stopped { id },
+                msg:
+                    BastionMessage::Termination {
+                        id,
+                        reason: TerminationReason::Fault(reason),
+                    },
                 ..
             } => {
                 // TODO: Err if None?
                 if let Some(launched) = self.launched.remove(&id) {
-                    info!("System: Supervisor({}) stopped.", id);
+                    warn!("System: Supervisor({}) faulted: {:?}", id, reason);
+                    self.remove_ref(&id);
                     self.waiting.push(launched);
-                    self.restart.remove(&id);
+                    self.restart.insert(id);
                 }
             }
             Envelope {
-                msg: BastionMessage::Faulted { id },
+                msg: BastionMessage::Termination { id, reason },
                 ..
             } => {
                 // TODO: Err if None?
                 if let Some(launched) = self.launched.remove(&id) {
-                    warn!("System: Supervisor({}) faulted.", id);
+                    info!("System: Supervisor({}) stopped: {:?}", id, reason);
+                    self.remove_ref(&id);
                     self.waiting.push(launched);
-                    self.restart.insert(id);
+                    self.restart.remove(&id);
                 }
             }
         }
@@ -389,12 +566,12 @@ impl System {
                     for msg in msgs {
                         trace!("System: Replaying message: {:?}", msg);
                         // FIXME: Err(Error)?
-                        if self.handle(msg).await.is_err() {
+                        if let Err(reason) = self.handle(msg).await {
                             // FIXME: panics?
                             let mut system = SYSTEM.handle().lock_async().await.unwrap();
                             *system = None;
 
-                            SYSTEM.notify_stopped();
+                            SYSTEM.notify_stopped(reason);
 
                             return;
                         }
@@ -406,12 +583,12 @@ impl System {
                 }
                 Poll::Ready(Some(msg)) => {
                     trace!("System: Received a new message (started=true): {:?}", msg);
-                    if self.handle(msg).await.is_err() {
+                    if let Err(reason) = self.handle(msg).await {
                         // FIXME: panics?
                         let mut system = SYSTEM.handle().lock_async().await.unwrap();
                         *system = None;
 
-                        SYSTEM.notify_stopped();
+                        SYSTEM.notify_stopped(reason);
 
                         return;
                     }
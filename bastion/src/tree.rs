@@ -0,0 +1,273 @@
+//!
+//! Snapshots of the supervision tree, allowing to inspect the
+//! supervisors and children groups of the system (eg. for
+//! debugging or in admin UIs).
+//!
+//! A snapshot of the whole supervision tree can be taken using
+//! [`Bastion::tree`].
+//!
+//! [`Bastion::tree`]: ../struct.Bastion.html#method.tree
+use crate::children_ref::ChildrenRef;
+use crate::context::BastionId;
+use crate::supervisor::{SupervisedState, SupervisedStats, SupervisorRef};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone)]
+/// A snapshot of the supervision tree, returned by
+/// [`Bastion::tree`].
+///
+/// Its `Display` implementation prints the tree with one
+/// supervisor or children group per line.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+///     # Bastion::start();
+/// let tree = Bastion::tree();
+/// for supervisor in tree.supervisors() {
+///     let children_groups = supervisor.children_groups().len();
+///     println!("{} has {} children groups", supervisor.id(), children_groups);
+/// }
+///
+/// println!("{}", tree);
+///     #
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`Bastion::tree`]: ../struct.Bastion.html#method.tree
+pub struct SupervisionTree {
+    supervisors: Vec<SupervisorNode>,
+}
+
+#[derive(Debug, Clone)]
+/// A supervisor in a [`SupervisionTree`].
+///
+/// [`SupervisionTree`]: struct.SupervisionTree.html
+pub struct SupervisorNode {
+    id: BastionId,
+    name: Option<String>,
+    stats: Option<SupervisedStats>,
+    supervisors: Vec<SupervisorNode>,
+    children_groups: Vec<ChildrenNode>,
+}
+
+#[derive(Debug, Clone)]
+/// A children group in a [`SupervisionTree`].
+///
+/// [`SupervisionTree`]: struct.SupervisionTree.html
+pub struct ChildrenNode {
+    id: BastionId,
+    name: Option<String>,
+    stats: Option<SupervisedStats>,
+    elems: Vec<BastionId>,
+}
+
+impl SupervisionTree {
+    pub(crate) fn new(supervisors: Vec<SupervisorRef>) -> Self {
+        let supervisors = supervisors
+            .iter()
+            .map(|supervisor_ref| SupervisorNode::new(supervisor_ref, None))
+            .collect();
+
+        SupervisionTree { supervisors }
+    }
+
+    /// Returns the supervisors launched by the system, including
+    /// the "system supervisor" (which supervises the children
+    /// groups created with [`Bastion::children`]).
+    ///
+    /// [`Bastion::children`]: ../struct.Bastion.html#method.children
+    pub fn supervisors(&self) -> &[SupervisorNode] {
+        &self.supervisors
+    }
+}
+
+impl SupervisorNode {
+    fn new(supervisor_ref: &SupervisorRef, stats: Option<SupervisedStats>) -> Self {
+        let id = supervisor_ref.id().clone();
+        let name = supervisor_ref.name().map(str::to_string);
+        let supervised = supervisor_ref.stats();
+        let stats_of = |id: &BastionId| supervised.iter().find(|stats| stats.id() == id).cloned();
+
+        let supervisors = supervisor_ref
+            .supervisors()
+            .iter()
+            .map(|supervisor_ref| {
+                SupervisorNode::new(supervisor_ref, stats_of(supervisor_ref.id()))
+            })
+            .collect();
+        let children_groups = supervisor_ref
+            .children_groups()
+            .iter()
+            .map(|children_ref| ChildrenNode::new(children_ref, stats_of(children_ref.id())))
+            .collect();
+
+        SupervisorNode {
+            id,
+            name,
+            stats,
+            supervisors,
+            children_groups,
+        }
+    }
+
+    /// Returns the identifier of the supervisor.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns the name of the supervisor, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(String::as_str)
+    }
+
+    /// Returns whether the supervisor is running, stopped or
+    /// faulted.
+    pub fn state(&self) -> SupervisedState {
+        self.stats
+            .as_ref()
+            .map(SupervisedStats::state)
+            .unwrap_or(SupervisedState::Started)
+    }
+
+    /// Returns how many times the supervisor was restarted by its
+    /// own supervisor (always `0` for the supervisors launched by
+    /// the system).
+    pub fn restarts(&self) -> usize {
+        self.stats
+            .as_ref()
+            .map(SupervisedStats::restarts)
+            .unwrap_or(0)
+    }
+
+    /// Returns the supervisors supervised by this supervisor, in
+    /// the order they were added to it.
+    pub fn supervisors(&self) -> &[SupervisorNode] {
+        &self.supervisors
+    }
+
+    /// Returns the children groups supervised by this supervisor,
+    /// in the order they were added to it.
+    pub fn children_groups(&self) -> &[ChildrenNode] {
+        &self.children_groups
+    }
+
+    fn fmt_indented(&self, fmt: &mut Formatter, depth: usize) -> fmt::Result {
+        writeln!(
+            fmt,
+            "{:indent$}Supervisor({}){} {:?} restarts={}",
+            "",
+            self.id,
+            fmt_name(self.name()),
+            self.state(),
+            self.restarts(),
+            indent = depth * 2
+        )?;
+
+        for supervisor in &self.supervisors {
+            supervisor.fmt_indented(fmt, depth + 1)?;
+        }
+
+        for children in &self.children_groups {
+            children.fmt_indented(fmt, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ChildrenNode {
+    fn new(children_ref: &ChildrenRef, stats: Option<SupervisedStats>) -> Self {
+        let id = children_ref.id().clone();
+        let name = children_ref.name().map(str::to_string);
+        let elems = children_ref
+            .elems()
+            .iter()
+            .map(|child_ref| child_ref.id().clone())
+            .collect();
+
+        ChildrenNode {
+            id,
+            name,
+            stats,
+            elems,
+        }
+    }
+
+    /// Returns the identifier of the children group.
+    pub fn id(&self) -> &BastionId {
+        &self.id
+    }
+
+    /// Returns the name of the children group, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(String::as_str)
+    }
+
+    /// Returns whether the children group is running, stopped or
+    /// faulted.
+    pub fn state(&self) -> SupervisedState {
+        self.stats
+            .as_ref()
+            .map(SupervisedStats::state)
+            .unwrap_or(SupervisedState::Started)
+    }
+
+    /// Returns how many times the children group was restarted by
+    /// its supervisor.
+    pub fn restarts(&self) -> usize {
+        self.stats
+            .as_ref()
+            .map(SupervisedStats::restarts)
+            .unwrap_or(0)
+    }
+
+    /// Returns the amount of elements of the children group.
+    pub fn redundancy(&self) -> usize {
+        self.elems.len()
+    }
+
+    /// Returns the identifiers of the elements of the children
+    /// group.
+    pub fn elems(&self) -> &[BastionId] {
+        &self.elems
+    }
+
+    fn fmt_indented(&self, fmt: &mut Formatter, depth: usize) -> fmt::Result {
+        writeln!(
+            fmt,
+            "{:indent$}Children({}){} {:?} restarts={} redundancy={}",
+            "",
+            self.id,
+            fmt_name(self.name()),
+            self.state(),
+            self.restarts(),
+            self.redundancy(),
+            indent = depth * 2
+        )
+    }
+}
+
+fn fmt_name(name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!(" {:?}", name),
+        None => String::new(),
+    }
+}
+
+impl Display for SupervisionTree {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        for supervisor in &self.supervisors {
+            supervisor.fmt_indented(fmt, 0)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//!
+//! Resources used by the elements of children groups, allowing to
+//! find the ones using more than their share in large groups.
+//!
+//! The usage of an element can be retrieved using
+//! [`ChildRef::usage`].
+//!
+//! [`ChildRef::usage`]: ../child_ref/struct.ChildRef.html#method.usage
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Statistics about the resources used by an element of a
+/// children group since it was launched, retrieved using
+/// [`ChildRef::usage`].
+///
+/// The time spent polling the element's future approximates
+/// the CPU time it used, while the sizes of the messages it
+/// received approximate the memory that was allocated for it.
+///
+/// # Example
+///
+/// ```rust
+/// # use bastion::prelude::*;
+/// #
+/// # fn main() {
+///     # Bastion::init();
+///     #
+///     # let children_ref = Bastion::children(|children| children.with_redundancy(4)).unwrap();
+/// // Find the element using the most CPU time in the group...
+/// let noisiest = children_ref
+///     .elems()
+///     .iter()
+///     .max_by_key(|child_ref| child_ref.usage().poll_time());
+///     #
+///     # Bastion::start();
+///     # Bastion::stop();
+///     # Bastion::block_until_stopped();
+/// # }
+/// ```
+///
+/// [`ChildRef::usage`]: ../child_ref/struct.ChildRef.html#method.usage
+pub struct Usage {
+    polls: u64,
+    poll_time: Duration,
+    max_poll_time: Duration,
+    messages: u64,
+    allocated: u64,
+}
+
+#[derive(Debug, Default)]
+// The resources used by an element, updated by the element and
+// shared with the `ChildRef`s referencing it.
+pub(crate) struct UsageStatus {
+    polls: AtomicU64,
+    // The sum and maximum of the durations of the polls of the
+    // element's future, in nanoseconds.
+    poll_time: AtomicU64,
+    max_poll_time: AtomicU64,
+    messages: AtomicU64,
+    // The sum of the sizes of the messages the element received,
+    // in bytes.
+    allocated: AtomicU64,
+}
+
+impl Usage {
+    /// Returns how many times the element's future was polled.
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+
+    /// Returns the time spent polling the element's future.
+    pub fn poll_time(&self) -> Duration {
+        self.poll_time
+    }
+
+    /// Returns the longest time spent in a single poll of the
+    /// element's future.
+    pub fn max_poll_time(&self) -> Duration {
+        self.max_poll_time
+    }
+
+    /// Returns the number of messages the element received.
+    pub fn messages(&self) -> u64 {
+        self.messages
+    }
+
+    /// Returns the approximate number of bytes allocated for the
+    /// messages the element received.
+    pub fn allocated(&self) -> u64 {
+        self.allocated
+    }
+}
+
+impl UsageStatus {
+    pub(crate) fn new() -> Self {
+        UsageStatus::default()
+    }
+
+    // NOTE: the usage is only updated by the element, so loading
+    //       and storing the maximum separately is enough.
+    pub(crate) fn polled(&self, elapsed: Duration) {
+        let elapsed = elapsed.as_nanos() as u64;
+        self.polls.fetch_add(1, Ordering::Relaxed);
+        self.poll_time.fetch_add(elapsed, Ordering::Relaxed);
+        if elapsed > self.max_poll_time.load(Ordering::Relaxed) {
+            self.max_poll_time.store(elapsed, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn received(&self, size: usize) {
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        self.allocated.fetch_add(size as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn usage(&self) -> Usage {
+        Usage {
+            polls: self.polls.load(Ordering::Relaxed),
+            poll_time: Duration::from_nanos(self.poll_time.load(Ordering::Relaxed)),
+            max_poll_time: Duration::from_nanos(self.max_poll_time.load(Ordering::Relaxed)),
+            messages: self.messages.load(Ordering::Relaxed),
+            allocated: self.allocated.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//!
+//! Validation of the messages sent to the elements of a children
+//! group, before they get enqueued in their mailboxes.
+use crate::message::{Message, MessageSize, Msg};
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+// Returns the size of a message if it is of the type it measures.
+type Sizer = Arc<dyn Fn(&Msg) -> Option<usize> + Send + Sync>;
+
+#[derive(Default, Clone)]
+pub(crate) struct Validation {
+    // The maximum size (in bytes) that the messages of some
+    // types can have to be accepted, with what measures them.
+    max_sizes: Vec<(usize, Sizer)>,
+    // The closure deciding whether a message should be
+    // accepted or rejected.
+    validator: Option<Arc<dyn Fn(&Msg) -> bool + Send + Sync>>,
+}
+
+impl Validation {
+    pub(crate) fn new() -> Self {
+        Validation::default()
+    }
+
+    pub(crate) fn with_max_size<M>(mut self, max_size: usize) -> Self
+    where
+        M: Message + MessageSize,
+    {
+        let sizer: Sizer = Arc::new(|msg: &Msg| msg.peek::<M>().map(MessageSize::message_size));
+        self.max_sizes.push((max_size, sizer));
+        self
+    }
+
+    pub(crate) fn with_validator<V>(mut self, validator: V) -> Self
+    where
+        V: Fn(&Msg) -> bool + Send + Sync + 'static,
+    {
+        let validator = Arc::new(validator);
+        self.validator = Some(validator);
+        self
+    }
+
+    pub(crate) fn is_valid(&self, msg: &Msg) -> bool {
+        for (max_size, sizer) in &self.max_sizes {
+            let size = match sizer(msg) {
+                Some(size) => size,
+                None => continue,
+            };
+
+            if size > *max_size {
+                debug!(
+                    "Validation: Message too large ({} > {} bytes): {:?}",
+                    size, max_size, msg
+                );
+                return false;
+            }
+        }
+
+        if let Some(validator) = &self.validator {
+            validator(msg)
+        } else {
+            true
+        }
+    }
+
+    // Validates a message before it gets sent, giving it back
+    // as an error if it is rejected.
+    pub(crate) fn check<M: Message>(&self, msg: M) -> Result<M, M> {
+        if self.max_sizes.is_empty() && self.validator.is_none() {
+            return Ok(msg);
+        }
+
+        let (msg, valid) = Msg::inspect(msg, |msg| self.is_valid(msg));
+        if valid {
+            Ok(msg)
+        } else {
+            Err(msg)
+        }
+    }
+}
+
+impl Debug for Validation {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("Validation")
+            .field(
+                "max_sizes",
+                &self
+                    .max_sizes
+                    .iter()
+                    .map(|(max_size, _)| max_size)
+                    .collect::<Vec<_>>(),
+            )
+            .field("validator", &self.validator.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+
+#[test]
+fn resolves_named_elements() {
+    init_start();
+
+    let mut workers = None;
+    let supervisor_ref = Bastion::supervisor(|sp| {
+        let sp = sp.with_name("payment");
+        workers = Some(sp.children_ref(|children| {
+            children.with_name("workers").with_redundancy(2).with_exec(
+                |ctx: BastionContext| async move {
+                    loop {
+                        ctx.recv().await?;
+                    }
+                },
+            )
+        }));
+        sp
+    })
+    .expect("Couldn't create the supervisor.");
+    let workers = workers.unwrap();
+    // The addresses are resolved using the supervision tree,
+    // which is shared by the supervisors once they handled the
+    // deployments.
+    wait_until(|| Bastion::address_of(workers.id()).is_some());
+
+    assert_eq!(
+        Bastion::address_of(supervisor_ref.id()),
+        Some("bastion://sys/payment".to_string())
+    );
+    assert_eq!(
+        Bastion::address_of(workers.id()),
+        Some("bastion://sys/payment/workers".to_string())
+    );
+
+    let child_ref = Bastion::actor_at("bastion://sys/payment/workers/1")
+        .expect("Couldn't resolve the address.");
+    assert!(workers.elems().contains(&child_ref));
+    assert_eq!(
+        Bastion::address_of(child_ref.id()),
+        Some("bastion://sys/payment/workers/1".to_string())
+    );
+
+    // Elements can also be referenced using their identifier.
+    let address = format!("bastion://sys/payment/workers/{}", child_ref.id());
+    assert_eq!(Bastion::actor_at(&address), Some(child_ref));
+
+    assert!(Bastion::actor_at("bastion://sys/payment/workers/2").is_none());
+    assert!(Bastion::actor_at("bastion://sys/payment/unknown/0").is_none());
+    assert!(Bastion::actor_at("bastion://system/payment/workers/0").is_none());
+}
+
+#[test]
+fn resolves_system_supervised_groups() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_name("jobs")
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| Bastion::address_of(children_ref.id()).is_some());
+
+    assert_eq!(
+        Bastion::address_of(children_ref.id()),
+        Some("bastion://sys/user/jobs".to_string())
+    );
+    assert_eq!(
+        Bastion::actor_at("bastion://sys/user/jobs/0"),
+        Some(children_ref.elems()[0].clone())
+    );
+}
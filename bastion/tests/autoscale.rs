@@ -0,0 +1,101 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Removes the element's identifier from the running ones once
+// its future is dropped.
+struct Running {
+    id: BastionId,
+    running: Arc<Mutex<Vec<BastionId>>>,
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.running.lock().unwrap().retain(|id| id != &self.id);
+    }
+}
+
+#[test]
+fn grows_under_pressure() {
+    init_start();
+
+    let running = Arc::new(Mutex::new(Vec::new()));
+
+    let running_ = running.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_autoscaler(
+                Autoscaler::new(1, 4)
+                    .with_thresholds(0, 2)
+                    .with_interval(Duration::from_millis(50)),
+            )
+            .with_exec(move |ctx: BastionContext| {
+                let running = running_.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    running.lock().unwrap().push(id.clone());
+                    let _running = Running { id, running };
+
+                    // The messages are never retrieved...
+                    futures::future::pending::<()>().await;
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| running.lock().unwrap().len() == 1);
+
+    for _ in 0..20 {
+        children_ref
+            .broadcast("pressure")
+            .expect("Couldn't send the message.");
+    }
+    wait_until(|| running.lock().unwrap().len() == 4);
+}
+
+#[test]
+fn shrinks_when_idle() {
+    init_start();
+
+    let running = Arc::new(Mutex::new(Vec::new()));
+    let launched = Arc::new(AtomicUsize::new(0));
+
+    let running_ = running.clone();
+    let launched_ = launched.clone();
+    Bastion::children(move |children| {
+        children
+            .with_redundancy(4)
+            .with_autoscaler(Autoscaler::new(2, 4).with_interval(Duration::from_millis(50)))
+            .with_exec(move |ctx: BastionContext| {
+                let running = running_.clone();
+                launched_.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    let id = ctx.current().id().clone();
+                    running.lock().unwrap().push(id.clone());
+                    let _running = Running { id, running };
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    // The group only shrinks once all its elements were launched.
+    wait_until(|| launched.load(Ordering::SeqCst) == 4 && running.lock().unwrap().len() == 2);
+}
+
+#[test]
+fn target_bounds() {
+    let autoscaler = Autoscaler::new(0, 0);
+    assert_eq!(autoscaler.min(), 1);
+    assert_eq!(autoscaler.max(), 1);
+
+    let autoscaler = Autoscaler::new(2, 8).with_thresholds(4, 2);
+    assert_eq!(autoscaler.thresholds(), (4, 4));
+    assert_eq!(autoscaler.interval(), Duration::from_secs(1));
+}
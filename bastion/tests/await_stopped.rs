@@ -0,0 +1,53 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+
+#[test]
+fn resolves_on_stop() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let stopped = children_ref.stopped().expect("Couldn't send the message.");
+    children_ref.stop().expect("Couldn't send the message.");
+
+    let terminated = run!(stopped).expect("The group was dropped.");
+    assert_eq!(terminated.id(), children_ref.id());
+    match terminated.reason() {
+        TerminationReason::Normal => (),
+        reason => panic!("Unexpected reason: {:?}", reason),
+    }
+}
+
+#[test]
+fn resolves_on_fault() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_exec(|ctx: BastionContext| async move {
+                ctx.recv().await?;
+                Err(())
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let stopped = children_ref.stopped().expect("Couldn't send the message.");
+    children_ref
+        .broadcast("Fault!")
+        .expect("Couldn't send the message.");
+
+    let terminated = run!(stopped).expect("The group was dropped.");
+    assert!(terminated.reason().is_fault());
+}
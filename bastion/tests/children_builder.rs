@@ -0,0 +1,57 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+
+fn echo(redundancy: usize) -> Children {
+    Children::builder()
+        .with_redundancy(redundancy)
+        .with_name("echo")
+        .with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: &'static str =!> {
+                        answer!(ctx, msg).ok();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+}
+
+#[test]
+fn deploys_built_groups() {
+    init_start();
+
+    let children_ref =
+        Bastion::children_from(echo(3)).expect("Couldn't create the children group.");
+    assert_eq!(children_ref.elems().len(), 3);
+    assert_eq!(children_ref.name(), Some("echo"));
+
+    for child_ref in children_ref.elems() {
+        let answer = child_ref
+            .ask_anonymously("Hello!")
+            .expect("Couldn't send the message.");
+        let msg = run!(answer).expect("Couldn't receive the answer.");
+        msg! { msg,
+            msg: &'static str => assert_eq!(msg, "Hello!");
+            _: _ => panic!("Unexpected answer.");
+        }
+    }
+}
+
+#[test]
+fn deploys_built_groups_under_supervisors() {
+    init_start();
+
+    let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let children_ref = supervisor
+        .children_from(echo(2))
+        .expect("Couldn't create the children group.");
+    assert_eq!(children_ref.elems().len(), 2);
+    wait_until(|| !supervisor.children_groups().is_empty());
+
+    let groups = supervisor.children_groups();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].id(), children_ref.id());
+}
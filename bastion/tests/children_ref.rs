@@ -0,0 +1,68 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::collections::HashSet;
+
+#[test]
+fn stored_in_sets() {
+    init_start();
+
+    let first =
+        Bastion::children(|children| children).expect("Couldn't create the children group.");
+    let second =
+        Bastion::children(|children| children).expect("Couldn't create the children group.");
+
+    let mut set = HashSet::new();
+    assert!(set.insert(first.clone()));
+    assert!(set.insert(second.clone()));
+    assert!(!set.insert(first.clone()));
+    assert!(set.contains(&second));
+}
+
+#[test]
+fn finds_elems() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| children.with_redundancy(3))
+        .expect("Couldn't create the children group.");
+
+    for child_ref in children_ref.elems() {
+        assert_eq!(children_ref.find_elem(child_ref.id()), Some(child_ref));
+    }
+    assert!(children_ref.find_elem(children_ref.id()).is_none());
+}
+
+#[test]
+fn reports_broadcast_failures() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_redundancy(2)
+            .with_isolated_elems()
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_exec(|ctx: BastionContext| async move {
+                ctx.recv().await?;
+                Ok(())
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let stopped = children_ref.elems()[0].clone();
+    let running = children_ref.elems()[1].clone();
+
+    stopped
+        .tell_anonymously("Stop!")
+        .expect("Couldn't send the message.");
+    wait_until(|| !stopped.is_alive());
+
+    let report = children_ref
+        .broadcast_with_report("Hello!")
+        .expect("Couldn't send the message.");
+    assert!(!report.is_complete());
+    assert_eq!(report.delivered(), &[running.id().clone()]);
+    assert_eq!(
+        report.failed(),
+        &[(stopped.id().clone(), DeliveryFailure::Stopped)]
+    );
+}
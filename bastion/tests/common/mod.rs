@@ -0,0 +1,64 @@
+// Helpers shared by the integration tests (every test crate
+// only uses some of them).
+#![allow(dead_code)]
+
+use bastion::prelude::*;
+use std::sync::Once;
+use std::thread;
+use std::time::{Duration, Instant};
+
+static START: Once = Once::new();
+
+// How long `wait_until` waits for a condition before failing
+// the test.
+const TIMEOUT: Duration = Duration::from_secs(5);
+// How often `wait_until` checks its condition.
+const INTERVAL: Duration = Duration::from_millis(10);
+
+// Initializes the system the first time it is called and then
+// starts it.
+pub fn init_start() {
+    START.call_once(|| {
+        Bastion::init();
+    });
+    Bastion::start();
+}
+
+// Blocks until `cond` returns `true`, panicking if it still
+// didn't after a few seconds.
+pub fn wait_until<F: FnMut() -> bool>(mut cond: F) {
+    let deadline = Instant::now() + TIMEOUT;
+    while !cond() {
+        assert!(
+            Instant::now() < deadline,
+            "Timed out waiting for a condition."
+        );
+        thread::sleep(INTERVAL);
+    }
+}
+
+// Blocks until `f` returns `Some`, returning its value or
+// panicking if it still returned `None` after a few seconds.
+pub fn wait_for<T, F: FnMut() -> Option<T>>(mut f: F) -> T {
+    let mut value = None;
+    wait_until(|| {
+        value = f();
+        value.is_some()
+    });
+
+    value.unwrap()
+}
+
+// Blocks until the children group at `index` among those
+// supervised by `supervisor` was restarted (and thus isn't
+// identified by `id` anymore), returning a reference to the
+// new group.
+pub fn wait_restarted(supervisor: &SupervisorRef, index: usize, id: &BastionId) -> ChildrenRef {
+    wait_for(|| {
+        supervisor
+            .children_groups()
+            .get(index)
+            .filter(|children_ref| children_ref.id() != id)
+            .cloned()
+    })
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn exposes_identity() {
+    init_start();
+
+    let ids = Arc::new(Mutex::new(None));
+
+    let ids_ = ids.clone();
+    let children_ref = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let ids = ids_.clone();
+            async move {
+                assert_eq!(ctx.path().id(), ctx.id());
+                *ids.lock().unwrap() = Some((ctx.id().clone(), ctx.group_id().clone()));
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let elem_id = children_ref.elems()[0].id().clone();
+
+    let ids = wait_for(|| ids.lock().unwrap().take());
+    assert_eq!(ids, (elem_id, children_ref.id().clone()));
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Creates a children group whose element answers every `usize`
+// it is asked with its double.
+fn spawn_doubler() -> ChildrenRef {
+    Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: usize =!> {
+                        answer!(ctx, msg * 2).ok();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.")
+}
+
+// Creates a children group whose element asks `to` to double 21
+// and stores the answer in `answered` (or `usize::MAX` if it
+// timed out).
+fn spawn_asker(to: ChildRef, answered: Arc<AtomicUsize>) {
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let to = to.clone();
+            let answered = answered.clone();
+            async move {
+                let timeout = Duration::from_millis(200);
+                match ctx.ask_with_timeout(&to, 21usize, timeout).await {
+                    Ok(answer) => {
+                        msg! { answer,
+                            msg: usize => {
+                                answered.store(msg, Ordering::SeqCst);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                    Err(AskError::TimedOut) => answered.store(usize::MAX, Ordering::SeqCst),
+                    Err(_) => (),
+                }
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+}
+
+#[test]
+fn asks_with_timeout() {
+    init_start();
+
+    let doubler = spawn_doubler();
+    let answered = Arc::new(AtomicUsize::new(0));
+    spawn_asker(doubler.elems()[0].clone(), answered.clone());
+
+    wait_until(|| answered.load(Ordering::SeqCst) != 0);
+    assert_eq!(answered.load(Ordering::SeqCst), 42);
+}
+
+#[test]
+fn times_out_without_answer() {
+    init_start();
+
+    // The element never answers, but keeps the messages it
+    // receives (dropping them would make their sender fail).
+    let silent = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            let mut kept = Vec::new();
+            loop {
+                kept.push(ctx.recv().await?);
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let answered = Arc::new(AtomicUsize::new(0));
+    spawn_asker(silent.elems()[0].clone(), answered.clone());
+
+    wait_until(|| answered.load(Ordering::SeqCst) != 0);
+    assert_eq!(answered.load(Ordering::SeqCst), usize::MAX);
+}
+
+#[test]
+fn propagates_deadline() {
+    init_start();
+
+    let in_time = Arc::new(AtomicUsize::new(0));
+
+    let in_time_ = in_time.clone();
+    let recorder = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let in_time = in_time_.clone();
+            async move {
+                msg! { ctx.recv().await?,
+                    msg: usize =!> {
+                        let deadline = ctx.current_deadline().expect("No deadline.");
+                        let left = deadline.saturating_duration_since(Instant::now());
+                        if left > Duration::from_millis(0) && left <= Duration::from_millis(200) {
+                            in_time.fetch_add(1, Ordering::SeqCst);
+                        }
+                        answer!(ctx, msg * 2).ok();
+                    };
+                    _: _ => ();
+                }
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let answered = Arc::new(AtomicUsize::new(0));
+    spawn_asker(recorder.elems()[0].clone(), answered.clone());
+
+    wait_until(|| answered.load(Ordering::SeqCst) != 0);
+    assert_eq!(answered.load(Ordering::SeqCst), 42);
+    assert_eq!(in_time.load(Ordering::SeqCst), 1);
+}
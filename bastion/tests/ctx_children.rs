@@ -0,0 +1,49 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn spawns_groups_from_elems() {
+    init_start();
+
+    let started = Arc::new(AtomicUsize::new(0));
+
+    let started_ = started.clone();
+    let sp_ref = Bastion::supervisor(move |sp| {
+        sp.children(move |children| {
+            children.with_exec(move |ctx: BastionContext| {
+                let started = started_.clone();
+                async move {
+                    ctx.recv().await?;
+
+                    let workers = ctx.children(
+                        move |children| {
+                            children.with_exec(move |_: BastionContext| {
+                                let started = started.clone();
+                                async move {
+                                    started.fetch_add(1, Ordering::SeqCst);
+                                    Ok(())
+                                }
+                            })
+                        },
+                        3,
+                    )?;
+                    assert_eq!(workers.elems().len(), 3);
+
+                    Ok(())
+                }
+            })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    let group = wait_for(|| sp_ref.children_groups().pop());
+    group.broadcast(()).expect("Couldn't send the message.");
+    wait_until(|| started.load(Ordering::SeqCst) == 3);
+
+    // The new group is supervised by the element's supervisor.
+    assert_eq!(sp_ref.children_groups().len(), 2);
+}
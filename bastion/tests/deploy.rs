@@ -0,0 +1,160 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn deploys_to_running_supervisor() {
+    init_start();
+
+    let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let launched_ = launched.clone();
+    let nested = supervisor
+        .supervisor(|sp| sp)
+        .expect("Couldn't deploy the supervisor.");
+    nested
+        .children(move |children| {
+            children
+                .with_redundancy(2)
+                .with_exec(move |ctx: BastionContext| {
+                    launched_.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    }
+                })
+        })
+        .expect("Couldn't deploy the children group.");
+
+    wait_until(|| launched.load(Ordering::SeqCst) == 2);
+}
+
+#[test]
+fn prunes_from_running_supervisor() {
+    init_start();
+
+    let stopped = Arc::new(AtomicUsize::new(0));
+    let stopped_ = stopped.clone();
+
+    let supervisor = Bastion::supervisor(|sp| sp).expect("Couldn't create the supervisor.");
+    let children = supervisor
+        .children(move |children| {
+            let callbacks = Callbacks::new().with_after_stop(move || {
+                stopped_.fetch_add(1, Ordering::SeqCst);
+            });
+
+            children
+                .with_callbacks(callbacks)
+                .with_exec(|ctx: BastionContext| async move {
+                    loop {
+                        ctx.recv().await?;
+                    }
+                })
+        })
+        .expect("Couldn't deploy the children group.");
+
+    assert!(supervisor.prune(children.id()).is_ok());
+    wait_until(|| stopped.load(Ordering::SeqCst) == 1);
+
+    assert!(children.elems()[0].tell_anonymously("Hello!").is_err());
+}
+
+#[test]
+fn deploys_nested_tree() {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let exec = |launched: Arc<AtomicUsize>| {
+        move |children: Children| {
+            children.with_exec(move |ctx: BastionContext| {
+                launched.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+        }
+    };
+
+    let (first, second, third) = (launched.clone(), launched.clone(), launched.clone());
+    Bastion::supervisor(move |sp| {
+        sp.children(exec(first)).supervisor(move |sp| {
+            sp.children(exec(second))
+                .supervisor(move |sp| sp.children(exec(third)))
+        })
+    })
+    .expect("Couldn't create the supervision tree.");
+
+    wait_until(|| launched.load(Ordering::SeqCst) == 3);
+}
+
+#[test]
+fn stops_in_reverse_order() {
+    init_start();
+
+    let stopped = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let stopped_ = stopped.clone();
+
+    let supervisor = Bastion::supervisor(move |mut sp| {
+        for index in 0..3 {
+            let stopped = stopped_.clone();
+            sp = sp.children(move |children| {
+                let callbacks = Callbacks::new().with_after_stop(move || {
+                    stopped.lock().unwrap().push(index);
+                });
+
+                children
+                    .with_callbacks(callbacks)
+                    .with_exec(|ctx: BastionContext| async move {
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    })
+            });
+        }
+
+        sp
+    })
+    .expect("Couldn't create the supervisor.");
+
+    assert!(supervisor.stop().is_ok());
+    wait_until(|| stopped.lock().unwrap().len() == 3);
+
+    assert_eq!(*stopped.lock().unwrap(), vec![2, 1, 0]);
+}
+
+#[test]
+fn enumerates_supervised() {
+    init_start();
+
+    let exec = |children: Children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    };
+
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.children(move |children| exec(children).with_redundancy(2))
+            .supervisor(move |sp| sp.children(exec))
+    })
+    .expect("Couldn't create the supervision tree.");
+
+    wait_until(|| !supervisor.supervisors().is_empty());
+
+    let children_groups = supervisor.children_groups();
+    assert_eq!(children_groups.len(), 1);
+    assert_eq!(children_groups[0].elems().len(), 2);
+
+    let supervisors = supervisor.supervisors();
+    assert_eq!(supervisors.len(), 1);
+    assert_eq!(supervisors[0].children_groups().len(), 1);
+    assert!(supervisors[0].supervisors().is_empty());
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn receives_backlog_at_once() {
+    init_start();
+
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let drained = Arc::new(AtomicBool::new(false));
+
+    let (batches_, drained_) = (batches.clone(), drained.clone());
+    let children_ref = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let (batches, drained) = (batches_.clone(), drained_.clone());
+            async move {
+                assert!(ctx.drain().await.is_empty());
+                drained.store(true, Ordering::SeqCst);
+
+                loop {
+                    let msgs = ctx.recv_all().await?;
+                    let batch = msgs
+                        .into_iter()
+                        .filter_map(|msg| {
+                            let (msg, _) = msg.extract();
+                            msg.downcast_ref::<usize>().map(|msg| *msg)
+                        })
+                        .collect::<Vec<_>>();
+                    batches.lock().unwrap().push(batch);
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| drained.load(Ordering::SeqCst));
+
+    // The messages pile up in the element's mailbox while the
+    // group is paused.
+    children_ref.pause().expect("Couldn't send the message.");
+    for msg in 0..5usize {
+        children_ref
+            .broadcast(msg)
+            .expect("Couldn't send the message.");
+    }
+    let child_ref = &children_ref.elems()[0];
+    wait_until(|| child_ref.mailbox_stats().depth() == 5);
+    children_ref.resume().expect("Couldn't send the message.");
+    wait_until(|| !batches.lock().unwrap().is_empty());
+
+    let batches = batches.lock().unwrap();
+    assert_eq!(*batches, vec![vec![0, 1, 2, 3, 4]]);
+}
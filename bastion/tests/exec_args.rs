@@ -0,0 +1,55 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn group_args() {
+    init_start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    Bastion::children(|children| {
+        children.with_redundancy(2).with_exec_args(
+            received.clone(),
+            |ctx: BastionContext, received: Arc<Mutex<Vec<&'static str>>>| async move {
+                received.lock().unwrap().push("args");
+                loop {
+                    ctx.recv().await?;
+                }
+            },
+        )
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| received.lock().unwrap().len() == 2);
+
+    assert_eq!(*received.lock().unwrap(), vec!["args", "args"]);
+}
+
+#[test]
+fn elems_args() {
+    init_start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children.with_elems_args(vec![1, 2, 3], move |ctx: BastionContext, arg: usize| {
+            let received = received_.clone();
+            async move {
+                received.lock().unwrap().push(arg);
+                loop {
+                    ctx.recv().await?;
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| received.lock().unwrap().len() == 3);
+
+    assert_eq!(children_ref.elems().len(), 3);
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    assert_eq!(received, vec![1, 2, 3]);
+}
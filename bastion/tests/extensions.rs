@@ -0,0 +1,59 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone)]
+struct Handled(usize);
+
+#[test]
+fn keeps_values_across_recvs() {
+    init_start();
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let incarnations = Arc::new(AtomicUsize::new(0));
+
+    let handled_ = handled.clone();
+    let incarnations_ = incarnations.clone();
+    let children_ref = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let handled = handled_.clone();
+            let incarnations = incarnations_.clone();
+            async move {
+                // Values don't survive restarts.
+                assert!(ctx.get::<Handled>().is_none());
+                incarnations.fetch_add(1, Ordering::SeqCst);
+                assert!(ctx.set(Handled(0)).is_none());
+
+                loop {
+                    msg! { ctx.recv().await?,
+                        ref _msg: usize => {
+                            ctx.update(|handled: &mut Handled| handled.0 += 1);
+                        };
+                        ref _msg: &'static str => {
+                            let count = ctx.get::<Handled>().map(|handled| handled.0);
+                            handled.store(count.unwrap_or(0), Ordering::SeqCst);
+                            return Err(());
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    for msg in 0..3usize {
+        children_ref
+            .broadcast(msg)
+            .expect("Couldn't send the message.");
+    }
+    children_ref
+        .broadcast("report")
+        .expect("Couldn't send the message.");
+    wait_until(|| incarnations.load(Ordering::SeqCst) == 2);
+
+    assert_eq!(handled.load(Ordering::SeqCst), 3);
+}
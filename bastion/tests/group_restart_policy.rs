@@ -0,0 +1,64 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Creates a children group whose element's future returns
+// `result`, and checks that it was launched `expected` times.
+fn assert_launches(policy: GroupRestartPolicy, result: Result<(), ()>, expected: usize) {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let launched_ = launched.clone();
+
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_restart_policy(policy)
+            .with_exec(move |ctx: BastionContext| {
+                let launched = launched_.clone();
+                async move {
+                    // Prevents the group from being restarted in a
+                    // hot loop.
+                    if launched.fetch_add(1, Ordering::SeqCst) > 0 {
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    }
+
+                    result
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    if expected > 1 {
+        wait_until(|| launched.load(Ordering::SeqCst) == expected);
+    } else {
+        // The element stopped once it doesn't receive messages
+        // anymore.
+        let child_ref = children_ref.elems()[0].clone();
+        wait_until(|| child_ref.tell_anonymously(()).is_err());
+    }
+
+    assert_eq!(launched.load(Ordering::SeqCst), expected);
+}
+
+#[test]
+fn permanent() {
+    assert_launches(GroupRestartPolicy::Permanent, Ok(()), 2);
+    assert_launches(GroupRestartPolicy::Permanent, Err(()), 2);
+}
+
+#[test]
+fn transient() {
+    assert_launches(GroupRestartPolicy::Transient, Ok(()), 1);
+    assert_launches(GroupRestartPolicy::Transient, Err(()), 2);
+}
+
+#[test]
+fn temporary() {
+    assert_launches(GroupRestartPolicy::Temporary, Ok(()), 1);
+    assert_launches(GroupRestartPolicy::Temporary, Err(()), 1);
+}
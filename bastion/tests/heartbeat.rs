@@ -0,0 +1,81 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn restarts_hung_elems() {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let hung = Arc::new(AtomicUsize::new(0));
+
+    let launched_ = launched.clone();
+    let hung_ = hung.clone();
+    Bastion::children(move |children| {
+        let hung = hung_.clone();
+        children
+            .with_heartbeat(Duration::from_millis(50), 2, HangAction::Restart)
+            .with_hang_hook(move |_: ChildRef| {
+                hung.fetch_add(1, Ordering::SeqCst);
+            })
+            .with_exec(move |ctx: BastionContext| {
+                let launched = launched_.clone();
+                async move {
+                    launched.fetch_add(1, Ordering::SeqCst);
+                    // Never sends a heartbeat.
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    wait_until(|| hung.load(Ordering::SeqCst) >= 1);
+    wait_until(|| launched.load(Ordering::SeqCst) >= 2);
+}
+
+#[test]
+fn keeps_beating_elems() {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let hung = Arc::new(AtomicUsize::new(0));
+    let beats = Arc::new(AtomicUsize::new(0));
+
+    let launched_ = launched.clone();
+    let hung_ = hung.clone();
+    let beats_ = beats.clone();
+    Bastion::children(move |children| {
+        let hung = hung_.clone();
+        children
+            .with_heartbeat(Duration::from_millis(50), 2, HangAction::Kill)
+            .with_hang_hook(move |_: ChildRef| {
+                hung.fetch_add(1, Ordering::SeqCst);
+            })
+            .with_exec(move |ctx: BastionContext| {
+                let launched = launched_.clone();
+                let beats = beats_.clone();
+                async move {
+                    launched.fetch_add(1, Ordering::SeqCst);
+                    loop {
+                        ctx.heartbeat();
+                        beats.fetch_add(1, Ordering::SeqCst);
+                        ctx.blocking(|| thread::sleep(Duration::from_millis(10)))
+                            .await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    // Lasts for several heartbeat periods.
+    wait_until(|| beats.load(Ordering::SeqCst) >= 30);
+
+    assert_eq!(hung.load(Ordering::SeqCst), 0);
+    assert_eq!(launched.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn elems_keep_their_index() {
+    init_start();
+
+    let launched = Arc::new(Mutex::new(Vec::new()));
+    let faulted = Arc::new(AtomicBool::new(false));
+
+    let launched_ = launched.clone();
+    Bastion::children(move |children| {
+        children
+            .with_redundancy(3)
+            .with_isolated_elems()
+            .with_indexed_exec(move |index, ctx: BastionContext| {
+                let launched = launched_.clone();
+                let faulted = faulted.clone();
+                async move {
+                    launched.lock().unwrap().push(index);
+                    // The second element faults once...
+                    if index == 1 && !faulted.swap(true, Ordering::SeqCst) {
+                        return Err(());
+                    }
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| launched.lock().unwrap().len() == 4);
+
+    // ...and is restarted with the same index.
+    let mut launched = launched.lock().unwrap().clone();
+    launched.sort();
+    assert_eq!(launched, vec![0, 1, 1, 2]);
+}
+
+#[test]
+fn finds_elems_by_index() {
+    init_start();
+
+    let indices = Arc::new(Mutex::new(Vec::new()));
+
+    let indices_ = indices.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(3)
+            .with_indexed_exec(move |index, ctx: BastionContext| {
+                let indices = indices_.clone();
+                async move {
+                    indices.lock().unwrap().push((index, ctx.elem_index()));
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| indices.lock().unwrap().len() == 3);
+
+    for (index, elem_index) in indices.lock().unwrap().iter() {
+        assert_eq!(index, elem_index);
+    }
+
+    for index in 0..3 {
+        let child_ref = children_ref
+            .elem_by_index(index)
+            .expect("Couldn't find the element.");
+        assert_eq!(child_ref.index(), index);
+    }
+    assert!(children_ref.elem_by_index(3).is_none());
+}
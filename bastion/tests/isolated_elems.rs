@@ -0,0 +1,106 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Creates a children group of three isolated elements faulting on
+// their first message, makes one of them fault and checks that
+// elements were launched `expected` times.
+fn assert_launches(policy: GroupRestartPolicy, expected: usize) {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let launched_ = launched.clone();
+
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(3)
+            .with_isolated_elems()
+            .with_restart_policy(policy)
+            .with_exec(move |ctx: BastionContext| {
+                launched_.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    ctx.recv().await?;
+                    Err(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child_ref = &children_ref.elems()[0];
+    child_ref
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+    // The faulted element doesn't receive messages anymore,
+    // whether it was replaced or not.
+    wait_until(|| child_ref.tell_anonymously(()).is_err());
+    wait_until(|| launched.load(Ordering::SeqCst) >= expected);
+
+    assert_eq!(launched.load(Ordering::SeqCst), expected);
+}
+
+#[test]
+fn restarts_faulted_elem_only() {
+    assert_launches(GroupRestartPolicy::Transient, 4);
+}
+
+#[test]
+fn removes_temporary_elem() {
+    assert_launches(GroupRestartPolicy::Temporary, 3);
+}
+
+#[test]
+fn heals_without_supervisor() {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let received = Arc::new(AtomicUsize::new(0));
+    let launched_ = launched.clone();
+    let received_ = received.clone();
+
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.children(move |children| {
+            children.with_redundancy(2).with_isolated_elems().with_exec(
+                move |ctx: BastionContext| {
+                    launched_.fetch_add(1, Ordering::SeqCst);
+                    let received = received_.clone();
+                    async move {
+                        loop {
+                            msg! { ctx.recv().await?,
+                                _msg: &'static str => return Err(());
+                                _: _ => {
+                                    received.fetch_add(1, Ordering::SeqCst);
+                                };
+                            }
+                        }
+                    }
+                },
+            )
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+    wait_until(|| launched.load(Ordering::SeqCst) == 2);
+
+    let stats = wait_for(|| Some(supervisor.stats()).filter(|stats| !stats.is_empty()));
+    let elems = wait_for(|| supervisor.children_groups().pop())
+        .elems()
+        .to_vec();
+    elems[0]
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+    wait_until(|| launched.load(Ordering::SeqCst) == 3);
+
+    // The group itself wasn't restarted by its supervisor...
+    let healed = supervisor.stats();
+    assert_eq!(healed[0].id(), stats[0].id());
+    assert_eq!(healed[0].restarts(), 0);
+    assert_eq!(healed[0].last_fault(), None);
+
+    // ...and the element that didn't fault kept running.
+    elems[1]
+        .tell_anonymously(1usize)
+        .expect("Couldn't send the message.");
+    wait_until(|| received.load(Ordering::SeqCst) == 1);
+}
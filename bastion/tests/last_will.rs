@@ -0,0 +1,92 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::{Arc, Mutex};
+
+// Creates a children group whose element stores the messages it
+// receives.
+fn recipient() -> (ChildRef, Arc<Mutex<Vec<&'static str>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let received = received_.clone();
+            async move {
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            received.lock().unwrap().push(msg);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    (children_ref.elems()[0].clone(), received)
+}
+
+// Creates a children group whose element sets its last will and
+// returns an error if `fault` is true, or `Ok(())` otherwise, once
+// it receives a message.
+fn testator(recipient: ChildRef, fault: bool) -> ChildRef {
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_exec(move |ctx: BastionContext| {
+                let recipient = recipient.clone();
+                async move {
+                    ctx.set_last_will(&recipient, "Last will");
+                    ctx.recv().await?;
+
+                    if fault {
+                        Err(())
+                    } else {
+                        Ok(())
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    children_ref.elems()[0].clone()
+}
+
+#[test]
+fn sent_on_fault() {
+    init_start();
+
+    let (recipient, received) = recipient();
+    let testator = testator(recipient, true);
+
+    testator
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+    wait_until(|| !received.lock().unwrap().is_empty());
+
+    assert_eq!(*received.lock().unwrap(), vec!["Last will"]);
+}
+
+#[test]
+fn not_sent_on_stop() {
+    init_start();
+
+    let (recipient, received) = recipient();
+    let testator = testator(recipient.clone(), false);
+
+    testator
+        .tell_anonymously("Stop!")
+        .expect("Couldn't send the message.");
+    wait_until(|| testator.tell_anonymously("Stop!").is_err());
+
+    // Anything the testator sent would have been received first.
+    recipient
+        .tell_anonymously("Done")
+        .expect("Couldn't send the message.");
+    wait_until(|| !received.lock().unwrap().is_empty());
+    assert_eq!(*received.lock().unwrap(), vec!["Done"]);
+}
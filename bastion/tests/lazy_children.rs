@@ -0,0 +1,71 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn launches_elems_on_first_message() {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let launched_ = launched.clone();
+
+    let children = Bastion::children(move |children| {
+        children
+            .with_redundancy(3)
+            .with_lazy_elems()
+            .with_exec(move |ctx: BastionContext| {
+                launched_.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert_eq!(children.elems().len(), 3);
+    assert_eq!(launched.load(Ordering::SeqCst), 0);
+
+    let child = &children.elems()[1];
+    assert!(child.tell_anonymously("Wake up!").is_ok());
+    wait_until(|| launched.load(Ordering::SeqCst) > 0);
+    assert_eq!(launched.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn retires_stopped_isolated_elems() {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let launched_ = launched.clone();
+
+    let children = Bastion::children(move |children| {
+        children
+            .with_redundancy(3)
+            .with_lazy_elems()
+            .with_isolated_elems()
+            .with_exec(move |ctx: BastionContext| {
+                launched_.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // Only the stopped element is removed from the group.
+    let stopped = &children.elems()[0];
+    stopped.stop().expect("Couldn't send the message.");
+    wait_until(|| stopped.tell_anonymously(()).is_err());
+
+    let child = &children.elems()[1];
+    assert!(child.tell_anonymously("Wake up!").is_ok());
+    wait_until(|| launched.load(Ordering::SeqCst) > 0);
+    assert_eq!(launched.load(Ordering::SeqCst), 1);
+}
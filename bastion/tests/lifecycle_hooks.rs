@@ -0,0 +1,84 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn hooks_run_around_elems() {
+    init_start();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let (before_start, after_restart, after_stop, exec) = (
+        events.clone(),
+        events.clone(),
+        events.clone(),
+        events.clone(),
+    );
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.children(move |children| {
+            children
+                .with_before_start_hook(move |_| {
+                    let events = before_start.clone();
+                    async move {
+                        events.lock().unwrap().push("before_start");
+                    }
+                })
+                .with_after_restart_hook(move |_| {
+                    let events = after_restart.clone();
+                    async move {
+                        events.lock().unwrap().push("after_restart");
+                    }
+                })
+                .with_after_stop_hook(move |_| {
+                    let events = after_stop.clone();
+                    async move {
+                        events.lock().unwrap().push("after_stop");
+                    }
+                })
+                .with_exec(move |ctx: BastionContext| {
+                    let events = exec.clone();
+                    async move {
+                        let first = {
+                            let mut events = events.lock().unwrap();
+                            events.push("elem");
+                            events.len() == 2
+                        };
+                        // The element faults the first time, making
+                        // the group restart...
+                        if first {
+                            return Err(());
+                        }
+
+                        loop {
+                            ctx.recv().await?;
+                        }
+                    }
+                })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+    wait_until(|| events.lock().unwrap().len() == 5);
+
+    // The restarted group is referenced by a new `ChildrenRef`.
+    wait_until(|| {
+        supervisor
+            .children_groups()
+            .pop()
+            .map_or(false, |children_ref| children_ref.stop().is_ok())
+    });
+    wait_until(|| events.lock().unwrap().len() == 6);
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            "before_start",
+            "elem",
+            "after_stop",
+            "after_restart",
+            "elem",
+            "after_stop"
+        ]
+    );
+}
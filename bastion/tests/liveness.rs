@@ -0,0 +1,32 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+
+#[test]
+fn detects_stopped_elems() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| {
+        children.with_redundancy(2).with_isolated_elems().with_exec(
+            |ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            },
+        )
+    })
+    .expect("Couldn't create the children group.");
+
+    let stopped = children_ref.elems()[0].clone();
+    let running = children_ref.elems()[1].clone();
+    assert!(stopped.is_alive());
+    assert!(run!(stopped.ping()));
+
+    stopped.stop().expect("Couldn't send the message.");
+    wait_until(|| !stopped.is_alive());
+
+    assert!(!run!(stopped.ping()));
+    assert!(running.is_alive());
+    assert!(run!(running.ping()));
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn bounds_mailboxes() {
+    init_start();
+
+    let stored = Arc::new(AtomicUsize::new(0));
+    let wake = Arc::new(AtomicBool::new(false));
+
+    let (stored_, wake_) = (stored.clone(), wake.clone());
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_mailbox_limit(2, OverflowPolicy::DropNewest)
+            .with_exec(move |ctx: BastionContext| {
+                let (stored, wake) = (stored_.clone(), wake_.clone());
+                async move {
+                    // Not retrieving messages for a while...
+                    while !wake.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+                    stored.store(ctx.drain().await.len(), Ordering::SeqCst);
+
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child_ref = &children_ref.elems()[0];
+    for i in 0..5usize {
+        child_ref
+            .tell_anonymously(i)
+            .expect("Couldn't send the message.");
+    }
+    wait_until(|| child_ref.mailbox_stats().depth() == 2);
+    wake.store(true, Ordering::SeqCst);
+    wait_until(|| stored.load(Ordering::SeqCst) > 0);
+
+    assert_eq!(stored.load(Ordering::SeqCst), 2);
+}
+
+// Starts a group whose elements don't retrieve their messages
+// until `wake` is set, and then store the ones they receive
+// in `received`.
+fn sleeping_group(
+    redundancy: usize,
+    policy: OverflowPolicy,
+    wake: Arc<AtomicBool>,
+    received: Arc<Mutex<Vec<usize>>>,
+) -> ChildrenRef {
+    Bastion::children(move |children| {
+        children
+            .with_redundancy(redundancy)
+            .with_mailbox_limit(2, policy)
+            .with_exec(move |ctx: BastionContext| {
+                let (wake, received) = (wake.clone(), received.clone());
+                async move {
+                    while !wake.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: usize => {
+                                received.lock().unwrap().push(msg);
+                            };
+                            ref msg: usize => {
+                                received.lock().unwrap().push(*msg);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.")
+}
+
+#[test]
+fn fails_sends_when_full() {
+    init_start();
+
+    let wake = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let children_ref = sleeping_group(2, OverflowPolicy::Fail, wake.clone(), received.clone());
+
+    let elems = children_ref.elems();
+    elems[0].tell_anonymously(0usize).expect("Couldn't send the message.");
+    elems[0].tell_anonymously(1usize).expect("Couldn't send the message.");
+    assert_eq!(elems[0].tell_anonymously(2usize), Err(2));
+
+    // The other element has room, but none of them receives a
+    // broadcasted message if one of them doesn't...
+    assert_eq!(children_ref.broadcast(3usize), Err(3));
+    // ...while a message sent to one of them goes to the one
+    // which has room.
+    children_ref.send_one(4usize).expect("Couldn't send the message.");
+    children_ref.send_one(5usize).expect("Couldn't send the message.");
+    assert_eq!(children_ref.send_one(6usize), Err(6));
+
+    wake.store(true, Ordering::SeqCst);
+    wait_until(|| received.lock().unwrap().len() == 4);
+    thread::sleep(Duration::from_millis(100));
+
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    assert_eq!(received, vec![0, 1, 4, 5]);
+}
+
+#[test]
+fn blocks_senders_until_room() {
+    init_start();
+
+    let wake = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let children_ref = sleeping_group(1, OverflowPolicy::Block, wake.clone(), received.clone());
+
+    let sent = Arc::new(AtomicUsize::new(0));
+    let (children_ref_, sent_) = (children_ref.clone(), sent.clone());
+    let sender = thread::spawn(move || {
+        run!(async {
+            for i in 0..8usize {
+                children_ref_
+                    .broadcast_async(i)
+                    .await
+                    .expect("Couldn't send the message.");
+                sent_.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+    });
+
+    // The sender waits while the element has as many messages
+    // waiting as its mailbox can store...
+    wait_until(|| sent.load(Ordering::SeqCst) == 2);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(sent.load(Ordering::SeqCst), 2);
+    assert_eq!(children_ref.broadcast(8usize), Err(8));
+
+    // ...and sends the other ones once it retrieves them.
+    wake.store(true, Ordering::SeqCst);
+    sender.join().unwrap();
+    wait_until(|| received.lock().unwrap().len() == 8);
+
+    assert_eq!(*received.lock().unwrap(), (0..8).collect::<Vec<_>>());
+}
+
+#[test]
+fn broadcast_async_waits_for_slowest() {
+    init_start();
+
+    let wake = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(vec![Vec::new(), Vec::new()]));
+
+    let (wake_, received_) = (wake.clone(), received.clone());
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(2)
+            .with_mailbox_limit(2, OverflowPolicy::Block)
+            .with_indexed_exec(move |index, ctx: BastionContext| {
+                let (wake, received) = (wake_.clone(), received_.clone());
+                async move {
+                    // Only the second element is slow...
+                    while index == 1 && !wake.load(Ordering::SeqCst) {
+                        Delay::new(Duration::from_millis(10)).await;
+                    }
+                    loop {
+                        msg! { ctx.recv().await?,
+                            ref msg: usize => {
+                                received.lock().unwrap()[index].push(*msg);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let sent = Arc::new(AtomicUsize::new(0));
+    let (children_ref_, sent_) = (children_ref.clone(), sent.clone());
+    let sender = thread::spawn(move || {
+        run!(async {
+            for i in 0..8usize {
+                children_ref_
+                    .broadcast_async(i)
+                    .await
+                    .expect("Couldn't send the message.");
+                sent_.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+    });
+
+    // ...which slows the sender down, the first element only
+    // receiving the messages that the second one has room for.
+    wait_until(|| sent.load(Ordering::SeqCst) == 2);
+    wait_until(|| received.lock().unwrap()[0].len() == 2);
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(sent.load(Ordering::SeqCst), 2);
+    assert_eq!(received.lock().unwrap()[0], vec![0, 1]);
+
+    // Both elements receive all the messages once it catches up.
+    wake.store(true, Ordering::SeqCst);
+    sender.join().unwrap();
+    wait_until(|| received.lock().unwrap()[1].len() == 8);
+    wait_until(|| received.lock().unwrap()[0].len() == 8);
+
+    let received = received.lock().unwrap();
+    assert_eq!(received[0], (0..8).collect::<Vec<_>>());
+    assert_eq!(received[1], (0..8).collect::<Vec<_>>());
+}
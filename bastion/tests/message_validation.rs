@@ -0,0 +1,62 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+
+#[test]
+fn rejects_invalid_messages() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_validator(|msg: &Msg| msg.peek::<u8>().is_some())
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    assert!(children.broadcast(1u8).is_ok());
+    assert_eq!(children.broadcast("invalid"), Err("invalid"));
+
+    let child = &children.elems()[0];
+    assert!(child.tell_anonymously(2u8).is_ok());
+    assert_eq!(child.tell_anonymously(3u16), Err(3u16));
+    assert!(child.ask_anonymously(4u32).is_err());
+}
+
+#[test]
+fn rejects_large_messages() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_max_message_size::<String>(4)
+            .with_max_message_size::<Vec<u32>>(8)
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    // The heap allocations of the messages are counted...
+    assert!(children.broadcast("four".to_string()).is_ok());
+    assert_eq!(
+        children.broadcast("five!".to_string()),
+        Err("five!".to_string())
+    );
+    assert!(children.broadcast(vec![1u32, 2]).is_ok());
+    assert_eq!(children.broadcast(vec![1u32, 2, 3]), Err(vec![1u32, 2, 3]));
+
+    let child = &children.elems()[0];
+    assert_eq!(
+        child.tell_anonymously("too large".to_string()),
+        Err("too large".to_string())
+    );
+    // ...while the messages of other types aren't checked.
+    assert!(children.broadcast([0u64; 4]).is_ok());
+}
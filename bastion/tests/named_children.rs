@@ -0,0 +1,44 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for};
+
+#[test]
+fn looks_up_named_groups() {
+    init_start();
+
+    let resizer = Bastion::children_named("resizer", |children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    assert_eq!(resizer.name(), Some("resizer"));
+    assert!(format!("{:?}", resizer).contains("resizer"));
+
+    let mut nested = None;
+    Bastion::supervisor(|sp| {
+        nested = Some(sp.children_ref(|children| children.with_name("nested")));
+        sp
+    })
+    .expect("Couldn't create the supervisor.");
+    let nested = nested.unwrap();
+
+    let found = wait_for(|| Bastion::children_by_name("resizer"));
+    assert_eq!(found.id(), resizer.id());
+    let found = wait_for(|| Bastion::children_by_name("nested"));
+    assert_eq!(found.id(), nested.id());
+    assert!(Bastion::children_by_name("unknown").is_none());
+
+    let tree = Bastion::tree();
+    let node = tree
+        .supervisors()
+        .iter()
+        .flat_map(|supervisor| supervisor.children_groups())
+        .find(|children| children.id() == resizer.id())
+        .expect("Couldn't find the group in the tree.");
+    assert_eq!(node.name(), Some("resizer"));
+    assert!(tree.to_string().contains("\"resizer\""));
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn queues_msgs_while_paused() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(2)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received_.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        received.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    children_ref.pause().expect("Couldn't send the message.");
+    for msg in 0..3usize {
+        children_ref
+            .broadcast(msg)
+            .expect("Couldn't send the message.");
+    }
+    // The messages are queued without being handled.
+    for child_ref in children_ref.elems() {
+        wait_until(|| child_ref.mailbox_stats().depth() == 3);
+    }
+    assert_eq!(received.load(Ordering::SeqCst), 0);
+
+    // The paused elements are still alive.
+    for child_ref in children_ref.elems() {
+        assert!(run!(child_ref.ping()));
+    }
+
+    children_ref.resume().expect("Couldn't send the message.");
+    wait_until(|| received.load(Ordering::SeqCst) == 6);
+}
+
+#[test]
+fn drains_paused_group_when_stopped() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_shutdown_mode(ShutdownMode::Drain)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received_.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        received.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let child_ref = children_ref.elems()[0].clone();
+
+    children_ref.pause().expect("Couldn't send the message.");
+    for msg in 0..3usize {
+        child_ref
+            .tell_anonymously(msg)
+            .expect("Couldn't send the message.");
+    }
+    wait_until(|| child_ref.mailbox_stats().depth() == 3);
+
+    // The element is resumed to handle the messages that
+    // queued up before stopping.
+    children_ref.stop().expect("Couldn't send the message.");
+    wait_until(|| !child_ref.is_alive());
+    assert_eq!(received.load(Ordering::SeqCst), 3);
+}
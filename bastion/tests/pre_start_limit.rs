@@ -0,0 +1,57 @@
+mod common;
+
+use bastion::prelude::*;
+use common::wait_until;
+use std::sync::{Arc, Mutex};
+
+// The system is only started once the messages were sent, so
+// this file only contains one test.
+#[test]
+fn refuses_msgs_before_start() {
+    Bastion::init();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_pre_start_limit(2, PreStartOverflow::Fail)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received_.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: usize => {
+                                received.lock().unwrap().push(msg);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let child_ref = children_ref.elems()[0].clone();
+
+    for msg in 1..=2usize {
+        child_ref
+            .tell_anonymously(msg)
+            .expect("Couldn't send the message.");
+    }
+    // The limit is reached once the element received the
+    // first messages.
+    wait_until(|| child_ref.tell_anonymously(3usize).is_err());
+
+    Bastion::start();
+    wait_until(|| received.lock().unwrap().len() == 2);
+    assert_eq!(*received.lock().unwrap(), vec![1, 2]);
+
+    child_ref
+        .tell_anonymously(4usize)
+        .expect("Couldn't send the message.");
+    wait_until(|| received.lock().unwrap().len() == 3);
+    assert_eq!(*received.lock().unwrap(), vec![1, 2, 4]);
+
+    Bastion::stop();
+    Bastion::block_until_stopped();
+}
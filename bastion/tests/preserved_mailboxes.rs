@@ -0,0 +1,64 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[test]
+fn delivers_msgs_to_restarted_elem() {
+    init_start();
+
+    let launched = Arc::new(AtomicUsize::new(0));
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let fault = Arc::new(AtomicBool::new(false));
+
+    let launched_ = launched.clone();
+    let received_ = received.clone();
+    let fault_ = fault.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_isolated_elems()
+            .with_preserved_mailboxes()
+            .with_exec(move |ctx: BastionContext| {
+                let launched = launched_.clone();
+                let received = received_.clone();
+                let fault = fault_.clone();
+                async move {
+                    // The first incarnation faults without handling
+                    // its messages.
+                    if launched.fetch_add(1, Ordering::SeqCst) == 0 {
+                        while !fault.load(Ordering::SeqCst) {
+                            Delay::new(Duration::from_millis(10)).await;
+                        }
+                        return Err(());
+                    }
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            msg: usize => {
+                                received.lock().unwrap().push(msg);
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child_ref = children_ref.elems()[0].clone();
+    for msg in 1..=2usize {
+        child_ref
+            .tell_anonymously(msg)
+            .expect("Couldn't send the message.");
+    }
+    wait_until(|| child_ref.mailbox_stats().depth() == 2);
+    fault.store(true, Ordering::SeqCst);
+    wait_until(|| received.lock().unwrap().len() == 2);
+
+    assert_eq!(launched.load(Ordering::SeqCst), 2);
+    assert_eq!(*received.lock().unwrap(), vec![1, 2]);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+use futures_timer::Delay;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn waits_for_all_elems() {
+    init_start();
+
+    let initialized = Arc::new(AtomicUsize::new(0));
+
+    let initialized_ = initialized.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(3)
+            .with_exec(move |ctx: BastionContext| {
+                let initialized = initialized_.clone();
+                async move {
+                    Delay::new(Duration::from_millis(200)).await;
+                    initialized.fetch_add(1, Ordering::SeqCst);
+                    ctx.notify_started().expect("Couldn't send the message.");
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let ready = children_ref.ready().expect("Couldn't send the message.");
+    run!(ready).expect("The group was dropped.");
+    assert_eq!(initialized.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn fails_once_terminated() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_exec(|ctx: BastionContext| async move {
+                // Never tells it is ready.
+                ctx.recv().await?;
+                Ok(())
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let ready = children_ref.ready().expect("Couldn't send the message.");
+    children_ref.stop().expect("Couldn't send the message.");
+
+    assert!(run!(ready).is_err());
+}
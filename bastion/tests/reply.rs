@@ -0,0 +1,99 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn spawn_doubler() -> ChildrenRef {
+    Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            assert!(ctx.reply(0usize).is_err());
+
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: usize => {
+                        ctx.reply(msg * 2).expect("Couldn't reply.");
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.")
+}
+
+#[test]
+fn replies_to_current_sender() {
+    init_start();
+
+    let doubler = spawn_doubler();
+    let doubler_ref = doubler.elems()[0].clone();
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let received_ = received.clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let doubler_ref = doubler_ref.clone();
+            let received = received_.clone();
+            async move {
+                ctx.tell(&doubler_ref, 21usize)
+                    .expect("Couldn't send the message.");
+
+                msg! { ctx.recv().await?,
+                    msg: usize => {
+                        let sender = ctx.current_sender().expect("No sender.");
+                        assert_eq!(sender.path().elem(), doubler_ref.path().elem());
+                        received.store(msg, Ordering::SeqCst);
+                    };
+                    _: _ => ();
+                }
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    wait_until(|| received.load(Ordering::SeqCst) == 42);
+}
+
+#[test]
+fn tells_validate_messages() {
+    init_start();
+
+    let picky = Bastion::children(|children| {
+        children
+            .with_validator(|msg: &Msg| msg.peek::<usize>().is_some())
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let picky_ref = picky.elems()[0].clone();
+    let rejected = Arc::new(AtomicUsize::new(0));
+
+    let rejected_ = rejected.clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let picky_ref = picky_ref.clone();
+            let rejected = rejected_.clone();
+            async move {
+                ctx.tell(&picky_ref, 1usize)
+                    .expect("Couldn't send the message.");
+                // The messages that the element's group rejects
+                // are given back.
+                assert_eq!(ctx.tell(&picky_ref, "invalid"), Err("invalid"));
+                assert_eq!(ctx.ask(&picky_ref, 2u8).err(), Some(2u8));
+                rejected.store(2, Ordering::SeqCst);
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    wait_until(|| rejected.load(Ordering::SeqCst) == 2);
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn restarts_only_the_elem() {
+    init_start();
+
+    let launched = Arc::new(Mutex::new(Vec::new()));
+
+    let launched_ = launched.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(2)
+            .with_exec(move |ctx: BastionContext| {
+                let launched = launched_.clone();
+                async move {
+                    launched.lock().unwrap().push(ctx.current().id().clone());
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| launched.lock().unwrap().len() == 2);
+
+    let restarted = children_ref.elems()[0].clone();
+    restarted.restart().expect("Couldn't send the message.");
+    wait_until(|| launched.lock().unwrap().len() == 3);
+
+    let launched = launched.lock().unwrap();
+    assert!(!launched[2..].contains(restarted.id()));
+
+    // The group itself wasn't restarted.
+    let tree = Bastion::tree();
+    let node = tree
+        .supervisors()
+        .iter()
+        .flat_map(|supervisor| supervisor.children_groups())
+        .find(|children| children.id() == children_ref.id())
+        .expect("Couldn't find the group in the tree.");
+    assert_eq!(node.restarts(), 0);
+    assert_eq!(node.redundancy(), 2);
+}
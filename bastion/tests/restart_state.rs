@@ -0,0 +1,250 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for, wait_restarted, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn restores_saved_state() {
+    init_start();
+
+    let counts = Arc::new(Mutex::new(Vec::new()));
+    let hooks = Arc::new(AtomicUsize::new(0));
+
+    let counts_ = counts.clone();
+    let hooks_ = hooks.clone();
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.children(move |children| {
+            let hooks = hooks_.clone();
+            children
+                .with_before_restart_hook(move |snapshot: Snapshot| {
+                    hooks.fetch_add(1, Ordering::SeqCst);
+                    async move { Some(snapshot) }
+                })
+                .with_exec(move |ctx: BastionContext| {
+                    let counts = counts_.clone();
+                    async move {
+                        let mut count = ctx.take_restored_state::<usize>().unwrap_or(0);
+                        loop {
+                            let msg = ctx.recv().await?;
+                            count += 1;
+                            ctx.save_state(count);
+                            counts.lock().unwrap().push(count);
+
+                            msg! { msg,
+                                ref _msg: &'static str => return Err(());
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+    let children_ref = wait_for(|| supervisor.children_groups().pop());
+
+    children_ref
+        .broadcast(1usize)
+        .expect("Couldn't send the message.");
+    children_ref
+        .broadcast("Fault!")
+        .expect("Couldn't send the message.");
+
+    let children_ref = wait_restarted(&supervisor, 0, children_ref.id());
+    children_ref
+        .broadcast(2usize)
+        .expect("Couldn't send the message.");
+    wait_until(|| counts.lock().unwrap().len() == 3);
+
+    assert_eq!(*counts.lock().unwrap(), vec![1, 2, 3]);
+    assert_eq!(hooks.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn restores_state_by_index() {
+    init_start();
+
+    let restored = Arc::new(Mutex::new(Vec::new()));
+    let saved = Arc::new(AtomicUsize::new(0));
+
+    let restored_ = restored.clone();
+    let saved_ = saved.clone();
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.children(move |children| {
+            children
+                .with_redundancy(2)
+                .with_exec(move |ctx: BastionContext| {
+                    let restored = restored_.clone();
+                    let saved = saved_.clone();
+                    async move {
+                        let index = ctx.current().index();
+                        let state = ctx.take_restored_state::<usize>();
+                        restored.lock().unwrap().push((index, state));
+                        loop {
+                            let msg = ctx.recv().await?;
+                            msg! { msg,
+                                ref _msg: &'static str => return Err(());
+                                _: _ => {
+                                    // Only the first element saves
+                                    // its state.
+                                    if index == 0 {
+                                        ctx.save_state(index);
+                                        saved.fetch_add(1, Ordering::SeqCst);
+                                    }
+                                };
+                            }
+                        }
+                    }
+                })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+    let children_ref = wait_for(|| supervisor.children_groups().pop());
+
+    children_ref
+        .broadcast(())
+        .expect("Couldn't send the message.");
+    wait_until(|| saved.load(Ordering::SeqCst) == 1);
+    children_ref
+        .broadcast("Fault!")
+        .expect("Couldn't send the message.");
+
+    wait_restarted(&supervisor, 0, children_ref.id());
+    wait_until(|| restored.lock().unwrap().len() == 4);
+
+    // The element that didn't save its state doesn't get the
+    // one saved by the other element.
+    let mut restored = restored.lock().unwrap().split_off(2);
+    restored.sort();
+    assert_eq!(restored, vec![(0, Some(0)), (1, None)]);
+}
+
+#[test]
+fn hook_can_discard_state() {
+    init_start();
+
+    let counts = Arc::new(Mutex::new(Vec::new()));
+
+    let counts_ = counts.clone();
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.children(move |children| {
+            children
+                .with_before_restart_hook(|_: Snapshot| async { None })
+                .with_exec(move |ctx: BastionContext| {
+                    let counts = counts_.clone();
+                    async move {
+                        let mut count = ctx.take_restored_state::<usize>().unwrap_or(0);
+                        loop {
+                            let msg = ctx.recv().await?;
+                            count += 1;
+                            ctx.save_state(count);
+                            counts.lock().unwrap().push(count);
+
+                            msg! { msg,
+                                ref _msg: &'static str => return Err(());
+                                _: _ => ();
+                            }
+                        }
+                    }
+                })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+    let children_ref = wait_for(|| supervisor.children_groups().pop());
+
+    children_ref
+        .broadcast("Fault!")
+        .expect("Couldn't send the message.");
+
+    let children_ref = wait_restarted(&supervisor, 0, children_ref.id());
+    children_ref
+        .broadcast(1usize)
+        .expect("Couldn't send the message.");
+    wait_until(|| counts.lock().unwrap().len() == 2);
+
+    assert_eq!(*counts.lock().unwrap(), vec![1, 1]);
+}
+
+#[test]
+fn clones_seed_state() {
+    init_start();
+
+    let seeds = Arc::new(Mutex::new(Vec::new()));
+
+    let seeds_ = seeds.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(2)
+            .with_seed_state(vec![1usize, 2, 3])
+            .with_exec(move |ctx: BastionContext| {
+                let seeds = seeds_.clone();
+                async move {
+                    let seed = ctx.take_seed_state::<Vec<usize>>();
+                    seeds.lock().unwrap().push(seed);
+                    // The seed state can only be taken once.
+                    assert!(ctx.take_seed_state::<Vec<usize>>().is_none());
+
+                    msg! { ctx.recv().await?,
+                        ref _msg: &'static str => return Err(());
+                        _: _ => ();
+                    }
+
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| seeds.lock().unwrap().len() == 2);
+
+    // The elements replacing the faulted ones get the seed state
+    // too.
+    children_ref
+        .broadcast("Fault!")
+        .expect("Couldn't send the message.");
+    wait_until(|| seeds.lock().unwrap().len() == 4);
+
+    let seeds = seeds.lock().unwrap();
+    assert!(seeds.iter().all(|seed| seed == &Some(vec![1, 2, 3])));
+}
+
+#[test]
+fn restores_state_of_isolated_elems() {
+    init_start();
+
+    let restored = Arc::new(Mutex::new(Vec::new()));
+
+    let restored_ = restored.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_isolated_elems()
+            .with_exec(move |ctx: BastionContext| {
+                let restored = restored_.clone();
+                async move {
+                    let checkpoint = ctx.restored_state::<Vec<u8>>();
+                    // The snapshot is left in place.
+                    assert_eq!(ctx.restored_state::<Vec<u8>>(), checkpoint);
+                    restored.lock().unwrap().push(checkpoint);
+
+                    loop {
+                        msg! { ctx.recv().await?,
+                            ref msg: u8 => ctx.save_state(vec![*msg]);
+                            ref _msg: &'static str => return Err(());
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    children_ref
+        .broadcast(42u8)
+        .expect("Couldn't send the message.");
+    children_ref
+        .broadcast("Fault!")
+        .expect("Couldn't send the message.");
+    wait_until(|| restored.lock().unwrap().len() == 2);
+
+    assert_eq!(*restored.lock().unwrap(), vec![None, Some(vec![42])]);
+}
@@ -7,6 +7,9 @@ fn check_default_values() {
 
     assert_eq!(restart_strategy.restart_policy(), RestartPolicy::Always);
     assert_eq!(restart_strategy.strategy(), ActorRestartStrategy::Immediate);
+    assert_eq!(restart_strategy.restart_intensity(), None);
+    assert_eq!(restart_strategy.restart_jitter(), None);
+    assert_eq!(restart_strategy.circuit_breaker(), None);
 }
 
 #[test]
@@ -43,3 +46,36 @@ fn override_restart_strategy_and_policy() {
     assert_eq!(restart_strategy.restart_policy(), policy);
     assert_eq!(restart_strategy.strategy(), strategy);
 }
+
+#[test]
+fn override_restart_intensity() {
+    let restart_strategy =
+        RestartStrategy::default().with_restart_intensity(3, Duration::from_secs(5));
+
+    assert_eq!(restart_strategy.restart_policy(), RestartPolicy::Always);
+    assert_eq!(
+        restart_strategy.restart_intensity(),
+        Some((3, Duration::from_secs(5)))
+    );
+}
+
+#[test]
+fn override_restart_jitter() {
+    let jitter = Duration::from_millis(500);
+    let restart_strategy = RestartStrategy::default().with_restart_jitter(jitter);
+
+    assert_eq!(restart_strategy.restart_jitter(), Some(jitter));
+    assert_eq!(restart_strategy.strategy(), ActorRestartStrategy::Immediate);
+}
+
+#[test]
+fn override_circuit_breaker() {
+    let restart_strategy =
+        RestartStrategy::default().with_circuit_breaker(3, Duration::from_secs(10));
+
+    assert_eq!(restart_strategy.restart_policy(), RestartPolicy::Always);
+    assert_eq!(
+        restart_strategy.circuit_breaker(),
+        Some((3, Duration::from_secs(10)))
+    );
+}
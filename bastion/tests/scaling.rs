@@ -0,0 +1,158 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::{Arc, Mutex};
+
+// Removes the element's identifier from the running ones once
+// its future is dropped.
+struct Running {
+    id: BastionId,
+    running: Arc<Mutex<Vec<BastionId>>>,
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.running.lock().unwrap().retain(|id| id != &self.id);
+    }
+}
+
+// Blocks until `len` elements are running.
+fn wait_running(running: &Mutex<Vec<BastionId>>, len: usize) {
+    wait_until(|| running.lock().unwrap().len() == len);
+}
+
+#[test]
+fn scales_running_group() {
+    init_start();
+
+    let running = Arc::new(Mutex::new(Vec::new()));
+
+    let running_ = running.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(2)
+            .with_exec(move |ctx: BastionContext| {
+                let running = running_.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    running.lock().unwrap().push(id.clone());
+                    let _running = Running { id, running };
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    wait_running(&running, 2);
+
+    children_ref
+        .scale_to(4)
+        .expect("Couldn't send the message.");
+    wait_running(&running, 4);
+
+    children_ref
+        .scale_to(1)
+        .expect("Couldn't send the message.");
+    wait_running(&running, 1);
+
+    children_ref.add_elem().expect("Couldn't send the message.");
+    wait_running(&running, 2);
+
+    let removed = running.lock().unwrap()[0].clone();
+    children_ref
+        .remove_elem(&removed)
+        .expect("Couldn't send the message.");
+    wait_running(&running, 1);
+    let remaining = running.lock().unwrap().clone();
+    assert!(!remaining.contains(&removed));
+
+    // The last element can't be removed (the element added
+    // afterwards is launched once the removal was refused).
+    children_ref
+        .remove_elem(&remaining[0])
+        .expect("Couldn't send the message.");
+    children_ref.add_elem().expect("Couldn't send the message.");
+    wait_running(&running, 2);
+    assert!(running.lock().unwrap().contains(&remaining[0]));
+}
+
+#[test]
+fn populates_empty_group() {
+    init_start();
+
+    let running = Arc::new(Mutex::new(Vec::new()));
+
+    let running_ = running.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(0)
+            .with_exec(move |ctx: BastionContext| {
+                let running = running_.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    running.lock().unwrap().push(id.clone());
+                    let _running = Running { id, running };
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    assert!(children_ref.elems().is_empty());
+    assert!(running.lock().unwrap().is_empty());
+
+    children_ref
+        .scale_to(2)
+        .expect("Couldn't send the message.");
+    wait_running(&running, 2);
+
+    children_ref.add_elem().expect("Couldn't send the message.");
+    wait_running(&running, 3);
+}
+
+#[test]
+fn scales_empty_group_back_to_zero() {
+    init_start();
+
+    let running = Arc::new(Mutex::new(Vec::new()));
+
+    let running_ = running.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(0)
+            .with_exec(move |ctx: BastionContext| {
+                let running = running_.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    running.lock().unwrap().push(id.clone());
+                    let _running = Running { id, running };
+
+                    loop {
+                        ctx.recv().await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    children_ref
+        .scale_to(2)
+        .expect("Couldn't send the message.");
+    wait_running(&running, 2);
+
+    children_ref
+        .scale_to(0)
+        .expect("Couldn't send the message.");
+    wait_running(&running, 0);
+
+    // The group is still running without any element.
+    children_ref
+        .scale_to(1)
+        .expect("Couldn't send the message.");
+    wait_running(&running, 1);
+}
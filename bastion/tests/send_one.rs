@@ -0,0 +1,107 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use futures_timer::Delay;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Returns how many messages the elements received.
+fn total(received: &Mutex<HashMap<BastionId, usize>>) -> usize {
+    received.lock().unwrap().values().sum()
+}
+
+#[test]
+fn delivers_to_one_elem() {
+    init_start();
+
+    let received = Arc::new(Mutex::new(HashMap::new()));
+
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(3)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received_.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        let id = ctx.current().id().clone();
+                        *received.lock().unwrap().entry(id).or_insert(0) += 1;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    for msg in 0..6usize {
+        children_ref
+            .send_one(msg)
+            .expect("Couldn't send the message.");
+    }
+    wait_until(|| total(&received) == 6);
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 3);
+    assert!(received.values().all(|count| *count == 2));
+}
+
+#[test]
+fn delivers_to_least_loaded_elem() {
+    init_start();
+
+    let slow = Arc::new(Mutex::new(None));
+    let released = Arc::new(AtomicBool::new(false));
+    let received = Arc::new(Mutex::new(HashMap::new()));
+
+    let slow_ = slow.clone();
+    let released_ = released.clone();
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(2)
+            .with_dispatch_mode(DispatchMode::LeastLoaded)
+            .with_exec(move |ctx: BastionContext| {
+                let slow = slow_.clone();
+                let released = released_.clone();
+                let received = received_.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        let id = ctx.current().id().clone();
+                        *received.lock().unwrap().entry(id.clone()).or_insert(0) += 1;
+
+                        // One of the elements doesn't handle its
+                        // messages until it is released...
+                        let is_slow = slow.lock().unwrap().as_ref() == Some(&id);
+                        while is_slow && !released.load(Ordering::SeqCst) {
+                            Delay::new(Duration::from_millis(10)).await;
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let slow_id = children_ref.elems()[0].id().clone();
+    let fast_id = children_ref.elems()[1].id().clone();
+    *slow.lock().unwrap() = Some(slow_id.clone());
+
+    let slow_ref = children_ref.elems()[0].clone();
+    for msg in 0..10usize {
+        children_ref
+            .send_one(msg)
+            .expect("Couldn't send the message.");
+        // ...so that the messages pile up in its mailbox.
+        wait_until(|| total(&received) + slow_ref.mailbox_stats().depth() == msg + 1);
+    }
+    released.store(true, Ordering::SeqCst);
+    wait_until(|| total(&received) == 10);
+
+    let received = received.lock().unwrap();
+    let slow_count = received.get(&slow_id).cloned().unwrap_or(0);
+    let fast_count = received.get(&fast_id).cloned().unwrap_or(0);
+    assert_eq!(slow_count + fast_count, 10);
+    assert!(slow_count < fast_count);
+}
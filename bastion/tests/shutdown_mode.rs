@@ -0,0 +1,289 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn drains_mailbox_before_stopping() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let received_ = received.clone();
+
+    let children = Bastion::children(move |children| {
+        children
+            .with_shutdown_mode(ShutdownMode::Drain)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received_.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        received.fetch_add(1, Ordering::SeqCst);
+
+                        ctx.blocking(|| thread::sleep(Duration::from_millis(10)))
+                            .await?;
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    for i in 0..10u8 {
+        assert!(child.tell_anonymously(i).is_ok());
+    }
+    assert!(children.stop().is_ok());
+
+    wait_until(|| received.load(Ordering::SeqCst) == 10);
+}
+
+#[test]
+fn stops_before_deadline() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let shutdown = children
+        .stop_with_deadline(Duration::from_secs(1))
+        .expect("Couldn't send the message.");
+    assert_eq!(run!(shutdown), Ok(true));
+}
+
+#[test]
+fn kills_after_deadline() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children.with_shutdown_mode(ShutdownMode::Drain).with_exec(
+            |ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                    ctx.blocking(|| thread::sleep(Duration::from_millis(100)))
+                        .await?;
+                }
+            },
+        )
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    for i in 0..10u8 {
+        assert!(child.tell_anonymously(i).is_ok());
+    }
+
+    let shutdown = children
+        .stop_with_deadline(Duration::from_millis(100))
+        .expect("Couldn't send the message.");
+    assert_eq!(run!(shutdown), Ok(false));
+}
+
+#[test]
+fn kills_without_draining() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_shutdown_mode(ShutdownMode::Drain)
+            .with_exec(|_: BastionContext| async move {
+                // The messages are never retrieved...
+                futures::future::pending::<()>().await;
+                Ok(())
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    children.elems()[0]
+        .tell_anonymously("Work")
+        .expect("Couldn't send the message.");
+    let stopped = children.stopped().expect("Couldn't send the message.");
+    children.kill().expect("Couldn't send the message.");
+
+    let terminated = run!(stopped).expect("The group was dropped.");
+    match terminated.reason() {
+        TerminationReason::Killed => (),
+        reason => panic!("Unexpected reason: {:?}", reason),
+    }
+}
+
+#[test]
+fn stops_supervisor_before_deadline() {
+    init_start();
+
+    let supervisor = Bastion::supervisor(|sp| {
+        sp.children(|children| {
+            children.with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    let shutdown = supervisor
+        .stop_with_deadline(Duration::from_secs(1))
+        .expect("Couldn't send the message.");
+    assert_eq!(run!(shutdown), Ok(true));
+}
+
+#[test]
+fn stops_elem_within_timeout() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let shutdown = children.elems()[0]
+        .stop_with_timeout(Duration::from_secs(1))
+        .expect("Couldn't send the message.");
+    assert_eq!(run!(shutdown), Ok(true));
+}
+
+#[test]
+fn kills_elem_after_timeout() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children.with_exec(|_: BastionContext| {
+            async move {
+                // The messages are never retrieved...
+                futures::future::pending::<()>().await;
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child_ref = &children.elems()[0];
+    child_ref
+        .tell_anonymously("Work")
+        .expect("Couldn't send the message.");
+    let shutdown = child_ref
+        .stop_with_timeout(Duration::from_millis(100))
+        .expect("Couldn't send the message.");
+    assert_eq!(run!(shutdown), Ok(false));
+}
+
+#[test]
+fn prepares_elems_before_stopping() {
+    init_start();
+
+    let prepared = Arc::new(AtomicUsize::new(0));
+    let prepared_ = prepared.clone();
+
+    let children = Bastion::children(move |children| {
+        children
+            .with_redundancy(2)
+            .with_prepare_stop(Duration::from_secs(5))
+            .with_exec(move |ctx: BastionContext| {
+                let prepared = prepared_.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: PrepareStop =!> {
+                                prepared.fetch_add(1, Ordering::SeqCst);
+                                answer!(ctx, ()).ok();
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let stopped = children.stopped().expect("Couldn't send the message.");
+    children.stop().expect("Couldn't send the message.");
+    run!(stopped).expect("The group was dropped.");
+
+    assert_eq!(prepared.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn stops_unprepared_elems_after_deadline() {
+    init_start();
+
+    let children = Bastion::children(|children| {
+        children
+            .with_prepare_stop(Duration::from_millis(100))
+            .with_exec(|_: BastionContext| {
+                async move {
+                    // The `PrepareStop` message is never retrieved...
+                    futures::future::pending::<()>().await;
+                    Ok(())
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let stopped = children.stopped().expect("Couldn't send the message.");
+    children.stop().expect("Couldn't send the message.");
+    run!(stopped).expect("The group was dropped.");
+}
+
+// Creates a children group whose element receives at most two
+// messages per second, sends it six messages and then tells it
+// to stop, returning how many messages it received once it did.
+fn stop_with_pending_msgs(mode: ShutdownMode) -> usize {
+    let received = Arc::new(AtomicUsize::new(0));
+    let received_ = received.clone();
+
+    let children = Bastion::children(move |children| {
+        children
+            .with_shutdown_mode(mode)
+            .with_rate_limit(2, RateLimitPolicy::Delay)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received_.clone();
+                async move {
+                    loop {
+                        ctx.recv().await?;
+                        received.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child = &children.elems()[0];
+    for i in 0..6u8 {
+        assert!(child.tell_anonymously(i).is_ok());
+    }
+    wait_until(|| received.load(Ordering::SeqCst) > 0);
+
+    assert!(child.stop().is_ok());
+    wait_until(|| !child.is_alive());
+
+    received.load(Ordering::SeqCst)
+}
+
+#[test]
+fn stop_overtakes_pending_msgs() {
+    init_start();
+
+    // The messages held back by the rate limit are dropped.
+    assert!(stop_with_pending_msgs(ShutdownMode::Immediate) < 6);
+}
+
+#[test]
+fn drains_pending_msgs_before_stopping() {
+    init_start();
+
+    assert_eq!(stop_with_pending_msgs(ShutdownMode::Drain), 6);
+}
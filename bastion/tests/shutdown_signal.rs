@@ -0,0 +1,53 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use futures::future::{self, Either};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn finishes_work_once_told_to_stop() {
+    init_start();
+
+    let started = Arc::new(AtomicBool::new(false));
+    let finished = Arc::new(AtomicBool::new(false));
+
+    let (started_, finished_) = (started.clone(), finished.clone());
+    let children_ref = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let (started, finished) = (started_.clone(), finished_.clone());
+            async move {
+                assert!(!ctx.is_shutdown_requested());
+                started.store(true, Ordering::SeqCst);
+
+                loop {
+                    let recv = Box::pin(ctx.recv());
+                    let shutdown = Box::pin(ctx.shutdown_requested());
+                    match future::select(recv, shutdown).await {
+                        Either::Left((msg, _)) => {
+                            msg?;
+                        }
+                        Either::Right(_) => break,
+                    }
+                }
+
+                assert!(ctx.is_shutdown_requested());
+                finished.store(true, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| started.load(Ordering::SeqCst));
+
+    let stopped = children_ref.stopped().expect("Couldn't send the message.");
+    children_ref.stop().expect("Couldn't send the message.");
+    let terminated = run!(stopped).expect("The group was dropped.");
+
+    assert!(finished.load(Ordering::SeqCst));
+    match terminated.reason() {
+        TerminationReason::Normal => (),
+        reason => panic!("Unexpected reason: {:?}", reason),
+    }
+}
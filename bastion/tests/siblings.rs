@@ -0,0 +1,47 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn broadcasts_to_siblings_only() {
+    init_start();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(3)
+            .with_exec(move |ctx: BastionContext| {
+                let received = received_.clone();
+                async move {
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: &'static str => {
+                                ctx.broadcast_siblings(ctx.elem_index())
+                                    .expect("Couldn't send the message.");
+                            };
+                            ref from: usize => {
+                                received.lock().unwrap().push((ctx.elem_index(), *from));
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    children_ref
+        .elem_by_index(0)
+        .expect("No element with index 0.")
+        .tell_anonymously("Gossip!")
+        .expect("Couldn't send the message.");
+    wait_until(|| received.lock().unwrap().len() == 2);
+
+    let mut received = received.lock().unwrap().clone();
+    received.sort();
+    assert_eq!(received, vec![(1, 0), (2, 0)]);
+}
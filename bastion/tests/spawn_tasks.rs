@@ -0,0 +1,58 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for, wait_until};
+use futures::future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn cancels_tasks_once_stopped() {
+    init_start();
+
+    let handle = Arc::new(Mutex::new(None));
+
+    let handle_ = handle.clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let handle = handle_.clone();
+            async move {
+                let task = ctx.spawn(future::pending::<()>());
+                *handle.lock().unwrap() = Some(task);
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    let task = wait_for(|| handle.lock().unwrap().take());
+    assert_eq!(run!(task), Err(TaskError::Cancelled));
+}
+
+#[test]
+fn reports_task_panics() {
+    init_start();
+
+    let reported = Arc::new(AtomicBool::new(false));
+
+    let reported_ = reported.clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let reported = reported_.clone();
+            async move {
+                let task = ctx.spawn(async {
+                    panic!("helper panicked");
+                });
+                assert_eq!(task.await, Err(TaskError::Panicked));
+                assert_eq!(ctx.spawn(async { 42 }).await, Ok(42));
+                reported.store(true, Ordering::SeqCst);
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    wait_until(|| reported.load(Ordering::SeqCst));
+}
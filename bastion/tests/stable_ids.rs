@@ -0,0 +1,59 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn child_ref_survives_restart() {
+    init_start();
+
+    let ids = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let ids_ = ids.clone();
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_stable_ids()
+            .with_exec(move |ctx: BastionContext| {
+                let ids = ids_.clone();
+                let received = received_.clone();
+                async move {
+                    let launches = {
+                        let mut ids = ids.lock().unwrap();
+                        ids.push(ctx.current().id().clone());
+                        ids.len()
+                    };
+
+                    loop {
+                        ctx.recv().await?;
+                        // The first launch faults on its first
+                        // message, restarting the group.
+                        if launches == 1 {
+                            return Err(());
+                        }
+
+                        received.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child_ref = children_ref.elems()[0].clone();
+    child_ref
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+    wait_until(|| ids.lock().unwrap().len() == 2);
+
+    child_ref
+        .tell_anonymously("Hello!")
+        .expect("Couldn't send the message.");
+    wait_until(|| received.load(Ordering::SeqCst) == 1);
+
+    let ids = ids.lock().unwrap();
+    assert_eq!(&ids[0], child_ref.id());
+    assert_eq!(&ids[1], child_ref.id());
+}
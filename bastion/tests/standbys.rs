@@ -0,0 +1,80 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn promotes_standbys() {
+    init_start();
+
+    let launched = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let launched_ = launched.clone();
+    let received_ = received.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_redundancy(1)
+            .with_standbys(1)
+            .with_exec(move |ctx: BastionContext| {
+                let launched = launched_.clone();
+                let received = received_.clone();
+                async move {
+                    let id = ctx.current().id().clone();
+                    launched.lock().unwrap().push(id.clone());
+                    loop {
+                        let msg = msg! { ctx.recv().await?,
+                            ref msg: &'static str => *msg;
+                            msg: &'static str => msg;
+                            _: _ => continue;
+                        };
+
+                        if msg == "Fault!" {
+                            return Err(());
+                        }
+
+                        received.lock().unwrap().push(id.clone());
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| launched.lock().unwrap().len() == 2);
+
+    // The standby is launched but isn't part of the group's
+    // elements...
+    assert_eq!(children_ref.elems().len(), 1);
+    let active = children_ref.elems()[0].clone();
+    let standby = launched
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|id| *id != active.id())
+        .cloned()
+        .expect("Couldn't find the standby.");
+
+    // ...and doesn't receive the messages sent to the group.
+    children_ref
+        .broadcast("Hello!")
+        .expect("Couldn't send the message.");
+    wait_until(|| !received.lock().unwrap().is_empty());
+    assert_eq!(*received.lock().unwrap(), vec![active.id().clone()]);
+
+    // Once the active element faults, the standby replaces it
+    // and a new standby is launched (without restarting the
+    // group).
+    active
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+    wait_until(|| launched.lock().unwrap().len() == 3);
+
+    children_ref
+        .broadcast("Hello!")
+        .expect("Couldn't send the message.");
+    wait_until(|| received.lock().unwrap().len() == 2);
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![active.id().clone(), standby]
+    );
+}
@@ -0,0 +1,401 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Supervises three children groups, the second one faulting the
+// first time it receives a message (and using the overriding
+// strategy if any), and checks how many times each of them was
+// launched.
+fn assert_launches_with(
+    strategy: SupervisionStrategy,
+    overriding: Option<SupervisionStrategy>,
+    expected: Vec<usize>,
+) {
+    let launched = Arc::new((0..3).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>());
+    let faulted = Arc::new(AtomicBool::new(false));
+
+    let launched_ = launched.clone();
+    let supervisor = Bastion::supervisor(move |mut sp| {
+        for index in 0..3 {
+            let launched = launched_.clone();
+            let faulted = faulted.clone();
+            let overriding = overriding.clone();
+            sp = sp.children(move |mut children| {
+                if let (1, Some(strategy)) = (index, overriding) {
+                    children = children.with_supervision_strategy(strategy);
+                }
+
+                children.with_exec(move |ctx: BastionContext| {
+                    launched[index].fetch_add(1, Ordering::SeqCst);
+                    let faulted = faulted.clone();
+                    async move {
+                        loop {
+                            ctx.recv().await?;
+                            if index == 1 && !faulted.swap(true, Ordering::SeqCst) {
+                                return Err(());
+                            }
+                        }
+                    }
+                })
+            });
+        }
+
+        sp
+    })
+    .expect("Couldn't create the supervisor.");
+
+    supervisor
+        .strategy(strategy)
+        .expect("Couldn't set the strategy.");
+
+    init_start();
+    supervisor
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+
+    wait_until(|| {
+        let launches = launched
+            .iter()
+            .map(|launched| launched.load(Ordering::SeqCst))
+            .collect::<Vec<_>>();
+        launches == expected
+    });
+}
+
+fn assert_launches(strategy: SupervisionStrategy, expected: Vec<usize>) {
+    assert_launches_with(strategy, None, expected)
+}
+
+#[test]
+fn one_for_one() {
+    assert_launches(SupervisionStrategy::OneForOne, vec![1, 2, 1]);
+}
+
+#[test]
+fn one_for_all() {
+    assert_launches(SupervisionStrategy::OneForAll, vec![2, 2, 2]);
+}
+
+#[test]
+fn rest_for_one() {
+    assert_launches(SupervisionStrategy::RestForOne, vec![1, 2, 2]);
+}
+
+#[test]
+fn overridden_strategy() {
+    let overriding = Some(SupervisionStrategy::OneForOne);
+    assert_launches_with(SupervisionStrategy::OneForAll, overriding, vec![1, 2, 1]);
+
+    let overriding = Some(SupervisionStrategy::RestForOne);
+    assert_launches_with(SupervisionStrategy::OneForOne, overriding, vec![1, 2, 2]);
+}
+
+#[test]
+fn changed_group_strategy() {
+    let launched = Arc::new((0..2).map(|_| AtomicUsize::new(0)).collect::<Vec<_>>());
+    let children_refs = Arc::new(Mutex::new(Vec::new()));
+
+    let launched_ = launched.clone();
+    let children_refs_ = children_refs.clone();
+    Bastion::supervisor(move |sp| {
+        for index in 0..2 {
+            let launched = launched_.clone();
+            let children_ref = sp.children_ref(move |children| {
+                children.with_exec(move |ctx: BastionContext| {
+                    launched[index].fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        ctx.recv().await?;
+                        Err(())
+                    }
+                })
+            });
+            children_refs_.lock().unwrap().push(children_ref);
+        }
+
+        sp
+    })
+    .expect("Couldn't create the supervisor.");
+
+    init_start();
+    let children_refs = children_refs.lock().unwrap().clone();
+
+    // The supervisor's "one-for-one" strategy only restarts the
+    // faulted group...
+    children_refs[1]
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+    wait_until(|| launched[1].load(Ordering::SeqCst) == 2);
+    assert_eq!(launched[0].load(Ordering::SeqCst), 1);
+
+    // ...until the group changes it.
+    children_refs[0]
+        .strategy(SupervisionStrategy::OneForAll)
+        .expect("Couldn't set the strategy.");
+    children_refs[0]
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+    wait_until(|| launched[0].load(Ordering::SeqCst) == 2);
+    wait_until(|| launched[1].load(Ordering::SeqCst) == 3);
+}
+
+#[test]
+fn fault_callback() {
+    let faults = Arc::new(Mutex::new(Vec::new()));
+    let faults_ = faults.clone();
+
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.with_fault_callback(move |fault: &Fault| {
+            faults_.lock().unwrap().push(fault.clone());
+        })
+        .children(|children| {
+            children.with_exec(|ctx: BastionContext| async move {
+                ctx.recv().await?;
+                Err(())
+            })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    init_start();
+    supervisor
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+    wait_until(|| !faults.lock().unwrap().is_empty());
+
+    let faults = faults.lock().unwrap();
+    assert_eq!(faults.len(), 1);
+    assert_eq!(faults[0].restarts(), 0);
+    assert_eq!(faults[0].reason(), &FaultReason::Error);
+    assert_eq!(faults[0].decision(), Decision::Restart);
+}
+
+#[test]
+fn temporary_group_fault() {
+    let faults = Arc::new(Mutex::new(Vec::new()));
+    let faults_ = faults.clone();
+
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.with_fault_callback(move |fault: &Fault| {
+            faults_.lock().unwrap().push(fault.clone());
+        })
+        .children(|children| {
+            children
+                .with_restart_policy(GroupRestartPolicy::Temporary)
+                .with_exec(|ctx: BastionContext| async move {
+                    ctx.recv().await?;
+                    Err(())
+                })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    init_start();
+    supervisor
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+    wait_until(|| !faults.lock().unwrap().is_empty());
+
+    // The fault is reported, but the group isn't restarted.
+    let faults = faults.lock().unwrap();
+    assert_eq!(faults.len(), 1);
+    assert_eq!(faults[0].reason(), &FaultReason::Error);
+    assert_eq!(faults[0].decision(), Decision::Stop);
+}
+
+#[derive(Debug)]
+struct StopOnError;
+
+impl Strategy for StopOnError {
+    fn decide(&mut self, fault: &FaultInfo) -> Decision {
+        match fault.reason() {
+            FaultReason::Error => Decision::Stop,
+            _ => Decision::Restart,
+        }
+    }
+}
+
+#[test]
+fn custom_strategy() {
+    let launched = Arc::new(AtomicUsize::new(0));
+    let launched_ = launched.clone();
+    let children_ref = Arc::new(Mutex::new(None));
+    let children_ref_ = children_ref.clone();
+
+    let supervisor = Bastion::supervisor(move |sp| {
+        let sp = sp.with_custom_strategy(StopOnError);
+        *children_ref_.lock().unwrap() = Some(sp.children_ref(|children| {
+            children.with_exec(move |ctx: BastionContext| {
+                launched_.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    ctx.recv().await?;
+                    Err(())
+                }
+            })
+        }));
+
+        sp
+    })
+    .expect("Couldn't create the supervisor.");
+
+    init_start();
+    supervisor
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+    // The element stopped once it doesn't receive messages anymore.
+    let child_ref = children_ref.lock().unwrap().as_ref().unwrap().elems()[0].clone();
+    wait_until(|| child_ref.tell_anonymously(()).is_err());
+
+    assert_eq!(launched.load(Ordering::SeqCst), 1);
+}
+
+#[derive(Debug)]
+struct RestartOnPanic;
+
+impl Strategy for RestartOnPanic {
+    fn decide(&mut self, fault: &FaultInfo) -> Decision {
+        match fault.kind() {
+            FaultKind::Panic => Decision::Restart,
+            _ => Decision::Stop,
+        }
+    }
+}
+
+// Supervises a children group whose element fails (by panicking
+// or by returning an error) every time it receives a message, and
+// returns how many times it was launched after two faults.
+fn launches_with_kind(panic: bool, policy: RestartPolicy) -> usize {
+    let launched = Arc::new(AtomicUsize::new(0));
+    let launched_ = launched.clone();
+    let children_ref = Arc::new(Mutex::new(None));
+    let children_ref_ = children_ref.clone();
+
+    let supervisor = Bastion::supervisor(move |sp| {
+        let restart_strategy = RestartStrategy::default().with_restart_policy(policy);
+        let sp = sp
+            .with_custom_strategy(RestartOnPanic)
+            .with_restart_strategy(restart_strategy);
+        *children_ref_.lock().unwrap() = Some(sp.children_ref(|children| {
+            children.with_exec(move |ctx: BastionContext| {
+                launched_.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    ctx.recv().await?;
+                    if panic {
+                        panic!("Fault!");
+                    }
+
+                    Err(())
+                }
+            })
+        }));
+
+        sp
+    })
+    .expect("Couldn't create the supervisor.");
+
+    init_start();
+    let child_ref = children_ref.lock().unwrap().as_ref().unwrap().elems()[0].clone();
+    for launches in 2..4 {
+        supervisor
+            .broadcast("Fault!")
+            .expect("Couldn't broadcast the message.");
+        if panic {
+            // The group is restarted...
+            wait_until(|| launched.load(Ordering::SeqCst) == launches);
+        } else {
+            // ...or stopped, in which case its element doesn't
+            // receive messages anymore.
+            wait_until(|| child_ref.tell_anonymously(()).is_err());
+        }
+    }
+
+    launched.load(Ordering::SeqCst)
+}
+
+#[test]
+fn strategy_by_fault_kind() {
+    assert_eq!(launches_with_kind(true, RestartPolicy::Always), 3);
+    assert_eq!(launches_with_kind(false, RestartPolicy::Always), 1);
+}
+
+#[test]
+fn strategy_overrides_restart_policy() {
+    assert_eq!(launches_with_kind(true, RestartPolicy::Never), 3);
+}
+
+#[test]
+fn restart_stats() {
+    let supervisor = Bastion::supervisor(|sp| {
+        sp.children(|children| {
+            children.with_exec(|ctx: BastionContext| async move {
+                ctx.recv().await?;
+                Err(())
+            })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    init_start();
+    wait_until(|| !supervisor.stats().is_empty());
+
+    let stats = supervisor.stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].restarts(), 0);
+    assert_eq!(stats[0].last_fault(), None);
+    assert_eq!(stats[0].backoff(), None);
+
+    supervisor
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+    wait_until(|| supervisor.stats()[0].restarts() == 1);
+
+    let restarted = supervisor.stats();
+    assert_eq!(restarted.len(), 1);
+    assert_ne!(restarted[0].id(), stats[0].id());
+    assert!(restarted[0].last_fault().is_some());
+}
+
+#[test]
+fn circuit_breaker() {
+    let supervisor = Bastion::supervisor(|sp| {
+        sp.with_restart_strategy(
+            RestartStrategy::default().with_circuit_breaker(1, Duration::from_millis(500)),
+        )
+        .children(|children| {
+            children.with_exec(|ctx: BastionContext| async move {
+                ctx.recv().await?;
+                Err(())
+            })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    init_start();
+    wait_until(|| !supervisor.stats().is_empty());
+
+    supervisor
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+    wait_until(|| supervisor.stats()[0].restarts() == 1);
+
+    let restarted = supervisor.stats();
+    assert_eq!(restarted[0].state(), SupervisedState::Started);
+
+    // The group faulted again after one restart.
+    supervisor
+        .broadcast("Fault!")
+        .expect("Couldn't broadcast the message.");
+    wait_until(|| supervisor.stats()[0].state() == SupervisedState::Open);
+
+    let open = supervisor.stats();
+    assert_eq!(open.len(), 1);
+
+    // The group is restarted once the cooldown elapsed.
+    wait_until(|| supervisor.stats()[0].state() == SupervisedState::Started);
+
+    let half_open = supervisor.stats();
+    assert_ne!(half_open[0].id(), open[0].id());
+}
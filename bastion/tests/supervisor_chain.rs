@@ -0,0 +1,50 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_for, wait_until};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn walks_up_supervisors() {
+    init_start();
+
+    let chain = Arc::new(Mutex::new(None));
+
+    let chain_ = chain.clone();
+    let outer = Bastion::supervisor(move |sp| {
+        sp.supervisor(move |sp| {
+            sp.children(move |children| {
+                children.with_exec(move |ctx: BastionContext| {
+                    let chain = chain_.clone();
+                    async move {
+                        ctx.recv().await?;
+
+                        let ids = ctx
+                            .supervisor_chain()
+                            .iter()
+                            .map(|sp| sp.id().clone())
+                            .collect::<Vec<_>>();
+                        *chain.lock().unwrap() = Some(ids);
+
+                        Ok(())
+                    }
+                })
+            })
+        })
+    })
+    .expect("Couldn't create the supervisor.");
+
+    // The chain is found by walking down the supervision tree.
+    wait_until(|| {
+        Bastion::tree()
+            .supervisors()
+            .iter()
+            .any(|node| node.id() == outer.id())
+    });
+    let inner = wait_for(|| outer.supervisors().pop());
+    let group = wait_for(|| inner.children_groups().pop());
+    group.broadcast(()).expect("Couldn't send the message.");
+
+    let chain = wait_for(|| chain.lock().unwrap().take());
+    assert_eq!(chain, vec![inner.id().clone(), outer.id().clone()]);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn delivers_ticks_until_cancelled() {
+    init_start();
+
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let (ticks_, done_) = (ticks.clone(), done.clone());
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let (ticks, done) = (ticks_.clone(), done_.clone());
+            async move {
+                let guard = ctx.tick_every(Duration::from_millis(50), "Tick");
+                while ticks.load(Ordering::SeqCst) < 3 {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            assert_eq!(msg, "Tick");
+                            ticks.fetch_add(1, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+                guard.cancel();
+
+                // No other tick is received once cancelled, while
+                // another one would have been by the time two
+                // ticks of the same interval are.
+                let _guard = ctx.tick_every(Duration::from_millis(50), "Tock");
+                let mut tocks = 0;
+                while tocks < 2 {
+                    msg! { ctx.recv().await?,
+                        msg: &'static str => {
+                            if msg == "Tock" {
+                                tocks += 1;
+                            } else {
+                                ticks.fetch_add(1, Ordering::SeqCst);
+                            }
+                        };
+                        _: _ => ();
+                    }
+                }
+                done.store(true, Ordering::SeqCst);
+
+                Ok(())
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| done.load(Ordering::SeqCst));
+
+    assert_eq!(ticks.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn receives_exit() {
+    init_start();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let exits = Arc::new(AtomicUsize::new(0));
+
+    let received_ = received.clone();
+    let exits_ = exits.clone();
+    let children_ref = Bastion::children(move |children| {
+        children
+            .with_trapped_exits()
+            .with_exec(move |ctx: BastionContext| {
+                let received = received_.clone();
+                let exits = exits_.clone();
+                async move {
+                    let mut exiting = false;
+                    loop {
+                        msg! { ctx.recv().await?,
+                            _msg: Exit => {
+                                exits.fetch_add(1, Ordering::SeqCst);
+                                exiting = true;
+                            };
+                            _msg: &'static str => {
+                                received.fetch_add(1, Ordering::SeqCst);
+                                // The element only stops after handling
+                                // one more message.
+                                if exiting {
+                                    return Ok(());
+                                }
+                            };
+                            _: _ => ();
+                        }
+                    }
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+
+    let child_ref = children_ref.elems()[0].clone();
+    child_ref.stop().expect("Couldn't send the message.");
+    wait_until(|| exits.load(Ordering::SeqCst) == 1);
+
+    // The element is still running after trapping the exit.
+    child_ref
+        .tell_anonymously("Hello!")
+        .expect("Couldn't send the message.");
+    wait_until(|| received.load(Ordering::SeqCst) == 1);
+
+    assert_eq!(exits.load(Ordering::SeqCst), 1);
+}
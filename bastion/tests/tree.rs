@@ -0,0 +1,51 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+
+#[test]
+fn snapshots_tree() {
+    init_start();
+
+    let exec = |children: Children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    };
+
+    let supervisor = Bastion::supervisor(move |sp| {
+        sp.children(move |children| exec(children).with_redundancy(3))
+            .supervisor(move |sp| sp.children(exec))
+    })
+    .expect("Couldn't create the supervision tree.");
+
+    wait_until(|| {
+        let tree = Bastion::tree();
+        let node = tree
+            .supervisors()
+            .iter()
+            .find(|node| node.id() == supervisor.id());
+        node.map_or(false, |node| !node.supervisors().is_empty())
+    });
+
+    let tree = Bastion::tree();
+    let node = tree
+        .supervisors()
+        .iter()
+        .find(|node| node.id() == supervisor.id())
+        .expect("Couldn't find the supervisor.");
+
+    assert_eq!(node.state(), SupervisedState::Started);
+    assert_eq!(node.restarts(), 0);
+
+    assert_eq!(node.children_groups().len(), 1);
+    let children = &node.children_groups()[0];
+    assert_eq!(children.redundancy(), 3);
+    assert_eq!(children.state(), SupervisedState::Started);
+
+    assert_eq!(node.supervisors().len(), 1);
+    assert_eq!(node.supervisors()[0].children_groups().len(), 1);
+    assert!(!tree.to_string().is_empty());
+}
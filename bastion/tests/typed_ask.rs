@@ -0,0 +1,51 @@
+mod common;
+
+use bastion::prelude::*;
+use common::init_start;
+
+// Creates a children group whose element answers every `u64`
+// it is asked with its double, and every `&'static str` with
+// its length.
+fn spawn_responder() -> ChildRef {
+    let children_ref = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                msg! { ctx.recv().await?,
+                    msg: u64 =!> {
+                        answer!(ctx, msg * 2).ok();
+                    };
+                    msg: &'static str =!> {
+                        answer!(ctx, msg.len()).ok();
+                    };
+                    _: _ => ();
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    children_ref.elems()[0].clone()
+}
+
+#[test]
+fn typed_answer() {
+    init_start();
+
+    let child_ref = spawn_responder();
+    let answer = run!(child_ref.ask::<_, u64>(21u64));
+    assert_eq!(answer.ok(), Some(42));
+}
+
+#[test]
+fn unexpected_answer() {
+    init_start();
+
+    let child_ref = spawn_responder();
+    match run!(child_ref.ask::<_, u64>("Hello")) {
+        Err(AskError::UnexpectedAnswer(answer)) => {
+            let (msg, _) = answer.extract();
+            assert_eq!(msg.downcast::<usize>().ok(), Some(5));
+        }
+        answer => panic!("Unexpected answer: {:?}", answer),
+    }
+}
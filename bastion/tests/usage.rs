@@ -0,0 +1,38 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+
+#[test]
+fn tracks_usage() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            loop {
+                ctx.recv().await?;
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let child_ref = children_ref.elems()[0].clone();
+
+    // The first message is handled on its own so that the
+    // element gets polled more than once.
+    child_ref
+        .tell_anonymously(0usize)
+        .expect("Couldn't send the message.");
+    wait_until(|| child_ref.usage().messages() == 1);
+
+    for msg in 1..3usize {
+        child_ref
+            .tell_anonymously(msg)
+            .expect("Couldn't send the message.");
+    }
+    wait_until(|| child_ref.usage().messages() == 3);
+
+    let usage = child_ref.usage();
+    assert!(usage.allocated() >= 3 * std::mem::size_of::<usize>() as u64);
+    assert!(usage.polls() >= 2);
+    assert!(usage.poll_time() >= usage.max_poll_time());
+}
@@ -0,0 +1,288 @@
+mod common;
+
+use bastion::prelude::*;
+use common::{init_start, wait_until};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Creates a children group whose element watches the given one
+// and stores the `Terminated` messages it receives, returning
+// once it started watching.
+fn watcher(watched: ChildRef) -> Arc<Mutex<Vec<Terminated>>> {
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+    let watching = Arc::new(AtomicBool::new(false));
+
+    let terminated_ = terminated.clone();
+    let watching_ = watching.clone();
+    Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let watched = watched.clone();
+            let terminated = terminated_.clone();
+            let watching = watching_.clone();
+            async move {
+                ctx.watch(&watched)?;
+                watching.store(true, Ordering::SeqCst);
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: Terminated => {
+                            terminated.lock().unwrap().push(msg);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+
+    wait_until(|| watching.load(Ordering::SeqCst));
+    terminated
+}
+
+fn is_normal(reason: &TerminationReason) -> bool {
+    match reason {
+        TerminationReason::Normal => true,
+        _ => false,
+    }
+}
+
+#[test]
+fn notified_on_fault() {
+    init_start();
+
+    let watched = Bastion::children(|children| {
+        children
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_exec(|ctx: BastionContext| async move {
+                ctx.recv().await?;
+                Err(())
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let watched = watched.elems()[0].clone();
+
+    let terminated = watcher(watched.clone());
+
+    watched
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+    wait_until(|| !terminated.lock().unwrap().is_empty());
+
+    let terminated = terminated.lock().unwrap();
+    assert_eq!(terminated.len(), 1);
+    assert_eq!(terminated[0].id(), watched.id());
+    assert_eq!(terminated[0].reason().fault(), Some(&FaultReason::Error));
+}
+
+#[test]
+fn notified_on_failure() {
+    init_start();
+
+    let watched = Bastion::children(|children| {
+        children
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_fallible_exec(|ctx: BastionContext| async move {
+                ctx.recv().await.map_err(|()| "Stopped.")?;
+                Err("Failed.")
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let watched = watched.elems()[0].clone();
+
+    let terminated = watcher(watched.clone());
+
+    watched
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+    wait_until(|| !terminated.lock().unwrap().is_empty());
+
+    let terminated = terminated.lock().unwrap();
+    assert_eq!(terminated.len(), 1);
+    match terminated[0].reason().fault() {
+        Some(FaultReason::Failure(err)) => assert_eq!(err.to_string(), "Failed."),
+        reason => panic!("Unexpected fault: {:?}", reason),
+    }
+}
+
+#[test]
+fn notified_on_timeout() {
+    init_start();
+
+    let watched = Bastion::children(|children| {
+        children
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_max_runtime(Duration::from_millis(500))
+            .with_exec(|ctx: BastionContext| async move {
+                loop {
+                    ctx.recv().await?;
+                }
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let watched = watched.elems()[0].clone();
+
+    let terminated = watcher(watched.clone());
+    wait_until(|| !terminated.lock().unwrap().is_empty());
+
+    let terminated = terminated.lock().unwrap();
+    assert_eq!(terminated.len(), 1);
+    assert_eq!(terminated[0].reason().fault(), Some(&FaultReason::Timeout));
+}
+
+#[test]
+fn notified_on_stop() {
+    init_start();
+
+    let watched = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            ctx.recv().await?;
+            Ok(())
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let watched = watched.elems()[0].clone();
+
+    let terminated = watcher(watched.clone());
+
+    watched
+        .tell_anonymously("Stop!")
+        .expect("Couldn't send the message.");
+    wait_until(|| !terminated.lock().unwrap().is_empty());
+
+    // Watching an element that already stopped notifies right
+    // away.
+    let terminated_again = watcher(watched.clone());
+    wait_until(|| !terminated_again.lock().unwrap().is_empty());
+
+    let terminated = terminated.lock().unwrap();
+    assert_eq!(terminated.len(), 1);
+    assert_eq!(terminated[0].id(), watched.id());
+    assert!(is_normal(terminated[0].reason()));
+
+    let terminated_again = terminated_again.lock().unwrap();
+    assert_eq!(terminated_again.len(), 1);
+    assert!(is_normal(terminated_again[0].reason()));
+}
+
+#[test]
+fn custom_reason() {
+    init_start();
+
+    let watched = Bastion::children(|children| {
+        children.with_exec(|ctx: BastionContext| async move {
+            ctx.recv().await?;
+            ctx.exit_with("Done");
+            Ok(())
+        })
+    })
+    .expect("Couldn't create the children group.");
+    let watched = watched.elems()[0].clone();
+
+    let terminated = watcher(watched.clone());
+
+    watched
+        .tell_anonymously("Stop!")
+        .expect("Couldn't send the message.");
+    wait_until(|| !terminated.lock().unwrap().is_empty());
+
+    let terminated = terminated.lock().unwrap();
+    assert_eq!(terminated.len(), 1);
+    assert_eq!(
+        terminated[0].reason().custom::<&'static str>(),
+        Some(&"Done")
+    );
+}
+
+#[test]
+fn exited_with_reason() {
+    init_start();
+
+    let children_ref = Bastion::children(|children| {
+        children
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_exec(|ctx: BastionContext| async move {
+                ctx.recv().await?;
+                Err(())
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let child_ref = children_ref.elems()[0].clone();
+
+    let exited = child_ref.exited().expect("Couldn't send the message.");
+    child_ref
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+
+    let reason = run!(exited).expect("The child was dropped.");
+    assert_eq!(reason.fault(), Some(&FaultReason::Error));
+
+    // Waiting for an element that already terminated fails.
+    wait_until(|| child_ref.exited().is_err());
+}
+
+#[test]
+fn not_notified_once_unwatched() {
+    init_start();
+
+    let watched = Bastion::children(|children| {
+        children
+            .with_restart_policy(GroupRestartPolicy::Temporary)
+            .with_exec(|ctx: BastionContext| async move {
+                ctx.recv().await?;
+                Err(())
+            })
+    })
+    .expect("Couldn't create the children group.");
+    let watched = watched.elems()[0].clone();
+
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+    let unwatched = Arc::new(AtomicBool::new(false));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let watched_ = watched.clone();
+    let terminated_ = terminated.clone();
+    let unwatched_ = unwatched.clone();
+    let done_ = done.clone();
+    let watcher = Bastion::children(move |children| {
+        children.with_exec(move |ctx: BastionContext| {
+            let watched = watched_.clone();
+            let terminated = terminated_.clone();
+            let unwatched = unwatched_.clone();
+            let done = done_.clone();
+            async move {
+                ctx.watch(&watched)?;
+                ctx.unwatch(&watched)?;
+                unwatched.store(true, Ordering::SeqCst);
+                loop {
+                    msg! { ctx.recv().await?,
+                        msg: Terminated => {
+                            terminated.lock().unwrap().push(msg);
+                        };
+                        _msg: &'static str => {
+                            done.store(true, Ordering::SeqCst);
+                        };
+                        _: _ => ();
+                    }
+                }
+            }
+        })
+    })
+    .expect("Couldn't create the children group.");
+    wait_until(|| unwatched.load(Ordering::SeqCst));
+
+    let exited = watched.exited().expect("Couldn't send the message.");
+    watched
+        .tell_anonymously("Fault!")
+        .expect("Couldn't send the message.");
+    run!(exited).expect("The child was dropped.");
+
+    // The watchers were told before `exited` resolved, so this
+    // message is received after the notification would have.
+    watcher.elems()[0]
+        .tell_anonymously("Done")
+        .expect("Couldn't send the message.");
+    wait_until(|| done.load(Ordering::SeqCst));
+
+    assert!(terminated.lock().unwrap().is_empty());
+}
@@ -51,6 +51,12 @@ pub struct ProcStack {
     /// This callback is only called when a panic has been occurred.
     /// Mind that [ProcHandle](proc_handle/struct.ProcHandle.html) is not using this
     pub(crate) after_panic: Option<Arc<dyn Fn(ProcState) + Send + Sync>>,
+
+    /// Core affinity
+    ///
+    /// The identifiers of the cores that the process is allowed to run on,
+    /// or `None` if it can run on any core.
+    pub(crate) affinity: Option<Arc<[usize]>>,
 }
 
 impl ProcStack {
@@ -146,6 +152,44 @@ impl ProcStack {
         self
     }
 
+    /// Pins the process which is going to take this stack to the given cores,
+    /// which executors supporting core affinity will only run it on.
+    ///
+    /// Passing an empty set of cores removes any previously set affinity.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    ///
+    /// ProcStack::default()
+    ///     .with_affinity(vec![0, 1]);
+    /// ```
+    pub fn with_affinity<I>(mut self, cores: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let cores: Vec<usize> = cores.into_iter().collect();
+        self.affinity = if cores.is_empty() {
+            None
+        } else {
+            Some(cores.into())
+        };
+        self
+    }
+
+    /// Utility function to get the cores the process is pinned to for the
+    /// implementation of executors.
+    ///
+    /// ```rust
+    /// use lightproc::proc_stack::ProcStack;
+    ///
+    /// let proc = ProcStack::default().with_affinity(vec![2]);
+    ///
+    /// assert_eq!(proc.get_affinity(), Some(&[2][..]));
+    /// ```
+    pub fn get_affinity(&self) -> Option<&[usize]> {
+        self.affinity.as_ref().map(|cores| &cores[..])
+    }
+
     /// Utility function to get_pid for the implementation of executors.
     ///
     /// ```rust
@@ -214,6 +258,7 @@ impl Default for ProcStack {
             before_start: None,
             after_complete: None,
             after_panic: None,
+            affinity: None,
         }
     }
 }
@@ -226,6 +271,7 @@ impl Debug for ProcStack {
             .field("before_start", &self.before_start.is_some())
             .field("after_complete", &self.after_complete.is_some())
             .field("after_panic", &self.after_panic.is_some())
+            .field("affinity", &self.affinity)
             .finish()
     }
 }
@@ -238,6 +284,7 @@ impl Clone for ProcStack {
             before_start: self.before_start.clone(),
             after_complete: self.after_complete.clone(),
             after_panic: self.after_panic.clone(),
+            affinity: self.affinity.clone(),
         }
     }
 }